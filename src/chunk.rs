@@ -1,49 +1,128 @@
+#[cfg(feature = "tokio")]
+use crate::storage::{plain::AsyncPlainStorage, AsyncStorageFile};
 use crate::storage::{plain::PlainStorage, Storage};
 use crate::stream;
-use crate::{Error, ErrorKind, StorageFile, Tag, Version};
+use crate::{ChunkId, Error, ErrorKind, StorageFile, Tag, Version, Warning, WriteOutcome};
 use byteorder::{BigEndian, ByteOrder, LittleEndian};
 use std::convert::TryFrom;
 use std::fmt;
 use std::io::prelude::*;
 use std::io::{BufReader, Seek, SeekFrom};
+use std::ops::Range;
 use std::{convert::TryInto, io};
 
 const TAG_LEN: u32 = 4; // Size of a tag.
-const SIZE_LEN: u32 = 4; // Size of a 32 bits integer.
-const CHUNK_HEADER_LEN: u32 = TAG_LEN + SIZE_LEN;
+const MAX_HEADER_LEN: usize = 12; // Size of a header with a 64 bits size.
+const MAX_ROOT_HEADER_LEN: usize = MAX_HEADER_LEN + TAG_LEN as usize;
 
 const ID3_TAG: ChunkTag = ChunkTag(*b"ID3 ");
+/// The IDs of ID3 chunks that are found in the wild. Comparison is case insensitive, so this
+/// includes `id3 `.
+const ID3_TAGS: &[ChunkTag] = &[ID3_TAG, ChunkTag(*b"ID32")];
+const DS64_TAG: ChunkTag = ChunkTag(*b"ds64");
+const DATA_TAG: ChunkTag = ChunkTag(*b"data");
+const LIST_TAG: ChunkTag = ChunkTag(*b"LIST");
+const INFO_TAG: ChunkTag = ChunkTag(*b"INFO");
+
+/// The 32 bits size of a chunk of which the actual size is stored in the ds64 chunk.
+const DS64_SIZE: u64 = 0xffff_ffff;
 
 /// Attempts to load a ID3 tag from the given chunk stream.
-pub fn load_id3_chunk<F, R>(mut reader: R) -> crate::Result<Tag>
+pub fn load_id3_chunk<F, R>(reader: R) -> crate::Result<Tag>
+where
+    F: ChunkFormat,
+    R: io::Read + io::Seek,
+{
+    load_id3_chunk_with_warnings::<F, R>(reader, &mut Vec::new())
+}
+
+/// Attempts to load a ID3 tag from the given chunk stream, collecting any recoverable issues into
+/// `warnings`.
+pub fn load_id3_chunk_with_warnings<F, R>(
+    mut reader: R,
+    warnings: &mut Vec<Warning>,
+) -> crate::Result<Tag>
 where
     F: ChunkFormat,
     R: io::Read + io::Seek,
 {
-    let root_chunk = ChunkHeader::read_root_chunk_header::<F, _>(&mut reader)?;
+    let root_chunk = RootChunk::read::<F, _>(&mut reader)?;
+
+    let tag_chunk = root_chunk.find_id3::<F, _>(&mut reader)?;
+    let chunk_reader = reader.take(tag_chunk.size);
+    stream::tag::decode_with_warnings(chunk_reader, warnings)
+}
+
+/// Attempts to load a ID3 tag from the given chunk stream via Tokio, like [`load_id3_chunk`].
+///
+/// The contents of the chunks preceding the ID3 chunk are read and discarded, so the reader does
+/// not need to be seekable.
+#[cfg(feature = "tokio")]
+pub async fn async_load_id3_chunk<F, R>(mut reader: R) -> crate::Result<Tag>
+where
+    F: ChunkFormat,
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let (root_chunk, root_len) = RootChunk::async_read::<F, _>(&mut reader).await?;
+    let eof = root_chunk.end()?;
+
+    // Track the stream position like the seeking implementation does.
+    let mut pos = 0;
+    let mut stream_pos = root_len;
+    while pos < eof {
+        let mut header = [0; MAX_HEADER_LEN];
+        let header = &mut header[..F::HEADER_LEN as usize];
+        reader.read_exact(header).await?;
+        let chunk = root_chunk.resolve(ChunkHeader::parse::<F>(header));
+
+        if ID3_TAGS.contains(&chunk.tag) {
+            return stream::tag::async_decode(reader.take(chunk.size)).await;
+        }
+
+        // Skip the chunk's contents, and padding if any.
+        let skip = chunk.size.saturating_add(chunk.size % 2);
+        tokio::io::copy(&mut (&mut reader).take(skip), &mut tokio::io::sink()).await?;
 
-    // Prevent reading past the root chunk, as there may be non-standard trailing data.
-    let eof = root_chunk
-        .size
-        .checked_sub(TAG_LEN) // We must disconsider the format tag that was already read.
-        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid root chunk size"))?;
+        stream_pos = stream_pos
+            .saturating_add(F::HEADER_LEN.into())
+            .saturating_add(skip);
+        pos = stream_pos;
+    }
 
-    let tag_chunk = ChunkHeader::find_id3::<F, _>(&mut reader, eof.into())?;
-    let chunk_reader = reader.take(tag_chunk.size.into());
-    stream::tag::decode(chunk_reader)
+    Err(Error::new(ErrorKind::NoTag, "No tag chunk found!"))
+}
+
+/// Returns the byte range of the data of the ID3 chunk in the given chunk stream.
+pub fn locate_id3_chunk<F, R>(mut reader: R) -> crate::Result<Range<u64>>
+where
+    F: ChunkFormat,
+    R: io::Read + io::Seek,
+{
+    let root_chunk = RootChunk::read::<F, _>(&mut reader)?;
+
+    let tag_chunk = root_chunk.find_id3::<F, _>(&mut reader)?;
+    let start = reader.stream_position()?;
+    Ok(start..start.saturating_add(tag_chunk.size))
 }
 
 /// Writes a tag to the given file. If the file contains no previous tag data, a new ID3
 /// chunk is created. Otherwise, the tag is overwritten in place.
+///
+/// The ID of the chunk is set according to `chunk_id`.
 pub fn write_id3_chunk_file<F: ChunkFormat>(
     mut file: impl StorageFile,
     tag: &Tag,
     version: Version,
-) -> crate::Result<()> {
+    chunk_id: ChunkId,
+) -> crate::Result<WriteOutcome> {
+    let chunk_tag = chunk_id.tag().map(|tag| ChunkTag(*tag));
+
     // Locate relevant chunks:
     let (mut root_chunk, id3_chunk_option) = locate_relevant_chunks::<F, _>(&mut file)?;
+    let old_id3_chunk_size = id3_chunk_option.as_ref().map(|chunk| chunk.size);
 
-    let root_chunk_pos = SeekFrom::Start(0);
     let id3_chunk_pos;
     let mut id3_chunk;
 
@@ -57,13 +136,13 @@ pub fn write_id3_chunk_file<F: ChunkFormat>(
         // If there is a ID3 chunk, use it. Otherwise, create one.
         id3_chunk = if let Some(chunk) = id3_chunk_option {
             let id3_tag_pos = file.stream_position()?;
-            let id3_tag_end_pos = id3_tag_pos
-                .checked_add(chunk.size.into())
-                .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid ID3 chunk size"))?;
+            let file_len = file.seek(SeekFrom::End(0))?;
+            file.seek(SeekFrom::Start(id3_tag_pos))?;
+            let id3_tag_end_pos = chunk.data_end(id3_tag_pos, file_len)?;
 
             id3_chunk_pos = SeekFrom::Start(
                 id3_tag_pos
-                    .checked_sub(CHUNK_HEADER_LEN.into())
+                    .checked_sub(F::HEADER_LEN.into())
                     .expect("failed to calculate id3 chunk position"),
             );
 
@@ -72,12 +151,16 @@ pub fn write_id3_chunk_file<F: ChunkFormat>(
 
             // As we'll overwrite the existing tag, we must subtract it's size and sum the
             // new size later.
-            root_chunk.size = root_chunk
+            root_chunk.header.size = root_chunk
+                .header
                 .size
-                .checked_sub(chunk.size)
+                .checked_sub(id3_tag_end_pos - id3_tag_pos)
                 .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid root chunk size"))?;
 
-            chunk
+            ChunkHeader {
+                tag: chunk_tag.unwrap_or(chunk.tag),
+                ..chunk
+            }
         } else {
             let pos = file.stream_position()?;
 
@@ -88,22 +171,17 @@ pub fn write_id3_chunk_file<F: ChunkFormat>(
 
             // Create a new empty chunk at the end of the file:
             let chunk = ChunkHeader {
-                tag: ID3_TAG,
+                tag: chunk_tag.unwrap_or(ID3_TAG),
                 size: 0,
             };
 
             chunk.write_to::<F, _>(&mut writer)?;
 
             // Update the riff chunk size:
-            root_chunk.size = root_chunk
-                .size
-                .checked_add(CHUNK_HEADER_LEN)
-                .ok_or_else(|| {
-                    Error::new(ErrorKind::InvalidInput, "root chunk max size reached")
-                })?;
+            root_chunk.grow::<F>(F::HEADER_LEN.into())?;
 
             // The AIFF header shouldn't be included in the chunk length
-            offset = CHUNK_HEADER_LEN;
+            offset = F::HEADER_LEN.into();
 
             chunk
         };
@@ -111,20 +189,19 @@ pub fn write_id3_chunk_file<F: ChunkFormat>(
         // Write the tag:
         tag.write_to(&mut writer, version)?;
 
-        id3_chunk.size = writer
-            .stream_position()?
-            .try_into()
-            .expect("ID3 chunk max size reached");
-        id3_chunk.size -= offset;
+        id3_chunk.size = writer.stream_position()? - offset;
 
         // Add padding if necessary.
         if id3_chunk.size % 2 == 1 {
             let padding = [0];
             writer.write_all(&padding)?;
-            id3_chunk.size = id3_chunk
-                .size
-                .checked_add(padding.len() as u32)
-                .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "ID3 chunk max size reached"))?;
+            id3_chunk.size += padding.len() as u64;
+        }
+        if id3_chunk.size > root_chunk.max_chunk_size::<F>() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "ID3 chunk max size reached",
+            ));
         }
 
         // We must flush manually to prevent silecing write errors.
@@ -136,35 +213,32 @@ pub fn write_id3_chunk_file<F: ChunkFormat>(
     file.seek(id3_chunk_pos)?;
     id3_chunk.write_to::<F, _>(&mut file)?;
 
-    root_chunk.size = root_chunk
-        .size
-        .checked_add(id3_chunk.size)
-        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "root chunk max size reached"))?;
+    root_chunk.grow::<F>(id3_chunk.size)?;
 
-    file.seek(root_chunk_pos)?;
-    root_chunk.write_to::<F, _>(file)?;
+    let (root_size_pos, root_size) = root_chunk.encode_size::<F>();
+    file.seek(SeekFrom::Start(root_size_pos))?;
+    file.write_all(&root_size)?;
 
-    Ok(())
+    Ok(WriteOutcome {
+        bytes_written: id3_chunk.size,
+        rewritten: old_id3_chunk_size != Some(id3_chunk.size),
+        padding: 0,
+        v1_touched: false,
+    })
 }
 
 /// Locates the root and ID3 chunks, returning their headers. The ID3 chunk may not be
-/// present. Returns a pair of (root chunk header, ID3 header).
-fn locate_relevant_chunks<F, R>(mut input: R) -> crate::Result<(ChunkHeader, Option<ChunkHeader>)>
+/// present. Returns a pair of (root chunk, ID3 header).
+fn locate_relevant_chunks<F, R>(mut input: R) -> crate::Result<(RootChunk, Option<ChunkHeader>)>
 where
     F: ChunkFormat,
     R: Read + Seek,
 {
     let mut reader = BufReader::new(&mut input);
 
-    let root_chunk = ChunkHeader::read_root_chunk_header::<F, _>(&mut reader)?;
-
-    // Prevent reading past the root chunk, as there may be non-standard trailing data.
-    let eof = root_chunk
-        .size
-        .checked_sub(TAG_LEN) // We must disconsider the WAVE tag that was already read.
-        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid root chunk size"))?;
+    let root_chunk = RootChunk::read::<F, _>(&mut reader)?;
 
-    let id3_chunk = match ChunkHeader::find_id3::<F, _>(&mut reader, eof.into()) {
+    let id3_chunk = match root_chunk.find_id3::<F, _>(&mut reader) {
         Ok(chunk) => Some(chunk),
         Err(Error {
             kind: ErrorKind::NoTag,
@@ -177,11 +251,556 @@ where
     // drop the BufReader, and then seek.
     let pos = reader.stream_position()?;
     drop(reader);
+    let file_len = input.seek(SeekFrom::End(0))?;
+    check_tag_region(pos, id3_chunk.as_ref(), file_len)?;
     input.seek(SeekFrom::Start(pos))?;
 
     Ok((root_chunk, id3_chunk))
 }
 
+/// Checks that the tag, which is written at `pos` over the data of `id3_chunk` if there is one,
+/// starts within the file. Corrupt sizes of the chunks before it would otherwise make the tag be
+/// written far past the end of the file.
+fn check_tag_region(pos: u64, id3_chunk: Option<&ChunkHeader>, file_len: u64) -> crate::Result<()> {
+    let end = match id3_chunk {
+        Some(chunk) => chunk.data_end(pos, file_len)?,
+        None => pos,
+    };
+    if end > file_len {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "chunk extends past the end of the file",
+        ));
+    }
+    Ok(())
+}
+
+/// Writes a tag to the given file via Tokio, like [`write_id3_chunk_file`].
+#[cfg(feature = "tokio")]
+pub async fn async_write_id3_chunk_file<F: ChunkFormat>(
+    mut file: impl AsyncStorageFile,
+    tag: &Tag,
+    version: Version,
+    chunk_id: ChunkId,
+) -> crate::Result<WriteOutcome> {
+    use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+    let (mut root_chunk, id3_chunk_option) =
+        async_locate_relevant_chunks::<F, _>(&mut file).await?;
+    let old_id3_chunk_size = id3_chunk_option.as_ref().map(|chunk| chunk.size);
+
+    // The tag is encoded up front, so the chunk can be written at once.
+    let mut data = tag.to_bytes(version)?;
+    // Add padding if necessary.
+    if data.len() % 2 == 1 {
+        data.push(0);
+    }
+    let id3_chunk = ChunkHeader {
+        tag: match (chunk_id.tag(), &id3_chunk_option) {
+            (Some(tag), _) => ChunkTag(*tag),
+            (None, Some(chunk)) => chunk.tag,
+            (None, None) => ID3_TAG,
+        },
+        size: data.len() as u64,
+    };
+    if id3_chunk.size > root_chunk.max_chunk_size::<F>() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "ID3 chunk max size reached",
+        ));
+    }
+    let mut id3_chunk_header = Vec::new();
+    id3_chunk.write_to::<F, _>(&mut id3_chunk_header)?;
+
+    // If there is a ID3 chunk, use it. Otherwise, create one.
+    let pos = file.stream_position().await?;
+    let (id3_chunk_pos, region) = if let Some(chunk) = id3_chunk_option {
+        let file_len = file.seek(SeekFrom::End(0)).await?;
+        file.seek(SeekFrom::Start(pos)).await?;
+        let end = chunk.data_end(pos, file_len)?;
+        root_chunk.header.size = root_chunk
+            .header
+            .size
+            .checked_sub(end - pos)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid root chunk size"))?;
+        let id3_chunk_pos = pos
+            .checked_sub(F::HEADER_LEN.into())
+            .expect("failed to calculate id3 chunk position");
+        (id3_chunk_pos, pos..end)
+    } else {
+        root_chunk.grow::<F>(F::HEADER_LEN.into())?;
+        data.splice(0..0, id3_chunk_header.iter().copied());
+        (pos, pos..pos)
+    };
+    AsyncPlainStorage::new(&mut file, region)
+        .write(&data)
+        .await?;
+
+    // Update chunk sizes in the file:
+
+    file.seek(SeekFrom::Start(id3_chunk_pos)).await?;
+    file.write_all(&id3_chunk_header).await?;
+
+    root_chunk.grow::<F>(id3_chunk.size)?;
+
+    let (root_size_pos, root_size) = root_chunk.encode_size::<F>();
+    file.seek(SeekFrom::Start(root_size_pos)).await?;
+    file.write_all(&root_size).await?;
+    file.flush().await?;
+
+    Ok(WriteOutcome {
+        bytes_written: id3_chunk.size,
+        rewritten: old_id3_chunk_size != Some(id3_chunk.size),
+        padding: 0,
+        v1_touched: false,
+    })
+}
+
+/// Locates the root and ID3 chunks via Tokio, like [`locate_relevant_chunks`].
+#[cfg(feature = "tokio")]
+async fn async_locate_relevant_chunks<F, R>(
+    mut input: R,
+) -> crate::Result<(RootChunk, Option<ChunkHeader>)>
+where
+    F: ChunkFormat,
+    R: tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin,
+{
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let (root_chunk, _) = RootChunk::async_read::<F, _>(&mut input).await?;
+    let eof = root_chunk.end()?;
+
+    let mut id3_chunk = None;
+    let mut pos = 0;
+    while pos < eof {
+        let mut header = [0; MAX_HEADER_LEN];
+        let header = &mut header[..F::HEADER_LEN as usize];
+        input.read_exact(header).await?;
+        let chunk = root_chunk.resolve(ChunkHeader::parse::<F>(header));
+
+        if ID3_TAGS.contains(&chunk.tag) {
+            id3_chunk = Some(chunk);
+            break;
+        }
+
+        pos = input.seek(SeekFrom::Current(chunk.skip()?)).await?;
+    }
+
+    let pos = input.stream_position().await?;
+    let file_len = input.seek(SeekFrom::End(0)).await?;
+    check_tag_region(pos, id3_chunk.as_ref(), file_len)?;
+    input.seek(SeekFrom::Start(pos)).await?;
+
+    Ok((root_chunk, id3_chunk))
+}
+
+/// Returns the data of the LIST chunk of the INFO form type in the given chunk stream, excluding
+/// the form type.
+pub fn load_info_chunk<F, R>(mut reader: R) -> crate::Result<Vec<u8>>
+where
+    F: ChunkFormat,
+    R: io::Read + io::Seek,
+{
+    let root_chunk = RootChunk::read::<F, _>(&mut reader)?;
+
+    let (_, chunk) = root_chunk
+        .find_list::<F, _>(&mut reader, INFO_TAG)?
+        .ok_or_else(|| Error::new(ErrorKind::NoTag, "No INFO chunk found!"))?;
+    let mut data = Vec::new();
+    reader
+        .take(chunk.size - u64::from(TAG_LEN))
+        .read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Replaces the LIST chunk of the INFO form type in the given file. A new chunk is appended if
+/// the file has none.
+///
+/// `update` receives the data of the existing chunk excluding the form type, which is empty if
+/// there is none, and returns the new data including the form type. The chunk is removed if the
+/// new data is empty.
+pub fn write_info_chunk_file<F: ChunkFormat>(
+    mut file: impl StorageFile,
+    update: impl FnOnce(&[u8]) -> Vec<u8>,
+) -> crate::Result<()> {
+    let mut data = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    let (mut root_chunk, info_chunk) = {
+        let mut reader = BufReader::new(&mut file);
+        let root_chunk = RootChunk::read::<F, _>(&mut reader)?;
+        let info_chunk = root_chunk.find_list::<F, _>(&mut reader, INFO_TAG)?;
+        if let Some((_, chunk)) = &info_chunk {
+            (&mut reader)
+                .take(chunk.size - u64::from(TAG_LEN))
+                .read_to_end(&mut data)?;
+        }
+        (root_chunk, info_chunk)
+    };
+    let file_len = file.seek(SeekFrom::End(0))?;
+    let region = root_chunk.list_region::<F>(info_chunk, file_len)?;
+    let chunk = root_chunk.replace_list::<F>(&region, &update(&data))?;
+
+    let mut storage = PlainStorage::new(&mut file, region);
+    let mut writer = storage.writer()?;
+    writer.write_all(&chunk)?;
+    writer.flush()?;
+    drop(writer);
+
+    let (root_size_pos, root_size) = root_chunk.encode_size::<F>();
+    file.seek(SeekFrom::Start(root_size_pos))?;
+    file.write_all(&root_size)?;
+    Ok(())
+}
+
+/// Replaces the LIST chunk of the INFO form type via Tokio, like [`write_info_chunk_file`].
+#[cfg(feature = "tokio")]
+pub async fn async_write_info_chunk_file<F: ChunkFormat>(
+    mut file: impl AsyncStorageFile,
+    update: impl FnOnce(&[u8]) -> Vec<u8>,
+) -> crate::Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+    file.seek(SeekFrom::Start(0)).await?;
+    let (mut root_chunk, _) = RootChunk::async_read::<F, _>(&mut file).await?;
+    let eof = root_chunk.end()?;
+
+    let mut data = Vec::new();
+    let mut info_chunk = None;
+    let mut pos = 0;
+    while pos < eof {
+        let chunk_pos = file.stream_position().await?;
+        let mut header = [0; MAX_HEADER_LEN];
+        let header = &mut header[..F::HEADER_LEN as usize];
+        file.read_exact(header).await?;
+        let chunk = root_chunk.resolve(ChunkHeader::parse::<F>(header));
+
+        if chunk.tag == LIST_TAG && chunk.size >= u64::from(TAG_LEN) {
+            let mut form = [0; TAG_LEN as usize];
+            file.read_exact(&mut form).await?;
+            if ChunkTag(form) == INFO_TAG {
+                (&mut file)
+                    .take(chunk.size - u64::from(TAG_LEN))
+                    .read_to_end(&mut data)
+                    .await?;
+                info_chunk = Some((chunk_pos, chunk));
+                break;
+            }
+            file.seek(SeekFrom::Current(-i64::from(TAG_LEN))).await?;
+        }
+
+        pos = file.seek(SeekFrom::Current(chunk.skip()?)).await?;
+    }
+
+    let file_len = file.seek(SeekFrom::End(0)).await?;
+    let region = root_chunk.list_region::<F>(info_chunk, file_len)?;
+    let chunk = root_chunk.replace_list::<F>(&region, &update(&data))?;
+    AsyncPlainStorage::new(&mut file, region)
+        .write(&chunk)
+        .await?;
+
+    let (root_size_pos, root_size) = root_chunk.encode_size::<F>();
+    file.seek(SeekFrom::Start(root_size_pos)).await?;
+    file.write_all(&root_size).await?;
+    file.flush().await?;
+    Ok(())
+}
+
+/// The root chunk of a stream. The 64 bits variants of a format, like RF64, store the sizes that
+/// do not fit in a chunk header in a ds64 chunk that directly follows the root chunk header.
+#[derive(Debug)]
+struct RootChunk {
+    header: ChunkHeader,
+    ds64: Option<Ds64>,
+}
+
+impl RootChunk {
+    /// Reads the root chunk header and the ds64 chunk, if the format variant has one.
+    fn read<F, R>(mut reader: R) -> crate::Result<Self>
+    where
+        F: ChunkFormat,
+        R: io::Read,
+    {
+        let header = ChunkHeader::read_root_chunk_header::<F, _>(&mut reader)?;
+        if !F::DS64_ROOT_TAGS.contains(&header.tag) {
+            return Ok(Self { header, ds64: None });
+        }
+
+        let ds64_header = ChunkHeader::read::<F, _>(&mut reader)?;
+        let mut data = Vec::new();
+        (&mut reader)
+            .take(ds64_header.size.saturating_add(ds64_header.size % 2))
+            .read_to_end(&mut data)?;
+        Self::with_ds64::<F>(header, ds64_header, &data)
+    }
+
+    /// Reads the root chunk via Tokio, like [`RootChunk::read`]. Returns the root chunk and the
+    /// number of bytes that were read.
+    #[cfg(feature = "tokio")]
+    async fn async_read<F, R>(mut reader: R) -> crate::Result<(Self, u64)>
+    where
+        F: ChunkFormat,
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        use tokio::io::AsyncReadExt;
+
+        let mut buffer = [0; MAX_ROOT_HEADER_LEN];
+        let buffer = &mut buffer[..F::ROOT_HEADER_LEN as usize];
+        reader.read_exact(buffer).await?;
+        let header = ChunkHeader::parse_root_chunk_header::<F>(buffer)?;
+        if !F::DS64_ROOT_TAGS.contains(&header.tag) {
+            return Ok((Self { header, ds64: None }, F::ROOT_HEADER_LEN.into()));
+        }
+
+        let mut buffer = [0; MAX_HEADER_LEN];
+        let buffer = &mut buffer[..F::HEADER_LEN as usize];
+        reader.read_exact(buffer).await?;
+        let ds64_header = ChunkHeader::parse::<F>(buffer);
+        let mut data = Vec::new();
+        (&mut reader)
+            .take(ds64_header.size.saturating_add(ds64_header.size % 2))
+            .read_to_end(&mut data)
+            .await?;
+        let len = u64::from(F::ROOT_HEADER_LEN + F::HEADER_LEN) + data.len() as u64;
+        Ok((Self::with_ds64::<F>(header, ds64_header, &data)?, len))
+    }
+
+    fn with_ds64<F: ChunkFormat>(
+        mut header: ChunkHeader,
+        ds64_header: ChunkHeader,
+        data: &[u8],
+    ) -> crate::Result<Self> {
+        if ds64_header.tag != DS64_TAG {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "the ds64 chunk is missing",
+            ));
+        }
+        let ds64 = Ds64::parse::<F>(data)?;
+        if header.size == DS64_SIZE {
+            header.size = ds64.root_size;
+        }
+        Ok(Self {
+            header,
+            ds64: Some(ds64),
+        })
+    }
+
+    /// Returns the stream position at which the search for chunks ends. This prevents reading
+    /// past the root chunk, as there may be non-standard trailing data.
+    fn end(&self) -> crate::Result<u64> {
+        self.header
+            .size
+            .checked_sub(TAG_LEN.into()) // We must disconsider the format tag that was already read.
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid root chunk size"))
+    }
+
+    /// Replaces the size of a chunk with the size from the ds64 chunk, if it has one.
+    fn resolve(&self, mut chunk: ChunkHeader) -> ChunkHeader {
+        if let Some(ds64) = &self.ds64 {
+            if chunk.size == DS64_SIZE {
+                if let Some(size) = ds64.size_of(&chunk.tag) {
+                    chunk.size = size;
+                }
+            }
+        }
+        chunk
+    }
+
+    /// Finds the ID3 chunk in the chunks that follow the root chunk header. The reader must be
+    /// positioned right after the root chunk header, or the ds64 chunk if there is one.
+    fn find_id3<F, R>(&self, reader: R) -> crate::Result<ChunkHeader>
+    where
+        F: ChunkFormat,
+        R: io::Read + io::Seek,
+    {
+        ChunkHeader::find::<F, _>(ID3_TAGS, reader, self.end()?, |chunk| self.resolve(chunk))?
+            .ok_or_else(|| Error::new(ErrorKind::NoTag, "No tag chunk found!"))
+    }
+
+    /// Finds the LIST chunk of the specified form type in the chunks that follow the root chunk
+    /// header. Returns the stream position of the header of the chunk and the header itself,
+    /// leaving the reader positioned right after the form type.
+    fn find_list<F, R>(
+        &self,
+        mut reader: R,
+        form: ChunkTag,
+    ) -> crate::Result<Option<(u64, ChunkHeader)>>
+    where
+        F: ChunkFormat,
+        R: io::Read + io::Seek,
+    {
+        let end = self.end()?;
+        let mut pos = 0;
+
+        while pos < end {
+            let chunk_pos = reader.stream_position()?;
+            let chunk = self.resolve(ChunkHeader::read::<F, _>(&mut reader)?);
+
+            if chunk.tag == LIST_TAG && chunk.size >= u64::from(TAG_LEN) {
+                let mut list_form = [0; TAG_LEN as usize];
+                reader.read_exact(&mut list_form)?;
+                if ChunkTag(list_form) == form {
+                    return Ok(Some((chunk_pos, chunk)));
+                }
+                reader.seek(SeekFrom::Current(-i64::from(TAG_LEN)))?;
+            }
+
+            pos = reader.seek(SeekFrom::Current(chunk.skip()?))?;
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the region of a LIST chunk found by [`RootChunk::find_list`] including its header
+    /// and padding, or an empty region at the end of the root chunk if there is none.
+    fn list_region<F: ChunkFormat>(
+        &self,
+        list_chunk: Option<(u64, ChunkHeader)>,
+        file_len: u64,
+    ) -> crate::Result<Range<u64>> {
+        match list_chunk {
+            Some((pos, chunk)) => {
+                let start = pos
+                    .checked_add(F::HEADER_LEN.into())
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid chunk size"))?;
+                Ok(pos..chunk.data_end(start, file_len)?)
+            }
+            None => {
+                let end = self
+                    .header
+                    .size
+                    .saturating_add(F::HEADER_LEN.into())
+                    .min(file_len);
+                Ok(end..end)
+            }
+        }
+    }
+
+    /// Encodes a LIST chunk holding `data` to replace the chunk in `region`, and updates the size
+    /// of the root chunk accordingly. Empty data encodes to no chunk at all.
+    fn replace_list<F: ChunkFormat>(
+        &mut self,
+        region: &Range<u64>,
+        data: &[u8],
+    ) -> crate::Result<Vec<u8>> {
+        let mut chunk = Vec::new();
+        if !data.is_empty() {
+            let header = ChunkHeader {
+                tag: LIST_TAG,
+                size: data.len() as u64,
+            };
+            if header.size > self.max_chunk_size::<F>() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "LIST chunk max size reached",
+                ));
+            }
+            header.write_to::<F, _>(&mut chunk)?;
+            chunk.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                chunk.push(0);
+            }
+        }
+
+        self.header.size = self
+            .header
+            .size
+            .checked_sub(region.end - region.start)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid root chunk size"))?;
+        self.grow::<F>(chunk.len() as u64)?;
+        Ok(chunk)
+    }
+
+    /// Returns the largest size of a chunk other than the root chunk.
+    fn max_chunk_size<F: ChunkFormat>(&self) -> u64 {
+        match self.ds64 {
+            // The largest value marks a size that is stored in the ds64 chunk.
+            Some(_) => F::MAX_SIZE - 1,
+            None => F::MAX_SIZE,
+        }
+    }
+
+    /// Grows the root chunk by `size` bytes.
+    fn grow<F: ChunkFormat>(&mut self, size: u64) -> crate::Result<()> {
+        let max_size = match self.ds64 {
+            Some(_) => u64::MAX,
+            None => F::MAX_SIZE,
+        };
+        self.header.size = self
+            .header
+            .size
+            .checked_add(size)
+            .filter(|size| *size <= max_size)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "root chunk max size reached"))?;
+        Ok(())
+    }
+
+    /// Encodes the size of the root chunk, returning the position in the stream at which it is
+    /// stored together with the encoded bytes.
+    fn encode_size<F: ChunkFormat>(&self) -> (u64, Vec<u8>) {
+        match self.ds64 {
+            Some(_) => {
+                // The ds64 chunk starts with the size of the root chunk.
+                let mut buf = vec![0; 8];
+                F::Endianness::write_u64(&mut buf, self.header.size);
+                (u64::from(F::ROOT_HEADER_LEN + F::HEADER_LEN), buf)
+            }
+            None => {
+                let mut buf = Vec::new();
+                self.header
+                    .write_to::<F, _>(&mut buf)
+                    .expect("writing to a Vec never fails");
+                (0, buf)
+            }
+        }
+    }
+}
+
+/// The contents of a ds64 chunk.
+#[derive(Debug)]
+struct Ds64 {
+    root_size: u64,
+    data_size: u64,
+    table: Vec<(ChunkTag, u64)>,
+}
+
+impl Ds64 {
+    fn parse<F: ChunkFormat>(data: &[u8]) -> crate::Result<Self> {
+        let invalid = || Error::new(ErrorKind::InvalidInput, "invalid ds64 chunk");
+        if data.len() < 28 {
+            return Err(invalid());
+        }
+        let table_len = F::Endianness::read_u32(&data[24..28]) as usize;
+        let table = data[28..]
+            .chunks_exact(12)
+            .take(table_len)
+            .map(|entry| {
+                let tag = entry[0..4].try_into().expect("slice with incorrect length");
+                (tag, F::Endianness::read_u64(&entry[4..12]))
+            })
+            .collect::<Vec<_>>();
+        if table.len() != table_len {
+            return Err(invalid());
+        }
+        Ok(Self {
+            root_size: F::Endianness::read_u64(&data[0..8]),
+            data_size: F::Endianness::read_u64(&data[8..16]),
+            table,
+        })
+    }
+
+    /// Returns the size of the chunk with the specified tag.
+    fn size_of(&self, tag: &ChunkTag) -> Option<u64> {
+        if *tag == DATA_TAG {
+            return Some(self.data_size);
+        }
+        self.table
+            .iter()
+            .find(|(entry, _)| entry == tag)
+            .map(|(_, size)| *size)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq)]
 pub struct ChunkTag(pub [u8; TAG_LEN as usize]);
 
@@ -205,6 +824,17 @@ pub trait ChunkFormat {
     type Endianness: ByteOrder;
     const ROOT_TAG: ChunkTag;
     const ROOT_FORMAT: Option<ChunkTag>;
+    /// The number of bytes in which the size of a chunk is stored.
+    const SIZE_LEN: u32 = 4;
+
+    /// The size of a chunk header.
+    const HEADER_LEN: u32 = TAG_LEN + Self::SIZE_LEN;
+    /// The size of the root chunk header, which includes the format tag.
+    const ROOT_HEADER_LEN: u32 = Self::HEADER_LEN + TAG_LEN;
+    /// The largest chunk size that can be stored.
+    const MAX_SIZE: u64 = u64::MAX >> (64 - 8 * Self::SIZE_LEN);
+    /// The root tags of the format variants that store 64 bits sizes in a ds64 chunk.
+    const DS64_ROOT_TAGS: &'static [ChunkTag] = &[];
 }
 
 #[derive(Debug)]
@@ -216,6 +846,10 @@ impl ChunkFormat for AiffFormat {
     const ROOT_TAG: ChunkTag = ChunkTag(*b"FORM");
     // AIFF may have many formats, beign AIFF and AIFC the most common. Technically, it
     // can be anything, so we won't check those.
+    //
+    // AIFC files start with a FVER chunk that holds the version of the format. As the ID3 chunk
+    // is either overwritten in place or appended after the last chunk, the FVER chunk and the
+    // order of the other chunks are left untouched.
     const ROOT_FORMAT: Option<ChunkTag> = None;
 }
 
@@ -227,12 +861,25 @@ impl ChunkFormat for WavFormat {
 
     const ROOT_TAG: ChunkTag = ChunkTag(*b"RIFF");
     const ROOT_FORMAT: Option<ChunkTag> = Some(ChunkTag(*b"WAVE"));
+    const DS64_ROOT_TAGS: &'static [ChunkTag] = &[ChunkTag(*b"RF64"), ChunkTag(*b"BW64")];
+}
+
+/// DSDIFF is built up like AIFF, but stores the sizes of chunks in 64 bits.
+#[derive(Debug)]
+pub struct DsdiffFormat;
+
+impl ChunkFormat for DsdiffFormat {
+    type Endianness = BigEndian;
+
+    const ROOT_TAG: ChunkTag = ChunkTag(*b"FRM8");
+    const ROOT_FORMAT: Option<ChunkTag> = Some(ChunkTag(*b"DSD "));
+    const SIZE_LEN: u32 = 8;
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 struct ChunkHeader {
     tag: ChunkTag,
-    size: u32,
+    size: u64,
 }
 
 impl ChunkHeader {
@@ -241,33 +888,39 @@ impl ChunkHeader {
     /// | Field   | Size | Type            |
     /// |---------+------+-----------------|
     /// | tag     |    4 | ChunkTag        |
-    /// | size    |    4 | 32 bits integer |
+    /// | size    | 4, 8 | integer         |
     /// | format  |    4 | ChunkTag        |
     pub fn read_root_chunk_header<F, R>(mut reader: R) -> crate::Result<Self>
     where
         F: ChunkFormat,
         R: io::Read,
     {
-        let invalid_header_error = Error::new(ErrorKind::InvalidInput, "invalid chunk header");
+        let mut buffer = [0; MAX_ROOT_HEADER_LEN];
+        let buffer = &mut buffer[..F::ROOT_HEADER_LEN as usize];
 
-        const BUFFER_SIZE: usize = (CHUNK_HEADER_LEN + TAG_LEN) as usize;
+        // Use a single read call to improve performance on unbuffered readers.
+        reader.read_exact(buffer)?;
 
-        let mut buffer = [0; BUFFER_SIZE];
+        Self::parse_root_chunk_header::<F>(buffer)
+    }
 
-        // Use a single read call to improve performance on unbuffered readers.
-        reader.read_exact(&mut buffer)?;
+    /// Parses a root chunk header that was read by the caller, see
+    /// [`ChunkHeader::read_root_chunk_header`].
+    fn parse_root_chunk_header<F: ChunkFormat>(buffer: &[u8]) -> crate::Result<Self> {
+        let invalid_header_error = Error::new(ErrorKind::InvalidInput, "invalid chunk header");
 
         let tag = buffer[0..4]
             .try_into()
             .expect("slice with incorrect length");
 
-        let size = F::Endianness::read_u32(&buffer[4..8]);
+        let header_len = F::HEADER_LEN as usize;
+        let size = F::Endianness::read_uint(&buffer[4..header_len], F::SIZE_LEN as usize);
 
-        if tag != F::ROOT_TAG {
+        if tag != F::ROOT_TAG && !F::DS64_ROOT_TAGS.contains(&tag) {
             return Err(invalid_header_error);
         }
 
-        let chunk_format: ChunkTag = buffer[8..12]
+        let chunk_format: ChunkTag = buffer[header_len..header_len + 4]
             .try_into()
             .expect("slice with incorrect length");
 
@@ -285,56 +938,72 @@ impl ChunkHeader {
     /// | Field | Size | Value           |
     /// |-------+------+-----------------|
     /// | tag   |    4 | chunk type      |
-    /// | size  |    4 | 32 bits integer |
+    /// | size  | 4, 8 | integer         |
     pub fn read<F, R>(mut reader: R) -> io::Result<Self>
     where
         F: ChunkFormat,
         R: io::Read,
     {
-        const BUFFER_SIZE: usize = CHUNK_HEADER_LEN as usize;
-
-        let mut header = [0; BUFFER_SIZE];
+        let mut header = [0; MAX_HEADER_LEN];
+        let header = &mut header[..F::HEADER_LEN as usize];
 
         // Use a single read call to improve performance on unbuffered readers.
-        reader.read_exact(&mut header)?;
+        reader.read_exact(header)?;
 
+        Ok(Self::parse::<F>(header))
+    }
+
+    /// Parses a chunk header that was read by the caller, see [`ChunkHeader::read`].
+    fn parse<F: ChunkFormat>(header: &[u8]) -> Self {
         let tag = header[0..4]
             .try_into()
             .expect("slice with incorrect length");
 
-        let size = F::Endianness::read_u32(&header[4..8]);
+        let size =
+            F::Endianness::read_uint(&header[4..F::HEADER_LEN as usize], F::SIZE_LEN as usize);
 
-        Ok(Self { tag, size })
+        Self { tag, size }
     }
 
-    /// Finds an ID3 chunk in a flat sequence of chunks. This should be called after reading
-    /// the root chunk.
-    ///
-    /// # Arguments
-    ///
-    /// * `reader` - The input stream. The reader must be positioned right after the root
-    ///              chunk header.
-    /// * `end` - The stream position where the chunk sequence ends. This is used to
-    ///           prevent searching past the end.
-    pub fn find_id3<F, R>(reader: R, end: u64) -> crate::Result<Self>
-    where
-        F: ChunkFormat,
-        R: io::Read + io::Seek,
-    {
-        Self::find::<F, _>(&ID3_TAG, reader, end)?
-            .ok_or_else(|| Error::new(ErrorKind::NoTag, "No tag chunk found!"))
+    /// Returns the distance to the next chunk from the end of the header: the size of the
+    /// contents plus padding, if any.
+    fn skip(&self) -> crate::Result<i64> {
+        self.size
+            .checked_add(self.size % 2)
+            .and_then(|skip| i64::try_from(skip).ok())
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid chunk size"))
+    }
+
+    /// Returns the end of the data of the chunk of which the data starts at `start`. This includes
+    /// the padding byte of an odd-sized chunk if the file has one, so it is overwritten along with
+    /// the data instead of being left behind and misaligning the chunks that follow.
+    fn data_end(&self, start: u64, file_len: u64) -> crate::Result<u64> {
+        let end = start
+            .checked_add(self.size)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Invalid chunk size"))?;
+        if self.size % 2 == 1 && end < file_len {
+            Ok(end + 1)
+        } else {
+            Ok(end)
+        }
     }
 
     /// Finds a chunk in a flat sequence of chunks. This won't search chunks recursively.
     ///
     /// # Arguments
     ///
-    /// * `tag` - The chunk tag to search for.
+    /// * `tags` - The chunk tags to search for.
     /// * `reader` - The input stream. The reader must be positioned at the start of a
-    ///              sequence of chunks.
+    ///   sequence of chunks.
     /// * `end` - The stream position where the chunk sequence ends. This is used to
-    ///           prevent searching past the end.
-    fn find<F, R>(tag: &ChunkTag, mut reader: R, end: u64) -> crate::Result<Option<Self>>
+    ///   prevent searching past the end.
+    /// * `resolve` - Resolves the actual size of a chunk, see [`RootChunk::resolve`].
+    fn find<F, R>(
+        tags: &[ChunkTag],
+        mut reader: R,
+        end: u64,
+        resolve: impl Fn(Self) -> Self,
+    ) -> crate::Result<Option<Self>>
     where
         F: ChunkFormat,
         R: io::Read + io::Seek,
@@ -342,16 +1011,13 @@ impl ChunkHeader {
         let mut pos = 0;
 
         while pos < end {
-            let chunk = Self::read::<F, _>(&mut reader)?;
+            let chunk = resolve(Self::read::<F, _>(&mut reader)?);
 
-            if &chunk.tag == tag {
+            if tags.contains(&chunk.tag) {
                 return Ok(Some(chunk));
             }
 
-            // Skip the chunk's contents, and padding if any.
-            let skip = chunk.size.saturating_add(chunk.size % 2);
-
-            pos = reader.seek(SeekFrom::Current(skip as i64))?;
+            pos = reader.seek(SeekFrom::Current(chunk.skip()?))?;
         }
 
         Ok(None)
@@ -362,22 +1028,21 @@ impl ChunkHeader {
     /// | Field | Size | Value                         |
     /// |-------+------+-------------------------------|
     /// | tag   |    4 | chunk type                    |
-    /// | size  |    4 | 32 bits little endian integer |
+    /// | size  | 4, 8 | integer                       |
     pub fn write_to<F, W>(&self, mut writer: W) -> io::Result<()>
     where
         F: ChunkFormat,
         W: io::Write,
     {
-        const BUFFER_SIZE: usize = CHUNK_HEADER_LEN as usize;
-
-        let mut buffer = [0; BUFFER_SIZE];
+        let mut buffer = [0; MAX_HEADER_LEN];
+        let buffer = &mut buffer[..F::HEADER_LEN as usize];
 
         buffer[0..4].copy_from_slice(&self.tag.0);
 
-        F::Endianness::write_u32(&mut buffer[4..8], self.size);
+        F::Endianness::write_uint(&mut buffer[4..], self.size, F::SIZE_LEN as usize);
 
         // Use a single write call to improve performance on unbuffered writers.
-        writer.write_all(&buffer)
+        writer.write_all(buffer)
     }
 }
 
@@ -430,7 +1095,12 @@ mod tests {
 
         // Find the TEST chunk
         let length = cursor.get_ref().len() as u64;
-        let result = ChunkHeader::find::<MockFormat, _>(&ChunkTag(*b"TEST"), &mut cursor, length);
+        let result = ChunkHeader::find::<MockFormat, _>(
+            &[ChunkTag(*b"TEST")],
+            &mut cursor,
+            length,
+            |chunk| chunk,
+        );
 
         // Verify the result
         assert!(result.is_ok());
@@ -1,7 +1,9 @@
+use crate::stream::encoding::Encoding;
 use crate::tag::Tag;
 use std::error;
 use std::fmt;
 use std::io;
+use std::ops::Range;
 use std::string;
 
 /// Type alias for the result of tag operations.
@@ -10,16 +12,17 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// Takes a tag result and maps any partial tag to Ok. An Ok result is left untouched. An Err
 /// without partial tag is returned as the initial error.
 ///
+/// When the content of a frame could not be decoded, decoding continues with the next frame. The
+/// partial tag then holds all frames that could be decoded, plus a stub for each invalid frame
+/// that holds its raw content as [`Content::Unknown`](crate::Content::Unknown).
+///
 /// # Example
 /// ```
 /// use id3::{Tag, Error, ErrorKind, partial_tag_ok};
 ///
-/// let rs = Err(Error{
-///     kind: ErrorKind::Parsing,
-///     description: "frame 12 could not be decoded".to_string(),
-///     partial_tag: Some(Tag::new()),
-/// });
-/// assert!(partial_tag_ok(rs).is_ok());
+/// let mut err = Error::new(ErrorKind::Parsing, "frame 12 could not be decoded");
+/// err.partial_tag = Some(Tag::new());
+/// assert!(partial_tag_ok(Err(err)).is_ok());
 /// ```
 pub fn partial_tag_ok(rs: Result<Tag>) -> Result<Tag> {
     match rs {
@@ -38,19 +41,12 @@ pub fn partial_tag_ok(rs: Result<Tag>) -> Result<Tag> {
 /// ```
 /// use id3::{Tag, Error, ErrorKind, no_tag_ok};
 ///
-/// let rs = Err(Error{
-///     kind: ErrorKind::NoTag,
-///     description: "the file contains no ID3 tag".to_string(),
-///     partial_tag: None,
-/// });
+/// let rs = Err(Error::new(ErrorKind::NoTag, "the file contains no ID3 tag"));
 /// assert!(matches!(no_tag_ok(rs), Ok(None)));
 ///
-/// let rs = Err(Error{
-///     kind: ErrorKind::Parsing,
-///     description: "frame 12 could not be decoded".to_string(),
-///     partial_tag: Some(Tag::new()),
-/// });
-/// assert!(no_tag_ok(rs).is_err());
+/// let mut err = Error::new(ErrorKind::Parsing, "frame 12 could not be decoded");
+/// err.partial_tag = Some(Tag::new());
+/// assert!(no_tag_ok(Err(err)).is_err());
 /// ```
 pub fn no_tag_ok(rs: Result<Tag>) -> Result<Option<Tag>> {
     match rs {
@@ -64,12 +60,16 @@ pub fn no_tag_ok(rs: Result<Tag>) -> Result<Option<Tag>> {
 }
 
 /// Kinds of errors that may occur while performing metadata operations.
+///
+/// New kinds may be added in the future, so matches on this enum need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// An error kind indicating that an IO error has occurred. Contains the original io::Error.
     Io(io::Error),
-    /// An error kind indicating that a string decoding error has occurred. Contains the invalid
-    /// bytes.
+    /// An error kind indicating that a string decoding error has occurred. Contains the bytes of
+    /// the string that could not be decoded. The declared encoding and the position of the
+    /// invalid bytes are returned by [`Error::string_encoding`] and [`Error::string_range`].
     StringDecoding(Vec<u8>),
     /// An error kind indicating that the reader does not contain an ID3 tag.
     NoTag,
@@ -79,9 +79,25 @@ pub enum ErrorKind {
     InvalidInput,
     /// An error kind indicating that a feature is not supported.
     UnsupportedFeature,
+    /// An error kind indicating that the tag has a version that is not supported. The version is
+    /// ID3v2.`major`.`minor`.
+    UnsupportedVersion {
+        /// The major version, e.g. 4 for ID3v2.4.
+        major: u8,
+        /// The revision of the major version.
+        minor: u8,
+    },
+    /// An error kind indicating that decoding was stopped because a limit set on the
+    /// [`Decoder`](crate::Decoder) was exceeded.
+    LimitExceeded,
 }
 
 /// A structure able to represent any error that may occur while performing metadata operations.
+///
+/// Errors are created using [`Error::new`]. Where the error occurred is available through
+/// [`Error::frame_id`] and [`Error::offset`], and the lower level error that caused it, if any, is
+/// returned by [`source`](error::Error::source).
+#[non_exhaustive]
 pub struct Error {
     /// The kind of error.
     pub kind: ErrorKind,
@@ -89,6 +105,18 @@ pub struct Error {
     pub description: String,
     /// If any, the part of the tag that was able to be decoded before the error occurred.
     pub partial_tag: Option<Tag>,
+    /// Where and why the error occurred. Boxed, as most errors carry none of it.
+    context: Option<Box<Context>>,
+}
+
+/// The details of an [`Error`] that are only known for some errors.
+#[derive(Debug, Default)]
+struct Context {
+    frame_id: Option<String>,
+    offset: Option<u64>,
+    cause: Option<Box<dyn error::Error + Send + Sync + 'static>>,
+    /// The declared encoding and the position of the invalid bytes of a string decoding error.
+    string: Option<(Encoding, Range<usize>)>,
 }
 
 impl Error {
@@ -98,9 +126,59 @@ impl Error {
             kind,
             description: description.into(),
             partial_tag: None,
+            context: None,
         }
     }
 
+    /// Returns the ID of the frame that was being decoded when the error occurred.
+    pub fn frame_id(&self) -> Option<&str> {
+        self.context.as_ref()?.frame_id.as_deref()
+    }
+
+    /// Returns the byte offset from the start of the tag of the frame that was being decoded when
+    /// the error occurred. The offset is taken after removing any tag-wide unsynchronisation.
+    pub fn offset(&self) -> Option<u64> {
+        self.context.as_ref()?.offset
+    }
+
+    /// Returns the encoding that the string was declared to be in, if this is a string decoding
+    /// error.
+    pub fn string_encoding(&self) -> Option<Encoding> {
+        self.context
+            .as_ref()?
+            .string
+            .as_ref()
+            .map(|(encoding, _)| *encoding)
+    }
+
+    /// Returns the position of the invalid bytes, if this is a string decoding error. When
+    /// decoding a frame, the range is relative to the start of the frame content. Otherwise, it is
+    /// relative to the start of the string.
+    pub fn string_range(&self) -> Option<Range<usize>> {
+        self.context
+            .as_ref()?
+            .string
+            .as_ref()
+            .map(|(_, range)| range.clone())
+    }
+
+    /// Creates a string decoding error for the string `bytes` that was declared to be in
+    /// `encoding`, of which the bytes at `range` are invalid.
+    pub(crate) fn string_decoding(
+        bytes: &[u8],
+        encoding: Encoding,
+        range: Range<usize>,
+        description: impl Into<String>,
+    ) -> Error {
+        let mut err = Error::new(ErrorKind::StringDecoding(bytes.to_vec()), description);
+        err.context_mut().string = Some((encoding, range));
+        err
+    }
+
+    fn context_mut(&mut self) -> &mut Context {
+        self.context.get_or_insert_with(Default::default)
+    }
+
     /// Creates a new `Error` using the error kind and description.
     pub(crate) fn with_tag(self, tag: Tag) -> Error {
         Error {
@@ -108,52 +186,167 @@ impl Error {
             ..self
         }
     }
+
+    /// Sets the lower level error that caused this error.
+    pub(crate) fn with_cause(mut self, cause: impl error::Error + Send + Sync + 'static) -> Error {
+        self.context_mut().cause = Some(Box::new(cause));
+        self
+    }
+
+    /// Moves the range of a string decoding error by the specified number of bytes.
+    pub(crate) fn offset_string_range(mut self, by: usize) -> Error {
+        if let Some((_, range)) = self.context.as_mut().and_then(|c| c.string.as_mut()) {
+            *range = range.start + by..range.end + by;
+        }
+        self
+    }
+
+    /// Replaces the encoding of a string decoding error.
+    pub(crate) fn with_string_encoding(mut self, encoding: Encoding) -> Error {
+        if let Some((declared, _)) = self.context.as_mut().and_then(|c| c.string.as_mut()) {
+            *declared = encoding;
+        }
+        self
+    }
+
+    /// Attaches the ID of the frame that was being decoded, unless one is already set.
+    pub(crate) fn with_frame_id(mut self, frame_id: &str) -> Error {
+        let context = self.context_mut();
+        if context.frame_id.is_none() {
+            context.frame_id = Some(frame_id.to_string());
+        }
+        self
+    }
+
+    /// Attaches the offset of the frame that was being decoded, unless one is already set.
+    pub(crate) fn with_offset(mut self, offset: u64) -> Error {
+        let context = self.context_mut();
+        context.offset = context.offset.or(Some(offset));
+        self
+    }
+
+    /// Returns a numeric code identifying the kind of this error.
+    ///
+    /// Codes are stable across releases and are never reused, which makes them suitable for use
+    /// over FFI boundaries and in logs. See [`code_name`](Error::code_name) for a string variant.
+    ///
+    /// * 1 `"io"`: [`ErrorKind::Io`]
+    /// * 2 `"string_decoding"`: [`ErrorKind::StringDecoding`]
+    /// * 3 `"no_tag"`: [`ErrorKind::NoTag`]
+    /// * 4 `"parsing"`: [`ErrorKind::Parsing`]
+    /// * 5 `"invalid_input"`: [`ErrorKind::InvalidInput`]
+    /// * 6 `"unsupported_feature"`: [`ErrorKind::UnsupportedFeature`]
+    /// * 7 `"unsupported_version"`: [`ErrorKind::UnsupportedVersion`]
+    /// * 8 `"limit_exceeded"`: [`ErrorKind::LimitExceeded`]
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Error, ErrorKind};
+    ///
+    /// let err = Error::new(ErrorKind::NoTag, "no tag");
+    /// assert_eq!(err.code(), 3);
+    /// assert_eq!(err.code_name(), "no_tag");
+    /// ```
+    pub fn code(&self) -> u32 {
+        match self.kind {
+            ErrorKind::Io(_) => 1,
+            ErrorKind::StringDecoding(_) => 2,
+            ErrorKind::NoTag => 3,
+            ErrorKind::Parsing => 4,
+            ErrorKind::InvalidInput => 5,
+            ErrorKind::UnsupportedFeature => 6,
+            ErrorKind::UnsupportedVersion { .. } => 7,
+            ErrorKind::LimitExceeded => 8,
+        }
+    }
+
+    /// Returns a stable string identifying the kind of this error. Refer to
+    /// [`code`](Error::code) for the list of names.
+    pub fn code_name(&self) -> &'static str {
+        match self.kind {
+            ErrorKind::Io(_) => "io",
+            ErrorKind::StringDecoding(_) => "string_decoding",
+            ErrorKind::NoTag => "no_tag",
+            ErrorKind::Parsing => "parsing",
+            ErrorKind::InvalidInput => "invalid_input",
+            ErrorKind::UnsupportedFeature => "unsupported_feature",
+            ErrorKind::UnsupportedVersion { .. } => "unsupported_version",
+            ErrorKind::LimitExceeded => "limit_exceeded",
+        }
+    }
+
+    fn fmt_context(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let (Some(encoding), Some(range)) = (self.string_encoding(), self.string_range()) {
+            write!(
+                f,
+                " ({:?} at bytes {}..{})",
+                encoding, range.start, range.end
+            )?;
+        }
+        match (self.frame_id(), self.offset()) {
+            (Some(id), Some(offset)) => write!(f, " (frame {} at offset {})", id, offset),
+            (Some(id), None) => write!(f, " (frame {})", id),
+            (None, Some(offset)) => write!(f, " (at offset {})", offset),
+            (None, None) => Ok(()),
+        }
+    }
 }
 
 impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self.kind {
             ErrorKind::Io(ref err) => Some(err),
-            _ => None,
+            _ => self
+                .context
+                .as_ref()?
+                .cause
+                .as_deref()
+                .map(|cause| cause as &(dyn error::Error + 'static)),
         }
     }
 }
 
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Error {
-        Error {
-            kind: ErrorKind::Io(err),
-            description: "".to_string(),
-            partial_tag: None,
-        }
+        Error::new(ErrorKind::Io(err), "")
     }
 }
 
 impl From<string::FromUtf8Error> for Error {
     fn from(err: string::FromUtf8Error) -> Error {
-        Error {
-            kind: ErrorKind::StringDecoding(err.into_bytes()),
-            description: "data is not valid utf-8".to_string(),
-            partial_tag: None,
-        }
+        let utf8_error = err.utf8_error();
+        let start = utf8_error.valid_up_to();
+        let end = utf8_error
+            .error_len()
+            .map(|len| start + len)
+            .unwrap_or_else(|| err.as_bytes().len());
+        Error::string_decoding(
+            err.as_bytes(),
+            Encoding::UTF8,
+            start..end,
+            "data is not valid utf-8",
+        )
+        .with_cause(utf8_error)
     }
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.description.is_empty() {
-            true => write!(f, "{:?}", self.kind),
-            false => write!(f, "{:?}: {}", self.kind, self.description),
+            true => write!(f, "{:?}", self.kind)?,
+            false => write!(f, "{:?}: {}", self.kind, self.description)?,
         }
+        self.fmt_context(f)
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.description.is_empty() {
-            true => write!(f, "{}", self.kind),
-            false => write!(f, "{}: {}", self.kind, self.description),
+            true => write!(f, "{}", self.kind)?,
+            false => write!(f, "{}: {}", self.kind, self.description)?,
         }
+        self.fmt_context(f)
     }
 }
 
@@ -166,6 +359,53 @@ impl fmt::Display for ErrorKind {
             ErrorKind::Parsing => write!(f, "Parsing"),
             ErrorKind::InvalidInput => write!(f, "InvalidInput"),
             ErrorKind::UnsupportedFeature => write!(f, "UnsupportedFeature"),
+            ErrorKind::UnsupportedVersion { major, minor } => {
+                write!(f, "UnsupportedVersion(ID3v2.{}.{})", major, minor)
+            }
+            ErrorKind::LimitExceeded => write!(f, "LimitExceeded"),
+        }
+    }
+}
+
+/// A recoverable issue that was encountered while decoding a tag.
+///
+/// Warnings do not prevent a tag from being decoded, but may indicate that the tag does not
+/// fully conform to the specification or that some of its data is not interpreted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Warning {
+    /// A human readable string describing the issue.
+    pub description: String,
+    /// The ID of the frame the issue applies to, if any.
+    pub frame_id: Option<String>,
+    /// The byte offset from the start of the tag of the frame the issue applies to, if any.
+    pub offset: Option<u64>,
+}
+
+impl Warning {
+    pub(crate) fn new(description: impl Into<String>) -> Warning {
+        Warning {
+            description: description.into(),
+            frame_id: None,
+            offset: None,
+        }
+    }
+
+    pub(crate) fn with_frame_id(self, frame_id: &str) -> Warning {
+        Warning {
+            frame_id: Some(frame_id.to_string()),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description)?;
+        match (&self.frame_id, self.offset) {
+            (Some(id), Some(offset)) => write!(f, " (frame {} at offset {})", id, offset),
+            (Some(id), None) => write!(f, " (frame {})", id),
+            (None, Some(offset)) => write!(f, " (at offset {})", offset),
+            (None, None) => Ok(()),
         }
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::{Error, ErrorKind};
 use crate::frame::content_cmp::ContentCmp::{Comparable, Incomparable, Same};
 use crate::frame::Frame;
 use crate::stream::encoding::Encoding;
@@ -7,6 +8,15 @@ use std::borrow::Cow;
 use std::fmt;
 use std::io;
 
+/// The owner identifier of the private frames written by Native Instruments Traktor.
+const TRAKTOR_OWNER: &str = "TRAKTOR4";
+
+/// The maximum nesting depth of Traktor chunks, which bounds the recursion while parsing.
+const TRAKTOR_MAX_DEPTH: usize = 32;
+
+/// The description of the comment frame that iTunes uses for its Sound Check values.
+pub(crate) const ITUNES_SOUND_CHECK: &str = "iTunNORM";
+
 /// The decoded contents of a [`Frame`].
 ///
 /// # Compatibility
@@ -57,6 +67,46 @@ pub enum Content {
     UniqueFileIdentifier(UniqueFileIdentifier),
     /// A value containing the parsed contents of an involved people list frame (IPLS/TIPL/TMCL)
     InvolvedPeopleList(InvolvedPeopleList),
+    /// A value containing the parsed contents of a relative volume adjustment frame (RVA2).
+    RelativeVolumeAdjustment(RelativeVolumeAdjustment),
+    /// A value containing the parsed contents of an event timing codes frame (ETCO).
+    EventTimingCodes(EventTimingCodes),
+    /// A value containing the parsed contents of an ownership frame (OWNE).
+    Ownership(Ownership),
+    /// A value containing the parsed contents of a commercial frame (COMR).
+    Commercial(Commercial),
+    /// A value containing the parsed contents of a terms of use frame (USER).
+    TermsOfUse(TermsOfUse),
+    /// A value containing the parsed contents of a play counter frame (PCNT).
+    PlayCounter(u64),
+    /// A value containing the parsed contents of a music CD identifier frame (MCDI).
+    MusicCdIdentifier(MusicCdIdentifier),
+    /// A value containing the parsed contents of a linked information frame (LINK).
+    LinkedInformation(LinkedInformation),
+    /// A value containing the parsed contents of a synchronised tempo codes frame (SYTC).
+    SynchronisedTempoCodes(SynchronisedTempoCodes),
+    /// A value containing the parsed contents of an audio seek point index frame (ASPI).
+    AudioSeekPointIndex(AudioSeekPointIndex),
+    /// A value containing the parsed contents of a group identification registration frame (GRID).
+    GroupRegistration(GroupRegistration),
+    /// A value containing the parsed contents of a signature frame (SIGN).
+    Signature(Signature),
+    /// A value containing the parsed contents of an ID3v2.3 relative volume adjustment frame (RVAD).
+    LegacyVolumeAdjustment(LegacyVolumeAdjustment),
+    /// A value containing the parsed contents of an ID3v2.3 equalisation frame (EQUA).
+    LegacyEqualisation(LegacyEqualisation),
+    /// A value containing the parsed contents of an equalisation frame (EQU2).
+    Equalisation(Equalisation),
+    /// A value containing the iTunes podcast flag (PCST).
+    PodcastFlag(u32),
+    /// A value containing the parsed contents of an ID3v2.2 encrypted meta frame (CRM).
+    EncryptedMeta(EncryptedMeta),
+    /// A value containing the parsed contents of an encryption method registration frame (ENCR).
+    EncryptionMethod(EncryptionMethod),
+    /// A value containing the still encrypted content of a frame.
+    EncryptedFrame(EncryptedFrame),
+    /// The placeholder of a frame of which the content was skipped while decoding.
+    Skipped(Skipped),
     /// A value containing the bytes of a currently unknown frame type.
     ///
     /// Users that wish to write custom decoders must use [`Content::to_unknown`] instead of
@@ -117,6 +167,53 @@ impl Content {
                 unique_file_identifier.owner_identifier.as_bytes(),
             )]),
             Self::InvolvedPeopleList(_) => Same,
+            Self::RelativeVolumeAdjustment(relative_volume_adjustment) => {
+                Comparable(vec![Cow::Borrowed(
+                    relative_volume_adjustment.identification.as_bytes(),
+                )])
+            }
+            Self::EventTimingCodes(_) => Same,
+            Self::Ownership(_) => Same,
+            Self::Commercial(commercial) => Comparable(vec![
+                Cow::Borrowed(commercial.price.as_bytes()),
+                Cow::Borrowed(commercial.valid_until.as_bytes()),
+                Cow::Borrowed(commercial.seller_name.as_bytes()),
+                Cow::Borrowed(commercial.description.as_bytes()),
+            ]),
+            Self::TermsOfUse(terms_of_use) => {
+                Comparable(vec![Cow::Borrowed(terms_of_use.lang.as_bytes())])
+            }
+            Self::PlayCounter(_) => Same,
+            Self::MusicCdIdentifier(_) => Same,
+            Self::LinkedInformation(linked_information) => Comparable(vec![
+                Cow::Borrowed(linked_information.frame_identifier.as_bytes()),
+                Cow::Borrowed(linked_information.url.as_bytes()),
+                Cow::Borrowed(linked_information.additional_data.as_slice()),
+            ]),
+            Self::SynchronisedTempoCodes(_) => Same,
+            Self::AudioSeekPointIndex(_) => Same,
+            Self::GroupRegistration(group_registration) => {
+                Comparable(vec![Cow::Owned(vec![group_registration.group_symbol])])
+            }
+            Self::Signature(signature) => Comparable(vec![
+                Cow::Owned(vec![signature.group_symbol]),
+                Cow::Borrowed(signature.signature.as_slice()),
+            ]),
+            Self::LegacyVolumeAdjustment(_) => Same,
+            Self::LegacyEqualisation(_) => Same,
+            Self::Equalisation(equalisation) => {
+                Comparable(vec![Cow::Borrowed(equalisation.identification.as_bytes())])
+            }
+            Self::PodcastFlag(_) => Same,
+            Self::EncryptedMeta(encrypted_meta) => Comparable(vec![
+                Cow::Borrowed(encrypted_meta.owner_identifier.as_bytes()),
+                Cow::Borrowed(encrypted_meta.content_description.as_bytes()),
+            ]),
+            Self::EncryptionMethod(encryption_method) => {
+                Comparable(vec![Cow::Owned(vec![encryption_method.method_symbol])])
+            }
+            Self::EncryptedFrame(_) => Incomparable,
+            Self::Skipped(_) => Incomparable,
             Self::Unknown(_) => Incomparable,
         }
     }
@@ -273,6 +370,173 @@ impl Content {
         }
     }
 
+    /// Returns the `RelativeVolumeAdjustment` or None if the value is not
+    /// `RelativeVolumeAdjustment`.
+    pub fn relative_volume_adjustment(&self) -> Option<&RelativeVolumeAdjustment> {
+        match self {
+            Content::RelativeVolumeAdjustment(relative_volume_adjustment) => {
+                Some(relative_volume_adjustment)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the `EventTimingCodes` or None if the value is not `EventTimingCodes`.
+    pub fn event_timing_codes(&self) -> Option<&EventTimingCodes> {
+        match self {
+            Content::EventTimingCodes(event_timing_codes) => Some(event_timing_codes),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Ownership` or None if the value is not `Ownership`.
+    pub fn ownership(&self) -> Option<&Ownership> {
+        match self {
+            Content::Ownership(ownership) => Some(ownership),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Commercial` or None if the value is not `Commercial`.
+    pub fn commercial(&self) -> Option<&Commercial> {
+        match self {
+            Content::Commercial(commercial) => Some(commercial),
+            _ => None,
+        }
+    }
+
+    /// Returns the `TermsOfUse` or None if the value is not `TermsOfUse`.
+    pub fn terms_of_use(&self) -> Option<&TermsOfUse> {
+        match self {
+            Content::TermsOfUse(terms_of_use) => Some(terms_of_use),
+            _ => None,
+        }
+    }
+
+    /// Returns the play count or None if the value is not `PlayCounter`.
+    pub fn play_counter(&self) -> Option<u64> {
+        match self {
+            Content::PlayCounter(play_counter) => Some(*play_counter),
+            _ => None,
+        }
+    }
+
+    /// Returns the `MusicCdIdentifier` or None if the value is not `MusicCdIdentifier`.
+    pub fn music_cd_identifier(&self) -> Option<&MusicCdIdentifier> {
+        match self {
+            Content::MusicCdIdentifier(music_cd_identifier) => Some(music_cd_identifier),
+            _ => None,
+        }
+    }
+
+    /// Returns the `LinkedInformation` or None if the value is not `LinkedInformation`.
+    pub fn linked_information(&self) -> Option<&LinkedInformation> {
+        match self {
+            Content::LinkedInformation(linked_information) => Some(linked_information),
+            _ => None,
+        }
+    }
+
+    /// Returns the `SynchronisedTempoCodes` or None if the value is not `SynchronisedTempoCodes`.
+    pub fn synchronised_tempo_codes(&self) -> Option<&SynchronisedTempoCodes> {
+        match self {
+            Content::SynchronisedTempoCodes(synchronised_tempo_codes) => {
+                Some(synchronised_tempo_codes)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the `AudioSeekPointIndex` or None if the value is not `AudioSeekPointIndex`.
+    pub fn audio_seek_point_index(&self) -> Option<&AudioSeekPointIndex> {
+        match self {
+            Content::AudioSeekPointIndex(audio_seek_point_index) => Some(audio_seek_point_index),
+            _ => None,
+        }
+    }
+
+    /// Returns the `GroupRegistration` or None if the value is not `GroupRegistration`.
+    pub fn group_registration(&self) -> Option<&GroupRegistration> {
+        match self {
+            Content::GroupRegistration(group_registration) => Some(group_registration),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Signature` or None if the value is not `Signature`.
+    pub fn signature(&self) -> Option<&Signature> {
+        match self {
+            Content::Signature(signature) => Some(signature),
+            _ => None,
+        }
+    }
+
+    /// Returns the `LegacyVolumeAdjustment` or None if the value is not `LegacyVolumeAdjustment`.
+    pub fn legacy_volume_adjustment(&self) -> Option<&LegacyVolumeAdjustment> {
+        match self {
+            Content::LegacyVolumeAdjustment(legacy_volume_adjustment) => {
+                Some(legacy_volume_adjustment)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the `LegacyEqualisation` or None if the value is not `LegacyEqualisation`.
+    pub fn legacy_equalisation(&self) -> Option<&LegacyEqualisation> {
+        match self {
+            Content::LegacyEqualisation(legacy_equalisation) => Some(legacy_equalisation),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Equalisation` or None if the value is not `Equalisation`.
+    pub fn equalisation(&self) -> Option<&Equalisation> {
+        match self {
+            Content::Equalisation(equalisation) => Some(equalisation),
+            _ => None,
+        }
+    }
+
+    /// Returns the podcast flag or None if the value is not `PodcastFlag`.
+    pub fn podcast_flag(&self) -> Option<u32> {
+        match self {
+            Content::PodcastFlag(podcast_flag) => Some(*podcast_flag),
+            _ => None,
+        }
+    }
+
+    /// Returns the `EncryptedMeta` or None if the value is not `EncryptedMeta`.
+    pub fn encrypted_meta(&self) -> Option<&EncryptedMeta> {
+        match self {
+            Content::EncryptedMeta(encrypted_meta) => Some(encrypted_meta),
+            _ => None,
+        }
+    }
+
+    /// Returns the `EncryptionMethod` or None if the value is not `EncryptionMethod`.
+    pub fn encryption_method(&self) -> Option<&EncryptionMethod> {
+        match self {
+            Content::EncryptionMethod(encryption_method) => Some(encryption_method),
+            _ => None,
+        }
+    }
+
+    /// Returns the `EncryptedFrame` or None if the value is not `EncryptedFrame`.
+    pub fn encrypted_frame(&self) -> Option<&EncryptedFrame> {
+        match self {
+            Content::EncryptedFrame(encrypted_frame) => Some(encrypted_frame),
+            _ => None,
+        }
+    }
+
+    /// Returns the `Skipped` or None if the value is not `Skipped`.
+    pub fn skipped(&self) -> Option<&Skipped> {
+        match self {
+            Content::Skipped(skipped) => Some(skipped),
+            _ => None,
+        }
+    }
+
     /// Returns the `Unknown` or None if the value is not `Unknown`.
     #[deprecated(note = "Use to_unknown")]
     pub fn unknown(&self) -> Option<&[u8]> {
@@ -322,6 +586,36 @@ impl fmt::Display for Content {
             Content::InvolvedPeopleList(involved_people_list) => {
                 write!(f, "{}", involved_people_list)
             }
+            Content::RelativeVolumeAdjustment(relative_volume_adjustment) => {
+                write!(f, "{}", relative_volume_adjustment)
+            }
+            Content::EventTimingCodes(event_timing_codes) => write!(f, "{}", event_timing_codes),
+            Content::Ownership(ownership) => write!(f, "{}", ownership),
+            Content::Commercial(commercial) => write!(f, "{}", commercial),
+            Content::TermsOfUse(terms_of_use) => write!(f, "{}", terms_of_use),
+            Content::PlayCounter(play_counter) => write!(f, "{}", play_counter),
+            Content::MusicCdIdentifier(music_cd_identifier) => write!(f, "{}", music_cd_identifier),
+            Content::LinkedInformation(linked_information) => write!(f, "{}", linked_information),
+            Content::SynchronisedTempoCodes(synchronised_tempo_codes) => {
+                write!(f, "{}", synchronised_tempo_codes)
+            }
+            Content::AudioSeekPointIndex(audio_seek_point_index) => {
+                write!(f, "{}", audio_seek_point_index)
+            }
+            Content::GroupRegistration(group_registration) => write!(f, "{}", group_registration),
+            Content::Signature(signature) => write!(f, "{}", signature),
+            Content::LegacyVolumeAdjustment(legacy_volume_adjustment) => {
+                write!(f, "{}", legacy_volume_adjustment)
+            }
+            Content::LegacyEqualisation(legacy_equalisation) => {
+                write!(f, "{}", legacy_equalisation)
+            }
+            Content::Equalisation(equalisation) => write!(f, "{}", equalisation),
+            Content::PodcastFlag(podcast_flag) => write!(f, "{}", podcast_flag),
+            Content::EncryptedMeta(encrypted_meta) => write!(f, "{}", encrypted_meta),
+            Content::EncryptionMethod(encryption_method) => write!(f, "{}", encryption_method),
+            Content::EncryptedFrame(encrypted_frame) => write!(f, "{}", encrypted_frame),
+            Content::Skipped(skipped) => write!(f, "{}", skipped),
             Content::Unknown(unknown) => write!(f, "{}", unknown),
         }
     }
@@ -430,6 +724,66 @@ impl fmt::Display for Comment {
     }
 }
 
+impl Comment {
+    /// Creates an iTunes Sound Check comment (description "iTunNORM") from a linear gain and an
+    /// optional peak amplitude relative to full scale.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::Comment;
+    ///
+    /// let comment = Comment::from_sound_check(0.5, Some(1.0));
+    /// assert_eq!(comment.description, "iTunNORM");
+    /// assert_eq!(comment.text, " 00000FA0 00000FA0 00002710 00002710 00000000 00000000 00008000 00008000 00000000 00000000");
+    /// assert_eq!(comment.sound_check(), Some(0.5));
+    /// ```
+    pub fn from_sound_check(gain: f64, peak: Option<f64>) -> Self {
+        let level = |reference: f64| {
+            (reference / (gain * gain))
+                .round()
+                .clamp(0.0, f64::from(u32::MAX)) as u32
+        };
+        let peak = peak.map_or(0, |peak| {
+            (peak * 32768.0).round().clamp(0.0, f64::from(u32::MAX)) as u32
+        });
+        let values = [
+            level(1000.0),
+            level(1000.0),
+            level(2500.0),
+            level(2500.0),
+            0,
+            0,
+            peak,
+            peak,
+            0,
+            0,
+        ];
+        Comment {
+            lang: "eng".to_string(),
+            description: ITUNES_SOUND_CHECK.to_string(),
+            text: values.iter().map(|v| format!(" {:08X}", v)).collect(),
+        }
+    }
+
+    /// Returns the linear gain of an iTunes Sound Check comment (description "iTunNORM").
+    ///
+    /// The gain of the loudest channel is returned. Returns `None` if this comment is not a Sound
+    /// Check comment or if it can not be parsed.
+    pub fn sound_check(&self) -> Option<f64> {
+        if self.description != ITUNES_SOUND_CHECK {
+            return None;
+        }
+        let values = self
+            .text
+            .split_whitespace()
+            .take(2)
+            .map(|v| u32::from_str_radix(v, 16).ok())
+            .collect::<Option<Vec<u32>>>()?;
+        let level = values.into_iter().max().filter(|v| *v != 0)?;
+        Some((1000.0 / f64::from(level)).sqrt())
+    }
+}
+
 impl From<Comment> for Frame {
     fn from(c: Comment) -> Self {
         Self::with_content("COMM", Content::Comment(c))
@@ -460,6 +814,111 @@ impl From<Popularimeter> for Frame {
     }
 }
 
+impl Popularimeter {
+    /// Returns the rating as a number of stars according to the specified convention.
+    ///
+    /// Returns `None` if the rating is unknown.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{Popularimeter, RatingConvention};
+    ///
+    /// let popm = Popularimeter {
+    ///     user: "MusicBee".to_string(),
+    ///     rating: 186,
+    ///     counter: 0,
+    /// };
+    /// assert_eq!(popm.stars(RatingConvention::MusicBee), Some(3.5));
+    /// assert_eq!(popm.stars(RatingConvention::WindowsMediaPlayer), Some(4.0));
+    /// ```
+    pub fn stars(&self, convention: RatingConvention) -> Option<f32> {
+        if self.rating == 0 {
+            return None;
+        }
+        let ratings = convention.ratings();
+        let step = 5.0 / ratings.len() as f32;
+        // Pick the closest rating, preferring the higher one on a tie.
+        let index = ratings
+            .iter()
+            .enumerate()
+            .min_by_key(|(i, &r)| ((r as i16 - self.rating as i16).abs(), usize::MAX - i))
+            .map(|(i, _)| i)?;
+        Some((index + 1) as f32 * step)
+    }
+
+    /// Sets the rating to the specified number of stars according to the specified convention.
+    ///
+    /// The number of stars is clamped to the range 0 to 5 and rounded to the nearest step that the
+    /// convention supports. Zero stars clears the rating.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{Popularimeter, RatingConvention};
+    ///
+    /// let mut popm = Popularimeter {
+    ///     user: "traktor@native-instruments.de".to_string(),
+    ///     rating: 0,
+    ///     counter: 0,
+    /// };
+    /// popm.set_stars(RatingConvention::Traktor, 3.0);
+    /// assert_eq!(popm.rating, 153);
+    /// popm.set_stars(RatingConvention::Traktor, 3.5);
+    /// assert_eq!(popm.rating, 204);
+    /// ```
+    pub fn set_stars(&mut self, convention: RatingConvention, stars: f32) {
+        let ratings = convention.ratings();
+        let steps = ratings.len() as f32;
+        let index = (stars.clamp(0.0, 5.0) * steps / 5.0).round() as usize;
+        self.rating = match index {
+            0 => 0,
+            i => ratings[i - 1],
+        };
+    }
+}
+
+/// The way an application maps star ratings onto the rating byte of a popularimeter frame
+/// (POPM).
+///
+/// The ID3 standard does not specify how a rating should be displayed, so each application has
+/// its own mapping and writes its frames using its own user identifier.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum RatingConvention {
+    /// Windows Media Player, which uses 1, 64, 128, 196 and 255 for one to five stars.
+    WindowsMediaPlayer,
+    /// MusicBee, which supports half stars.
+    MusicBee,
+    /// Kodi, which reads half star ratings using the same values as MusicBee.
+    Kodi,
+    /// Traktor, which uses multiples of 51 for one to five stars.
+    Traktor,
+}
+
+impl RatingConvention {
+    /// Returns the user identifier with which the application writes its POPM frame.
+    ///
+    /// Kodi does not write POPM frames and reads ratings regardless of the user, for which an
+    /// empty identifier is returned.
+    pub fn user(self) -> &'static str {
+        match self {
+            RatingConvention::WindowsMediaPlayer => "Windows Media Player 9 Series",
+            RatingConvention::MusicBee => "MusicBee",
+            RatingConvention::Kodi => "",
+            RatingConvention::Traktor => "traktor@native-instruments.de",
+        }
+    }
+
+    /// The rating bytes for each supported step, starting at the lowest non-zero rating.
+    fn ratings(self) -> &'static [u8] {
+        match self {
+            RatingConvention::WindowsMediaPlayer => &[1, 64, 128, 196, 255],
+            RatingConvention::MusicBee | RatingConvention::Kodi => {
+                &[13, 1, 54, 64, 118, 128, 186, 196, 242, 255]
+            }
+            RatingConvention::Traktor => &[51, 102, 153, 204, 255],
+        }
+    }
+}
+
 /// The parsed contents of an unsynchronized lyrics frame.
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[allow(missing_docs)]
@@ -479,6 +938,54 @@ impl fmt::Display for Lyrics {
     }
 }
 
+impl Lyrics {
+    /// Creates unsynchronised lyrics from lyrics in the LRC format by stripping all timestamps
+    /// and tags.
+    ///
+    /// This is useful as a fallback for applications that do not support synchronised lyrics.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::Lyrics;
+    ///
+    /// let lyrics = Lyrics::from_lrc("[ar:Artist]\n[00:12.00]Song line one\n[00:22.00]Song line two");
+    /// assert_eq!(lyrics.text, "Song line one\nSong line two");
+    /// assert_eq!(lyrics.lang, "XXX");
+    /// ```
+    pub fn from_lrc(lrc: &str) -> Lyrics {
+        match SynchronisedLyrics::from_lrc(lrc) {
+            Ok(sylt) => Lyrics::from(sylt),
+            Err(_) => Lyrics {
+                lang: "XXX".to_string(),
+                description: String::new(),
+                text: lrc
+                    .lines()
+                    .filter(|line| !line.trim_start().starts_with('['))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            },
+        }
+    }
+}
+
+impl From<SynchronisedLyrics> for Lyrics {
+    /// Joins the text of the synchronised lyrics, dropping the timestamps. Entries that share the
+    /// same text at different times, such as a repeated chorus, are kept.
+    fn from(sylt: SynchronisedLyrics) -> Self {
+        let text = sylt
+            .content
+            .into_iter()
+            .map(|(_, text)| text)
+            .collect::<Vec<_>>()
+            .join("\n");
+        Lyrics {
+            lang: sylt.lang,
+            description: sylt.description,
+            text,
+        }
+    }
+}
+
 impl From<Lyrics> for Frame {
     fn from(c: Lyrics) -> Self {
         Self::with_content("USLT", Content::Lyrics(c))
@@ -546,6 +1053,193 @@ impl SynchronisedLyrics {
 
         Ok(())
     }
+
+    /// Parses lyrics in the LRC format.
+    ///
+    /// Each line may be prefixed by one or more `[mm:ss.xx]` timestamps, for which an entry is
+    /// created. Fractions of a second may have up to three digits and are rounded to whole
+    /// milliseconds. Lines without a timestamp continue the text of the previous entry. The
+    /// `offset` tag is applied to all timestamps, the `la` tag sets the language and other tags
+    /// are ignored. Timestamps that overflow, either by themselves or once the offset is applied,
+    /// are skipped. Timestamps that end up before the start of the song are moved to the start.
+    ///
+    /// Returns an error if the text contains no timestamped lines, or if all of their timestamps
+    /// are skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::SynchronisedLyrics;
+    ///
+    /// let lrc = "[ti:Song]\n[00:12.50]Song line one\n[00:22.456][01:02.00]Song line two";
+    /// let sylt = SynchronisedLyrics::from_lrc(lrc).unwrap();
+    /// assert_eq!(sylt.content, vec![
+    ///     (12500, "Song line one".to_string()),
+    ///     (22456, "Song line two".to_string()),
+    ///     (62000, "Song line two".to_string()),
+    /// ]);
+    /// ```
+    pub fn from_lrc(lrc: &str) -> crate::Result<SynchronisedLyrics> {
+        let mut lang = "XXX".to_string();
+        let mut offset = 0i64;
+        let mut content: Vec<(i64, String)> = Vec::new();
+        let mut last_line = None;
+
+        for line in lrc.lines() {
+            let line = line.trim_end_matches('\r');
+            let mut rest = line.trim_start();
+            let mut timestamps = Vec::new();
+            while let Some(tag) = rest.strip_prefix('[') {
+                let Some(end) = tag.find(']') else {
+                    break;
+                };
+                let (tag, next) = (&tag[..end], &tag[end + 1..]);
+                if let Some(ms) = parse_lrc_timestamp(tag) {
+                    timestamps.push(ms);
+                } else if timestamps.is_empty() {
+                    match tag.split_once(':') {
+                        Some(("offset", v)) => offset = v.trim().parse().unwrap_or(offset),
+                        Some(("la", v)) if v.trim().len() == 3 => lang = v.trim().to_string(),
+                        _ => {}
+                    }
+                    last_line = None;
+                    break;
+                } else {
+                    break;
+                }
+                rest = next;
+            }
+
+            if !timestamps.is_empty() {
+                let first = content.len();
+                for ms in timestamps {
+                    content.push((ms, rest.to_string()));
+                }
+                last_line = Some(first..content.len());
+            } else if let Some(range) = last_line.clone() {
+                if !line.is_empty() {
+                    for (_, text) in &mut content[range] {
+                        text.push('\n');
+                        text.push_str(line);
+                    }
+                }
+            }
+        }
+
+        // A positive offset makes the lyrics appear earlier.
+        let mut content: Vec<(u32, String)> = content
+            .into_iter()
+            .filter_map(|(ms, text)| {
+                let ms = ms.checked_sub(offset)?.max(0);
+                Some((u32::try_from(ms).ok()?, text))
+            })
+            .collect();
+        if content.is_empty() {
+            return Err(Error::new(
+                ErrorKind::Parsing,
+                "LRC lyrics contain no timestamped lines",
+            ));
+        }
+        content.sort_by_key(|(ms, _)| *ms);
+
+        Ok(SynchronisedLyrics {
+            lang,
+            timestamp_format: TimestampFormat::Ms,
+            content_type: SynchronisedLyricsType::Lyrics,
+            description: String::new(),
+            content,
+        })
+    }
+
+    /// Formats the lyrics in the LRC format.
+    ///
+    /// Timestamps are written as `[mm:ss.xx]`, rounded to the nearest hundredth of a second.
+    /// Entries spanning multiple lines are written as a timestamped line followed by untimed
+    /// lines, which [`SynchronisedLyrics::from_lrc`] reads back into a single entry.
+    ///
+    /// Returns an error if the timestamps are not in milliseconds, or if an entry can not be read
+    /// back as the same text: LRC has no escaping, so a line following the first line of an entry
+    /// must not start with `[` and the first line must not start with a timestamp.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{SynchronisedLyrics, SynchronisedLyricsType, TimestampFormat};
+    ///
+    /// let sylt = SynchronisedLyrics {
+    ///     lang: "eng".to_string(),
+    ///     timestamp_format: TimestampFormat::Ms,
+    ///     content_type: SynchronisedLyricsType::Lyrics,
+    ///     description: String::new(),
+    ///     content: vec![
+    ///         (12345, "Song line one".to_string()),
+    ///         (62000, "Song line two\nand three".to_string()),
+    ///     ],
+    /// };
+    /// assert_eq!(
+    ///     sylt.to_lrc().unwrap(),
+    ///     "[la:eng]\n[00:12.35]Song line one\n[01:02.00]Song line two\nand three\n",
+    /// );
+    /// ```
+    pub fn to_lrc(&self) -> crate::Result<String> {
+        if self.timestamp_format != TimestampFormat::Ms {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "LRC timestamps can only be created from milliseconds",
+            ));
+        }
+        let mut lrc = String::new();
+        if self.lang.len() == 3 && self.lang != "XXX" {
+            lrc.push_str(&format!("[la:{}]\n", self.lang));
+        }
+        for (ms, text) in &self.content {
+            let mut lines = text.split('\n');
+            let first = lines.next().unwrap_or_default().trim_start();
+            let starts_with_timestamp = first
+                .strip_prefix('[')
+                .and_then(|tag| tag.split_once(']'))
+                .is_some_and(|(tag, _)| parse_lrc_timestamp(tag).is_some());
+            if starts_with_timestamp || lines.any(|line| line.trim_start().starts_with('[')) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "LRC lyrics lines can not start with a tag",
+                ));
+            }
+            let centis = (u64::from(*ms) + 5) / 10;
+            lrc.push_str(&format!(
+                "[{:02}:{:02}.{:02}]{}\n",
+                centis / 6000,
+                centis / 100 % 60,
+                centis % 100,
+                text
+            ));
+        }
+        Ok(lrc)
+    }
+}
+
+/// Parses an LRC timestamp of the form `mm:ss`, `mm:ss.x`, `mm:ss.xx` or `mm:ss.xxx` into
+/// milliseconds.
+fn parse_lrc_timestamp(s: &str) -> Option<i64> {
+    let (mins, secs) = s.split_once(':')?;
+    let (secs, frac) = match secs.split_once('.') {
+        Some((secs, frac)) => (secs, frac),
+        None => (secs, ""),
+    };
+    let all_digits = |v: &str| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit());
+    if !all_digits(mins) || !all_digits(secs) || (!frac.is_empty() && !all_digits(frac)) {
+        return None;
+    }
+    if frac.len() > 3 {
+        return None;
+    }
+    let mins: i64 = mins.parse().ok()?;
+    let secs: i64 = secs.parse().ok()?;
+    let frac_ms = match frac.len() {
+        0 => 0,
+        n => frac.parse::<i64>().ok()? * 10i64.pow(3 - n as u32),
+    };
+    mins.checked_mul(60_000)?
+        .checked_add(secs.checked_mul(1000)?)?
+        .checked_add(frac_ms)
 }
 
 impl From<SynchronisedLyrics> for Frame {
@@ -837,113 +1531,1555 @@ impl From<Private> for Frame {
     }
 }
 
-/// The parsed contents of a UFID frame.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct UniqueFileIdentifier {
-    /// Owner identifier
-    pub owner_identifier: String,
-    /// Identifier
-    pub identifier: Vec<u8>,
-}
-
-impl fmt::Display for UniqueFileIdentifier {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}: {}",
-            self.owner_identifier,
-            String::from_utf8(self.identifier.clone())
-                .unwrap_or(format!("{:x?}", &self.identifier))
-        )
+impl Private {
+    /// Returns true if this frame was written by Native Instruments Traktor.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::Private;
+    ///
+    /// let private = Private {
+    ///     owner_identifier: "TRAKTOR4".to_string(),
+    ///     private_data: Vec::new(),
+    /// };
+    /// assert!(private.is_traktor());
+    /// ```
+    pub fn is_traktor(&self) -> bool {
+        self.owner_identifier == TRAKTOR_OWNER
     }
-}
 
-impl From<UniqueFileIdentifier> for Frame {
-    fn from(c: UniqueFileIdentifier) -> Self {
-        Self::with_content("UFID", Content::UniqueFileIdentifier(c))
+    /// Parses the data of a Traktor private frame into a tree of chunks.
+    ///
+    /// Traktor stores its analysis, such as cue points and the beat grid, as nested chunks. The
+    /// returned root chunk can be walked using [`TraktorChunk::child`] to get to the payloads of
+    /// interest.
+    ///
+    /// Returns an error if the frame is not owned by Traktor, if the data is malformed or if
+    /// chunks are nested more than 32 levels deep.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{Private, TraktorChunk};
+    ///
+    /// let root = TraktorChunk {
+    ///     id: "TRMD".to_string(),
+    ///     data: Vec::new(),
+    ///     children: vec![TraktorChunk {
+    ///         id: "CUEP".to_string(),
+    ///         data: vec![1, 2, 3],
+    ///         children: Vec::new(),
+    ///     }],
+    /// };
+    /// let private = Private {
+    ///     owner_identifier: "TRAKTOR4".to_string(),
+    ///     private_data: root.to_bytes(),
+    /// };
+    ///
+    /// let parsed = private.traktor_chunk().unwrap();
+    /// assert_eq!(parsed, root);
+    /// assert_eq!(parsed.child("CUEP").unwrap().data, [1, 2, 3]);
+    /// ```
+    pub fn traktor_chunk(&self) -> crate::Result<TraktorChunk> {
+        if !self.is_traktor() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "private frame is not owned by Traktor",
+            ));
+        }
+        let (chunk, rest) = TraktorChunk::parse(&self.private_data, 0)?;
+        if !rest.is_empty() {
+            return Err(Error::new(
+                ErrorKind::Parsing,
+                "trailing data after Traktor chunk",
+            ));
+        }
+        Ok(chunk)
+    }
+
+    /// Interprets the data of a private frame with a well-known owner.
+    ///
+    /// If the owner is not known or the data does not have the expected format,
+    /// [`KnownPrivate::Unknown`] is returned, holding the raw data.
+    ///
+    /// Frames of rekordbox are not recognized: it keeps its analysis, such as cue points and the
+    /// beat grid, in its own database and analysis files rather than in private frames.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{KnownPrivate, Private};
+    ///
+    /// let private = Private {
+    ///     owner_identifier: "WM/PeakValue".to_string(),
+    ///     private_data: 8000u32.to_le_bytes().to_vec(),
+    /// };
+    /// assert_eq!(private.known(), KnownPrivate::PeakValue(8000));
+    ///
+    /// let private = Private {
+    ///     owner_identifier: "example.com".to_string(),
+    ///     private_data: vec![1, 2, 3],
+    /// };
+    /// assert_eq!(private.known(), KnownPrivate::Unknown(&[1, 2, 3]));
+    /// ```
+    pub fn known(&self) -> KnownPrivate<'_> {
+        let data = &self.private_data[..];
+        let known = match self.owner_identifier.as_str() {
+            "WM/AverageLevel" => <[u8; 4]>::try_from(data)
+                .ok()
+                .map(|b| KnownPrivate::AverageLevel(u32::from_le_bytes(b))),
+            "WM/PeakValue" => <[u8; 4]>::try_from(data)
+                .ok()
+                .map(|b| KnownPrivate::PeakValue(u32::from_le_bytes(b))),
+            "WM/MediaClassPrimaryID"
+            | "WM/MediaClassSecondaryID"
+            | "WM/WMContentID"
+            | "WM/WMCollectionID"
+            | "WM/WMCollectionGroupID" => <[u8; 16]>::try_from(data).ok().map(KnownPrivate::Guid),
+            "WM/Provider" | "WM/UniqueFileIdentifier" => {
+                if data.len() % 2 != 0 {
+                    None
+                } else {
+                    let units: Vec<u16> = data
+                        .chunks_exact(2)
+                        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                        .collect();
+                    String::from_utf16(&units)
+                        .ok()
+                        .map(|s| KnownPrivate::Text(s.trim_end_matches('\0').to_string()))
+                }
+            }
+            "Google/StoreId" | "Google/StoreLabelCode" => std::str::from_utf8(data)
+                .ok()
+                .map(|s| KnownPrivate::Text(s.trim_end_matches('\0').to_string())),
+            "com.apple.streaming.transportStreamTimestamp" => <[u8; 8]>::try_from(data)
+                .ok()
+                .map(|b| KnownPrivate::TransportStreamTimestamp(u64::from_be_bytes(b))),
+            "www.amazon.com" => Some(KnownPrivate::Amazon(data)),
+            TRAKTOR_OWNER => self.traktor_chunk().ok().map(KnownPrivate::Traktor),
+            _ => None,
+        };
+        known.unwrap_or(KnownPrivate::Unknown(data))
+    }
+}
+
+/// The interpreted data of a private frame with a well-known owner, see [`Private::known`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum KnownPrivate<'a> {
+    /// The average volume level written by Windows Media Player (`WM/AverageLevel`).
+    AverageLevel(u32),
+    /// The peak volume level written by Windows Media Player (`WM/PeakValue`).
+    PeakValue(u32),
+    /// A GUID written by Windows Media Player, such as `WM/MediaClassPrimaryID` or
+    /// `WM/WMContentID`, in the little-endian byte order used by Windows.
+    Guid([u8; 16]),
+    /// A text identifier, such as `WM/Provider`, `WM/UniqueFileIdentifier` or the store
+    /// identifiers written by Google (`Google/StoreId`, `Google/StoreLabelCode`).
+    Text(String),
+    /// The MPEG-2 transport stream timestamp of HTTP Live Streaming audio segments
+    /// (`com.apple.streaming.transportStreamTimestamp`), in units of 90kHz.
+    TransportStreamTimestamp(u64),
+    /// The analysis data of Native Instruments Traktor, see [`Private::traktor_chunk`].
+    Traktor(TraktorChunk),
+    /// The data written by the Amazon MP3 store (`www.amazon.com`). Its format is not documented.
+    Amazon(&'a [u8]),
+    /// The raw data of a frame with an unknown owner or with data of an unexpected format.
+    Unknown(&'a [u8]),
+}
+
+/// A chunk of the data stored by Native Instruments Traktor in its private frames.
+///
+/// A chunk holds a list of child chunks, followed by its data. In the serialized form, chunk identifiers
+/// are stored in reverse byte order. The identifier held by this struct is the readable form,
+/// e.g. `TRMD` for the root chunk.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TraktorChunk {
+    /// The four character identifier of the chunk.
+    pub id: String,
+    /// The payload of the chunk. If the chunk has children, this holds any data that follows
+    /// them.
+    pub data: Vec<u8>,
+    /// The child chunks.
+    pub children: Vec<TraktorChunk>,
+}
+
+impl TraktorChunk {
+    /// Returns the first direct child with the specified identifier.
+    pub fn child(&self, id: &str) -> Option<&TraktorChunk> {
+        self.children.iter().find(|child| child.id == id)
+    }
+
+    /// Serializes the chunk and its children into the format stored in the private frame.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend((self.children.len() as u32).to_le_bytes());
+        for child in &self.children {
+            body.extend(child.to_bytes());
+        }
+        body.extend(&self.data);
+
+        let mut id = [b' '; 4];
+        for (dst, src) in id.iter_mut().rev().zip(self.id.bytes()) {
+            *dst = src;
+        }
+        let mut out = Vec::with_capacity(8 + body.len());
+        out.extend(id);
+        out.extend((body.len() as u32).to_le_bytes());
+        out.extend(body);
+        out
+    }
+
+    fn parse(data: &[u8], depth: usize) -> crate::Result<(TraktorChunk, &[u8])> {
+        let truncated = || Error::new(ErrorKind::Parsing, "truncated Traktor chunk");
+        if depth > TRAKTOR_MAX_DEPTH {
+            return Err(Error::new(
+                ErrorKind::Parsing,
+                "Traktor chunks are nested too deeply",
+            ));
+        }
+        if data.len() < 12 {
+            return Err(truncated());
+        }
+        let id = data[0..4].iter().rev().map(|&b| b as char).collect();
+        let size = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+        let body = data[8..].get(..size).ok_or_else(truncated)?;
+        let rest = &data[8 + body.len()..];
+        if body.len() < 4 {
+            return Err(truncated());
+        }
+        let num_children = u32::from_le_bytes([body[0], body[1], body[2], body[3]]);
+
+        let mut chunk = TraktorChunk {
+            id,
+            data: Vec::new(),
+            children: Vec::new(),
+        };
+        let mut remaining = &body[4..];
+        for _ in 0..num_children {
+            let (child, next) = TraktorChunk::parse(remaining, depth + 1)?;
+            chunk.children.push(child);
+            remaining = next;
+        }
+        chunk.data = remaining.to_vec();
+        Ok((chunk, rest))
+    }
+}
+
+impl fmt::Display for TraktorChunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.children.is_empty() {
+            write!(f, "{}: {} bytes", self.id, self.data.len())
+        } else {
+            write!(f, "{}: {} children", self.id, self.children.len())
+        }
+    }
+}
+
+/// The parsed contents of a UFID frame.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct UniqueFileIdentifier {
+    /// Owner identifier
+    pub owner_identifier: String,
+    /// Identifier
+    pub identifier: Vec<u8>,
+}
+
+impl fmt::Display for UniqueFileIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}",
+            self.owner_identifier,
+            String::from_utf8(self.identifier.clone())
+                .unwrap_or(format!("{:x?}", &self.identifier))
+        )
+    }
+}
+
+impl From<UniqueFileIdentifier> for Frame {
+    fn from(c: UniqueFileIdentifier) -> Self {
+        Self::with_content("UFID", Content::UniqueFileIdentifier(c))
+    }
+}
+
+/// The parsed contents of an `IPLS` (ID3v2.3) or `TIPL`/`TMCL` (ID3v2.4) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InvolvedPeopleList {
+    /// Items in the People List.
+    pub items: Vec<InvolvedPeopleListItem>,
+}
+
+/// A entry inside the list in an `IPLS` (ID3v2.3) or `TIPL`/`TMCL` (ID3v2.4) frame.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InvolvedPeopleListItem {
+    /// Role of the involved person.
+    pub involvement: String,
+    /// Name of the involved person.
+    pub involvee: String,
+}
+
+impl fmt::Display for InvolvedPeopleList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let item_count = self.items.len();
+        for (i, item) in self.items.iter().enumerate() {
+            if i == 0 && item_count > 1 {
+                write!(f, "{}: {} / ", item.involvement, item.involvee)?;
+            } else {
+                write!(f, "{}: {}", item.involvement, item.involvee)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<InvolvedPeopleList> for Frame {
+    fn from(c: InvolvedPeopleList) -> Self {
+        Self::with_content("TIPL", Content::InvolvedPeopleList(c))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[allow(missing_docs)]
+pub struct TableOfContents {
+    pub element_id: String,
+    pub top_level: bool,
+    pub ordered: bool,
+    pub elements: Vec<String>,
+    pub frames: Vec<Frame>,
+}
+
+impl fmt::Display for TableOfContents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frames: Vec<&str> = self.frames.iter().map(|f| f.id()).collect();
+        write!(
+            f,
+            "isTopLevel:{top_level}, isOrdered:{ordered}, childList: []: {elements}, frames:{frames}",
+            top_level = self.top_level,
+            ordered = self.ordered,
+            elements = self.elements.join(", "),
+            frames = frames.join(", "),
+        )
+    }
+}
+
+impl TableOfContents {
+    /// Returns the position of the child element with the specified element ID.
+    pub fn element_position(&self, element_id: impl AsRef<str>) -> Option<usize> {
+        let element_id = element_id.as_ref();
+        self.elements.iter().position(|e| e == element_id)
+    }
+
+    /// Appends a reference to a child element.
+    ///
+    /// Returns false and leaves the list untouched if the element is already referenced.
+    pub fn add_element(&mut self, element_id: impl Into<String>) -> bool {
+        let len = self.elements.len();
+        self.insert_element(len, element_id)
+    }
+
+    /// Inserts a reference to a child element at the specified position.
+    ///
+    /// The position is only meaningful if the table of contents is `ordered`. Returns false and
+    /// leaves the list untouched if the element is already referenced.
+    ///
+    /// # Panics
+    /// If `index` is greater than the number of child elements.
+    pub fn insert_element(&mut self, index: usize, element_id: impl Into<String>) -> bool {
+        let element_id = element_id.into();
+        if self.element_position(&element_id).is_some() {
+            return false;
+        }
+        self.elements.insert(index, element_id);
+        true
+    }
+
+    /// Moves the reference to a child element to the specified position, shifting the elements
+    /// in between.
+    ///
+    /// Returns false if the element is not referenced.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn move_element(&mut self, element_id: impl AsRef<str>, index: usize) -> bool {
+        let Some(from) = self.element_position(element_id) else {
+            return false;
+        };
+        assert!(index < self.elements.len(), "index out of bounds");
+        let element = self.elements.remove(from);
+        self.elements.insert(index, element);
+        true
+    }
+
+    /// Removes the reference to a child element.
+    ///
+    /// Returns false if the element was not referenced.
+    pub fn remove_element(&mut self, element_id: impl AsRef<str>) -> bool {
+        match self.element_position(element_id) {
+            Some(index) => {
+                self.elements.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Extend<Frame> for TableOfContents {
+    fn extend<I: IntoIterator<Item = Frame>>(&mut self, iter: I) {
+        self.frames.extend(iter)
+    }
+}
+
+impl TagLike for TableOfContents {
+    fn frames_vec(&self) -> &Vec<Frame> {
+        &self.frames
+    }
+
+    fn frames_vec_mut(&mut self) -> &mut Vec<Frame> {
+        &mut self.frames
+    }
+}
+
+impl From<TableOfContents> for Frame {
+    fn from(c: TableOfContents) -> Self {
+        Self::with_content("CTOC", Content::TableOfContents(c))
+    }
+}
+
+/// The parsed contents of a relative volume adjustment frame (RVA2).
+///
+/// This frame is only defined in ID3v2.4.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct RelativeVolumeAdjustment {
+    /// Identifies the situation and/or device where this adjustment should apply, such as
+    /// "track" or "album".
+    pub identification: String,
+    /// The adjustments for each channel.
+    pub adjustments: Vec<VolumeAdjustment>,
+}
+
+impl RelativeVolumeAdjustment {
+    /// Returns the adjustment for the specified channel, if present.
+    pub fn adjustment(&self, channel_type: ChannelType) -> Option<&VolumeAdjustment> {
+        self.adjustments
+            .iter()
+            .find(|adjustment| adjustment.channel_type == channel_type)
+    }
+
+    /// Sets the adjustment for its channel, replacing and returning any previous adjustment for
+    /// that channel.
+    pub fn set_adjustment(&mut self, adjustment: VolumeAdjustment) -> Option<VolumeAdjustment> {
+        match self
+            .adjustments
+            .iter_mut()
+            .find(|a| a.channel_type == adjustment.channel_type)
+        {
+            Some(a) => Some(std::mem::replace(a, adjustment)),
+            None => {
+                self.adjustments.push(adjustment);
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Display for RelativeVolumeAdjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.identification)?;
+        for (i, adjustment) in self.adjustments.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(
+                f,
+                "{}{} {:+.2} dB",
+                sep,
+                adjustment.channel_type,
+                adjustment.gain_db()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl From<RelativeVolumeAdjustment> for Frame {
+    fn from(c: RelativeVolumeAdjustment) -> Self {
+        Self::with_content("RVA2", Content::RelativeVolumeAdjustment(c))
+    }
+}
+
+/// The volume adjustment of a single channel in an RVA2 frame.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct VolumeAdjustment {
+    /// The channel this adjustment applies to.
+    pub channel_type: ChannelType,
+    /// The volume adjustment in units of 1/512 dB.
+    pub volume_adjustment: i16,
+    /// The number of bits used to represent the peak volume. Zero if no peak is present.
+    pub bits_representing_peak: u8,
+    /// The peak volume as a big endian integer of `ceil(bits_representing_peak / 8)` bytes.
+    pub peak_volume: Vec<u8>,
+}
+
+impl VolumeAdjustment {
+    /// Creates an adjustment from a gain in decibels and an optional peak amplitude relative to
+    /// full scale.
+    ///
+    /// The gain is rounded to the nearest 1/512 dB and the peak is stored using 16 bits.
+    pub fn from_gain(channel_type: ChannelType, gain_db: f64, peak: Option<f64>) -> Self {
+        let volume_adjustment = (gain_db * 512.0)
+            .round()
+            .clamp(f64::from(i16::MIN), f64::from(i16::MAX)) as i16;
+        let (bits_representing_peak, peak_volume) = match peak {
+            Some(peak) => {
+                let peak = (peak * 32768.0).round().clamp(0.0, f64::from(u16::MAX)) as u16;
+                (16, peak.to_be_bytes().to_vec())
+            }
+            None => (0, Vec::new()),
+        };
+        VolumeAdjustment {
+            channel_type,
+            volume_adjustment,
+            bits_representing_peak,
+            peak_volume,
+        }
+    }
+
+    /// Returns the volume adjustment in decibels.
+    pub fn gain_db(&self) -> f64 {
+        f64::from(self.volume_adjustment) / 512.0
+    }
+
+    /// Returns the peak amplitude relative to full scale, if present.
+    pub fn peak(&self) -> Option<f64> {
+        if self.bits_representing_peak == 0 || self.peak_volume.is_empty() {
+            return None;
+        }
+        let value = self
+            .peak_volume
+            .iter()
+            .take(8)
+            .fold(0u64, |acc, b| acc << 8 | u64::from(*b));
+        let bits = usize::from(self.bits_representing_peak).min(self.peak_volume.len().min(8) * 8);
+        Some(value as f64 / 2f64.powi(bits as i32 - 1))
+    }
+}
+
+/// Types of channels used in RVA2 frames.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[allow(missing_docs)]
+pub enum ChannelType {
+    Other,
+    MasterVolume,
+    FrontRight,
+    FrontLeft,
+    BackRight,
+    BackLeft,
+    FrontCentre,
+    BackCentre,
+    Subwoofer,
+    Undefined(u8),
+}
+
+impl From<ChannelType> for u8 {
+    fn from(ct: ChannelType) -> Self {
+        match ct {
+            ChannelType::Other => 0,
+            ChannelType::MasterVolume => 1,
+            ChannelType::FrontRight => 2,
+            ChannelType::FrontLeft => 3,
+            ChannelType::BackRight => 4,
+            ChannelType::BackLeft => 5,
+            ChannelType::FrontCentre => 6,
+            ChannelType::BackCentre => 7,
+            ChannelType::Subwoofer => 8,
+            ChannelType::Undefined(b) => b,
+        }
+    }
+}
+
+impl From<u8> for ChannelType {
+    fn from(b: u8) -> Self {
+        match b {
+            0 => ChannelType::Other,
+            1 => ChannelType::MasterVolume,
+            2 => ChannelType::FrontRight,
+            3 => ChannelType::FrontLeft,
+            4 => ChannelType::BackRight,
+            5 => ChannelType::BackLeft,
+            6 => ChannelType::FrontCentre,
+            7 => ChannelType::BackCentre,
+            8 => ChannelType::Subwoofer,
+            b => ChannelType::Undefined(b),
+        }
+    }
+}
+
+impl fmt::Display for ChannelType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelType::Other => f.write_str("Other"),
+            ChannelType::MasterVolume => f.write_str("Master volume"),
+            ChannelType::FrontRight => f.write_str("Front right"),
+            ChannelType::FrontLeft => f.write_str("Front left"),
+            ChannelType::BackRight => f.write_str("Back right"),
+            ChannelType::BackLeft => f.write_str("Back left"),
+            ChannelType::FrontCentre => f.write_str("Front centre"),
+            ChannelType::BackCentre => f.write_str("Back centre"),
+            ChannelType::Subwoofer => f.write_str("Subwoofer"),
+            ChannelType::Undefined(b) => write!(f, "Undefined channel {}", b),
+        }
+    }
+}
+
+/// The scope of a set of ReplayGain values.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ReplayGainScope {
+    /// Values that apply to a single track.
+    Track,
+    /// Values that apply to an entire album.
+    Album,
+}
+
+impl ReplayGainScope {
+    /// Returns the RVA2 identification that is conventionally used for this scope.
+    pub fn identification(self) -> &'static str {
+        match self {
+            ReplayGainScope::Track => "track",
+            ReplayGainScope::Album => "album",
+        }
+    }
+
+    /// Returns the description of the TXXX frame that holds the gain of this scope.
+    pub(crate) fn gain_description(self) -> &'static str {
+        match self {
+            ReplayGainScope::Track => "REPLAYGAIN_TRACK_GAIN",
+            ReplayGainScope::Album => "REPLAYGAIN_ALBUM_GAIN",
+        }
+    }
+
+    /// Returns the description of the TXXX frame that holds the peak of this scope.
+    pub(crate) fn peak_description(self) -> &'static str {
+        match self {
+            ReplayGainScope::Track => "REPLAYGAIN_TRACK_PEAK",
+            ReplayGainScope::Album => "REPLAYGAIN_ALBUM_PEAK",
+        }
+    }
+}
+
+/// The parsed contents of an event timing codes frame (ETCO).
+///
+/// The events should be sorted in chronological order.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct EventTimingCodes {
+    /// The unit of the timestamps of the events.
+    pub timestamp_format: TimestampFormat,
+    /// The events, as pairs of event type and timestamp.
+    pub events: Vec<(EventType, u32)>,
+}
+
+impl fmt::Display for EventTimingCodes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.timestamp_format)?;
+        for (i, (event_type, timestamp)) in self.events.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(f, "{}{} at {}", sep, event_type, timestamp)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<EventTimingCodes> for Frame {
+    fn from(c: EventTimingCodes) -> Self {
+        Self::with_content("ETCO", Content::EventTimingCodes(c))
+    }
+}
+
+/// Types of events in ETCO frames.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[allow(missing_docs)]
+pub enum EventType {
+    Padding,
+    EndOfInitialSilence,
+    IntroStart,
+    MainPartStart,
+    OutroStart,
+    OutroEnd,
+    VerseStart,
+    RefrainStart,
+    InterludeStart,
+    ThemeStart,
+    VariationStart,
+    KeyChange,
+    TimeChange,
+    MomentaryUnwantedNoise,
+    SustainedNoise,
+    SustainedNoiseEnd,
+    IntroEnd,
+    MainPartEnd,
+    VerseEnd,
+    RefrainEnd,
+    ThemeEnd,
+    Profanity,
+    ProfanityEnd,
+    /// A synchronisation event that is not predefined, numbered 0 to 15.
+    Sync(u8),
+    /// The start of the silence at the end of the audio.
+    AudioEnd,
+    AudioFileEnd,
+    /// An event type that is reserved for future use.
+    Reserved(u8),
+}
+
+impl From<EventType> for u8 {
+    fn from(et: EventType) -> Self {
+        match et {
+            EventType::Padding => 0x00,
+            EventType::EndOfInitialSilence => 0x01,
+            EventType::IntroStart => 0x02,
+            EventType::MainPartStart => 0x03,
+            EventType::OutroStart => 0x04,
+            EventType::OutroEnd => 0x05,
+            EventType::VerseStart => 0x06,
+            EventType::RefrainStart => 0x07,
+            EventType::InterludeStart => 0x08,
+            EventType::ThemeStart => 0x09,
+            EventType::VariationStart => 0x0A,
+            EventType::KeyChange => 0x0B,
+            EventType::TimeChange => 0x0C,
+            EventType::MomentaryUnwantedNoise => 0x0D,
+            EventType::SustainedNoise => 0x0E,
+            EventType::SustainedNoiseEnd => 0x0F,
+            EventType::IntroEnd => 0x10,
+            EventType::MainPartEnd => 0x11,
+            EventType::VerseEnd => 0x12,
+            EventType::RefrainEnd => 0x13,
+            EventType::ThemeEnd => 0x14,
+            EventType::Profanity => 0x15,
+            EventType::ProfanityEnd => 0x16,
+            EventType::Sync(n) => 0xE0 | (n & 0x0F),
+            EventType::AudioEnd => 0xFD,
+            EventType::AudioFileEnd => 0xFE,
+            EventType::Reserved(b) => b,
+        }
+    }
+}
+
+impl From<u8> for EventType {
+    fn from(b: u8) -> Self {
+        match b {
+            0x00 => EventType::Padding,
+            0x01 => EventType::EndOfInitialSilence,
+            0x02 => EventType::IntroStart,
+            0x03 => EventType::MainPartStart,
+            0x04 => EventType::OutroStart,
+            0x05 => EventType::OutroEnd,
+            0x06 => EventType::VerseStart,
+            0x07 => EventType::RefrainStart,
+            0x08 => EventType::InterludeStart,
+            0x09 => EventType::ThemeStart,
+            0x0A => EventType::VariationStart,
+            0x0B => EventType::KeyChange,
+            0x0C => EventType::TimeChange,
+            0x0D => EventType::MomentaryUnwantedNoise,
+            0x0E => EventType::SustainedNoise,
+            0x0F => EventType::SustainedNoiseEnd,
+            0x10 => EventType::IntroEnd,
+            0x11 => EventType::MainPartEnd,
+            0x12 => EventType::VerseEnd,
+            0x13 => EventType::RefrainEnd,
+            0x14 => EventType::ThemeEnd,
+            0x15 => EventType::Profanity,
+            0x16 => EventType::ProfanityEnd,
+            0xE0..=0xEF => EventType::Sync(b & 0x0F),
+            0xFD => EventType::AudioEnd,
+            0xFE => EventType::AudioFileEnd,
+            b => EventType::Reserved(b),
+        }
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventType::Padding => f.write_str("Padding"),
+            EventType::EndOfInitialSilence => f.write_str("End of initial silence"),
+            EventType::IntroStart => f.write_str("Intro start"),
+            EventType::MainPartStart => f.write_str("Main part start"),
+            EventType::OutroStart => f.write_str("Outro start"),
+            EventType::OutroEnd => f.write_str("Outro end"),
+            EventType::VerseStart => f.write_str("Verse start"),
+            EventType::RefrainStart => f.write_str("Refrain start"),
+            EventType::InterludeStart => f.write_str("Interlude start"),
+            EventType::ThemeStart => f.write_str("Theme start"),
+            EventType::VariationStart => f.write_str("Variation start"),
+            EventType::KeyChange => f.write_str("Key change"),
+            EventType::TimeChange => f.write_str("Time change"),
+            EventType::MomentaryUnwantedNoise => f.write_str("Momentary unwanted noise"),
+            EventType::SustainedNoise => f.write_str("Sustained noise"),
+            EventType::SustainedNoiseEnd => f.write_str("Sustained noise end"),
+            EventType::IntroEnd => f.write_str("Intro end"),
+            EventType::MainPartEnd => f.write_str("Main part end"),
+            EventType::VerseEnd => f.write_str("Verse end"),
+            EventType::RefrainEnd => f.write_str("Refrain end"),
+            EventType::ThemeEnd => f.write_str("Theme end"),
+            EventType::Profanity => f.write_str("Profanity"),
+            EventType::ProfanityEnd => f.write_str("Profanity end"),
+            EventType::Sync(n) => write!(f, "Sync {}", n),
+            EventType::AudioEnd => f.write_str("Audio end"),
+            EventType::AudioFileEnd => f.write_str("Audio file end"),
+            EventType::Reserved(b) => write!(f, "Reserved event {}", b),
+        }
+    }
+}
+
+/// The parsed contents of an ownership frame (OWNE).
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Ownership {
+    /// The price paid, as a three letter ISO 4217 currency code followed by the amount, e.g.
+    /// "EUR9.99". Multiple prices may be separated by "/".
+    pub price_paid: String,
+    /// The date of purchase as an 8 character string in the format YYYYMMDD.
+    pub date_of_purchase: String,
+    /// The name of the seller.
+    pub seller: String,
+}
+
+impl fmt::Display for Ownership {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on {} from {}",
+            self.price_paid, self.date_of_purchase, self.seller
+        )
+    }
+}
+
+impl From<Ownership> for Frame {
+    fn from(c: Ownership) -> Self {
+        Self::with_content("OWNE", Content::Ownership(c))
+    }
+}
+
+/// The parsed contents of a commercial frame (COMR).
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Commercial {
+    /// The price, as a three letter ISO 4217 currency code followed by the amount, e.g.
+    /// "EUR9.99". Multiple prices may be separated by "/".
+    pub price: String,
+    /// The date until which the price is valid, as an 8 character string in the format YYYYMMDD.
+    pub valid_until: String,
+    /// A URL, email address or other contact information of the seller.
+    pub contact_url: String,
+    /// Describes how the audio is delivered when bought.
+    pub received_as: ReceivedAs,
+    /// The name of the seller.
+    pub seller_name: String,
+    /// A short description of the product.
+    pub description: String,
+    /// The logo of the seller, if present.
+    pub seller_logo: Option<SellerLogo>,
+}
+
+impl fmt::Display for Commercial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} until {} from {}",
+            self.price, self.valid_until, self.seller_name
+        )?;
+        if !self.description.is_empty() {
+            write!(f, ": {}", self.description)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Commercial> for Frame {
+    fn from(c: Commercial) -> Self {
+        Self::with_content("COMR", Content::Commercial(c))
+    }
+}
+
+/// The logo of the seller in a COMR frame.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SellerLogo {
+    /// The MIME type of the image. Only "image/png" and "image/jpeg" are allowed.
+    pub mime_type: String,
+    /// The image data.
+    pub data: Vec<u8>,
+}
+
+/// The ways in which the audio of a COMR frame can be delivered.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[allow(missing_docs)]
+pub enum ReceivedAs {
+    Other,
+    StandardCdAlbum,
+    CompressedAudioOnCd,
+    FileOverInternet,
+    StreamOverInternet,
+    NoteSheets,
+    NoteSheetsInBook,
+    MusicOnOtherMedia,
+    NonMusicalMerchandise,
+    Undefined(u8),
+}
+
+impl From<ReceivedAs> for u8 {
+    fn from(ra: ReceivedAs) -> Self {
+        match ra {
+            ReceivedAs::Other => 0,
+            ReceivedAs::StandardCdAlbum => 1,
+            ReceivedAs::CompressedAudioOnCd => 2,
+            ReceivedAs::FileOverInternet => 3,
+            ReceivedAs::StreamOverInternet => 4,
+            ReceivedAs::NoteSheets => 5,
+            ReceivedAs::NoteSheetsInBook => 6,
+            ReceivedAs::MusicOnOtherMedia => 7,
+            ReceivedAs::NonMusicalMerchandise => 8,
+            ReceivedAs::Undefined(b) => b,
+        }
+    }
+}
+
+impl From<u8> for ReceivedAs {
+    fn from(b: u8) -> Self {
+        match b {
+            0 => ReceivedAs::Other,
+            1 => ReceivedAs::StandardCdAlbum,
+            2 => ReceivedAs::CompressedAudioOnCd,
+            3 => ReceivedAs::FileOverInternet,
+            4 => ReceivedAs::StreamOverInternet,
+            5 => ReceivedAs::NoteSheets,
+            6 => ReceivedAs::NoteSheetsInBook,
+            7 => ReceivedAs::MusicOnOtherMedia,
+            8 => ReceivedAs::NonMusicalMerchandise,
+            b => ReceivedAs::Undefined(b),
+        }
+    }
+}
+
+impl fmt::Display for ReceivedAs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReceivedAs::Other => f.write_str("Other"),
+            ReceivedAs::StandardCdAlbum => f.write_str("Standard CD album with other songs"),
+            ReceivedAs::CompressedAudioOnCd => f.write_str("Compressed audio on CD"),
+            ReceivedAs::FileOverInternet => f.write_str("File over the Internet"),
+            ReceivedAs::StreamOverInternet => f.write_str("Stream over the Internet"),
+            ReceivedAs::NoteSheets => f.write_str("As note sheets"),
+            ReceivedAs::NoteSheetsInBook => {
+                f.write_str("As note sheets in a book with other sheets")
+            }
+            ReceivedAs::MusicOnOtherMedia => f.write_str("Music on other media"),
+            ReceivedAs::NonMusicalMerchandise => f.write_str("Non-musical merchandise"),
+            ReceivedAs::Undefined(b) => write!(f, "Undefined delivery {}", b),
+        }
+    }
+}
+
+/// The parsed contents of a terms of use frame (USER).
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TermsOfUse {
+    /// The ISO 639-2 language code of the text.
+    pub lang: String,
+    /// The terms of use.
+    pub text: String,
+}
+
+impl fmt::Display for TermsOfUse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl From<TermsOfUse> for Frame {
+    fn from(c: TermsOfUse) -> Self {
+        Self::with_content("USER", Content::TermsOfUse(c))
+    }
+}
+
+/// The parsed contents of a music CD identifier frame (MCDI).
+///
+/// There may only be one MCDI frame in a tag.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MusicCdIdentifier {
+    /// The binary table of contents of the CD, as returned by the CD-ROM READ TOC command.
+    pub toc: Vec<u8>,
+}
+
+impl fmt::Display for MusicCdIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CD table of contents, {} bytes", self.toc.len())
+    }
+}
+
+impl From<MusicCdIdentifier> for Frame {
+    fn from(c: MusicCdIdentifier) -> Self {
+        Self::with_content("MCDI", Content::MusicCdIdentifier(c))
+    }
+}
+
+/// The parsed contents of a linked information frame (LINK).
+///
+/// A linked information frame points to a frame in another tag, for example to share
+/// information between several files.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LinkedInformation {
+    /// The ID of the linked frame. Both ID3v2.2 and ID3v2.3+ IDs are accepted, the ID is
+    /// converted to the width of the version of the tag that is written.
+    pub frame_identifier: String,
+    /// The URL of the file that contains the linked frame.
+    pub url: String,
+    /// Additional data that identifies the linked frame, like the language and description of
+    /// a comment.
+    pub additional_data: Vec<u8>,
+}
+
+impl fmt::Display for LinkedInformation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.frame_identifier, self.url)
+    }
+}
+
+impl From<LinkedInformation> for Frame {
+    fn from(c: LinkedInformation) -> Self {
+        Self::with_content("LINK", Content::LinkedInformation(c))
+    }
+}
+
+/// The parsed contents of a synchronised tempo codes frame (SYTC).
+///
+/// The tempo codes should be sorted in chronological order.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SynchronisedTempoCodes {
+    /// The unit of the timestamps of the tempo codes.
+    pub timestamp_format: TimestampFormat,
+    /// The tempo changes, as pairs of tempo and timestamp.
+    ///
+    /// The tempo is in beats per minute and ranges from 2 up to and including 510. A tempo of 0
+    /// indicates a beat-free section and 1 a single beat-stroke followed by a beat-free section.
+    pub tempos: Vec<(u16, u32)>,
+}
+
+impl fmt::Display for SynchronisedTempoCodes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.timestamp_format)?;
+        for (i, (tempo, timestamp)) in self.tempos.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(f, "{}{} BPM at {}", sep, tempo, timestamp)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<SynchronisedTempoCodes> for Frame {
+    fn from(c: SynchronisedTempoCodes) -> Self {
+        Self::with_content("SYTC", Content::SynchronisedTempoCodes(c))
+    }
+}
+
+/// The parsed contents of an audio seek point index frame (ASPI).
+///
+/// The index divides the audio data into equally sized parts. Each index point is the offset at
+/// which the part starts, as a fraction of the length of the audio data.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct AudioSeekPointIndex {
+    /// The byte offset of the audio data from the start of the file.
+    pub indexed_data_start: u32,
+    /// The length of the audio data in bytes.
+    pub indexed_data_length: u32,
+    /// The number of bits that is used to store each index point, either 8 or 16.
+    pub bits_per_index_point: u8,
+    /// The index points, as numerators of a fraction of `2^bits_per_index_point`.
+    pub index_points: Vec<u16>,
+}
+
+impl AudioSeekPointIndex {
+    /// Returns the fraction of the audio data at which the part with the given index starts.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::AudioSeekPointIndex;
+    ///
+    /// let aspi = AudioSeekPointIndex {
+    ///     indexed_data_start: 1000,
+    ///     indexed_data_length: 5120,
+    ///     bits_per_index_point: 8,
+    ///     index_points: vec![0, 64, 128, 192],
+    /// };
+    /// assert_eq!(aspi.fraction_at(1), Some(0.25));
+    /// assert_eq!(aspi.offset_at(2), Some(3560));
+    /// assert_eq!(aspi.offset_at(4), None);
+    /// ```
+    pub fn fraction_at(&self, index: usize) -> Option<f64> {
+        let point = *self.index_points.get(index)?;
+        Some(f64::from(point) / f64::from(1u32 << self.bits_per_index_point.min(16)))
+    }
+
+    /// Returns the byte offset in the file at which the part with the given index starts.
+    pub fn offset_at(&self, index: usize) -> Option<u64> {
+        let point = u64::from(*self.index_points.get(index)?);
+        let length = u64::from(self.indexed_data_length);
+        let offset = (point * length) >> self.bits_per_index_point.min(16);
+        Some(u64::from(self.indexed_data_start) + offset)
+    }
+}
+
+impl fmt::Display for AudioSeekPointIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} index points for {} bytes at {}",
+            self.index_points.len(),
+            self.indexed_data_length,
+            self.indexed_data_start
+        )
+    }
+}
+
+impl From<AudioSeekPointIndex> for Frame {
+    fn from(c: AudioSeekPointIndex) -> Self {
+        Self::with_content("ASPI", Content::AudioSeekPointIndex(c))
     }
 }
 
-/// The parsed contents of an `IPLS` (ID3v2.3) or `TIPL`/`TMCL` (ID3v2.4) frame.
+/// The parsed contents of a group identification registration frame (GRID).
+///
+/// A registration associates a group symbol with an owner, so other frames like signatures (SIGN)
+/// can refer to the group.
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct InvolvedPeopleList {
-    /// Items in the People List.
-    pub items: Vec<InvolvedPeopleListItem>,
+pub struct GroupRegistration {
+    /// A URL or email address of the organisation responsible for the group.
+    pub owner_identifier: String,
+    /// The symbol of the group, in the range 0x80 up to and including 0xf0.
+    pub group_symbol: u8,
+    /// Data that depends on the owner of the group.
+    pub data: Vec<u8>,
 }
 
-/// A entry inside the list in an `IPLS` (ID3v2.3) or `TIPL`/`TMCL` (ID3v2.4) frame.
+impl fmt::Display for GroupRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#04x}: {}", self.group_symbol, self.owner_identifier)
+    }
+}
+
+impl From<GroupRegistration> for Frame {
+    fn from(c: GroupRegistration) -> Self {
+        Self::with_content("GRID", Content::GroupRegistration(c))
+    }
+}
+
+/// The parsed contents of a signature frame (SIGN).
+///
+/// The group symbol refers to a group identification registration frame (GRID) that describes
+/// how the signature should be verified.
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-pub struct InvolvedPeopleListItem {
-    /// Role of the involved person.
-    pub involvement: String,
-    /// Name of the involved person.
-    pub involvee: String,
+pub struct Signature {
+    /// The symbol of the group the signature belongs to.
+    pub group_symbol: u8,
+    /// The signature data.
+    pub signature: Vec<u8>,
 }
 
-impl fmt::Display for InvolvedPeopleList {
+impl fmt::Display for Signature {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let item_count = self.items.len();
-        for (i, item) in self.items.iter().enumerate() {
-            if i == 0 && item_count > 1 {
-                write!(f, "{}: {} / ", item.involvement, item.involvee)?;
-            } else {
-                write!(f, "{}: {}", item.involvement, item.involvee)?;
-            }
+        write!(
+            f,
+            "{:#04x}: {} byte signature",
+            self.group_symbol,
+            self.signature.len()
+        )
+    }
+}
+
+impl From<Signature> for Frame {
+    fn from(c: Signature) -> Self {
+        Self::with_content("SIGN", Content::Signature(c))
+    }
+}
+
+/// The parsed contents of an ID3v2.3 relative volume adjustment frame (RVAD).
+///
+/// This frame was replaced by RVA2 in ID3v2.4, see [`RelativeVolumeAdjustment`]. The encoder
+/// converts it when writing an ID3v2.4 tag, see [`Encoder::convert_legacy_frames`].
+///
+/// [`Encoder::convert_legacy_frames`]: crate::Encoder::convert_legacy_frames
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LegacyVolumeAdjustment {
+    /// The number of bits that is used to store each volume change and peak, between 1 and 32.
+    pub bits_used: u8,
+    /// The adjustments for each channel.
+    ///
+    /// The channels are stored in a fixed order: front right and front left, optionally followed
+    /// by back right and back left, front centre and subwoofer.
+    pub adjustments: Vec<LegacyChannelVolume>,
+}
+
+impl LegacyVolumeAdjustment {
+    /// The channels of an RVAD frame, in the order in which they are stored.
+    pub(crate) const CHANNELS: [ChannelType; 6] = [
+        ChannelType::FrontRight,
+        ChannelType::FrontLeft,
+        ChannelType::BackRight,
+        ChannelType::BackLeft,
+        ChannelType::FrontCentre,
+        ChannelType::Subwoofer,
+    ];
+
+    /// Converts the adjustment to its ID3v2.4 equivalent.
+    ///
+    /// The volume change is interpreted as a change of the amplitude relative to
+    /// `2^bits_used`, so a change of `c` corresponds to a gain of `20 * log10(1 + c / 2^bits_used)`
+    /// dB. The peaks are kept as is. The identification of the result is empty, because RVAD
+    /// frames do not have one.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{ChannelType, LegacyChannelVolume, LegacyVolumeAdjustment};
+    ///
+    /// let rvad = LegacyVolumeAdjustment {
+    ///     bits_used: 16,
+    ///     adjustments: vec![
+    ///         LegacyChannelVolume {
+    ///             channel_type: ChannelType::FrontRight,
+    ///             volume_change: 65536,
+    ///             peak_volume: 0x7fff,
+    ///         },
+    ///         LegacyChannelVolume {
+    ///             channel_type: ChannelType::FrontLeft,
+    ///             volume_change: -32768,
+    ///             peak_volume: 0x4000,
+    ///         },
+    ///     ],
+    /// };
+    /// let rva2 = rvad.to_relative_volume_adjustment();
+    /// let right = rva2.adjustment(ChannelType::FrontRight).unwrap();
+    /// assert!((right.gain_db() - 6.02).abs() < 0.01);
+    /// assert_eq!(right.peak_volume, vec![0x7f, 0xff]);
+    /// let left = rva2.adjustment(ChannelType::FrontLeft).unwrap();
+    /// assert!((left.gain_db() + 6.02).abs() < 0.01);
+    /// ```
+    pub fn to_relative_volume_adjustment(&self) -> RelativeVolumeAdjustment {
+        let peak_len = (usize::from(self.bits_used) + 7) / 8;
+        let adjustments = self
+            .adjustments
+            .iter()
+            .map(|adjustment| VolumeAdjustment {
+                bits_representing_peak: self.bits_used,
+                peak_volume: adjustment.peak_volume.to_be_bytes()[4 - peak_len.min(4)..].to_vec(),
+                ..VolumeAdjustment::from_gain(
+                    adjustment.channel_type,
+                    legacy_gain_db(adjustment.volume_change, self.bits_used),
+                    None,
+                )
+            })
+            .collect();
+        RelativeVolumeAdjustment {
+            identification: String::new(),
+            adjustments,
         }
+    }
+}
 
+impl fmt::Display for LegacyVolumeAdjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bits:", self.bits_used)?;
+        for (i, adjustment) in self.adjustments.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(
+                f,
+                "{}{} {:+}",
+                sep, adjustment.channel_type, adjustment.volume_change
+            )?;
+        }
         Ok(())
     }
 }
 
-impl From<InvolvedPeopleList> for Frame {
-    fn from(c: InvolvedPeopleList) -> Self {
-        Self::with_content("TIPL", Content::InvolvedPeopleList(c))
+impl From<LegacyVolumeAdjustment> for Frame {
+    fn from(c: LegacyVolumeAdjustment) -> Self {
+        Self::with_content("RVAD", Content::LegacyVolumeAdjustment(c))
     }
 }
 
+/// The volume adjustment of a single channel in an RVAD frame.
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
-#[allow(missing_docs)]
-pub struct TableOfContents {
-    pub element_id: String,
-    pub top_level: bool,
-    pub ordered: bool,
-    pub elements: Vec<String>,
-    pub frames: Vec<Frame>,
+pub struct LegacyChannelVolume {
+    /// The channel this adjustment applies to.
+    pub channel_type: ChannelType,
+    /// The relative volume change. Positive values increase the volume. The magnitude must fit in
+    /// the number of bits used by the frame.
+    pub volume_change: i64,
+    /// The peak volume. Must fit in the number of bits used by the frame.
+    pub peak_volume: u32,
 }
 
-impl fmt::Display for TableOfContents {
+/// The parsed contents of an ID3v2.3 equalisation frame (EQUA).
+///
+/// This frame was replaced by EQU2 in ID3v2.4, see [`Equalisation`]. The encoder converts it
+/// when writing an ID3v2.4 tag, see [`Encoder::convert_legacy_frames`].
+///
+/// [`Encoder::convert_legacy_frames`]: crate::Encoder::convert_legacy_frames
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LegacyEqualisation {
+    /// The number of bits that is used to store each adjustment, between 1 and 32.
+    pub adjustment_bits: u8,
+    /// The adjustments, as pairs of a frequency in Hz and a volume change. The frequency must not
+    /// exceed 32767 Hz. Positive changes increase the volume.
+    pub bands: Vec<(u16, i64)>,
+}
+
+impl LegacyEqualisation {
+    /// Converts the equalisation to its ID3v2.4 equivalent.
+    ///
+    /// The volume changes are interpreted like those of [`LegacyVolumeAdjustment`]. The result
+    /// uses band interpolation and has an empty identification.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{InterpolationMethod, LegacyEqualisation};
+    ///
+    /// let equa = LegacyEqualisation {
+    ///     adjustment_bits: 16,
+    ///     bands: vec![(1000, 0), (100, 65536)],
+    /// };
+    /// let equ2 = equa.to_equalisation();
+    /// assert_eq!(equ2.interpolation_method, InterpolationMethod::Band);
+    /// assert_eq!(equ2.adjustment_points[0].0, 200);
+    /// assert_eq!(equ2.adjustment_points[1], (2000, 0));
+    /// ```
+    pub fn to_equalisation(&self) -> Equalisation {
+        let mut adjustment_points: Vec<_> = self
+            .bands
+            .iter()
+            .map(|(frequency, volume_change)| {
+                let gain = legacy_gain_db(*volume_change, self.adjustment_bits);
+                let adjustment = VolumeAdjustment::from_gain(ChannelType::Other, gain, None);
+                (frequency.saturating_mul(2), adjustment.volume_adjustment)
+            })
+            .collect();
+        adjustment_points.sort_by_key(|(frequency, _)| *frequency);
+        Equalisation {
+            interpolation_method: InterpolationMethod::Band,
+            identification: String::new(),
+            adjustment_points,
+        }
+    }
+}
+
+impl fmt::Display for LegacyEqualisation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bits:", self.adjustment_bits)?;
+        for (i, (frequency, volume_change)) in self.bands.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(f, "{}{} Hz {:+}", sep, frequency, volume_change)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<LegacyEqualisation> for Frame {
+    fn from(c: LegacyEqualisation) -> Self {
+        Self::with_content("EQUA", Content::LegacyEqualisation(c))
+    }
+}
+
+/// Converts a volume change of an RVAD or EQUA frame to a gain in decibels.
+fn legacy_gain_db(volume_change: i64, bits: u8) -> f64 {
+    let factor = 1.0 + volume_change as f64 / 2f64.powi(i32::from(bits));
+    20.0 * factor.max(0.0).log10()
+}
+
+/// The parsed contents of an equalisation frame (EQU2).
+///
+/// This frame is only defined in ID3v2.4.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Equalisation {
+    /// How the adjustment between the adjustment points is calculated.
+    pub interpolation_method: InterpolationMethod,
+    /// Identifies the situation and/or device where this equalisation should apply.
+    pub identification: String,
+    /// The adjustment points, as pairs of a frequency in units of 1/2 Hz and a volume adjustment
+    /// in units of 1/512 dB. The points should be sorted by frequency.
+    pub adjustment_points: Vec<(u16, i16)>,
+}
+
+impl fmt::Display for Equalisation {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let frames: Vec<&str> = self.frames.iter().map(|f| f.id()).collect();
         write!(
             f,
-            "isTopLevel:{top_level}, isOrdered:{ordered}, childList: []: {elements}, frames:{frames}",
-            top_level = self.top_level,
-            ordered = self.ordered,
-            elements = self.elements.join(", "),
-            frames = frames.join(", "),
-        )
+            "{} ({}):",
+            self.identification, self.interpolation_method
+        )?;
+        for (i, (frequency, adjustment)) in self.adjustment_points.iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(
+                f,
+                "{}{} Hz {:+.2} dB",
+                sep,
+                f64::from(*frequency) / 2.0,
+                f64::from(*adjustment) / 512.0
+            )?;
+        }
+        Ok(())
     }
 }
 
-impl Extend<Frame> for TableOfContents {
-    fn extend<I: IntoIterator<Item = Frame>>(&mut self, iter: I) {
-        self.frames.extend(iter)
+impl From<Equalisation> for Frame {
+    fn from(c: Equalisation) -> Self {
+        Self::with_content("EQU2", Content::Equalisation(c))
     }
 }
 
-impl TagLike for TableOfContents {
-    fn frames_vec(&self) -> &Vec<Frame> {
-        &self.frames
+/// Methods of interpolation between the adjustment points of EQU2 frames.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum InterpolationMethod {
+    /// No interpolation, the adjustment of a point applies up to the next point.
+    Band,
+    /// Linear interpolation between the adjustment points.
+    Linear,
+    /// A method that is not defined by the specification.
+    Undefined(u8),
+}
+
+impl From<InterpolationMethod> for u8 {
+    fn from(im: InterpolationMethod) -> Self {
+        match im {
+            InterpolationMethod::Band => 0,
+            InterpolationMethod::Linear => 1,
+            InterpolationMethod::Undefined(b) => b,
+        }
     }
+}
 
-    fn frames_vec_mut(&mut self) -> &mut Vec<Frame> {
-        &mut self.frames
+impl From<u8> for InterpolationMethod {
+    fn from(b: u8) -> Self {
+        match b {
+            0 => InterpolationMethod::Band,
+            1 => InterpolationMethod::Linear,
+            b => InterpolationMethod::Undefined(b),
+        }
     }
 }
 
-impl From<TableOfContents> for Frame {
-    fn from(c: TableOfContents) -> Self {
-        Self::with_content("CTOC", Content::TableOfContents(c))
+impl fmt::Display for InterpolationMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpolationMethod::Band => f.write_str("Band"),
+            InterpolationMethod::Linear => f.write_str("Linear"),
+            InterpolationMethod::Undefined(b) => write!(f, "Undefined interpolation {}", b),
+        }
+    }
+}
+
+/// The parsed contents of an ID3v2.2 encrypted meta frame (CRM).
+///
+/// The frame holds other frames that were encrypted. It has no equivalent in later versions and
+/// is only written to ID3v2.2 tags.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct EncryptedMeta {
+    /// A URL or email address of the organisation responsible for the encryption method.
+    pub owner_identifier: String,
+    /// A short explanation of the encrypted content.
+    pub content_description: String,
+    /// The encrypted frames.
+    pub encrypted_data: Vec<u8>,
+}
+
+impl fmt::Display for EncryptedMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.owner_identifier, self.content_description)
+    }
+}
+
+impl From<EncryptedMeta> for Frame {
+    fn from(c: EncryptedMeta) -> Self {
+        Self::with_content("CRM", Content::EncryptedMeta(c))
+    }
+}
+
+/// The parsed contents of an encryption method registration frame (ENCR).
+///
+/// A registration associates a method symbol with the owner of an encryption method. Encrypted
+/// frames refer to their method by this symbol, see [`EncryptedFrame`].
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct EncryptionMethod {
+    /// A URL or email address of the organisation responsible for the encryption method.
+    pub owner_identifier: String,
+    /// The symbol of the method, in the range 0x80 up to and including 0xf0.
+    pub method_symbol: u8,
+    /// Data that depends on the encryption method.
+    pub encryption_data: Vec<u8>,
+}
+
+impl fmt::Display for EncryptionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#04x}: {}", self.method_symbol, self.owner_identifier)
+    }
+}
+
+impl From<EncryptionMethod> for Frame {
+    fn from(c: EncryptionMethod) -> Self {
+        Self::with_content("ENCR", Content::EncryptionMethod(c))
+    }
+}
+
+/// The content of a frame that has its encryption flag set.
+///
+/// The content can not be decoded without the encryption method, so it is kept as is together
+/// with the frame header fields that describe it. This allows the frame to be written back
+/// unchanged to an ID3v2.3 or ID3v2.4 tag.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct EncryptedFrame {
+    /// The symbol of the encryption method, which refers to an encryption method registration
+    /// frame (ENCR).
+    pub method_symbol: u8,
+    /// The symbol of the group the frame belongs to, if any.
+    pub group_symbol: Option<u8>,
+    /// Whether the content was compressed before it was encrypted.
+    pub compressed: bool,
+    /// Whether the content is unsynchronised. Only ID3v2.4 has unsynchronisation per frame.
+    pub unsynchronised: bool,
+    /// The size of the content after decryption and decompression, if known. Required for
+    /// compressed content.
+    pub data_length: Option<u32>,
+    /// The encrypted content.
+    pub data: Vec<u8>,
+}
+
+impl fmt::Display for EncryptedFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} bytes encrypted with {:#04x}",
+            self.data.len(),
+            self.method_symbol
+        )
+    }
+}
+
+/// The placeholder of a frame of which the content was skipped while decoding, see
+/// [`Decoder::skip_content_of`](crate::Decoder::skip_content_of) and
+/// [`Decoder::max_frame_size`](crate::Decoder::max_frame_size).
+///
+/// The content is not available, so frames holding a placeholder can not be written.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Skipped {
+    /// The size of the content as stored in the tag, excluding the frame header.
+    pub size: u64,
+    /// The byte offset of the frame header from the start of the tag.
+    pub offset: u64,
+    /// The version of the tag which contained this frame.
+    pub version: Version,
+}
+
+impl fmt::Display for Skipped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {} bytes at offset {} (skipped)",
+            self.version, self.size, self.offset
+        )
     }
 }
 
@@ -967,6 +3103,164 @@ impl fmt::Display for Unknown {
 mod tests {
     use super::*;
 
+    #[test]
+    fn synchronised_lyrics_lrc() {
+        let lrc = "[la:deu]\r\n[offset:+100]\n[00:10.5]One\n[00:01.999]Two\ncontinued\n\n[ar:Artist]\nignored\n[00:00.050][02:00]Three\n";
+        let sylt = SynchronisedLyrics::from_lrc(lrc).unwrap();
+        assert_eq!(sylt.lang, "deu");
+        assert_eq!(sylt.timestamp_format, TimestampFormat::Ms);
+        assert_eq!(
+            sylt.content,
+            vec![
+                (0, "Three".to_string()),
+                (1899, "Two\ncontinued".to_string()),
+                (10400, "One".to_string()),
+                (119900, "Three".to_string()),
+            ]
+        );
+        assert_eq!(
+            sylt.to_lrc().unwrap(),
+            "[la:deu]\n[00:00.00]Three\n[00:01.90]Two\ncontinued\n[00:10.40]One\n[01:59.90]Three\n"
+        );
+        let reparsed = SynchronisedLyrics::from_lrc(&sylt.to_lrc().unwrap()).unwrap();
+        assert_eq!(reparsed.content[1], (1900, "Two\ncontinued".to_string()));
+
+        assert!(SynchronisedLyrics::from_lrc("[ti:Title]\nno timestamps").is_err());
+        assert_eq!(
+            Lyrics::from_lrc("[ti:Title]\nno timestamps").text,
+            "no timestamps"
+        );
+
+        assert!(SynchronisedLyrics::from_lrc("[0:9999999999999999]x").is_err());
+        assert!(
+            SynchronisedLyrics::from_lrc("[offset:-9223372036854775808]\n[00:01.00]x\n").is_err()
+        );
+        assert!(SynchronisedLyrics::from_lrc("[71583:00]x\n").is_err());
+        let partial = SynchronisedLyrics::from_lrc("[71583:00]x\n[00:01.00]y\n").unwrap();
+        assert_eq!(partial.content, vec![(1000, "y".to_string())]);
+
+        for text in ["One\n[Chorus]", "One\n  [x", "[00:01.00]One", " [01:00]One"] {
+            let sylt = SynchronisedLyrics {
+                content: vec![(0, text.to_string())],
+                ..sylt.clone()
+            };
+            assert!(sylt.to_lrc().is_err(), "{:?}", text);
+        }
+        let bracketed = SynchronisedLyrics {
+            content: vec![(0, "[Chorus] One\ntwo ]".to_string())],
+            ..sylt.clone()
+        };
+        let reparsed = SynchronisedLyrics::from_lrc(&bracketed.to_lrc().unwrap()).unwrap();
+        assert_eq!(reparsed.content, bracketed.content);
+
+        let mpeg = SynchronisedLyrics {
+            timestamp_format: TimestampFormat::Mpeg,
+            ..sylt
+        };
+        assert!(mpeg.to_lrc().is_err());
+    }
+
+    #[test]
+    fn popularimeter_stars() {
+        let mut popm = Popularimeter {
+            user: String::new(),
+            rating: 0,
+            counter: 0,
+        };
+        for convention in [
+            RatingConvention::WindowsMediaPlayer,
+            RatingConvention::MusicBee,
+            RatingConvention::Kodi,
+            RatingConvention::Traktor,
+        ] {
+            let step = 5.0 / convention.ratings().len() as f32;
+            for i in 1..=convention.ratings().len() {
+                let stars = i as f32 * step;
+                popm.set_stars(convention, stars);
+                assert_eq!(popm.stars(convention), Some(stars), "{:?}", convention);
+            }
+            popm.set_stars(convention, 0.0);
+            assert_eq!(popm.stars(convention), None);
+            popm.set_stars(convention, 9.0);
+            assert_eq!(popm.stars(convention), Some(5.0));
+        }
+
+        popm.rating = 2;
+        assert_eq!(popm.stars(RatingConvention::MusicBee), Some(1.0));
+        popm.rating = 100;
+        assert_eq!(popm.stars(RatingConvention::WindowsMediaPlayer), Some(3.0));
+        assert_eq!(popm.stars(RatingConvention::Traktor), Some(2.0));
+    }
+
+    #[test]
+    fn traktor_chunk() {
+        #[rustfmt::skip]
+        let data = vec![
+            b'D', b'M', b'R', b'T', 30, 0, 0, 0, 2, 0, 0, 0,
+                b'P', b'E', b'U', b'C', 6, 0, 0, 0, 0, 0, 0, 0, 0xab, 0xcd,
+                b'Q', b'M', b'P', b'B', 4, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        let private = Private {
+            owner_identifier: "TRAKTOR4".to_string(),
+            private_data: data.clone(),
+        };
+        let root = private.traktor_chunk().unwrap();
+        assert_eq!(root.id, "TRMD");
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.child("CUEP").unwrap().data, [0xab, 0xcd]);
+        assert!(root.child("BPMQ").unwrap().data.is_empty());
+        assert!(root.child("HDR ").is_none());
+        assert_eq!(root.to_bytes(), data);
+
+        let truncated = Private {
+            owner_identifier: "TRAKTOR4".to_string(),
+            private_data: data[..20].to_vec(),
+        };
+        assert!(truncated.traktor_chunk().is_err());
+        let other = Private {
+            owner_identifier: "other".to_string(),
+            private_data: data,
+        };
+        assert!(other.traktor_chunk().is_err());
+
+        let with_data = TraktorChunk {
+            id: "TRMD".to_string(),
+            data: vec![1, 2, 3],
+            children: vec![root.child("CUEP").unwrap().clone()],
+        };
+        let with_data_private = Private {
+            owner_identifier: "TRAKTOR4".to_string(),
+            private_data: with_data.to_bytes(),
+        };
+        assert_eq!(with_data_private.traktor_chunk().unwrap(), with_data);
+
+        let oversized = Private {
+            owner_identifier: "TRAKTOR4".to_string(),
+            private_data: vec![b'D', b'M', b'R', b'T', 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0],
+        };
+        assert!(oversized.traktor_chunk().is_err());
+
+        let mut nested = TraktorChunk {
+            id: "TRMD".to_string(),
+            data: Vec::new(),
+            children: Vec::new(),
+        };
+        for _ in 0..1000 {
+            nested = TraktorChunk {
+                id: "TRMD".to_string(),
+                data: Vec::new(),
+                children: vec![nested],
+            };
+        }
+        let nested = Private {
+            owner_identifier: "TRAKTOR4".to_string(),
+            private_data: nested.to_bytes(),
+        };
+        let err = nested.traktor_chunk().unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Parsing));
+        assert!(matches!(nested.known(), KnownPrivate::Unknown(_)));
+    }
+
     #[test]
     fn content_text_display() {
         let text = Content::Text(String::from("text value"));
@@ -1126,4 +3420,98 @@ mod tests {
         };
         assert_eq!(*content.to_unknown().unwrap(), unknown);
     }
+
+    #[test]
+    fn table_of_contents_elements() {
+        let mut toc = TableOfContents {
+            element_id: String::from("toc"),
+            top_level: true,
+            ordered: true,
+            elements: Vec::new(),
+            frames: Vec::new(),
+        };
+        assert!(toc.add_element("chp1"));
+        assert!(toc.add_element("chp2"));
+        assert!(!toc.add_element("chp1"));
+        assert!(toc.insert_element(0, "chp0"));
+        assert_eq!(toc.elements, ["chp0", "chp1", "chp2"]);
+
+        assert!(toc.move_element("chp0", 2));
+        assert_eq!(toc.elements, ["chp1", "chp2", "chp0"]);
+        assert!(toc.move_element("chp2", 0));
+        assert_eq!(toc.elements, ["chp2", "chp1", "chp0"]);
+        assert!(!toc.move_element("chp3", 0));
+
+        assert!(toc.remove_element("chp1"));
+        assert!(!toc.remove_element("chp1"));
+        assert_eq!(toc.elements, ["chp2", "chp0"]);
+        assert_eq!(toc.element_position("chp0"), Some(1));
+        assert!(toc.ordered);
+    }
+
+    #[test]
+    fn private_known() {
+        let private = |owner: &str, data: &[u8]| Private {
+            owner_identifier: owner.to_string(),
+            private_data: data.to_vec(),
+        };
+        assert_eq!(
+            private("WM/AverageLevel", &[0x10, 0x27, 0, 0]).known(),
+            KnownPrivate::AverageLevel(10000)
+        );
+        assert_eq!(
+            private("WM/AverageLevel", &[0x10, 0x27]).known(),
+            KnownPrivate::Unknown(&[0x10, 0x27])
+        );
+        assert_eq!(
+            private("WM/MediaClassPrimaryID", &[7; 16]).known(),
+            KnownPrivate::Guid([7; 16])
+        );
+        assert_eq!(
+            private("WM/Provider", b"A\0M\0G\0\0\0").known(),
+            KnownPrivate::Text("AMG".to_string())
+        );
+        assert_eq!(
+            private("Google/StoreId", b"Tabc123").known(),
+            KnownPrivate::Text("Tabc123".to_string())
+        );
+        assert_eq!(
+            private(
+                "com.apple.streaming.transportStreamTimestamp",
+                &[0, 0, 0, 1, 0, 0, 0, 0]
+            )
+            .known(),
+            KnownPrivate::TransportStreamTimestamp(1 << 32)
+        );
+        assert_eq!(
+            private("www.amazon.com", &[1, 2]).known(),
+            KnownPrivate::Amazon(&[1, 2])
+        );
+        assert_eq!(
+            private(TRAKTOR_OWNER, &[1, 2]).known(),
+            KnownPrivate::Unknown(&[1, 2])
+        );
+    }
+
+    #[test]
+    fn comment_sound_check() {
+        let comment = Comment {
+            lang: String::from("eng"),
+            description: String::from("iTunNORM"),
+            text: String::from(" 000003E8 000000FA 00001866 00001778 00024CA8 00024CA8 00007FFF 00007FFF 00024CA8 00024CA8"),
+        };
+        assert_eq!(comment.sound_check(), Some(1.0));
+
+        let comment = Comment {
+            text: String::from(" 00000000 00000000"),
+            ..comment
+        };
+        assert_eq!(comment.sound_check(), None);
+
+        let comment = Comment {
+            description: String::from("iTunSMPB"),
+            ..Comment::from_sound_check(2.0, None)
+        };
+        assert_eq!(comment.sound_check(), None);
+    }
 }
@@ -0,0 +1,296 @@
+use crate::frame::Picture;
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use std::fmt;
+
+/// An image format that can be detected from the data of a picture.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum ImageFormat {
+    /// JPEG, `image/jpeg`.
+    Jpeg,
+    /// PNG, `image/png`.
+    Png,
+    /// GIF, `image/gif`.
+    Gif,
+    /// WebP, `image/webp`.
+    WebP,
+}
+
+impl ImageFormat {
+    /// Detects the image format from the magic bytes at the start of the data.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::ImageFormat;
+    ///
+    /// assert_eq!(ImageFormat::detect(b"\x89PNG\r\n\x1a\n"), Some(ImageFormat::Png));
+    /// assert_eq!(ImageFormat::detect(b"not an image"), None);
+    /// ```
+    pub fn detect(data: &[u8]) -> Option<ImageFormat> {
+        if data.starts_with(b"\xFF\xD8\xFF") {
+            Some(ImageFormat::Jpeg)
+        } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(ImageFormat::Png)
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            Some(ImageFormat::Gif)
+        } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+            Some(ImageFormat::WebP)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the MIME type of the format.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::WebP => "image/webp",
+        }
+    }
+
+    /// Reads the width and height in pixels from the image data.
+    fn dimensions(self, data: &[u8]) -> Option<(u32, u32)> {
+        match self {
+            ImageFormat::Jpeg => jpeg_dimensions(data),
+            ImageFormat::Png => {
+                let ihdr = data.get(12..24).filter(|ihdr| &ihdr[..4] == b"IHDR")?;
+                Some((
+                    BigEndian::read_u32(&ihdr[4..8]),
+                    BigEndian::read_u32(&ihdr[8..12]),
+                ))
+            }
+            ImageFormat::Gif => {
+                let screen = data.get(6..10)?;
+                Some((
+                    u32::from(LittleEndian::read_u16(&screen[0..2])),
+                    u32::from(LittleEndian::read_u16(&screen[2..4])),
+                ))
+            }
+            ImageFormat::WebP => webp_dimensions(data),
+        }
+    }
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Png => "PNG",
+            ImageFormat::Gif => "GIF",
+            ImageFormat::WebP => "WebP",
+        })
+    }
+}
+
+/// Properties of the image in a picture frame. Created by [`Picture::image_info`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ImageInfo {
+    /// The format of the image.
+    pub format: ImageFormat,
+    /// The width in pixels.
+    pub width: u32,
+    /// The height in pixels.
+    pub height: u32,
+    /// The size of the image data in bytes.
+    pub size: usize,
+}
+
+impl Picture {
+    /// Detects the format of the image from the magic bytes of its data.
+    pub fn image_format(&self) -> Option<ImageFormat> {
+        ImageFormat::detect(&self.data)
+    }
+
+    /// Returns the format, dimensions and size of the image. Returns `None` if the format is not
+    /// recognized or the dimensions can not be read.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{ImageFormat, Picture, PictureType};
+    ///
+    /// let picture = Picture {
+    ///     mime_type: String::new(),
+    ///     picture_type: PictureType::CoverFront,
+    ///     description: String::new(),
+    ///     data: std::fs::read("testdata/image.jpg")?,
+    /// };
+    /// let info = picture.image_info().unwrap();
+    /// assert_eq!(info.format, ImageFormat::Jpeg);
+    /// assert_eq!((info.width, info.height), (256, 255));
+    /// assert!(info.width < 3000 || info.height < 3000);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn image_info(&self) -> Option<ImageInfo> {
+        let format = self.image_format()?;
+        let (width, height) = format.dimensions(&self.data)?;
+        Some(ImageInfo {
+            format,
+            width,
+            height,
+            size: self.data.len(),
+        })
+    }
+
+    /// Sets the MIME type to the one of the detected image format if it is missing or does not
+    /// match. Returns true if the MIME type was changed.
+    ///
+    /// The MIME type is left as is if the format is not recognized.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{Picture, PictureType};
+    ///
+    /// let mut picture = Picture {
+    ///     mime_type: "image/png".to_string(),
+    ///     picture_type: PictureType::CoverFront,
+    ///     description: String::new(),
+    ///     data: std::fs::read("testdata/image.jpg")?,
+    /// };
+    /// assert!(picture.fix_mime_type());
+    /// assert_eq!(picture.mime_type, "image/jpeg");
+    /// assert!(!picture.fix_mime_type());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn fix_mime_type(&mut self) -> bool {
+        let mime_type = match self.image_format() {
+            Some(format) => format.mime_type(),
+            None => return false,
+        };
+        // "image/jpg" is a common misspelling that readers understand nonetheless.
+        let matches = self.mime_type.eq_ignore_ascii_case(mime_type)
+            || (mime_type == "image/jpeg" && self.mime_type.eq_ignore_ascii_case("image/jpg"));
+        if matches {
+            return false;
+        }
+        self.mime_type = mime_type.to_string();
+        true
+    }
+}
+
+/// Walks the JPEG markers up to the first start of frame segment, which holds the dimensions.
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    loop {
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+        // Markers may be preceded by any number of fill bytes.
+        while *data.get(pos + 1)? == 0xFF {
+            pos += 1;
+        }
+        let marker = data[pos + 1];
+        pos += 2;
+        match marker {
+            0x01 | 0xD0..=0xD7 => continue,
+            0xD9 | 0xDA => return None,
+            _ => {}
+        }
+        let segment = data.get(pos..pos + 2)?;
+        let len = usize::from(BigEndian::read_u16(segment));
+        let is_start_of_frame =
+            (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker);
+        if is_start_of_frame {
+            let frame = data.get(pos + 3..pos + 7)?;
+            return Some((
+                u32::from(BigEndian::read_u16(&frame[2..4])),
+                u32::from(BigEndian::read_u16(&frame[0..2])),
+            ));
+        }
+        pos += len;
+    }
+}
+
+/// Reads the dimensions from the first chunk of a WebP image, which is either a lossy (VP8), a
+/// lossless (VP8L) or an extended (VP8X) bitstream.
+fn webp_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let chunk = data.get(12..16)?;
+    match chunk {
+        b"VP8 " => {
+            let frame = data.get(23..30).filter(|f| f[..3] == [0x9D, 0x01, 0x2A])?;
+            Some((
+                u32::from(LittleEndian::read_u16(&frame[3..5]) & 0x3FFF),
+                u32::from(LittleEndian::read_u16(&frame[5..7]) & 0x3FFF),
+            ))
+        }
+        b"VP8L" => {
+            let header = data.get(20..25).filter(|h| h[0] == 0x2F)?;
+            let bits = LittleEndian::read_u32(&header[1..5]);
+            Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        b"VP8X" => {
+            let canvas = data.get(24..30)?;
+            Some((
+                LittleEndian::read_u24(&canvas[0..3]) + 1,
+                LittleEndian::read_u24(&canvas[3..6]) + 1,
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::PictureType;
+
+    fn picture(data: Vec<u8>) -> Picture {
+        Picture {
+            mime_type: String::new(),
+            picture_type: PictureType::Other,
+            description: String::new(),
+            data,
+        }
+    }
+
+    #[test]
+    fn dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 0x0B, 0xB8, 0, 0, 0x0B, 0xB8, 8, 2, 0, 0, 0]);
+        let gif = b"GIF89a\x40\x01\xf0\x00\x00\x00\x00".to_vec();
+        let mut vp8 = b"RIFF\x00\x00\x00\x00WEBPVP8 \x00\x00\x00\x00".to_vec();
+        vp8.extend_from_slice(&[0, 0, 0, 0x9D, 0x01, 0x2A, 0x80, 0x02, 0xE0, 0x01]);
+        let mut vp8l = b"RIFF\x00\x00\x00\x00WEBPVP8L\x00\x00\x00\x00\x2f".to_vec();
+        vp8l.extend_from_slice(&(99 | (49 << 14) as u32).to_le_bytes());
+        let mut vp8x = b"RIFF\x00\x00\x00\x00WEBPVP8X\x0a\x00\x00\x00".to_vec();
+        vp8x.extend_from_slice(&[0, 0, 0, 0, 0x0F, 0x0, 0x0, 0x07, 0x0, 0x0]);
+
+        let cases = [
+            (png, ImageFormat::Png, 3000, 3000),
+            (gif, ImageFormat::Gif, 320, 240),
+            (vp8, ImageFormat::WebP, 640, 480),
+            (vp8l, ImageFormat::WebP, 100, 50),
+            (vp8x, ImageFormat::WebP, 16, 8),
+        ];
+        for (data, format, width, height) in cases {
+            let size = data.len();
+            let info = picture(data).image_info().unwrap();
+            assert_eq!(
+                info,
+                ImageInfo {
+                    format,
+                    width,
+                    height,
+                    size
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn unrecognized_or_truncated() {
+        assert_eq!(picture(b"plain text".to_vec()).image_info(), None);
+        assert_eq!(picture(b"\xFF\xD8\xFF\xE0\x00".to_vec()).image_info(), None);
+        let mut pic = picture(b"GIF89a".to_vec());
+        assert_eq!(pic.image_format(), Some(ImageFormat::Gif));
+        assert_eq!(pic.image_info(), None);
+        assert!(pic.fix_mime_type());
+        assert_eq!(pic.mime_type, "image/gif");
+
+        let mut pic = picture(b"plain text".to_vec());
+        pic.mime_type = "image/png".to_string();
+        assert!(!pic.fix_mime_type());
+        assert_eq!(pic.mime_type, "image/png");
+    }
+}
@@ -1,19 +1,33 @@
-use crate::error::{Error, ErrorKind};
+use crate::error::{Error, ErrorKind, Warning};
 use crate::stream::encoding::Encoding;
+use crate::stream::tag::UfidPolicy;
 use crate::tag::Version;
+use std::borrow::Cow;
 use std::fmt;
 use std::str;
 
 pub use self::content::{
-    Chapter, Comment, Content, EncapsulatedObject, ExtendedLink, ExtendedText, InvolvedPeopleList,
-    InvolvedPeopleListItem, Lyrics, MpegLocationLookupTable, MpegLocationLookupTableReference,
-    Picture, PictureType, Popularimeter, Private, SynchronisedLyrics, SynchronisedLyricsType,
-    TableOfContents, TimestampFormat, UniqueFileIdentifier, Unknown,
+    AudioSeekPointIndex, ChannelType, Chapter, Comment, Commercial, Content, EncapsulatedObject,
+    EncryptedFrame, EncryptedMeta, EncryptionMethod, Equalisation, EventTimingCodes, EventType,
+    ExtendedLink, ExtendedText, GroupRegistration, InterpolationMethod, InvolvedPeopleList,
+    InvolvedPeopleListItem, KnownPrivate, LegacyChannelVolume, LegacyEqualisation,
+    LegacyVolumeAdjustment, LinkedInformation, Lyrics, MpegLocationLookupTable,
+    MpegLocationLookupTableReference, MusicCdIdentifier, Ownership, Picture, PictureType,
+    Popularimeter, Private, RatingConvention, ReceivedAs, RelativeVolumeAdjustment,
+    ReplayGainScope, SellerLogo, Signature, Skipped, SynchronisedLyrics, SynchronisedLyricsType,
+    SynchronisedTempoCodes, TableOfContents, TermsOfUse, TimestampFormat, TraktorChunk,
+    UniqueFileIdentifier, Unknown, VolumeAdjustment,
 };
-pub use self::timestamp::Timestamp;
+#[cfg(feature = "picture-info")]
+pub use self::image::{ImageFormat, ImageInfo};
+pub use self::timestamp::{Timestamp, TimestampBuilder};
+
+pub(crate) use self::content::ITUNES_SOUND_CHECK;
 
 mod content;
 mod content_cmp;
+#[cfg(feature = "picture-info")]
+mod image;
 mod timestamp;
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -41,6 +55,40 @@ pub struct Frame {
 
 impl Frame {
     /// Check if this Frame is identical to another frame
+    /// Checks whether the frame matches a key of the form `ID` or `ID:description`.
+    ///
+    /// The description is matched against the field that distinguishes frames with the same ID,
+    /// such as the description of TXXX, COMM and APIC frames or the owner of PRIV and UFID frames.
+    pub(crate) fn matches_key(&self, key: &str) -> bool {
+        let (id, description) = match key.split_once(':') {
+            Some((id, description)) => (id, Some(description)),
+            None => (key, None),
+        };
+        if self.id() != id {
+            return false;
+        }
+        let description = match description {
+            Some(description) => description,
+            None => return true,
+        };
+        let frame_description = match &self.content {
+            Content::ExtendedText(c) => &c.description,
+            Content::ExtendedLink(c) => &c.description,
+            Content::Comment(c) => &c.description,
+            Content::Lyrics(c) => &c.description,
+            Content::SynchronisedLyrics(c) => &c.description,
+            Content::Picture(c) => &c.description,
+            Content::EncapsulatedObject(c) => &c.description,
+            Content::Popularimeter(c) => &c.user,
+            Content::Private(c) => &c.owner_identifier,
+            Content::UniqueFileIdentifier(c) => &c.owner_identifier,
+            Content::Chapter(c) => &c.element_id,
+            Content::TableOfContents(c) => &c.element_id,
+            _ => return false,
+        };
+        frame_description == description
+    }
+
     pub(crate) fn compare(&self, other: &Frame) -> bool {
         if self.id == other.id {
             let content_eq = if let ID::Valid(id) = &self.id {
@@ -62,7 +110,74 @@ impl Frame {
         }
     }
 
-    pub(crate) fn validate(&self) -> crate::Result<()> {
+    /// Checks whether the frame can be encoded, returning the frame that should be written.
+    ///
+    /// Limits of the specification on UFID frames are enforced according to `ufid_policy`.
+    pub(crate) fn validate(
+        &self,
+        ufid_policy: UfidPolicy,
+        warnings: &mut Vec<Warning>,
+    ) -> crate::Result<Cow<'_, Frame>> {
+        self.validate_content()?;
+        let ufid = match &self.content {
+            Content::UniqueFileIdentifier(ufid) => ufid,
+            _ => return Ok(Cow::Borrowed(self)),
+        };
+        let mut frame = Cow::Borrowed(self);
+        // (violated, can be repaired by truncation, description)
+        for (violated, truncatable, description) in [
+            (
+                ufid.owner_identifier.is_empty(),
+                false,
+                "UFID owner identifier is empty",
+            ),
+            (
+                ufid.identifier.len() > 64,
+                true,
+                "UFID identifier is longer than 64 bytes",
+            ),
+        ] {
+            if !violated {
+                continue;
+            }
+            match ufid_policy {
+                UfidPolicy::Warn => {}
+                UfidPolicy::Truncate if truncatable => {
+                    if let Content::UniqueFileIdentifier(ufid) = &mut frame.to_mut().content {
+                        ufid.identifier.truncate(64);
+                    }
+                }
+                UfidPolicy::Truncate | UfidPolicy::Error => {
+                    return Err(
+                        Error::new(ErrorKind::InvalidInput, description).with_frame_id(self.id())
+                    );
+                }
+            }
+            warnings.push(Warning::new(description).with_frame_id(self.id()));
+        }
+        Ok(frame)
+    }
+
+    /// Converts an ID3v2.3 frame that was replaced in ID3v2.4 to its replacement. Returns `None`
+    /// for all other frames.
+    pub(crate) fn upgrade_legacy(&self) -> Option<Frame> {
+        let (id, content) = match &self.content {
+            Content::LegacyVolumeAdjustment(rvad) => (
+                "RVA2",
+                Content::RelativeVolumeAdjustment(rvad.to_relative_volume_adjustment()),
+            ),
+            Content::LegacyEqualisation(equa) => {
+                ("EQU2", Content::Equalisation(equa.to_equalisation()))
+            }
+            _ => return None,
+        };
+        let mut frame = Frame::with_content(id, content);
+        frame.set_tag_alter_preservation(self.tag_alter_preservation);
+        frame.set_file_alter_preservation(self.file_alter_preservation);
+        Some(frame)
+    }
+
+    fn validate_content(&self) -> crate::Result<()> {
         // The valid/invalid ID enum exists to be able to read and write back unknown and possibly
         // invalid IDs. If it can be read, it can also be written again.
         let id = match &self.id {
@@ -71,7 +186,9 @@ impl Frame {
         };
         // The matching groups must match the decoding groups of stream/frame/content.rs:decode().
         match (id.as_str(), &self.content) {
-            ("GRP1", Content::Text(_)) => Ok(()),
+            // Any frame can be encrypted.
+            (_, Content::EncryptedFrame(_)) => Ok(()),
+            ("GRP1" | "WFED" | "MVNM" | "MVIN", Content::Text(_)) => Ok(()),
             (id, Content::Text(_)) if id.starts_with('T') && !matches!(id, "TIPL" | "TMCL") => {
                 Ok(())
             }
@@ -90,6 +207,23 @@ impl Frame {
             ("PRIV", Content::Private(_)) => Ok(()),
             ("CTOC", Content::TableOfContents(_)) => Ok(()),
             ("UFID", Content::UniqueFileIdentifier(_)) => Ok(()),
+            ("RVA2", Content::RelativeVolumeAdjustment(_)) => Ok(()),
+            ("ETCO", Content::EventTimingCodes(_)) => Ok(()),
+            ("OWNE", Content::Ownership(_)) => Ok(()),
+            ("COMR", Content::Commercial(_)) => Ok(()),
+            ("USER", Content::TermsOfUse(_)) => Ok(()),
+            ("PCNT", Content::PlayCounter(_)) => Ok(()),
+            ("MCDI", Content::MusicCdIdentifier(_)) => Ok(()),
+            ("LINK", Content::LinkedInformation(_)) => Ok(()),
+            ("SYTC", Content::SynchronisedTempoCodes(_)) => Ok(()),
+            ("ASPI", Content::AudioSeekPointIndex(_)) => Ok(()),
+            ("GRID", Content::GroupRegistration(_)) => Ok(()),
+            ("SIGN", Content::Signature(_)) => Ok(()),
+            ("RVAD", Content::LegacyVolumeAdjustment(_)) => Ok(()),
+            ("EQUA", Content::LegacyEqualisation(_)) => Ok(()),
+            ("EQU2", Content::Equalisation(_)) => Ok(()),
+            ("PCST", Content::PodcastFlag(_)) => Ok(()),
+            ("ENCR", Content::EncryptionMethod(_)) => Ok(()),
             (_, Content::Unknown(_)) => Ok(()),
             (id, content) => {
                 let content_kind = match content {
@@ -109,6 +243,26 @@ impl Frame {
                     Content::TableOfContents(_) => "TableOfContents",
                     Content::UniqueFileIdentifier(_) => "UFID",
                     Content::InvolvedPeopleList(_) => "InvolvedPeopleList",
+                    Content::RelativeVolumeAdjustment(_) => "RelativeVolumeAdjustment",
+                    Content::EventTimingCodes(_) => "EventTimingCodes",
+                    Content::Ownership(_) => "Ownership",
+                    Content::Commercial(_) => "Commercial",
+                    Content::TermsOfUse(_) => "TermsOfUse",
+                    Content::PlayCounter(_) => "PlayCounter",
+                    Content::MusicCdIdentifier(_) => "MusicCdIdentifier",
+                    Content::LinkedInformation(_) => "LinkedInformation",
+                    Content::SynchronisedTempoCodes(_) => "SynchronisedTempoCodes",
+                    Content::AudioSeekPointIndex(_) => "AudioSeekPointIndex",
+                    Content::GroupRegistration(_) => "GroupRegistration",
+                    Content::Signature(_) => "Signature",
+                    Content::LegacyVolumeAdjustment(_) => "LegacyVolumeAdjustment",
+                    Content::LegacyEqualisation(_) => "LegacyEqualisation",
+                    Content::Equalisation(_) => "Equalisation",
+                    Content::PodcastFlag(_) => "PodcastFlag",
+                    Content::EncryptedMeta(_) => "EncryptedMeta",
+                    Content::EncryptionMethod(_) => "EncryptionMethod",
+                    Content::EncryptedFrame(_) => "EncryptedFrame",
+                    Content::Skipped(_) => "Skipped",
                     Content::Unknown(_) => "Unknown",
                 };
                 Err(Error::new(
@@ -227,6 +381,11 @@ impl Frame {
         &self.content
     }
 
+    /// Returns a mutable reference to the content of the frame.
+    pub(crate) fn content_mut(&mut self) -> &mut Content {
+        &mut self.content
+    }
+
     /// Returns whether the tag_alter_preservation flag is set.
     pub fn tag_alter_preservation(&self) -> bool {
         self.tag_alter_preservation
@@ -363,6 +522,24 @@ impl Frame {
             "TSIZ" => "Size",
             "TYER" => "Year",
 
+            // Non-standard ids written by iTunes for compilations
+            "TCMP" => "Compilation",
+            "TCP" => "Compilation",
+
+            // Non-standard ids written by iTunes for classical music
+            "MVIN" => "Movement number",
+            "MVNM" => "Movement name",
+            "MVI" => "Movement number",
+            "MVN" => "Movement name",
+
+            // Non-standard ids written by iTunes for podcasts
+            "PCST" => "Podcast",
+            "TCAT" => "Podcast category",
+            "TDES" => "Podcast description",
+            "TGID" => "Podcast identifier",
+            "TKWD" => "Podcast keywords",
+            "WFED" => "Podcast feed URL",
+
             // Ids and names defined in section 4 of
             // http://id3.org/d3v2-00 which have not been previously
             // defined above
@@ -455,14 +632,14 @@ impl fmt::Display for Frame {
 
 macro_rules! convert_2_to_3_and_back {
     ( $( $id2:expr, $id3:expr ),* ) => {
-        fn convert_id_2_to_3(id: impl AsRef<str>) -> Option<&'static str> {
+        pub(crate) fn convert_id_2_to_3(id: impl AsRef<str>) -> Option<&'static str> {
             match id.as_ref() {
                 $($id2 => Some($id3),)*
                 _ => None,
             }
         }
 
-        fn convert_id_3_to_2(id: impl AsRef<str>) -> Option<&'static str> {
+        pub(crate) fn convert_id_3_to_2(id: impl AsRef<str>) -> Option<&'static str> {
             match id.as_ref() {
                 $($id3 => Some($id2),)*
                 _ => None,
@@ -491,12 +668,14 @@ convert_2_to_3_and_back!(
 
     "MCI", "MCDI",
     "MLL", "MLLT",
+    "MVI", "MVIN",
+    "MVN", "MVNM",
 
     "PIC", "APIC",
     "POP", "POPM",
 
     "REV", "RVRB",
-    "RVA", "RVA2",
+    "RVA", "RVAD",
 
     "SLT", "SYLT",
     "STC", "SYTC",
@@ -505,6 +684,7 @@ convert_2_to_3_and_back!(
     "TBP", "TBPM",
     "TCM", "TCOM",
     "TCO", "TCON",
+    "TCP", "TCMP",
     "TCR", "TCOP",
     "TDA", "TDAT",
     "TDY", "TDLY",
@@ -575,6 +755,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_frame_matches_key() {
+        let title = Frame::text("TIT2", "title");
+        assert!(title.matches_key("TIT2"));
+        assert!(!title.matches_key("TIT2:title"));
+        assert!(!title.matches_key("TALB"));
+
+        let private = Frame::with_content(
+            "PRIV",
+            Content::Private(Private {
+                owner_identifier: "owner".to_owned(),
+                private_data: Vec::new(),
+            }),
+        );
+        assert!(private.matches_key("PRIV"));
+        assert!(private.matches_key("PRIV:owner"));
+        assert!(!private.matches_key("PRIV:other"));
+        assert!(!private.matches_key("PRIV:"));
+    }
+
     #[test]
     fn test_frame_cmp_text() {
         let frame_a = Frame::with_content("TIT2", Content::Text("A".to_owned()));
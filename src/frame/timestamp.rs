@@ -1,3 +1,4 @@
+use crate::error::{Error, ErrorKind};
 use std::cmp;
 use std::convert::TryFrom;
 use std::error;
@@ -24,6 +25,182 @@ pub struct Timestamp {
     pub second: Option<u8>,
 }
 
+impl Timestamp {
+    /// Returns a builder that constructs a validated timestamp.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::Timestamp;
+    ///
+    /// let timestamp = Timestamp::builder().year(2024).month(2).day(29).build().unwrap();
+    /// assert_eq!(timestamp.to_string(), "2024-02-29");
+    ///
+    /// assert!(Timestamp::builder().year(2023).month(2).day(29).build().is_err());
+    /// assert!(Timestamp::builder().year(2024).month(13).build().is_err());
+    /// ```
+    pub fn builder() -> TimestampBuilder {
+        TimestampBuilder::default()
+    }
+
+    /// Returns true if all fields are within their range and the date exists.
+    ///
+    /// Fields must also be set in order of precision, e.g. a day may only be set if the month is
+    /// set too.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::Timestamp;
+    ///
+    /// let mut timestamp: Timestamp = "2024-04-30".parse().unwrap();
+    /// assert!(timestamp.is_valid());
+    /// timestamp.day = Some(31);
+    /// assert!(!timestamp.is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// Parses a timestamp, also accepting notations that are commonly found in the wild but do
+    /// not conform to the ID3v2.4 spec, such as "2014/05/02", "02.05.2014" and "2014-5-2".
+    ///
+    /// Along with the timestamp, a flag is returned that is true if all of the text was
+    /// interpreted. If it is false, some trailing text was ignored. Dates are only accepted if they
+    /// exist and are not ambiguous, so "05/02/2014" is rejected while "13/02/2014" is not.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::Timestamp;
+    ///
+    /// let (timestamp, lossless) = Timestamp::parse_lenient("02.05.2014").unwrap();
+    /// assert_eq!(timestamp.to_string(), "2014-05-02");
+    /// assert!(lossless);
+    ///
+    /// let (timestamp, lossless) = Timestamp::parse_lenient("2014/05/02 (remastered)").unwrap();
+    /// assert_eq!(timestamp.to_string(), "2014-05-02");
+    /// assert!(!lossless);
+    /// ```
+    pub fn parse_lenient(text: &str) -> Option<(Timestamp, bool)> {
+        let mut parser = Parser(text);
+        let timestamp = parser.parse_lenient().ok()?;
+        if !timestamp.is_valid() {
+            return None;
+        }
+        Some((timestamp, parser.0.trim().is_empty()))
+    }
+
+    fn validate(&self) -> Result<(), &'static str> {
+        let fields = [self.month, self.day, self.hour, self.minute, self.second];
+        if fields
+            .windows(2)
+            .any(|pair| pair[0].is_none() && pair[1].is_some())
+        {
+            return Err("a timestamp field is set while a less precise field is not");
+        }
+        if let Some(month) = self.month {
+            if !(1..=12).contains(&month) {
+                return Err("month is out of range");
+            }
+            if let Some(day) = self.day {
+                if day < 1 || day > days_in_month(self.year, month) {
+                    return Err("day does not exist in the month");
+                }
+            }
+        }
+        if self.hour.is_some_and(|hour| hour > 23) {
+            return Err("hour is out of range");
+        }
+        if self.minute.is_some_and(|minute| minute > 59) {
+            return Err("minute is out of range");
+        }
+        if self.second.is_some_and(|second| second > 59) {
+            return Err("second is out of range");
+        }
+        Ok(())
+    }
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Builds a [`Timestamp`], checking that the fields describe a date and time that exists.
+///
+/// Created using [`Timestamp::builder`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TimestampBuilder {
+    year: Option<i32>,
+    month: Option<u8>,
+    day: Option<u8>,
+    hour: Option<u8>,
+    minute: Option<u8>,
+    second: Option<u8>,
+}
+
+impl TimestampBuilder {
+    /// Sets the year. This is the only required field.
+    pub fn year(mut self, year: i32) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    /// Sets the month, starting at 1 for January.
+    pub fn month(mut self, month: u8) -> Self {
+        self.month = Some(month);
+        self
+    }
+
+    /// Sets the day of the month, starting at 1.
+    pub fn day(mut self, day: u8) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    /// Sets the hour, from 0 to 23.
+    pub fn hour(mut self, hour: u8) -> Self {
+        self.hour = Some(hour);
+        self
+    }
+
+    /// Sets the minute, from 0 to 59.
+    pub fn minute(mut self, minute: u8) -> Self {
+        self.minute = Some(minute);
+        self
+    }
+
+    /// Sets the second, from 0 to 59.
+    pub fn second(mut self, second: u8) -> Self {
+        self.second = Some(second);
+        self
+    }
+
+    /// Validates the fields and returns the timestamp.
+    ///
+    /// An error of kind [`ErrorKind::InvalidInput`] is returned if the year is not set, if a field
+    /// is set without the less precise fields or if the date or time does not exist.
+    pub fn build(self) -> crate::Result<Timestamp> {
+        let year = self
+            .year
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "timestamp year is not set"))?;
+        let timestamp = Timestamp {
+            year,
+            month: self.month,
+            day: self.day,
+            hour: self.hour,
+            minute: self.minute,
+            second: self.second,
+        };
+        timestamp
+            .validate()
+            .map_err(|description| Error::new(ErrorKind::InvalidInput, description))?;
+        Ok(timestamp)
+    }
+}
+
 impl Ord for Timestamp {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.year
@@ -96,6 +273,90 @@ impl Parser<'_> {
         Ok(timestamp)
     }
 
+    /// Parses a date in one of the formats that are commonly found in the wild, leaving any
+    /// text that could not be interpreted in the parser.
+    fn parse_lenient(&mut self) -> Result<Timestamp, ()> {
+        self.skip_leading_whitespace();
+        let (first, first_digits) = self.parse_digits()?;
+        let sep = match self.0.chars().next() {
+            Some(c @ ('-' | '/' | '.')) => c,
+            _ => {
+                // Only a year.
+                if first_digits < 4 {
+                    return Err(());
+                }
+                let year = i32::try_from(first).map_err(|_| ())?;
+                return Ok(Timestamp {
+                    year,
+                    ..Timestamp::default()
+                });
+            }
+        };
+        let mut parts = vec![(first, first_digits)];
+        while parts.len() < 3 && self.0.starts_with(sep) {
+            let rest = self.0;
+            self.0 = &self.0[1..];
+            match self.parse_digits() {
+                Ok(part) => parts.push(part),
+                Err(()) => {
+                    self.0 = rest;
+                    break;
+                }
+            }
+        }
+
+        let small = |n: u32| u8::try_from(n).map_err(|_| ());
+        let (year, month, day) = match parts[..] {
+            [(year, 4..), (month, _)] => (year, small(month)?, None),
+            [(year, 4..), (month, _), (day, _)] => (year, small(month)?, Some(small(day)?)),
+            [(month, ..=2), (year, 4..)] => (year, small(month)?, None),
+            [(a, ..=2), (b, ..=2), (year, 4..)] => {
+                let (a, b) = (small(a)?, small(b)?);
+                // Dots are used in day-first notation. With other separators, the order of the
+                // month and day is only known if one of them can not be a month.
+                let (month, day) = if sep == '.' || a > 12 {
+                    (b, a)
+                } else if b > 12 {
+                    (a, b)
+                } else {
+                    return Err(());
+                };
+                (year, month, Some(day))
+            }
+            _ => return Err(()),
+        };
+        let mut timestamp = Timestamp {
+            year: i32::try_from(year).map_err(|_| ())?,
+            month: Some(month),
+            day,
+            ..Timestamp::default()
+        };
+
+        if day.is_some() && self.0.starts_with(['T', ' ']) {
+            let rest = self.0;
+            self.0 = &self.0[1..];
+            let mut parse_time = || -> Result<(), ()> {
+                timestamp.hour = Some(self.parse_other()?);
+                self.expect(b':')?;
+                timestamp.minute = Some(self.parse_other()?);
+                let rest = self.0;
+                if self.expect(b':').is_ok() {
+                    match self.parse_other() {
+                        Ok(second) => timestamp.second = Some(second),
+                        Err(()) => self.0 = rest,
+                    }
+                }
+                Ok(())
+            };
+            if parse_time().is_err() {
+                timestamp.hour = None;
+                timestamp.minute = None;
+                self.0 = rest;
+            }
+        }
+        Ok(timestamp)
+    }
+
     fn skip_leading_whitespace(&mut self) {
         self.0 = self.0.trim_start();
     }
@@ -123,10 +384,15 @@ impl Parser<'_> {
     }
 
     fn parse_number(&mut self) -> Result<u32, ()> {
-        let mut ok = false;
+        self.parse_digits().map(|(n, _)| n)
+    }
+
+    /// Parses a number, returning its value and the number of digits.
+    fn parse_digits(&mut self) -> Result<(u32, usize), ()> {
+        let mut digits = 0;
         let mut r = 0u32;
         while self.0.starts_with(|c: char| c.is_ascii_digit()) {
-            ok = true;
+            digits += 1;
             r = if let Some(r) = r
                 .checked_mul(10)
                 .and_then(|r| r.checked_add(u32::from(self.0.as_bytes()[0] - b'0')))
@@ -137,8 +403,8 @@ impl Parser<'_> {
             };
             self.0 = &self.0[1..];
         }
-        if ok {
-            Ok(r)
+        if digits > 0 {
+            Ok((r, digits))
         } else {
             Err(())
         }
@@ -349,6 +615,79 @@ fn test_parse_timestamp() {
     );
 }
 
+#[test]
+fn test_timestamp_builder() {
+    let timestamp = Timestamp::builder()
+        .year(1989)
+        .month(12)
+        .day(27)
+        .hour(9)
+        .minute(15)
+        .second(30)
+        .build()
+        .unwrap();
+    assert_eq!(timestamp, "1989-12-27T09:15:30".parse().unwrap());
+    assert!(timestamp.is_valid());
+
+    assert!(Timestamp::builder().month(1).build().is_err());
+    assert!(Timestamp::builder().year(1989).day(1).build().is_err());
+    assert!(Timestamp::builder().year(1989).month(0).build().is_err());
+    assert!(Timestamp::builder()
+        .year(1900)
+        .month(2)
+        .day(29)
+        .build()
+        .is_err());
+    assert!(Timestamp::builder()
+        .year(2000)
+        .month(2)
+        .day(29)
+        .build()
+        .is_ok());
+    assert!(Timestamp::builder()
+        .year(1989)
+        .month(1)
+        .day(1)
+        .hour(24)
+        .build()
+        .is_err());
+    assert!(!"1989-12-27T09:60".parse::<Timestamp>().unwrap().is_valid());
+}
+
+#[test]
+fn test_parse_timestamp_lenient() {
+    let parse = |text: &str| {
+        Timestamp::parse_lenient(text)
+            .map(|(timestamp, lossless)| (timestamp.to_string(), lossless))
+    };
+    assert_eq!(parse("2014"), Some(("2014".to_string(), true)));
+    assert_eq!(parse("2014 "), Some(("2014".to_string(), true)));
+    assert_eq!(parse("2014-5-2"), Some(("2014-05-02".to_string(), true)));
+    assert_eq!(parse("2014/05/02"), Some(("2014-05-02".to_string(), true)));
+    assert_eq!(parse("2014.05"), Some(("2014-05".to_string(), true)));
+    assert_eq!(parse("05/2014"), Some(("2014-05".to_string(), true)));
+    assert_eq!(parse("02.05.2014"), Some(("2014-05-02".to_string(), true)));
+    assert_eq!(parse("13/02/2014"), Some(("2014-02-13".to_string(), true)));
+    assert_eq!(parse("02/13/2014"), Some(("2014-02-13".to_string(), true)));
+    assert_eq!(parse("05/02/2014"), None);
+    assert_eq!(
+        parse("2014/05/02 09:15:30"),
+        Some(("2014-05-02T09:15:30".to_string(), true))
+    );
+    assert_eq!(
+        parse("1989-12-27T09:15"),
+        Some(("1989-12-27T09:15".to_string(), true))
+    );
+    assert_eq!(
+        parse("2014-05-02 live"),
+        Some(("2014-05-02".to_string(), false))
+    );
+    assert_eq!(parse("2014 remaster"), Some(("2014".to_string(), false)));
+    assert_eq!(parse("2014-02-30"), None);
+    assert_eq!(parse("14"), None);
+    assert_eq!(parse("December 1989"), None);
+}
+
 #[test]
 fn test_encode_timestamp() {
     assert_eq!("1989".parse::<Timestamp>().unwrap().to_string(), "1989");
@@ -0,0 +1,433 @@
+use crate::stream::unsynch;
+use crate::tag::Version;
+use crate::{Error, ErrorKind};
+use byteorder::{BigEndian, ByteOrder};
+use flate2::read::ZlibDecoder;
+use std::fmt;
+use std::io::{self, Read};
+
+/// The raw structure of an ID3v2 tag, as reported by [`inspect`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TagReport {
+    /// The version of the tag.
+    pub version: Version,
+    /// The raw bytes of the tag header.
+    pub header: [u8; 10],
+    /// The flags byte of the tag header.
+    pub flags: u8,
+    /// The size of the tag as declared in the header, excluding the header itself.
+    pub size: u32,
+    /// Whether the unsynchronisation flag is set in the tag header.
+    pub unsynchronised: bool,
+    /// The size of the extended header, if present.
+    pub extended_header_size: Option<usize>,
+    /// The frames of the tag, in the order they are stored.
+    pub frames: Vec<FrameReport>,
+    /// The number of bytes after the last frame.
+    pub padding: usize,
+}
+
+/// The raw structure of a single frame, as reported by [`inspect`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameReport {
+    /// The offset of the frame header from the start of the tag header.
+    ///
+    /// If unsynchronisation is applied to the whole tag (ID3v2.2 and ID3v2.3), the offset is
+    /// relative to the tag data with unsynchronisation removed.
+    pub offset: usize,
+    /// The frame ID. Bytes that are not valid UTF-8 are replaced.
+    pub id: String,
+    /// The raw bytes of the frame header.
+    pub header: Vec<u8>,
+    /// The raw frame flags. Always 0 for ID3v2.2.
+    pub flags: u16,
+    /// The size of the frame as declared in the frame header, excluding the header itself.
+    pub size: u32,
+    /// For ID3v2.4, whether the declared size is not a valid synchsafe integer. This is a common
+    /// bug of encoders that write ID3v2.3 sizes in ID3v2.4 tags.
+    pub size_not_synchsafe: bool,
+    /// The number of bytes of the frame that are actually present in the tag. This is less than
+    /// `size` if the frame extends past the end of the tag.
+    pub available_size: usize,
+    /// The size of the content after unsynchronisation and compression have been undone, or
+    /// `None` if the content is encrypted or could not be decompressed.
+    pub content_size: Option<usize>,
+    /// The text encoding byte, for frames whose content starts with one.
+    pub encoding: Option<u8>,
+    /// Whether unsynchronisation is applied to the frame, either by the frame flags or by the
+    /// tag header.
+    pub unsynchronised: bool,
+    /// Whether the frame is compressed.
+    pub compressed: bool,
+    /// Whether the frame is encrypted.
+    pub encrypted: bool,
+    /// Whether the frame has a grouping identity.
+    pub grouped: bool,
+    /// The decompressed size stored in the frame, for compressed ID3v2.3 frames and ID3v2.4
+    /// frames with a data length indicator.
+    pub data_length: Option<u32>,
+}
+
+/// Walks the ID3v2 tag read from `reader` and reports the raw structure of the tag and every
+/// frame without interpreting their content.
+///
+/// This is intended for debugging interoperability issues with other software. The [`Display`]
+/// implementation of [`TagReport`] produces a human readable dump.
+///
+/// [`Display`]: fmt::Display
+///
+/// # Example
+/// ```
+/// use id3::{Tag, TagLike, Version};
+///
+/// let mut tag = Tag::new();
+/// tag.set_title("Title");
+/// let bytes = tag.to_bytes(Version::Id3v24)?;
+///
+/// let report = id3::inspect::inspect(&bytes[..])?;
+/// assert_eq!(report.frames[0].id, "TIT2");
+/// assert_eq!(report.frames[0].encoding, Some(3));
+/// println!("{}", report);
+/// # Ok::<(), id3::Error>(())
+/// ```
+pub fn inspect(mut reader: impl io::Read) -> crate::Result<TagReport> {
+    let mut header = [0; 10];
+    reader.read_exact(&mut header)?;
+    if &header[..3] != b"ID3" {
+        return Err(Error::new(
+            ErrorKind::NoTag,
+            "reader does not contain an id3 tag",
+        ));
+    }
+    let version = match header[3] {
+        2 => Version::Id3v22,
+        3 => Version::Id3v23,
+        4 => Version::Id3v24,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::UnsupportedVersion {
+                    major: header[3],
+                    minor: header[4],
+                },
+                "unsupported id3 tag version",
+            ))
+        }
+    };
+    let flags = header[5];
+    let size = unsynch::decode_u32(BigEndian::read_u32(&header[6..10]));
+    let unsynchronised = flags & 0x80 != 0;
+
+    let mut data = Vec::new();
+    reader.take(u64::from(size)).read_to_end(&mut data)?;
+    if unsynchronised && version != Version::Id3v24 {
+        data = remove_unsynchronisation(&data);
+    }
+
+    let mut pos = 0;
+    let mut extended_header_size = None;
+    if flags & 0x40 != 0 && version != Version::Id3v22 && data.len() >= 4 {
+        let raw = BigEndian::read_u32(&data[..4]);
+        let ext_size = match version {
+            Version::Id3v23 => raw as usize + 4,
+            _ => unsynch::decode_u32(raw) as usize,
+        };
+        extended_header_size = Some(ext_size);
+        pos = ext_size.min(data.len());
+    }
+
+    let header_len = match version {
+        Version::Id3v22 => 6,
+        _ => 10,
+    };
+    let mut frames = Vec::new();
+    while pos + header_len <= data.len() && data[pos] != 0 {
+        let frame = inspect_frame(
+            version,
+            unsynchronised,
+            &data[pos..pos + header_len],
+            &data[pos + header_len..],
+        );
+        frames.push(FrameReport {
+            offset: header.len() + pos,
+            ..frame
+        });
+        pos += header_len + frame.available_size;
+    }
+
+    Ok(TagReport {
+        version,
+        header,
+        flags,
+        size,
+        unsynchronised,
+        extended_header_size,
+        frames,
+        padding: data.len() - pos.min(data.len()),
+    })
+}
+
+/// Inspects a single frame. `rest` holds all tag data after the frame header.
+fn inspect_frame(version: Version, tag_unsynch: bool, header: &[u8], rest: &[u8]) -> FrameReport {
+    let (id, size, flags, size_not_synchsafe) = match version {
+        Version::Id3v22 => {
+            let size = BigEndian::read_u24(&header[3..6]);
+            (&header[..3], size, 0, false)
+        }
+        Version::Id3v23 => {
+            let size = BigEndian::read_u32(&header[4..8]);
+            (
+                &header[..4],
+                size,
+                BigEndian::read_u16(&header[8..10]),
+                false,
+            )
+        }
+        Version::Id3v24 => {
+            let raw = BigEndian::read_u32(&header[4..8]);
+            let size = unsynch::decode_u32(raw);
+            let flags = BigEndian::read_u16(&header[8..10]);
+            (&header[..4], size, flags, raw & 0x8080_8080 != 0)
+        }
+    };
+    let available = &rest[..(size as usize).min(rest.len())];
+
+    let (compressed, encrypted, grouped, frame_unsynch, has_dli) = match version {
+        Version::Id3v22 => (false, false, false, false, false),
+        Version::Id3v23 => (
+            flags & 0x0080 != 0,
+            flags & 0x0040 != 0,
+            flags & 0x0020 != 0,
+            false,
+            flags & 0x0080 != 0,
+        ),
+        Version::Id3v24 => (
+            flags & 0x0008 != 0,
+            flags & 0x0004 != 0,
+            flags & 0x0040 != 0,
+            flags & 0x0002 != 0,
+            flags & 0x0001 != 0,
+        ),
+    };
+
+    // The additional data that follows the frame header depends on the flags and its order
+    // differs between versions.
+    let mut extra = 0;
+    let mut data_length = None;
+    let mut read_data_length = |extra: &mut usize| {
+        if let Some(b) = available.get(*extra..*extra + 4) {
+            let raw = BigEndian::read_u32(b);
+            data_length = Some(match version {
+                Version::Id3v24 => unsynch::decode_u32(raw),
+                _ => raw,
+            });
+        }
+        *extra += 4;
+    };
+    match version {
+        Version::Id3v22 => {}
+        Version::Id3v23 => {
+            if has_dli {
+                read_data_length(&mut extra);
+            }
+            extra += usize::from(encrypted) + usize::from(grouped);
+        }
+        Version::Id3v24 => {
+            extra += usize::from(grouped) + usize::from(encrypted);
+            if has_dli {
+                read_data_length(&mut extra);
+            }
+        }
+    }
+
+    let content_size;
+    let mut encoding = None;
+    if encrypted {
+        content_size = None;
+    } else {
+        let mut content = available.get(extra..).unwrap_or_default().to_vec();
+        if frame_unsynch {
+            content = remove_unsynchronisation(&content);
+        }
+        if compressed {
+            let mut decompressed = Vec::new();
+            content = match ZlibDecoder::new(&content[..]).read_to_end(&mut decompressed) {
+                Ok(_) => decompressed,
+                Err(_) => Vec::new(),
+            };
+            content_size = Some(content.len()).filter(|&len| len > 0);
+        } else {
+            content_size = Some(content.len());
+        }
+        if content_size.is_some() && has_encoding_byte(id) {
+            encoding = content.first().copied();
+        }
+    }
+
+    FrameReport {
+        offset: 0,
+        id: String::from_utf8_lossy(id).into_owned(),
+        header: header.to_vec(),
+        flags,
+        size,
+        size_not_synchsafe,
+        available_size: available.len(),
+        content_size,
+        encoding,
+        unsynchronised: frame_unsynch || (tag_unsynch && version != Version::Id3v24),
+        compressed,
+        encrypted,
+        grouped,
+        data_length,
+    }
+}
+
+fn remove_unsynchronisation(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    // Reading from a slice can not fail.
+    let _ = unsynch::Reader::new(data).read_to_end(&mut out);
+    out
+}
+
+/// Returns whether the content of a frame with the specified ID starts with a text encoding byte.
+fn has_encoding_byte(id: &[u8]) -> bool {
+    matches!(
+        id,
+        [b'T', ..]
+            | b"WXXX"
+            | b"COMM"
+            | b"USLT"
+            | b"APIC"
+            | b"GEOB"
+            | b"SYLT"
+            | b"IPLS"
+            | b"USER"
+            | b"COMR"
+            | b"OWNE"
+            | b"WXX"
+            | b"COM"
+            | b"ULT"
+            | b"PIC"
+            | b"GEO"
+            | b"SLT"
+            | b"IPL"
+    )
+}
+
+impl fmt::Display for TagReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} tag, {} bytes, flags {:#04x}, header {:02x?}",
+            self.version, self.size, self.flags, self.header
+        )?;
+        if let Some(size) = self.extended_header_size {
+            writeln!(f, "  extended header: {} bytes", size)?;
+        }
+        for frame in &self.frames {
+            writeln!(f, "  {}", frame)?;
+        }
+        write!(f, "  padding: {} bytes", self.padding)
+    }
+}
+
+impl fmt::Display for FrameReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} @{}: size {}", self.id, self.offset, self.size)?;
+        if self.available_size != self.size as usize {
+            write!(f, " (only {} present)", self.available_size)?;
+        }
+        if self.size_not_synchsafe {
+            write!(f, " (not synchsafe)")?;
+        }
+        match self.content_size {
+            Some(size) => write!(f, ", content {}", size)?,
+            None => write!(f, ", content unknown")?,
+        }
+        write!(f, ", flags {:#06x}", self.flags)?;
+        if let Some(encoding) = self.encoding {
+            write!(f, ", encoding {}", encoding)?;
+        }
+        for (set, name) in [
+            (self.unsynchronised, "unsynchronised"),
+            (self.compressed, "compressed"),
+            (self.encrypted, "encrypted"),
+            (self.grouped, "grouped"),
+        ] {
+            if set {
+                write!(f, ", {}", name)?;
+            }
+        }
+        if let Some(length) = self.data_length {
+            write!(f, ", data length {}", length)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Encoder, Tag, TagLike};
+    use std::fs;
+
+    #[test]
+    fn inspect_testdata() {
+        let report = inspect(fs::File::open("testdata/id3v24.id3").unwrap()).unwrap();
+        assert_eq!(report.version, Version::Id3v24);
+        assert!(!report.frames.is_empty());
+        let tag = Tag::read_from_path("testdata/id3v24.id3").unwrap();
+        assert_eq!(report.frames.len(), tag.frames().count());
+        for (frame, report) in tag.frames().zip(&report.frames) {
+            assert_eq!(frame.id(), report.id);
+            assert_eq!(report.available_size, report.size as usize);
+        }
+
+        let report = inspect(fs::File::open("testdata/id3v22.id3").unwrap()).unwrap();
+        assert_eq!(report.version, Version::Id3v22);
+        assert!(report.frames.iter().all(|f| f.header.len() == 6));
+    }
+
+    #[test]
+    fn inspect_unsynchronised() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        for version in [Version::Id3v23, Version::Id3v24] {
+            let mut buf = Vec::new();
+            Encoder::new()
+                .version(version)
+                .unsynchronisation(true)
+                .encode(&tag, &mut buf)
+                .unwrap();
+            let report = inspect(&buf[..]).unwrap();
+            assert!(report.unsynchronised);
+            let frame = &report.frames[0];
+            assert_eq!(frame.id, "TIT2");
+            assert_eq!(frame.offset, 10);
+            assert!(frame.unsynchronised);
+            assert!(!frame.compressed);
+            assert!(frame.encoding.is_some());
+            assert!(frame.to_string().starts_with("TIT2 @10"));
+        }
+    }
+
+    #[test]
+    fn inspect_truncated() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        let mut buf = tag.to_bytes(Version::Id3v23).unwrap();
+        // Claim a larger frame than is present.
+        buf[10 + 7] += 10;
+        let report = inspect(&buf[..]).unwrap();
+        assert_eq!(report.frames.len(), 1);
+        assert_eq!(
+            report.frames[0].available_size + 10,
+            report.frames[0].size as usize
+        );
+        assert_eq!(report.padding, 0);
+
+        assert!(matches!(
+            inspect(&b"not an id3 tag"[..]).unwrap_err().kind,
+            ErrorKind::NoTag
+        ));
+    }
+}
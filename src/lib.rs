@@ -7,16 +7,37 @@
 // * ID3v2.3 <http://id3.org/id3v2.3.0>
 // * ID3v2.4 <http://id3.org/id3v2.4.0-structure>
 
-pub use crate::error::{no_tag_ok, partial_tag_ok, Error, ErrorKind, Result};
+pub use crate::error::{no_tag_ok, partial_tag_ok, Error, ErrorKind, Result, Warning};
 pub use crate::frame::{Content, Frame, Timestamp};
-pub use crate::storage::StorageFile;
+#[cfg(feature = "tokio")]
+pub use crate::storage::AsyncStorageFile;
+pub use crate::storage::{Format, StorageFile};
 pub use crate::stream::encoding::Encoding;
-pub use crate::stream::tag::Encoder;
-pub use crate::tag::{Tag, Version};
-pub use crate::taglike::TagLike;
+pub use crate::stream::tag::{
+    AlterProfile, ChunkId, Decoder, EncapsulatedObjectReader, Encoder, EncodingPolicy,
+    PaddingPolicy, Placement, UfidPolicy, WriteOutcome,
+};
+pub use crate::tag::{
+    ChapterIndex, ImageSizeRestriction, Tag, TagRestrictions, TagSizeRestriction,
+    TextSizeRestriction, Version,
+};
+pub use crate::tagged_file::{read_tagged_file, TagKind, TagLocation, TaggedFile};
+pub use crate::taglike::{FrameEntry, TagLike};
 
 /// Contains types and methods for operating on ID3 frames.
 pub mod frame;
+/// Low-level inspection of the raw structure of tags, for debugging.
+pub mod inspect;
+/// Walking MPEG audio frames to determine the exact duration and build seek tables.
+#[cfg(feature = "mpeg-scan")]
+pub mod mpeg;
+/// Reading and writing of the INFO list of WAV files, and conversion to and from ID3 tags.
+pub mod riff_info;
+pub mod storage;
+/// Parsing of genres (TCON) and conversion between ID3v1 genre indices and names.
+pub mod tcon;
+/// Synchsafe integers and the unsynchronisation scheme, for use with raw ID3 and MPEG data.
+pub mod util;
 /// Utilities for working with ID3v1 tags.
 pub mod v1;
 /// Combined API that handles both ID3v1 and ID3v2 tags at the same time.
@@ -24,8 +45,7 @@ pub mod v1v2;
 
 mod chunk;
 mod error;
-mod storage;
 mod stream;
 mod tag;
+mod tagged_file;
 mod taglike;
-mod tcon;
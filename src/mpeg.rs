@@ -0,0 +1,476 @@
+use crate::frame::{MpegLocationLookupTable, MpegLocationLookupTableReference};
+use crate::stream::mpeg::{FrameHeader, Layer, MpegVersion};
+use crate::stream::unsynch;
+use crate::tag::Tag;
+use crate::taglike::TagLike;
+use crate::{Error, ErrorKind};
+use byteorder::{BigEndian, ByteOrder};
+use std::fs;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::time::Duration;
+
+/// The result of walking the MPEG audio frames of a file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Scan {
+    /// The playing time of the audio, computed from the number of samples in every frame.
+    pub duration: Duration,
+    /// The number of audio frames. A Xing, Info or VBRI header frame is not counted.
+    pub frames: u64,
+    /// The sample rate of the audio in Hz.
+    pub sample_rate: u32,
+    /// Whether the audio has a variable bitrate, either because the frames differ in bitrate or
+    /// because the file has a Xing header.
+    pub vbr: bool,
+}
+
+impl Scan {
+    /// Returns the duration in milliseconds, as stored in the TLEN frame.
+    pub fn duration_ms(&self) -> u32 {
+        self.duration.as_millis().min(u32::MAX as u128) as u32
+    }
+}
+
+/// Walks the MPEG audio frames read from `reader` to determine the exact duration of the audio.
+///
+/// Any ID3v2 tags in the stream are skipped, as is data that can not be recognized as an MPEG
+/// audio frame.
+///
+/// # Example
+/// ```
+/// use std::fs::File;
+///
+/// let scan = id3::mpeg::scan(File::open("testdata/quiet.mp3")?)?;
+/// assert_eq!(scan.duration_ms(), 1044);
+/// assert!(!scan.vbr);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn scan(reader: impl io::Read) -> crate::Result<Scan> {
+    let mut frames = Frames::new(BufReader::new(reader));
+    let mut first_bitrate = None;
+    let mut frame_count = 0u64;
+    let mut samples = 0u64;
+    let mut sample_rate = 0;
+    let mut vbr = false;
+    let mut first = true;
+    while let Some(AudioFrame { header, .. }) = frames.next_frame()? {
+        if first {
+            first = false;
+            match frames.vbr_header() {
+                Some(VbrHeader::Xing) => {
+                    vbr = true;
+                    continue;
+                }
+                Some(VbrHeader::Info) => continue,
+                None => {}
+            }
+        }
+        let bitrate = *first_bitrate.get_or_insert(header.bitrate);
+        vbr |= bitrate != header.bitrate;
+        sample_rate = header.sample_rate;
+        samples += u64::from(header.samples());
+        frame_count += 1;
+    }
+    if frame_count == 0 {
+        return Err(Error::new(ErrorKind::Parsing, "no MPEG audio frames found"));
+    }
+    let sample_rate64 = u64::from(sample_rate);
+    let duration = Duration::new(
+        samples / sample_rate64,
+        (samples % sample_rate64 * 1_000_000_000 / sample_rate64) as u32,
+    );
+    Ok(Scan {
+        duration,
+        frames: frame_count,
+        sample_rate,
+        vbr,
+    })
+}
+
+/// Walks the MPEG audio frames of the file at the indicated path. See [`scan`].
+pub fn scan_path(path: impl AsRef<Path>) -> crate::Result<Scan> {
+    scan(fs::File::open(path)?)
+}
+
+/// Scans the audio of the file at the indicated path and sets the duration (TLEN) of `tag` to
+/// the exact playing time.
+///
+/// The tag is not written to the file.
+///
+/// # Example
+/// ```
+/// use id3::{Tag, TagLike};
+///
+/// let mut tag = Tag::new();
+/// let scan = id3::mpeg::update_duration(&mut tag, "testdata/quiet.mp3")?;
+/// assert_eq!(tag.duration(), Some(scan.duration_ms()));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn update_duration(tag: &mut Tag, path: impl AsRef<Path>) -> crate::Result<Scan> {
+    let scan = scan_path(path)?;
+    tag.set_duration(scan.duration_ms());
+    Ok(scan)
+}
+
+/// Builds an MLLT seek table from the MPEG audio frames read from `reader`, with a reference
+/// point every `frames_between_reference` audio frames.
+///
+/// Byte offsets in the table are relative to the first audio frame. A Xing, Info or VBRI header
+/// frame that precedes the audio is not part of the table. Audio frames after the last complete
+/// group of `frames_between_reference` frames are not covered by a reference.
+///
+/// # Example
+/// ```
+/// use id3::{Tag, TagLike};
+/// use std::fs::File;
+///
+/// let table = id3::mpeg::location_lookup_table(File::open("testdata/quiet.mp3")?, 10)?;
+/// assert_eq!(table.references.len(), 4);
+///
+/// let mut tag = Tag::new();
+/// tag.add_frame(table);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn location_lookup_table(
+    reader: impl io::Read,
+    frames_between_reference: u16,
+) -> crate::Result<MpegLocationLookupTable> {
+    if frames_between_reference == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "frames between MLLT references must be > 0",
+        ));
+    }
+    let mut frames = Frames::new(BufReader::new(reader));
+    // The byte offset and time in milliseconds of every reference point.
+    let mut points = Vec::new();
+    let mut start = None;
+    let mut end = 0;
+    let mut frame_count = 0u64;
+    let mut samples = 0u64;
+    let mut sample_rate = 0;
+    let mut first = true;
+    while let Some(frame) = frames.next_frame()? {
+        if first {
+            first = false;
+            if frames.vbr_header().is_some() {
+                continue;
+            }
+        }
+        let start = *start.get_or_insert(frame.offset);
+        sample_rate = u64::from(frame.header.sample_rate);
+        if frame_count % u64::from(frames_between_reference) == 0 {
+            points.push((frame.offset - start, samples * 1000 / sample_rate));
+        }
+        samples += u64::from(frame.header.samples());
+        frame_count += 1;
+        end = frame.offset + frame.header.size() as u64 - start;
+    }
+    if frame_count == 0 {
+        return Err(Error::new(ErrorKind::Parsing, "no MPEG audio frames found"));
+    }
+    if frame_count % u64::from(frames_between_reference) == 0 {
+        points.push((end, samples * 1000 / sample_rate));
+    }
+
+    let intervals: Vec<(u64, u64)> = points
+        .windows(2)
+        .map(|w| (w[1].0 - w[0].0, w[1].1 - w[0].1))
+        .collect();
+    let bytes_between_reference = intervals.iter().map(|i| i.0).min().unwrap_or(0);
+    let millis_between_reference = intervals.iter().map(|i| i.1).min().unwrap_or(0);
+    if bytes_between_reference > 0xff_ffff || millis_between_reference > 0xff_ffff {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "MLLT reference spacing does not fit in 24 bits",
+        ));
+    }
+    let references: Vec<_> = intervals
+        .iter()
+        .map(|&(bytes, millis)| MpegLocationLookupTableReference {
+            deviate_bytes: (bytes - bytes_between_reference) as u32,
+            deviate_millis: (millis - millis_between_reference) as u32,
+        })
+        .collect();
+    // The deviations are stored using the smallest multiple of 4 bits that fits all of them.
+    let bits_for = |max: u32| ((32 - max.leading_zeros() + 3) / 4 * 4).max(4) as u8;
+    Ok(MpegLocationLookupTable {
+        frames_between_reference,
+        bytes_between_reference: bytes_between_reference as u32,
+        millis_between_reference: millis_between_reference as u32,
+        bits_for_bytes: bits_for(
+            references
+                .iter()
+                .map(|r| r.deviate_bytes)
+                .max()
+                .unwrap_or(0),
+        ),
+        bits_for_millis: bits_for(
+            references
+                .iter()
+                .map(|r| r.deviate_millis)
+                .max()
+                .unwrap_or(0),
+        ),
+        references,
+    })
+}
+
+/// Builds an MLLT seek table from the file at the indicated path. See [`location_lookup_table`].
+pub fn location_lookup_table_path(
+    path: impl AsRef<Path>,
+    frames_between_reference: u16,
+) -> crate::Result<MpegLocationLookupTable> {
+    location_lookup_table(fs::File::open(path)?, frames_between_reference)
+}
+
+impl FrameHeader {
+    /// The size of the Layer III side information that follows the header.
+    fn side_info_size(&self) -> usize {
+        match (self.version, self.mono) {
+            (MpegVersion::Mpeg1, false) => 32,
+            (MpegVersion::Mpeg1, true) | (_, false) => 17,
+            (_, true) => 9,
+        }
+    }
+
+    /// Whether a frame with the `other` header can be part of the same stream.
+    fn same_stream(&self, other: &FrameHeader) -> bool {
+        self.version == other.version
+            && self.layer == other.layer
+            && self.sample_rate == other.sample_rate
+    }
+}
+
+enum VbrHeader {
+    /// A Xing header, which is written for VBR streams.
+    Xing,
+    /// An Info or VBRI header, which does not indicate whether the bitrate is variable.
+    Info,
+}
+
+/// An MPEG audio frame found in a stream.
+struct AudioFrame {
+    /// The offset of the frame header from the start of the stream.
+    offset: u64,
+    header: FrameHeader,
+}
+
+/// Iterates over the MPEG audio frames of a stream.
+struct Frames<R> {
+    reader: R,
+    /// The number of bytes read from `reader`.
+    pos: u64,
+    stream: Option<FrameHeader>,
+    header: [u8; 4],
+    body: Vec<u8>,
+}
+
+impl<R: io::Read> Frames<R> {
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            pos: 0,
+            stream: None,
+            header: [0; 4],
+            body: Vec::new(),
+        }
+    }
+
+    /// Returns the next audio frame, or `None` at the end of the stream. A truncated frame at the
+    /// end of the stream is not returned.
+    fn next_frame(&mut self) -> io::Result<Option<AudioFrame>> {
+        let mut header = [0; 4];
+        if !self.read_full(&mut header)? {
+            return Ok(None);
+        }
+        loop {
+            if &header[..3] == b"ID3" {
+                let mut rest = [0; 6];
+                if !self.read_full(&mut rest)? {
+                    return Ok(None);
+                }
+                let mut size = u64::from(unsynch::decode_u32(BigEndian::read_u32(&rest[2..])));
+                if rest[1] & 0x10 != 0 {
+                    size += 10; // Footer
+                }
+                let skipped = io::copy(&mut (&mut self.reader).take(size), &mut io::sink())?;
+                self.pos += skipped;
+                if skipped < size || !self.read_full(&mut header)? {
+                    return Ok(None);
+                }
+                continue;
+            }
+
+            let parsed = FrameHeader::parse(header)
+                .filter(|h| self.stream.map_or(true, |stream| stream.same_stream(h)));
+            if let Some(parsed) = parsed {
+                let offset = self.pos - header.len() as u64;
+                let mut body = std::mem::take(&mut self.body);
+                body.resize(parsed.size() - header.len(), 0);
+                let complete = self.read_full(&mut body)?;
+                self.body = body;
+                if !complete {
+                    return Ok(None);
+                }
+                self.stream.get_or_insert(parsed);
+                self.header = header;
+                return Ok(Some(AudioFrame {
+                    offset,
+                    header: parsed,
+                }));
+            }
+
+            // Not a frame, try to resynchronize at the next byte.
+            header.rotate_left(1);
+            if !self.read_full(&mut header[3..])? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Fills `buf` completely, returning `false` if the end of the stream was reached first.
+    fn read_full(&mut self, buf: &mut [u8]) -> io::Result<bool> {
+        match self.reader.read_exact(buf) {
+            Ok(()) => {
+                self.pos += buf.len() as u64;
+                Ok(true)
+            }
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Checks whether the frame last returned by `next_frame` carries a Xing, Info or VBRI header
+    /// instead of audio.
+    fn vbr_header(&self) -> Option<VbrHeader> {
+        let header = FrameHeader::parse(self.header)?;
+        if header.layer != Layer::Layer3 {
+            return None;
+        }
+        let side_info = header.side_info_size();
+        match self.body.get(side_info..side_info + 4) {
+            Some(b"Xing") => return Some(VbrHeader::Xing),
+            Some(b"Info") => return Some(VbrHeader::Info),
+            _ => {}
+        }
+        match self.body.get(32..36) {
+            Some(b"VBRI") => Some(VbrHeader::Info),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Builds a stream of silent MPEG-1 Layer III frames with the given bitrate indices.
+    fn make_stream(bitrate_indices: &[u8]) -> Vec<u8> {
+        let mut stream = Vec::new();
+        for &index in bitrate_indices {
+            let header = [0xff, 0xfb, index << 4, 0x00];
+            let size = FrameHeader::parse(header).unwrap().size();
+            stream.extend(header);
+            stream.resize(stream.len() + size - header.len(), 0);
+        }
+        stream
+    }
+
+    #[test]
+    fn frame_header_size() {
+        let header = FrameHeader::parse([0xff, 0xfb, 0x90, 0x00]).unwrap();
+        assert_eq!(header.bitrate, 128_000);
+        assert_eq!(header.sample_rate, 44100);
+        assert_eq!(header.size(), 417);
+        let header = FrameHeader::parse([0xff, 0xfb, 0x92, 0x00]).unwrap();
+        assert_eq!(header.size(), 418);
+        assert_eq!(FrameHeader::parse([0xff, 0xfb, 0xf0, 0x00]), None);
+        assert_eq!(FrameHeader::parse([0xff, 0xfb, 0x0c, 0x00]), None);
+    }
+
+    #[test]
+    fn scan_quiet() {
+        let scan = scan_path("testdata/quiet.mp3").unwrap();
+        assert_eq!(scan.frames, 40);
+        assert_eq!(scan.sample_rate, 44100);
+        assert_eq!(scan.duration_ms(), 40 * 1152 * 1000 / 44100);
+        assert!(!scan.vbr);
+    }
+
+    #[test]
+    fn scan_skips_tag() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/quiet.mp3", &tmp).unwrap();
+        let mut tag = Tag::new();
+        let expected = update_duration(&mut tag, &tmp).unwrap();
+        tag.write_to_path(&tmp, crate::Version::Id3v24).unwrap();
+
+        assert_eq!(scan_path(&tmp).unwrap(), expected);
+        let tag = Tag::read_from_path(&tmp).unwrap();
+        assert_eq!(tag.duration(), Some(1044));
+    }
+
+    #[test]
+    fn scan_vbr() {
+        let mut stream = b"garbage".to_vec();
+        stream.extend(make_stream(&[9, 9, 10, 11]));
+        let result = scan(&stream[..]).unwrap();
+        assert_eq!(result.frames, 4);
+        assert!(result.vbr);
+
+        let result = scan(&make_stream(&[10, 10])[..]).unwrap();
+        assert_eq!(result.frames, 2);
+        assert!(!result.vbr);
+    }
+
+    #[test]
+    fn location_lookup_table_cbr() {
+        let table = location_lookup_table(&make_stream(&[9; 7])[..], 2).unwrap();
+        assert_eq!(table.frames_between_reference, 2);
+        assert_eq!(table.bytes_between_reference, 834);
+        assert_eq!(table.millis_between_reference, 52);
+        assert_eq!(table.bits_for_bytes, 4);
+        assert_eq!(table.bits_for_millis, 4);
+        // The cumulative time is rounded down, so every interval starts at 52, 104 and 156ms.
+        let deviations: Vec<_> = table
+            .references
+            .iter()
+            .map(|r| (r.deviate_bytes, r.deviate_millis))
+            .collect();
+        assert_eq!(deviations, [(0, 0), (0, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn location_lookup_table_vbr() {
+        let mut stream = b"garbage".to_vec();
+        stream.extend(make_stream(&[9, 9, 10, 10, 14, 14]));
+        let table = location_lookup_table(&stream[..], 2).unwrap();
+        assert_eq!(table.bytes_between_reference, 834);
+        assert_eq!(table.bits_for_bytes, 12);
+        let deviate_bytes: Vec<_> = table.references.iter().map(|r| r.deviate_bytes).collect();
+        assert_eq!(deviate_bytes, [0, 1044 - 834, 2088 - 834]);
+
+        // The table survives a round trip through a tag.
+        let mut tag = Tag::new();
+        tag.add_frame(table.clone());
+        let mut buffer = Vec::new();
+        tag.write_to(&mut buffer, crate::Version::Id3v24).unwrap();
+        let tag = Tag::read_from2(io::Cursor::new(buffer)).unwrap();
+        let decoded = tag
+            .get("MLLT")
+            .unwrap()
+            .content()
+            .mpeg_location_lookup_table();
+        assert_eq!(decoded, Some(&table));
+
+        assert!(location_lookup_table(&stream[..], 0).is_err());
+    }
+
+    #[test]
+    fn scan_no_audio() {
+        let err = scan(&b"not audio"[..]).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::Parsing));
+    }
+}
@@ -0,0 +1,393 @@
+use crate::chunk;
+use crate::frame::{Comment, Timestamp};
+use crate::{StorageFile, Tag, TagLike};
+use byteorder::{ByteOrder, LittleEndian};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The IDs of the entries that map to fields of [`Info`].
+const INAM: [u8; 4] = *b"INAM";
+const IART: [u8; 4] = *b"IART";
+const IPRD: [u8; 4] = *b"IPRD";
+const IGNR: [u8; 4] = *b"IGNR";
+const ICMT: [u8; 4] = *b"ICMT";
+const ICRD: [u8; 4] = *b"ICRD";
+const ITRK: [u8; 4] = *b"ITRK";
+const ICOP: [u8; 4] = *b"ICOP";
+const ISFT: [u8; 4] = *b"ISFT";
+
+/// The metadata of a WAV file that is stored in the INFO list, as shown by Windows Explorer and
+/// many other programs.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Info {
+    /// The title, INAM.
+    pub title: Option<String>,
+    /// The artist, IART.
+    pub artist: Option<String>,
+    /// The album, IPRD.
+    pub album: Option<String>,
+    /// The genre, IGNR.
+    pub genre: Option<String>,
+    /// A free-form comment, ICMT.
+    pub comment: Option<String>,
+    /// The creation date, ICRD. This is usually a year or a date like `2017-03-22`.
+    pub date: Option<String>,
+    /// The number of the track, ITRK.
+    pub track: Option<u32>,
+    /// The copyright notice, ICOP.
+    pub copyright: Option<String>,
+    /// The software that created the file, ISFT.
+    pub software: Option<String>,
+    /// Entries that do not map to any of the other fields, in the order in which they were read.
+    pub other: Vec<([u8; 4], String)>,
+}
+
+impl Info {
+    /// Creates a new empty INFO list.
+    pub fn new() -> Info {
+        Info::default()
+    }
+
+    /// Returns whether the list has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries().next().is_none()
+    }
+
+    /// Attempts to read the INFO list from a WAV file.
+    ///
+    /// If the file has no INFO list, an error with [`ErrorKind::NoTag`](crate::ErrorKind::NoTag) is returned.
+    pub fn read_from(reader: impl io::Read + io::Seek) -> crate::Result<Info> {
+        let data = chunk::load_info_chunk::<chunk::WavFormat, _>(reader)?;
+        Ok(Info::decode(&data))
+    }
+
+    /// Attempts to read the INFO list from the WAV file at the specified path.
+    pub fn read_from_path(path: impl AsRef<Path>) -> crate::Result<Info> {
+        Info::read_from(io::BufReader::new(fs::File::open(path)?))
+    }
+
+    /// Replaces the INFO list of a WAV file. The file must be opened read/write.
+    ///
+    /// Writing an empty list removes the INFO list from the file.
+    pub fn write_to_file(&self, file: impl StorageFile) -> crate::Result<()> {
+        chunk::write_info_chunk_file::<chunk::WavFormat>(file, |_| self.encode())
+    }
+
+    /// Replaces the INFO list of the WAV file at the specified path.
+    pub fn write_to_path(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        let mut file = fs::OpenOptions::new().read(true).write(true).open(path)?;
+        self.write_to_file(&mut file)?;
+        Ok(())
+    }
+
+    /// Converts an ID3 tag to an INFO list. Frames that have no INFO entry are left out.
+    pub fn from_id3v2(tag: &Tag) -> Info {
+        let comment = tag
+            .comments()
+            .find(|comment| comment.description.is_empty())
+            .or_else(|| tag.comments().next())
+            .map(|comment| comment.text.clone());
+        let date = tag
+            .date_recorded()
+            .map(|timestamp| timestamp.to_string())
+            .or_else(|| tag.year().map(|year| year.to_string()));
+        Info {
+            title: tag.title().map(str::to_string),
+            artist: tag.artist().map(str::to_string),
+            album: tag.album().map(str::to_string),
+            genre: tag.genre_parsed().map(|genre| genre.into_owned()),
+            comment,
+            date,
+            track: tag.track(),
+            copyright: tag.text_for_frame_id("TCOP").map(str::to_string),
+            software: tag.text_for_frame_id("TSSE").map(str::to_string),
+            other: Vec::new(),
+        }
+    }
+
+    /// Updates the list to match an ID3 tag, keeping the entries that have no ID3 counterpart.
+    fn sync_with(self, tag: &Tag) -> Info {
+        Info {
+            other: self.other,
+            ..Info::from_id3v2(tag)
+        }
+    }
+
+    /// Returns the entries of the list in the order in which they are written.
+    fn entries(&self) -> impl Iterator<Item = ([u8; 4], String)> + '_ {
+        let fields = [
+            (INAM, self.title.clone()),
+            (IART, self.artist.clone()),
+            (IPRD, self.album.clone()),
+            (IGNR, self.genre.clone()),
+            (ICMT, self.comment.clone()),
+            (ICRD, self.date.clone()),
+            (ITRK, self.track.map(|track| track.to_string())),
+            (ICOP, self.copyright.clone()),
+            (ISFT, self.software.clone()),
+        ];
+        fields
+            .into_iter()
+            .filter_map(|(id, value)| Some((id, value?)))
+            .chain(self.other.iter().cloned())
+    }
+
+    /// Decodes the entries of an INFO list, which follow the `INFO` form type.
+    pub(crate) fn decode(mut data: &[u8]) -> Info {
+        let mut info = Info::new();
+        while data.len() >= 8 {
+            let id: [u8; 4] = data[0..4].try_into().expect("slice with incorrect length");
+            let size = LittleEndian::read_u32(&data[4..8]) as usize;
+            let value = &data[8..8 + size.min(data.len() - 8)];
+            // Values are zero terminated.
+            let value = match value.iter().position(|b| *b == 0) {
+                Some(end) => &value[..end],
+                None => value,
+            };
+            // Most software writes the system code page, UTF-8 is tried first.
+            let value = match std::str::from_utf8(value) {
+                Ok(value) => value.to_string(),
+                Err(_) => value.iter().map(|b| char::from(*b)).collect(),
+            };
+            match id {
+                INAM => info.title = Some(value),
+                IART => info.artist = Some(value),
+                IPRD => info.album = Some(value),
+                IGNR => info.genre = Some(value),
+                ICMT => info.comment = Some(value),
+                ICRD => info.date = Some(value),
+                ITRK => match value.trim().parse() {
+                    Ok(track) => info.track = Some(track),
+                    Err(_) => info.other.push((id, value)),
+                },
+                ICOP => info.copyright = Some(value),
+                ISFT => info.software = Some(value),
+                _ => info.other.push((id, value)),
+            }
+            data = &data[(8 + size + size % 2).min(data.len())..];
+        }
+        info
+    }
+
+    /// Encodes the list, including the `INFO` form type. An empty list encodes to no data at
+    /// all.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let mut data = b"INFO".to_vec();
+        for (id, value) in self.entries() {
+            let size = value.len() + 1;
+            data.extend_from_slice(&id);
+            data.extend_from_slice(&(size as u32).to_le_bytes());
+            data.extend_from_slice(value.as_bytes());
+            data.push(0);
+            if size % 2 == 1 {
+                data.push(0);
+            }
+        }
+        data
+    }
+}
+
+impl From<&Tag> for Info {
+    /// Converts an ID3 tag to an INFO list, see [`Info::from_id3v2`].
+    fn from(tag: &Tag) -> Info {
+        Info::from_id3v2(tag)
+    }
+}
+
+impl From<&Info> for Tag {
+    fn from(info: &Info) -> Tag {
+        let mut tag = Tag::new();
+        if let Some(title) = &info.title {
+            tag.set_title(title.clone());
+        }
+        if let Some(artist) = &info.artist {
+            tag.set_artist(artist.clone());
+        }
+        if let Some(album) = &info.album {
+            tag.set_album(album.clone());
+        }
+        if let Some(genre) = &info.genre {
+            tag.set_genre(genre.clone());
+        }
+        if let Some(comment) = &info.comment {
+            tag.add_frame(Comment {
+                lang: "eng".to_string(),
+                description: "".to_string(),
+                text: comment.clone(),
+            });
+        }
+        // Dates that are not a valid timestamp are left out.
+        let date = info
+            .date
+            .as_deref()
+            .map(|date| date.trim().parse::<Timestamp>());
+        if let Some(Ok(timestamp)) = date {
+            tag.set_date_recorded(timestamp);
+        }
+        if let Some(track) = info.track {
+            tag.set_track(track);
+        }
+        if let Some(copyright) = &info.copyright {
+            tag.set_text("TCOP", copyright.clone());
+        }
+        if let Some(software) = &info.software {
+            tag.set_text("TSSE", software.clone());
+        }
+        tag
+    }
+}
+
+/// Updates the INFO list of a WAV file to match an ID3 tag, see
+/// [`Encoder::sync_riff_info`](crate::Encoder::sync_riff_info).
+pub(crate) fn sync_file(file: impl StorageFile, tag: &Tag) -> crate::Result<()> {
+    chunk::write_info_chunk_file::<chunk::WavFormat>(file, |data| {
+        Info::decode(data).sync_with(tag).encode()
+    })
+}
+
+/// Updates the INFO list of a WAV file via Tokio, like [`sync_file`].
+#[cfg(feature = "tokio")]
+pub(crate) async fn async_sync_file(
+    file: impl crate::AsyncStorageFile,
+    tag: &Tag,
+) -> crate::Result<()> {
+    chunk::async_write_info_chunk_file::<chunk::WavFormat>(file, |data| {
+        Info::decode(data).sync_with(tag).encode()
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Encoder, ErrorKind, Version};
+
+    #[test]
+    fn read_info() {
+        let info = Info::read_from_path("testdata/wav/multi-chunk.wav").unwrap();
+        assert_eq!(info.title.as_deref(), Some("Title"));
+        assert_eq!(info.artist.as_deref(), Some("Artist"));
+        assert!(info.other.is_empty());
+
+        let error = Info::read_from_path("testdata/wav/tagless.wav").unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::NoTag));
+    }
+
+    #[test]
+    fn encode_decode() {
+        let info = Info {
+            title: Some("Title".to_string()),
+            album: Some("Albüm".to_string()),
+            date: Some("2017".to_string()),
+            track: Some(3),
+            other: vec![(*b"IENG", "Engineer".to_string())],
+            ..Info::new()
+        };
+        let data = info.encode();
+        assert_eq!(&data[..4], b"INFO");
+        assert_eq!(data.len() % 2, 0);
+        assert_eq!(Info::decode(&data[4..]), info);
+        assert!(Info::new().encode().is_empty());
+
+        // Values in the system code page are read as Latin-1.
+        let data = b"INAM\x05\0\0\0Alb\xfcm\0";
+        assert_eq!(Info::decode(data).title.as_deref(), Some("Albüm"));
+    }
+
+    #[test]
+    fn tag_conversion() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_artist("Artist");
+        tag.set_genre("Trance");
+        tag.set_date_recorded("2017-03-22".parse().unwrap());
+        tag.set_track(3);
+        tag.add_frame(Comment {
+            lang: "eng".to_string(),
+            description: "".to_string(),
+            text: "Comment".to_string(),
+        });
+        tag.set_text("TCOP", "Copyright");
+
+        let info = Info::from(&tag);
+        assert_eq!(info.title.as_deref(), Some("Title"));
+        assert_eq!(info.artist.as_deref(), Some("Artist"));
+        assert_eq!(info.genre.as_deref(), Some("Trance"));
+        assert_eq!(info.date.as_deref(), Some("2017-03-22"));
+        assert_eq!(info.track, Some(3));
+        assert_eq!(info.comment.as_deref(), Some("Comment"));
+        assert_eq!(info.copyright.as_deref(), Some("Copyright"));
+        assert_eq!(Tag::from(&info), tag);
+    }
+
+    #[test]
+    fn write_info() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/wav/tagless.wav", &tmp).unwrap();
+        let audio = fs::read("testdata/wav/tagless.wav").unwrap();
+
+        let mut info = Info::new();
+        info.title = Some("Title".to_string());
+        info.write_to_path(&tmp).unwrap();
+        assert_eq!(Info::read_from_path(&tmp).unwrap(), info);
+
+        info.artist = Some("A longer artist".to_string());
+        info.write_to_path(&tmp).unwrap();
+        assert_eq!(Info::read_from_path(&tmp).unwrap(), info);
+        let data = fs::read(&tmp).unwrap();
+        assert_eq!(
+            u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize,
+            data.len() - 8
+        );
+
+        // Writing an empty list restores the original file.
+        Info::new().write_to_path(&tmp).unwrap();
+        assert_eq!(fs::read(&tmp).unwrap(), audio);
+    }
+
+    #[test]
+    fn encoder_sync() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let mut data = fs::read("testdata/wav/multi-chunk.wav").unwrap();
+        // Replace the IART entry by an entry that has no ID3 counterpart.
+        let pos = data.windows(4).position(|id| id == b"IART").unwrap();
+        data[pos..pos + 4].copy_from_slice(b"IENG");
+        fs::write(&tmp, &data).unwrap();
+
+        let mut tag = Tag::read_from_path(&tmp).unwrap();
+        tag.set_title("NewTitle");
+        tag.set_album("Album");
+        Encoder::new()
+            .version(Version::Id3v23)
+            .sync_riff_info(true)
+            .write_to_path(&tag, &tmp)
+            .unwrap();
+
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+        let info = Info::read_from_path(&tmp).unwrap();
+        assert_eq!(info.title.as_deref(), Some("NewTitle"));
+        assert_eq!(info.artist.as_deref(), tag.artist());
+        assert_eq!(info.album.as_deref(), Some("Album"));
+        assert_eq!(info.other, vec![(*b"IENG", "Artist".to_string())]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_encoder_sync() {
+        let sync_tmp = tempfile::NamedTempFile::new().unwrap();
+        let async_tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/wav/multi-chunk.wav", &sync_tmp).unwrap();
+        fs::copy("testdata/wav/multi-chunk.wav", &async_tmp).unwrap();
+
+        let mut tag = Tag::read_from_path(&sync_tmp).unwrap();
+        tag.set_album("Album");
+        let encoder = Encoder::new().sync_riff_info(true);
+        encoder.write_to_path(&tag, &sync_tmp).unwrap();
+        encoder.async_write_to_path(&tag, &async_tmp).await.unwrap();
+        assert_eq!(fs::read(&sync_tmp).unwrap(), fs::read(&async_tmp).unwrap());
+    }
+}
@@ -0,0 +1,174 @@
+//! DSF (DSD Stream File) stores its ID3v2 tag at the end of the file. The "DSD " chunk at the
+//! start of the file holds a pointer to the tag and the total size of the file, both of which must
+//! be kept up to date when the tag is written.
+
+use crate::stream;
+use crate::{Error, ErrorKind, Tag, Warning};
+use byteorder::{ByteOrder, LittleEndian};
+use std::io;
+use std::ops::Range;
+
+/// The size of the "DSD " chunk.
+pub const HEADER_LEN: usize = 28;
+
+/// The contents of the "DSD " chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DsdHeader {
+    /// The total size of the file.
+    pub file_size: u64,
+    /// The offset of the ID3v2 tag, or 0 if the file has no tag.
+    pub metadata_offset: u64,
+}
+
+impl DsdHeader {
+    /// Reads the header from the start of the reader.
+    pub fn read(mut reader: impl io::Read) -> crate::Result<Self> {
+        let mut buf = [0; HEADER_LEN];
+        // Use a single read call to improve performance on unbuffered readers.
+        reader.read_exact(&mut buf)?;
+        Self::parse(&buf)
+    }
+
+    /// Parses a header that was read by the caller, see [`DsdHeader::read`].
+    pub fn parse(buf: &[u8; HEADER_LEN]) -> crate::Result<Self> {
+        if &buf[0..4] != b"DSD " || LittleEndian::read_u64(&buf[4..12]) != HEADER_LEN as u64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid DSD chunk header",
+            ));
+        }
+        Ok(Self {
+            file_size: LittleEndian::read_u64(&buf[12..20]),
+            metadata_offset: LittleEndian::read_u64(&buf[20..28]),
+        })
+    }
+
+    /// Encodes the header.
+    pub fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut buf = [0; HEADER_LEN];
+        buf[0..4].copy_from_slice(b"DSD ");
+        LittleEndian::write_u64(&mut buf[4..12], HEADER_LEN as u64);
+        LittleEndian::write_u64(&mut buf[12..20], self.file_size);
+        LittleEndian::write_u64(&mut buf[20..28], self.metadata_offset);
+        buf
+    }
+
+    /// Returns the region that holds the tag in a file of which the data ends at `file_end`. If
+    /// the file has no tag, the region is empty and located at the end of the file.
+    pub fn metadata_region(&self, file_end: u64) -> crate::Result<Range<u64>> {
+        if self.metadata_offset == 0 {
+            return Ok(file_end..file_end);
+        }
+        if self.metadata_offset < HEADER_LEN as u64 || self.metadata_offset > file_end {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "the DSF metadata pointer is out of bounds",
+            ));
+        }
+        Ok(self.metadata_offset..file_end)
+    }
+
+    /// Updates the header after a tag of `tag_len` bytes was written at `region_start`. A tag of
+    /// zero bytes means the tag was removed.
+    pub fn update(&mut self, region_start: u64, tag_len: u64) {
+        self.metadata_offset = if tag_len == 0 { 0 } else { region_start };
+        self.file_size = region_start + tag_len;
+    }
+}
+
+/// Attempts to load the ID3 tag from the given DSF stream, collecting any recoverable issues into
+/// `warnings`.
+pub fn load_id3_with_warnings(
+    mut reader: impl io::Read + io::Seek,
+    warnings: &mut Vec<Warning>,
+) -> crate::Result<Tag> {
+    let header = DsdHeader::read(&mut reader)?;
+    if header.metadata_offset == 0 {
+        return Err(Error::new(ErrorKind::NoTag, "the DSF file has no metadata"));
+    }
+    reader.seek(io::SeekFrom::Start(header.metadata_offset))?;
+    stream::tag::decode_with_warnings(reader, warnings)
+}
+
+/// Attempts to load the ID3 tag from the given DSF stream via Tokio.
+///
+/// The data preceding the tag is read and discarded, so the reader does not need to be seekable.
+#[cfg(feature = "tokio")]
+pub async fn async_load_id3(
+    mut reader: impl tokio::io::AsyncRead + std::marker::Unpin,
+) -> crate::Result<Tag> {
+    use tokio::io::AsyncReadExt;
+
+    let mut buf = [0; HEADER_LEN];
+    reader.read_exact(&mut buf).await?;
+    let header = DsdHeader::parse(&buf)?;
+    if header.metadata_offset == 0 {
+        return Err(Error::new(ErrorKind::NoTag, "the DSF file has no metadata"));
+    }
+    let skip = header
+        .metadata_offset
+        .checked_sub(HEADER_LEN as u64)
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "the DSF metadata pointer is out of bounds",
+            )
+        })?;
+    tokio::io::copy(&mut (&mut reader).take(skip), &mut tokio::io::sink()).await?;
+    stream::tag::async_decode(reader).await
+}
+
+/// Returns the byte range of the ID3 tag in the given DSF stream.
+pub fn locate_id3(mut reader: impl io::Read + io::Seek) -> crate::Result<Range<u64>> {
+    let header = DsdHeader::read(&mut reader)?;
+    if header.metadata_offset == 0 {
+        return Err(Error::new(ErrorKind::NoTag, "the DSF file has no metadata"));
+    }
+    let file_end = reader.seek(io::SeekFrom::End(0))?;
+    header.metadata_region(file_end)
+}
+
+/// Reads the header and the region of the tag of a DSF file, leaving the file positioned at the
+/// start.
+pub fn read_region(mut file: impl io::Read + io::Seek) -> crate::Result<(DsdHeader, Range<u64>)> {
+    let header = DsdHeader::read(&mut file)?;
+    let file_end = file.seek(io::SeekFrom::End(0))?;
+    file.seek(io::SeekFrom::Start(0))?;
+    Ok((header, header.metadata_region(file_end)?))
+}
+
+/// Writes the header to the start of the file.
+pub fn write_header(mut file: impl io::Write + io::Seek, header: &DsdHeader) -> io::Result<()> {
+    file.seek(io::SeekFrom::Start(0))?;
+    file.write_all(&header.encode())?;
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_round_trip() {
+        let header = DsdHeader {
+            file_size: 0x1234_5678_9abc,
+            metadata_offset: 0x1234_5678,
+        };
+        assert_eq!(DsdHeader::parse(&header.encode()).unwrap(), header);
+    }
+
+    #[test]
+    fn metadata_region() {
+        let mut header = DsdHeader {
+            file_size: 1000,
+            metadata_offset: 0,
+        };
+        assert_eq!(header.metadata_region(1000).unwrap(), 1000..1000);
+        header.metadata_offset = 900;
+        assert_eq!(header.metadata_region(1000).unwrap(), 900..1000);
+        header.metadata_offset = 1001;
+        assert!(header.metadata_region(1000).is_err());
+        header.metadata_offset = 4;
+        assert!(header.metadata_region(1000).is_err());
+    }
+}
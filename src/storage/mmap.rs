@@ -0,0 +1,201 @@
+use super::StorageFile;
+use memmap2::MmapMut;
+use std::cmp;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A file that is accessed through a memory map instead of read and write calls.
+///
+/// This avoids a system call for each read and write, which speeds up tagging large numbers of
+/// files. Resizing the file remaps it.
+///
+/// Creating an `MmapFile` is unsafe, as the file must not be modified or truncated by anything
+/// else while it is mapped, see [`MmapFile::new`].
+///
+/// # Example
+/// ```
+/// use id3::storage::MmapFile;
+/// use id3::{Tag, TagLike, Version};
+///
+/// # let dir = tempfile::tempdir()?;
+/// # let path = dir.path().join("music.mp3");
+/// # std::fs::copy("testdata/quiet.mp3", &path)?;
+/// // SAFETY: Nothing else accesses the file while it is mapped.
+/// let mut file = unsafe { MmapFile::open(&path)? };
+/// let mut tag = Tag::new();
+/// tag.set_title("Title");
+/// tag.write_to_file(&mut file, Version::Id3v24)?;
+/// file.into_inner()?;
+///
+/// assert_eq!(Tag::read_from_path(&path)?.title(), Some("Title"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct MmapFile {
+    file: fs::File,
+    /// The mapping of the file. Empty files can not be mapped.
+    map: Option<MmapMut>,
+    pos: u64,
+}
+
+impl MmapFile {
+    /// Maps the file, which must be opened for both reading and writing.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated by other processes, or through other handles
+    /// in this process, for as long as the returned `MmapFile` exists. Otherwise, the data
+    /// could change underneath the reads and writes, or accessing it could fault.
+    pub unsafe fn new(file: fs::File) -> io::Result<MmapFile> {
+        let map = map(&file)?;
+        Ok(MmapFile { file, map, pos: 0 })
+    }
+
+    /// Opens and maps the file at the indicated path.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as for [`MmapFile::new`] apply.
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<MmapFile> {
+        let file = fs::OpenOptions::new().read(true).write(true).open(path)?;
+        // SAFETY: The caller upholds the requirements of `new`.
+        unsafe { MmapFile::new(file) }
+    }
+
+    /// Writes any changes to the file and returns it.
+    pub fn into_inner(mut self) -> io::Result<fs::File> {
+        io::Write::flush(&mut self)?;
+        Ok(self.file)
+    }
+
+    fn data(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or(&[])
+    }
+}
+
+/// Maps the whole file, or returns `None` if it is empty.
+fn map(file: &fs::File) -> io::Result<Option<MmapMut>> {
+    if file.metadata()?.len() == 0 {
+        return Ok(None);
+    }
+    // SAFETY: The mapping is only accessed through the `MmapFile` that owns the file. Changes made
+    // through other handles are ruled out by the caller of the unsafe `MmapFile::new`.
+    unsafe { MmapMut::map_mut(file) }.map(Some)
+}
+
+impl io::Read for MmapFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let data = self.data();
+        let start = cmp::min(self.pos, data.len() as u64) as usize;
+        let n = cmp::min(buf.len(), data.len() - start);
+        buf[..n].copy_from_slice(&data[start..start + n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl io::Write for MmapFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let end = self.pos + buf.len() as u64;
+        if end > self.data().len() as u64 {
+            self.set_len(end)?;
+        }
+        let start = self.pos as usize;
+        if let Some(map) = self.map.as_mut() {
+            map[start..start + buf.len()].copy_from_slice(buf);
+        }
+        self.pos = end;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &self.map {
+            Some(map) => map.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl io::Seek for MmapFile {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            io::SeekFrom::Start(i) => Some(i),
+            io::SeekFrom::End(i) => (self.data().len() as u64).checked_add_signed(i),
+            io::SeekFrom::Current(i) => self.pos.checked_add_signed(i),
+        };
+        self.pos = new_pos.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+}
+
+impl StorageFile for MmapFile {
+    fn set_len(&mut self, new_len: u64) -> io::Result<()> {
+        if let Some(map) = self.map.take() {
+            map.flush()?;
+        }
+        self.file.set_len(new_len)?;
+        self.map = map(&self.file)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Tag, TagLike, Version};
+    use std::io::{Read, Seek, Write};
+
+    #[test]
+    fn read_write_seek() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        fs::write(&path, b"").unwrap();
+
+        // SAFETY: The file is private to this test.
+        let mut file = unsafe { MmapFile::open(&path) }.unwrap();
+        file.write_all(b"hello").unwrap();
+        file.seek(io::SeekFrom::End(2)).unwrap();
+        file.write_all(b"world").unwrap();
+        file.rewind().unwrap();
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"hello\0\0world");
+
+        file.set_len(3).unwrap();
+        assert_eq!(file.stream_position().unwrap(), 12);
+        assert!(file.seek(io::SeekFrom::End(-4)).is_err());
+        file.into_inner().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"hel");
+    }
+
+    #[test]
+    fn write_tags() {
+        for path in ["testdata/quiet.mp3", "testdata/wav/tagless.wav"] {
+            let dir = tempfile::tempdir().unwrap();
+            let tmp = dir.path().join("file");
+            fs::copy(path, &tmp).unwrap();
+
+            let mut tag = Tag::new();
+            tag.set_title("Title");
+            tag.set_artist("A".repeat(5000));
+            // SAFETY: The file is private to this test.
+            let mut file = unsafe { MmapFile::open(&tmp) }.unwrap();
+            tag.write_to_file(&mut file, Version::Id3v24).unwrap();
+            tag.remove_artist();
+            file.rewind().unwrap();
+            tag.write_to_file(&mut file, Version::Id3v24).unwrap();
+            file.into_inner().unwrap();
+
+            assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag, "{}", path);
+        }
+    }
+}
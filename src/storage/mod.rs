@@ -4,12 +4,110 @@
 //! The need for this abstraction arises from the differences that audiofiles have when storing
 //! metadata. For example, MP3 uses a header for ID3v2, a trailer for ID3v1 while WAV has a special
 //! "RIFF-chunk" which stores an ID3 tag.
+//!
+//! All functions that write tags accept a [`StorageFile`], which is implemented for files and
+//! in-memory buffers. Implementing it for another type makes it possible to write tags to any
+//! container that supports random access and resizing. A [`Storage`] exposes a region of such a
+//! file, [`PlainStorage`] takes care of moving the data following the region when its size
+//! changes.
+//!
+//! Besides files, tags can be written to buffers through `Cursor<Vec<u8>>` and
+//! `Cursor<&mut Vec<u8>>`. With the `mmap` feature, `MmapFile` accesses files through a memory
+//! map.
+//!
+//! # Example
+//!
+//! A blob of an object store that is edited in memory and only uploaded again if a tag was
+//! actually written:
+//!
+//! ```
+//! use id3::storage::{PlainStorage, Storage, StorageFile};
+//! use id3::{Tag, TagLike, Version};
+//! use std::io::{self, Cursor, Read, Seek, Write};
+//!
+//! struct Blob {
+//!     data: Cursor<Vec<u8>>,
+//!     modified: bool,
+//! }
+//!
+//! impl Read for Blob {
+//!     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+//!         self.data.read(buf)
+//!     }
+//! }
+//!
+//! impl Write for Blob {
+//!     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+//!         self.modified = true;
+//!         self.data.write(buf)
+//!     }
+//!
+//!     fn flush(&mut self) -> io::Result<()> {
+//!         self.data.flush()
+//!     }
+//! }
+//!
+//! impl Seek for Blob {
+//!     fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+//!         self.data.seek(pos)
+//!     }
+//! }
+//!
+//! impl StorageFile for Blob {
+//!     fn set_len(&mut self, new_len: u64) -> io::Result<()> {
+//!         self.modified = true;
+//!         self.data.get_mut().resize(new_len as usize, 0);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut blob = Blob {
+//!     data: Cursor::new(b"audio".to_vec()),
+//!     modified: false,
+//! };
+//! let mut tag = Tag::new();
+//! tag.set_title("Title");
+//! tag.write_to_file(&mut blob, Version::Id3v24)?;
+//! assert!(blob.modified);
+//! blob.rewind()?;
+//! assert_eq!(Tag::read_from2(&mut blob)?.title(), Some("Title"));
+//! assert!(blob.data.get_ref().ends_with(b"audio"));
+//!
+//! // Regions can also be replaced directly, the data following them is moved as needed.
+//! let mut storage = PlainStorage::new(&mut blob, 0..0);
+//! let mut writer = storage.writer()?;
+//! writer.write_all(b"prefix")?;
+//! writer.flush()?;
+//! drop(writer);
+//! assert!(blob.data.get_ref().starts_with(b"prefix"));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
 
+use std::ffi::OsString;
 use std::fs;
+#[cfg(feature = "tokio")]
+use std::future::Future;
 use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "tokio")]
+use std::pin::Pin;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+pub(crate) mod dsf;
+#[cfg(feature = "mmap")]
+mod mmap;
+pub(crate) mod plain;
 
-pub mod plain;
+#[cfg(feature = "mmap")]
+pub use self::mmap::MmapFile;
 
+#[cfg(feature = "tokio")]
+pub use self::plain::AsyncPlainStorage;
+pub use self::plain::{PlainReader, PlainStorage, PlainWriter};
+
+/// The container format in which an ID3v2 tag is stored.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Format {
     /// ID3 is typically written as a header that precedes any audio content. For MPEG files, it is
@@ -22,12 +120,20 @@ pub enum Format {
     /// written to a chunk.
     Aiff,
 
-    /// Similar to Aiff.
+    /// Similar to Aiff. This includes the RF64 and BW64 variants, which store sizes that exceed
+    /// 32 bits in a ds64 chunk.
     Wav,
+
+    /// DSDIFF is a chunk-y format like Aiff, but with 64 bits chunk sizes.
+    Dsdiff,
+
+    /// DSF (DSD Stream File) stores the tag at the end of the file. Its location is stored in the
+    /// header of the file.
+    Dsf,
 }
 
 impl Format {
-    pub fn magic(probe: impl AsRef<[u8]>) -> Option<Self> {
+    pub(crate) fn magic(probe: impl AsRef<[u8]>) -> Option<Self> {
         let probe = probe.as_ref();
         if probe.len() < 12 {
             return None;
@@ -35,37 +141,158 @@ impl Format {
         match (&probe[..3], &probe[..4], &probe[8..12]) {
             (b"ID3", _, _) => Some(Format::Header),
             (_, b"FORM", _) => Some(Format::Aiff),
-            (_, b"RIFF", b"WAVE") => Some(Format::Wav),
+            (_, b"RIFF" | b"RF64" | b"BW64", b"WAVE") => Some(Format::Wav),
+            (_, b"DSD ", _) => Some(Format::Dsf),
+            (_, b"FRM8", _) if probe.get(12..16) == Some(&b"DSD "[..]) => Some(Format::Dsdiff),
             _ => None,
         }
     }
 }
 
-/// Refer to the module documentation.
+/// The number of names [`create_temp_copy`] tries before giving up.
+const TEMP_ATTEMPTS: usize = 100;
+
+/// Makes the names of the temporary files created by this process unique.
+pub(crate) static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Creates a temporary copy of the file at `path` in the same directory and returns its path and
+/// the copy, opened for reading and writing and positioned at the start.
+///
+/// The copy is always a new file: a name that already exists, e.g. a symbolic link planted by
+/// another user, is never opened but skipped in favour of the next one. The permissions of the
+/// original are applied to the copy.
+pub(crate) fn create_temp_copy(path: &Path) -> io::Result<(PathBuf, fs::File)> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "the path does not name a file")
+    })?;
+    let mut original = fs::File::open(path)?;
+    for _ in 0..TEMP_ATTEMPTS {
+        let mut tmp_name = OsString::from(".");
+        tmp_name.push(file_name);
+        tmp_name.push(format!(
+            ".{}-{}.tmp",
+            process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let tmp_path = path.with_file_name(tmp_name);
+        let mut file = match fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)
+        {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        };
+        let copied = io::copy(&mut original, &mut file)
+            .and_then(|_| file.set_permissions(original.metadata()?.permissions()))
+            .and_then(|_| io::Seek::rewind(&mut file));
+        return match copied {
+            Ok(()) => Ok((tmp_path, file)),
+            Err(err) => {
+                drop(file);
+                let _ = fs::remove_file(&tmp_path);
+                Err(err)
+            }
+        };
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "could not find an unused name for a temporary file",
+    ))
+}
+
+/// Applies `f` to a copy of the file at `path` which then atomically replaces the original file.
+/// If `f` fails, the original file is left untouched.
+///
+/// Symbolic links are resolved, so the file they point to is replaced rather than the link. The
+/// copy is created in the same directory as that file, so both are on the same file system. The
+/// permissions of the original are retained, but as the copy is a new file, its ownership is that
+/// of the current process and hard links to the original keep referring to the old data.
+pub(crate) fn write_atomically<T>(
+    path: &Path,
+    f: impl FnOnce(&mut fs::File) -> crate::Result<T>,
+) -> crate::Result<T> {
+    let path = &fs::canonicalize(path)?;
+    let (tmp_path, mut file) = create_temp_copy(path)?;
+    let result = f(&mut file).and_then(|value| {
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&tmp_path, path)?;
+        Ok(value)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+/// Sets the modification time of the file, leaving its access time unchanged.
+#[cfg(unix)]
+pub(crate) fn set_modified(file: &fs::File, modified: SystemTime) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let since_epoch = modified
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "time is before the epoch"))?;
+    let times = [
+        libc::timespec {
+            tv_sec: 0,
+            tv_nsec: libc::UTIME_OMIT,
+        },
+        libc::timespec {
+            tv_sec: since_epoch.as_secs() as libc::time_t,
+            tv_nsec: since_epoch.subsec_nanos() as _,
+        },
+    ];
+    // SAFETY: The file descriptor is valid for as long as `file` is borrowed and `times` holds the
+    // two timestamps futimens expects.
+    if unsafe { libc::futimens(file.as_raw_fd(), times.as_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Sets the modification time of the file, leaving its access time unchanged.
+#[cfg(not(unix))]
+pub(crate) fn set_modified(_file: &fs::File, _modified: SystemTime) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "restoring the modification time is not supported on this platform",
+    ))
+}
+
+/// A region of a file that can be read and replaced, see the [module documentation](self).
 pub trait Storage<'a> {
+    /// The reader returned by [`Storage::reader`].
     type Reader: io::Read + io::Seek + 'a;
+    /// The writer returned by [`Storage::writer`].
     type Writer: io::Write + io::Seek + 'a;
 
-    /// Opens the storage for reading.
-    #[allow(unused)] // Idk, it would be cool to use this for some formats.
+    /// Opens the storage for reading. Positions are relative to the start of the region.
     fn reader(&'a mut self) -> io::Result<Self::Reader>;
 
-    /// Opens the storage for writing.
+    /// Opens the storage for writing. The data written replaces the entire region.
     ///
-    /// The written data is comitted to persistent storage when the
-    /// writer is dropped, altough this will ignore any errors. The caller must manually commit by
-    /// using `io::Write::flush` to check for errors.
+    /// The written data is committed to persistent storage when the writer is dropped, although
+    /// this will ignore any errors. The caller must manually commit by using `io::Write::flush`
+    /// to check for errors.
     fn writer(&'a mut self) -> io::Result<Self::Writer>;
 }
 
 /// This trait is the combination of the [`std::io`] stream traits with an additional method to resize the
 /// file.
-pub trait StorageFile: io::Read + io::Write + io::Seek + private::Sealed {
-    /// Performs the resize. Assumes the same behaviour as [`std::fs::File::set_len`].
+///
+/// Implement this trait to write tags to a custom container, see the
+/// [module documentation](self) for an example.
+pub trait StorageFile: io::Read + io::Write + io::Seek {
+    /// Performs the resize. Assumes the same behaviour as [`std::fs::File::set_len`]: data past
+    /// the new length is discarded and added space is filled with zeros.
     fn set_len(&mut self, new_len: u64) -> io::Result<()>;
 }
 
-impl<T: StorageFile> StorageFile for &mut T {
+impl<T: StorageFile + ?Sized> StorageFile for &mut T {
     fn set_len(&mut self, new_len: u64) -> io::Result<()> {
         (*self).set_len(new_len)
     }
@@ -84,13 +311,67 @@ impl StorageFile for io::Cursor<Vec<u8>> {
     }
 }
 
-// https://rust-lang.github.io/api-guidelines/future-proofing.html#c-sealed
-mod private {
-    pub trait Sealed {}
+/// Allows writing tags to a buffer that is owned elsewhere. `&mut Vec<u8>` itself can not be
+/// used, as it does not implement [`io::Read`] and [`io::Seek`].
+impl StorageFile for io::Cursor<&mut Vec<u8>> {
+    fn set_len(&mut self, new_len: u64) -> io::Result<()> {
+        self.get_mut().resize(new_len as usize, 0);
+        Ok(())
+    }
+}
+
+/// The Tokio counterpart of [`StorageFile`], used for writing tags asynchronously.
+#[cfg(feature = "tokio")]
+pub trait AsyncStorageFile:
+    tokio::io::AsyncRead + tokio::io::AsyncWrite + tokio::io::AsyncSeek + Unpin
+{
+    /// Performs the resize. Assumes the same behaviour as [`tokio::fs::File::set_len`].
+    fn set_len(
+        &mut self,
+        new_len: u64,
+    ) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + '_>>;
+}
+
+#[cfg(feature = "tokio")]
+impl<T: AsyncStorageFile + ?Sized> AsyncStorageFile for &mut T {
+    fn set_len(
+        &mut self,
+        new_len: u64,
+    ) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + '_>> {
+        (**self).set_len(new_len)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncStorageFile for tokio::fs::File {
+    fn set_len(
+        &mut self,
+        new_len: u64,
+    ) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + '_>> {
+        Box::pin(tokio::fs::File::set_len(self, new_len))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncStorageFile for io::Cursor<Vec<u8>> {
+    fn set_len(
+        &mut self,
+        new_len: u64,
+    ) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + '_>> {
+        self.get_mut().resize(new_len as usize, 0);
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
 
-    impl<T: Sealed> Sealed for &mut T {}
-    impl Sealed for std::fs::File {}
-    impl Sealed for std::io::Cursor<Vec<u8>> {}
+#[cfg(feature = "tokio")]
+impl AsyncStorageFile for io::Cursor<&mut Vec<u8>> {
+    fn set_len(
+        &mut self,
+        new_len: u64,
+    ) -> Pin<Box<dyn Future<Output = io::Result<()>> + Send + '_>> {
+        self.get_mut().resize(new_len as usize, 0);
+        Box::pin(std::future::ready(Ok(())))
+    }
 }
 
 #[cfg(test)]
@@ -99,13 +380,39 @@ mod tests {
     use std::io::Read;
     use std::path::Path;
 
-    fn probe(path: impl AsRef<Path>) -> [u8; 12] {
+    fn probe(path: impl AsRef<Path>) -> [u8; 16] {
         let mut f = fs::File::open(path).unwrap();
-        let mut b = [0u8; 12];
-        f.read(&mut b[..]).unwrap();
+        let mut b = [0u8; 16];
+        f.read_exact(&mut b[..]).unwrap();
         b
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_create_temp_copy_skips_existing() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("song.mp3");
+        fs::write(&path, b"audio").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        let victim = dir.path().join("victim");
+        fs::write(&victim, b"victim").unwrap();
+        let next = TEMP_COUNTER.load(Ordering::Relaxed);
+        for counter in next..next + 10 {
+            let name = format!(".song.mp3.{}-{}.tmp", process::id(), counter);
+            std::os::unix::fs::symlink(&victim, dir.path().join(name)).unwrap();
+        }
+
+        let (tmp_path, mut file) = create_temp_copy(&path).unwrap();
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).unwrap();
+        assert_eq!(data, b"audio");
+        assert!(!fs::symlink_metadata(&tmp_path).unwrap().is_symlink());
+        assert_eq!(file.metadata().unwrap().permissions().mode() & 0o777, 0o640);
+        assert_eq!(fs::read(&victim).unwrap(), b"victim");
+    }
+
     #[test]
     fn test_format_magic() {
         assert_eq!(
@@ -124,10 +431,38 @@ mod tests {
             Format::magic(probe("testdata/wav/tagless.wav")),
             Some(Format::Wav)
         );
+        assert_eq!(
+            Format::magic(probe("testdata/aiff/tagless.aifc")),
+            Some(Format::Aiff)
+        );
+        assert_eq!(
+            Format::magic(probe("testdata/wav/rf64-tagless.wav")),
+            Some(Format::Wav)
+        );
         assert_eq!(
             Format::magic(probe("testdata/id3v22.id3")),
             Some(Format::Header)
         );
+        assert_eq!(
+            Format::magic(probe("testdata/dsf/tagless.dsf")),
+            Some(Format::Dsf)
+        );
+        assert_eq!(
+            Format::magic(probe("testdata/dsdiff/tagless.dff")),
+            Some(Format::Dsdiff)
+        );
         assert_eq!(Format::magic(probe("testdata/mpeg-header")), None);
     }
+
+    #[test]
+    fn borrowed_buffer() {
+        use crate::{Tag, TagLike, Version};
+
+        let mut data = fs::read("testdata/wav/tagless.wav").unwrap();
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.write_to_file(io::Cursor::new(&mut data), Version::Id3v24)
+            .unwrap();
+        assert_eq!(Tag::read_from2(io::Cursor::new(&data)).unwrap(), tag);
+    }
 }
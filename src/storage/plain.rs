@@ -1,3 +1,5 @@
+#[cfg(feature = "tokio")]
+use super::AsyncStorageFile;
 use super::{Storage, StorageFile};
 use std::cmp::{self, Ordering};
 use std::io::{self, Write};
@@ -8,7 +10,7 @@ const COPY_BUF_SIZE: usize = 65536;
 /// `PlainStorage` keeps track of a writeable region in a file and prevents accidental overwrites
 /// of unrelated data. Any data following after the region is moved left and right as needed.
 ///
-/// Padding is included from the reader.
+/// Padding is included from the reader. Writing nothing removes the region entirely.
 #[derive(Debug)]
 pub struct PlainStorage<F: StorageFile> {
     /// The backing storage.
@@ -18,7 +20,7 @@ pub struct PlainStorage<F: StorageFile> {
 }
 
 impl<F: StorageFile> PlainStorage<F> {
-    /// Creates a new storage.
+    /// Creates a new storage for the region of the file.
     pub fn new(file: F, region: ops::Range<u64>) -> PlainStorage<F> {
         PlainStorage { file, region }
     }
@@ -43,6 +45,7 @@ impl<'a, F: StorageFile + 'a> Storage<'a> for PlainStorage<F> {
     }
 }
 
+/// The reader of a [`PlainStorage`], limited to the region.
 pub struct PlainReader<'a, F: StorageFile + 'a> {
     storage: &'a mut PlainStorage<F>,
 }
@@ -84,6 +87,8 @@ impl<F: StorageFile> io::Seek for PlainReader<'_, F> {
     }
 }
 
+/// The writer of a [`PlainStorage`]. The data is buffered and replaces the region when the writer
+/// is flushed or dropped.
 pub struct PlainWriter<'a, F: StorageFile + 'a> {
     storage: &'a mut PlainStorage<F>,
     /// Data is writen to this buffer before it is committed to the underlying storage.
@@ -170,7 +175,9 @@ impl<F: StorageFile> io::Write for PlainWriter<'_, F> {
                 self.storage.file.set_len(new_file_end)?;
                 self.storage.region.end = new_region_end;
             }
-            Ordering::Equal => {}
+            Ordering::Equal => {
+                // The buffer fills the region exactly, the following data stays in place.
+            }
         }
 
         assert!(buf_len <= range_len(&self.storage.region));
@@ -197,7 +204,95 @@ impl<F: StorageFile> Drop for PlainWriter<'_, F> {
     }
 }
 
+/// The Tokio counterpart of [`PlainStorage`]. As the writer of [`PlainStorage`] buffers all data
+/// before committing it, the data is passed in at once.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct AsyncPlainStorage<F: AsyncStorageFile> {
+    /// The backing storage.
+    file: F,
+    /// The region that may be writen to including any padding.
+    region: ops::Range<u64>,
+}
+
+#[cfg(feature = "tokio")]
+impl<F: AsyncStorageFile> AsyncPlainStorage<F> {
+    /// Creates a new storage.
+    pub fn new(file: F, region: ops::Range<u64>) -> AsyncPlainStorage<F> {
+        AsyncPlainStorage { file, region }
+    }
+
+    /// Replaces the contents of the region with `data`, moving any data following the region as
+    /// needed.
+    pub async fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+        let buf_len = data.len() as u64;
+        let region_len = self.region.end - self.region.start;
+        let file = &mut self.file;
+
+        match buf_len.cmp(&region_len) {
+            Ordering::Greater => {
+                // Grow the region by moving the following data to the end, starting at the back.
+                let old_file_end = file.seek(io::SeekFrom::End(0)).await?;
+                let new_file_end = old_file_end + (buf_len - region_len);
+                let old_region_end = self.region.end;
+                let new_region_end = self.region.start + buf_len;
+
+                file.set_len(new_file_end).await?;
+                let mut rwbuf = vec![0; COPY_BUF_SIZE];
+                let mut from_end = old_file_end;
+                while from_end > old_region_end {
+                    let n = cmp::min(from_end - old_region_end, COPY_BUF_SIZE as u64);
+                    let from = from_end - n;
+                    let to = from + (new_region_end - old_region_end);
+                    let rwbuf_part = &mut rwbuf[..n as usize];
+                    file.seek(io::SeekFrom::Start(from)).await?;
+                    file.read_exact(rwbuf_part).await?;
+                    file.seek(io::SeekFrom::Start(to)).await?;
+                    file.write_all(rwbuf_part).await?;
+                    from_end = from;
+                }
+
+                self.region.end = new_region_end;
+            }
+            Ordering::Less => {
+                // Shrink the file by moving the following data closer to the start.
+                let old_file_end = file.seek(io::SeekFrom::End(0)).await?;
+                let old_region_end = self.region.end;
+                let new_region_end = self.region.start + buf_len;
+                let new_file_end = old_file_end - (old_region_end - new_region_end);
+
+                let mut rwbuf = vec![0; COPY_BUF_SIZE];
+                let mut from = old_region_end;
+                while from < old_file_end {
+                    let n = cmp::min(old_file_end - from, COPY_BUF_SIZE as u64);
+                    let to = from - (old_region_end - new_region_end);
+                    let rwbuf_part = &mut rwbuf[..n as usize];
+                    file.seek(io::SeekFrom::Start(from)).await?;
+                    file.read_exact(rwbuf_part).await?;
+                    file.seek(io::SeekFrom::Start(to)).await?;
+                    file.write_all(rwbuf_part).await?;
+                    from += n;
+                }
+
+                file.set_len(new_file_end).await?;
+                self.region.end = new_region_end;
+            }
+            Ordering::Equal => {
+                // The data fills the region exactly, the following data stays in place.
+            }
+        }
+
+        file.seek(io::SeekFrom::Start(self.region.start)).await?;
+        file.write_all(data).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::unbuffered_bytes)]
 mod tests {
     use super::*;
     use std::io::{Read, Seek};
@@ -365,4 +460,36 @@ mod tests {
             .skip(9_000)
             .all(|b| b.unwrap() == 0x00));
     }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_plain_writer_grow_large() {
+        let buf: Vec<u8> = (0..200_000).map(|i| (i & 0xff) as u8).collect();
+        let buf_reference = buf.clone();
+        let mut store = AsyncPlainStorage::new(io::Cursor::new(buf), 2_000..22_000);
+        store.write(&[0xff; 40_000]).await.unwrap();
+        assert_eq!(2_000..42_000, store.region);
+        assert_eq!(220_000, store.file.get_ref().len());
+        assert!(buf_reference[..2_000] == store.file.get_ref()[..2_000]);
+        assert!(store.file.get_ref()[2_000..42_000]
+            .iter()
+            .all(|b| *b == 0xff));
+        assert!(buf_reference[22_000..] == store.file.get_ref()[42_000..]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_plain_writer_shrink_large() {
+        let buf: Vec<u8> = (0..200_000).map(|i| (i & 0xff) as u8).collect();
+        let buf_reference = buf.clone();
+        let mut store = AsyncPlainStorage::new(io::Cursor::new(buf), 2_000..22_000);
+        store.write(&[0xff; 9_000]).await.unwrap();
+        assert_eq!(2_000..11_000, store.region);
+        assert_eq!(189_000, store.file.get_ref().len());
+        assert!(buf_reference[..2_000] == store.file.get_ref()[..2_000]);
+        assert!(store.file.get_ref()[2_000..11_000]
+            .iter()
+            .all(|b| *b == 0xff));
+        assert!(buf_reference[22_000..] == store.file.get_ref()[11_000..]);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{Error, ErrorKind};
+use crate::Error;
 use std::convert::TryInto;
 
 /// Types of text encodings used in ID3 frames.
@@ -49,16 +49,23 @@ fn string_from_latin1(data: &[u8]) -> String {
 /// Returns a string created from the vector using UTF-16 (with byte order mark) encoding.
 fn string_from_utf16(data: &[u8]) -> crate::Result<String> {
     if data.len() < 2 {
-        return Err(Error::new(
-            ErrorKind::StringDecoding(data.to_vec()),
+        return Err(Error::string_decoding(
+            data,
+            Encoding::UTF16,
+            0..data.len(),
             "data is not valid utf16",
         ));
     }
-    if data[0] == 0xFF && data[1] == 0xFE {
+    let rs = if data[0] == 0xFF && data[1] == 0xFE {
         string_from_utf16le(&data[2..])
     } else {
         string_from_utf16be(&data[2..])
-    }
+    };
+    // Account for the byte order mark and report the encoding that was declared.
+    rs.map_err(|err| {
+        err.with_string_encoding(Encoding::UTF16)
+            .offset_string_range(2)
+    })
 }
 
 fn string_from_utf16le(data: &[u8]) -> crate::Result<String> {
@@ -67,11 +74,8 @@ fn string_from_utf16le(data: &[u8]) -> crate::Result<String> {
         let bytes = chunk.try_into().unwrap();
         data2.push(u16::from_le_bytes(bytes));
     }
-    String::from_utf16(&data2).map_err(|_| {
-        Error::new(
-            ErrorKind::StringDecoding(data.to_vec()),
-            "data is not valid utf16-le",
-        )
+    String::from_utf16(&data2).map_err(|err| {
+        invalid_utf16(data, &data2, Encoding::UTF16, "data is not valid utf16-le").with_cause(err)
     })
 }
 
@@ -81,14 +85,30 @@ fn string_from_utf16be(data: &[u8]) -> crate::Result<String> {
         let bytes = chunk.try_into().unwrap();
         data2.push(u16::from_be_bytes(bytes));
     }
-    String::from_utf16(&data2).map_err(|_| {
-        Error::new(
-            ErrorKind::StringDecoding(data.to_vec()),
-            "data is not valid utf16-le",
+    String::from_utf16(&data2).map_err(|err| {
+        invalid_utf16(
+            data,
+            &data2,
+            Encoding::UTF16BE,
+            "data is not valid utf16-be",
         )
+        .with_cause(err)
     })
 }
 
+/// Creates an error pointing at the first unpaired surrogate in the UTF-16 code units.
+fn invalid_utf16(data: &[u8], units: &[u16], encoding: Encoding, description: &str) -> Error {
+    let mut index = 0;
+    for c in char::decode_utf16(units.iter().copied()) {
+        match c {
+            Ok(c) => index += c.len_utf16(),
+            Err(_) => break,
+        }
+    }
+    let range = index * 2..index * 2 + 2;
+    Error::string_decoding(data, encoding, range, description)
+}
+
 fn string_to_latin1(text: &str) -> Vec<u8> {
     text.chars().map(|c| c as u8).collect()
 }
@@ -131,6 +151,22 @@ fn string_to_utf16le(text: &str) -> Vec<u8> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn test_invalid_utf16() {
+        // "ab" followed by an unpaired high surrogate and "c".
+        let data = [0xFF, 0xFE, b'a', 0, b'b', 0, 0x00, 0xD8, b'c', 0];
+        let err = Encoding::UTF16.decode(data).unwrap_err();
+        match err.kind {
+            ErrorKind::StringDecoding(ref bytes) => assert_eq!(bytes, &data[2..]),
+            ref kind => panic!("unexpected error kind: {:?}", kind),
+        }
+        assert_eq!(err.string_encoding(), Some(Encoding::UTF16));
+        assert_eq!(err.string_range(), Some(6..8));
+        assert_eq!(&data[err.string_range().unwrap()], &[0x00, 0xD8]);
+        assert!(std::error::Error::source(&err).is_some());
+    }
 
     #[test]
     fn test_strings() {
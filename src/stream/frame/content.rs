@@ -1,13 +1,17 @@
 use crate::frame::{
-    Chapter, Comment, Content, EncapsulatedObject, ExtendedLink, ExtendedText, InvolvedPeopleList,
-    InvolvedPeopleListItem, Lyrics, MpegLocationLookupTable, MpegLocationLookupTableReference,
-    Picture, PictureType, Popularimeter, Private, SynchronisedLyrics, SynchronisedLyricsType,
-    TableOfContents, TimestampFormat, UniqueFileIdentifier, Unknown,
+    AudioSeekPointIndex, Chapter, Comment, Commercial, Content, EncapsulatedObject, EncryptedFrame,
+    EncryptedMeta, EncryptionMethod, Equalisation, EventTimingCodes, ExtendedLink, ExtendedText,
+    GroupRegistration, InvolvedPeopleList, InvolvedPeopleListItem, LegacyChannelVolume,
+    LegacyEqualisation, LegacyVolumeAdjustment, LinkedInformation, Lyrics, MpegLocationLookupTable,
+    MpegLocationLookupTableReference, MusicCdIdentifier, Ownership, Picture, PictureType,
+    Popularimeter, Private, RelativeVolumeAdjustment, SellerLogo, Signature, SynchronisedLyrics,
+    SynchronisedLyricsType, SynchronisedTempoCodes, TableOfContents, TermsOfUse, TimestampFormat,
+    UniqueFileIdentifier, Unknown, VolumeAdjustment,
 };
 use crate::stream::encoding::Encoding;
 use crate::stream::frame;
 use crate::tag::Version;
-use crate::{Error, ErrorKind};
+use crate::{Error, ErrorKind, Warning};
 use std::convert::{TryFrom, TryInto};
 use std::io;
 use std::iter;
@@ -70,6 +74,13 @@ impl<W: io::Write> Encoder<W> {
         })
     }
 
+    fn timestamp_format(&mut self, timestamp_format: TimestampFormat) -> crate::Result<()> {
+        self.byte(match timestamp_format {
+            TimestampFormat::Mpeg => 1,
+            TimestampFormat::Ms => 2,
+        })
+    }
+
     fn text_content(&mut self, content: &str) -> crate::Result<()> {
         self.encoding()?;
         match self.version {
@@ -124,17 +135,7 @@ impl<W: io::Write> Encoder<W> {
     }
 
     fn synchronised_lyrics_content(&mut self, content: &SynchronisedLyrics) -> crate::Result<()> {
-        // SYLT frames are really weird because they encode the text encoding and delimiters in a
-        // different way.
-        let encoding = match self.encoding {
-            Encoding::Latin1 => Encoding::Latin1,
-            _ => Encoding::UTF16,
-        };
-        self.byte(match encoding {
-            Encoding::Latin1 => 0,
-            Encoding::UTF16 => 1,
-            _ => unreachable!(),
-        })?;
+        self.encoding()?;
         self.bytes(
             content
                 .lang
@@ -143,10 +144,7 @@ impl<W: io::Write> Encoder<W> {
                 .take(3)
                 .collect::<Vec<u8>>(),
         )?;
-        self.byte(match content.timestamp_format {
-            TimestampFormat::Mpeg => 1,
-            TimestampFormat::Ms => 2,
-        })?;
+        self.timestamp_format(content.timestamp_format)?;
         self.byte(match content.content_type {
             SynchronisedLyricsType::Other => 0,
             SynchronisedLyricsType::Lyrics => 1,
@@ -156,17 +154,12 @@ impl<W: io::Write> Encoder<W> {
             SynchronisedLyricsType::Chord => 5,
             SynchronisedLyricsType::Trivia => 6,
         })?;
-        let text_delim: &[u8] = match encoding {
-            Encoding::Latin1 => &[0],
-            Encoding::UTF16 => &[0, 0],
-            _ => unreachable!(),
-        };
         // Description
-        self.string_with_other_encoding(encoding, &content.description)?;
-        self.bytes(text_delim)?;
+        self.string(&content.description)?;
+        self.delim()?;
         for (timestamp, text) in &content.content {
-            self.string_with_other_encoding(encoding, text)?;
-            self.bytes(text_delim)?;
+            self.string(text)?;
+            self.delim()?;
             self.uint32(*timestamp)?;
         }
         self.byte(0)
@@ -319,6 +312,325 @@ impl<W: io::Write> Encoder<W> {
         Ok(())
     }
 
+    fn relative_volume_adjustment_content(
+        &mut self,
+        content: &RelativeVolumeAdjustment,
+    ) -> crate::Result<()> {
+        self.string_with_other_encoding(Encoding::Latin1, &content.identification)?;
+        self.byte(0)?;
+        for adjustment in &content.adjustments {
+            let peak_len = (usize::from(adjustment.bits_representing_peak) + 7) / 8;
+            if adjustment.peak_volume.len() != peak_len {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "RVA2 peak volume length does not match bits representing peak",
+                ));
+            }
+            self.byte(u8::from(adjustment.channel_type))?;
+            self.bytes(adjustment.volume_adjustment.to_be_bytes())?;
+            self.byte(adjustment.bits_representing_peak)?;
+            self.bytes(&adjustment.peak_volume)?;
+        }
+        Ok(())
+    }
+
+    fn legacy_volume_adjustment_content(
+        &mut self,
+        content: &LegacyVolumeAdjustment,
+    ) -> crate::Result<()> {
+        let adjustments = &content.adjustments;
+        let channels_valid = matches!(adjustments.len(), 2 | 4 | 5 | 6)
+            && adjustments
+                .iter()
+                .zip(LegacyVolumeAdjustment::CHANNELS)
+                .all(|(adjustment, channel_type)| adjustment.channel_type == channel_type);
+        if !channels_valid {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "RVAD channels must be front right and left, optionally followed by back right \
+                 and left, front centre and subwoofer",
+            ));
+        }
+        let bits = legacy_bits(content.bits_used, ErrorKind::InvalidInput)?;
+        let increments = adjustments
+            .iter()
+            .enumerate()
+            .filter(|(_, adjustment)| adjustment.volume_change > 0)
+            .fold(0, |flags, (i, _)| flags | 1 << i);
+        self.byte(increments)?;
+        self.byte(bits)?;
+        // The changes and peaks are stored per group of channels.
+        for group in [0..2, 2..4, 4..5, 5..6] {
+            let Some(group) = adjustments.get(group) else {
+                break;
+            };
+            for adjustment in group {
+                self.legacy_value(adjustment.volume_change.unsigned_abs(), bits)?;
+            }
+            for adjustment in group {
+                self.legacy_value(u64::from(adjustment.peak_volume), bits)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn legacy_equalisation_content(&mut self, content: &LegacyEqualisation) -> crate::Result<()> {
+        let bits = legacy_bits(content.adjustment_bits, ErrorKind::InvalidInput)?;
+        self.byte(bits)?;
+        for (frequency, volume_change) in &content.bands {
+            if *frequency > 0x7fff {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "EQUA frequencies must not exceed 32767 Hz",
+                ));
+            }
+            let increment = if *volume_change > 0 { 0x8000 } else { 0 };
+            self.uint16(frequency | increment)?;
+            self.legacy_value(volume_change.unsigned_abs(), bits)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a value of an RVAD or EQUA frame using `ceil(bits / 8)` bytes.
+    fn legacy_value(&mut self, value: u64, bits: u8) -> crate::Result<()> {
+        if value >> bits != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("value {} does not fit in {} bits", value, bits),
+            ));
+        }
+        let len = (usize::from(bits) + 7) / 8;
+        self.bytes(&value.to_be_bytes()[8 - len..])
+    }
+
+    fn equalisation_content(&mut self, content: &Equalisation) -> crate::Result<()> {
+        self.byte(u8::from(content.interpolation_method))?;
+        self.string_with_other_encoding(Encoding::Latin1, &content.identification)?;
+        self.byte(0)?;
+        for (frequency, adjustment) in &content.adjustment_points {
+            self.uint16(*frequency)?;
+            self.bytes(adjustment.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn event_timing_codes_content(&mut self, content: &EventTimingCodes) -> crate::Result<()> {
+        self.timestamp_format(content.timestamp_format)?;
+        for (event_type, timestamp) in &content.events {
+            self.byte(u8::from(*event_type))?;
+            self.uint32(*timestamp)?;
+        }
+        Ok(())
+    }
+
+    fn synchronised_tempo_codes_content(
+        &mut self,
+        content: &SynchronisedTempoCodes,
+    ) -> crate::Result<()> {
+        self.timestamp_format(content.timestamp_format)?;
+        for (tempo, timestamp) in &content.tempos {
+            // Tempos of 255 and above are escaped with 0xff followed by the remainder.
+            match *tempo {
+                0..=254 => self.byte(*tempo as u8)?,
+                255..=510 => {
+                    self.byte(0xff)?;
+                    self.byte((*tempo - 255) as u8)?;
+                }
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "SYTC tempo must not exceed 510 BPM",
+                    ))
+                }
+            }
+            self.uint32(*timestamp)?;
+        }
+        Ok(())
+    }
+
+    fn audio_seek_point_index_content(
+        &mut self,
+        content: &AudioSeekPointIndex,
+    ) -> crate::Result<()> {
+        let num_points = u16::try_from(content.index_points.len()).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "ASPI frames can not hold more than 65535 index points",
+            )
+        })?;
+        self.uint32(content.indexed_data_start)?;
+        self.uint32(content.indexed_data_length)?;
+        self.uint16(num_points)?;
+        self.byte(content.bits_per_index_point)?;
+        match content.bits_per_index_point {
+            8 => {
+                for point in &content.index_points {
+                    let point = u8::try_from(*point).map_err(|_| {
+                        Error::new(
+                            ErrorKind::InvalidInput,
+                            "ASPI index points must not exceed 255 with 8 bits per point",
+                        )
+                    })?;
+                    self.byte(point)?;
+                }
+            }
+            16 => {
+                for point in &content.index_points {
+                    self.uint16(*point)?;
+                }
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "ASPI bits per index point must be 8 or 16",
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    fn ownership_content(&mut self, content: &Ownership) -> crate::Result<()> {
+        self.encoding()?;
+        self.string_with_other_encoding(Encoding::Latin1, &content.price_paid)?;
+        self.byte(0)?;
+        self.date(&content.date_of_purchase, "OWNE")?;
+        self.string(&content.seller)
+    }
+
+    /// Writes an 8 character date in the format YYYYMMDD.
+    fn date(&mut self, date: &str, frame_id: &str) -> crate::Result<()> {
+        if date.len() != 8 || !date.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} date must be 8 digits in the format YYYYMMDD", frame_id),
+            ));
+        }
+        self.bytes(date.as_bytes())
+    }
+
+    fn commercial_content(&mut self, content: &Commercial) -> crate::Result<()> {
+        self.encoding()?;
+        self.string_with_other_encoding(Encoding::Latin1, &content.price)?;
+        self.byte(0)?;
+        self.date(&content.valid_until, "COMR")?;
+        self.string_with_other_encoding(Encoding::Latin1, &content.contact_url)?;
+        self.byte(0)?;
+        self.byte(u8::from(content.received_as))?;
+        self.string(&content.seller_name)?;
+        self.delim()?;
+        self.string(&content.description)?;
+        self.delim()?;
+        if let Some(logo) = &content.seller_logo {
+            self.string_with_other_encoding(Encoding::Latin1, &logo.mime_type)?;
+            self.byte(0)?;
+            self.bytes(&logo.data)?;
+        }
+        Ok(())
+    }
+
+    fn terms_of_use_content(&mut self, content: &TermsOfUse) -> crate::Result<()> {
+        self.encoding()?;
+        self.bytes(
+            content
+                .lang
+                .bytes()
+                .chain(iter::repeat(b' '))
+                .take(3)
+                .collect::<Vec<u8>>(),
+        )?;
+        self.string(&content.text)
+    }
+
+    fn play_counter_content(&mut self, count: u64) -> crate::Result<()> {
+        // The counter is at least 32 bits and grows by a byte when it overflows.
+        let bytes = count.to_be_bytes();
+        let start = bytes.iter().take(4).take_while(|&&b| b == 0).count();
+        self.bytes(&bytes[start..])
+    }
+
+    fn music_cd_identifier_content(&mut self, content: &MusicCdIdentifier) -> crate::Result<()> {
+        self.bytes(&content.toc)
+    }
+
+    fn linked_information_content(&mut self, content: &LinkedInformation) -> crate::Result<()> {
+        let id = content.frame_identifier.as_str();
+        let (id, width) = match self.version {
+            Version::Id3v22 if id.len() == 4 => (crate::frame::convert_id_3_to_2(id), 3),
+            Version::Id3v22 => (Some(id), 3),
+            Version::Id3v23 | Version::Id3v24 if id.len() == 3 => {
+                (crate::frame::convert_id_2_to_3(id), 4)
+            }
+            Version::Id3v23 | Version::Id3v24 => (Some(id), 4),
+        };
+        let id = id.filter(|id| id.len() == width).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "LINK frame identifier {:?} can not be written as {}",
+                    content.frame_identifier, self.version
+                ),
+            )
+        })?;
+        self.bytes(id.as_bytes())?;
+        self.string_with_other_encoding(Encoding::Latin1, &content.url)?;
+        self.byte(0)?;
+        self.bytes(&content.additional_data)
+    }
+
+    fn group_registration_content(&mut self, content: &GroupRegistration) -> crate::Result<()> {
+        self.string_with_other_encoding(Encoding::Latin1, &content.owner_identifier)?;
+        self.byte(0)?;
+        self.group_symbol(content.group_symbol)?;
+        self.bytes(&content.data)
+    }
+
+    fn signature_content(&mut self, content: &Signature) -> crate::Result<()> {
+        self.group_symbol(content.group_symbol)?;
+        self.bytes(&content.signature)
+    }
+
+    fn group_symbol(&mut self, symbol: u8) -> crate::Result<()> {
+        if !(0x80..=0xf0).contains(&symbol) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "group symbols must be in the range 0x80 to 0xf0",
+            ));
+        }
+        self.byte(symbol)
+    }
+
+    fn encryption_method_content(&mut self, content: &EncryptionMethod) -> crate::Result<()> {
+        self.string_with_other_encoding(Encoding::Latin1, &content.owner_identifier)?;
+        self.byte(0)?;
+        if content.method_symbol < 0x80 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "encryption method symbols below 0x80 are reserved",
+            ));
+        }
+        self.byte(content.method_symbol)?;
+        self.bytes(&content.encryption_data)
+    }
+
+    fn encrypted_frame_content(&mut self, content: &EncryptedFrame) -> crate::Result<()> {
+        // The frame header fields are written by the frame encoder of the version.
+        if self.version == Version::Id3v22 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "encrypted frames can not be written to ID3v2.2 tags",
+            ));
+        }
+        self.bytes(&content.data)
+    }
+
+    fn encrypted_meta_content(&mut self, content: &EncryptedMeta) -> crate::Result<()> {
+        self.string_with_other_encoding(Encoding::Latin1, &content.owner_identifier)?;
+        self.byte(0)?;
+        self.string_with_other_encoding(Encoding::Latin1, &content.content_description)?;
+        self.byte(0)?;
+        self.bytes(&content.encrypted_data)
+    }
+
     fn table_of_contents_content(&mut self, content: &TableOfContents) -> crate::Result<()> {
         self.string_with_other_encoding(Encoding::Latin1, &content.element_id)?;
         self.byte(0)?;
@@ -375,6 +687,31 @@ pub fn encode(
         Content::TableOfContents(c) => encoder.table_of_contents_content(c)?,
         Content::UniqueFileIdentifier(c) => encoder.unique_file_identifier_content(c)?,
         Content::InvolvedPeopleList(c) => encoder.involved_people_list(c)?,
+        Content::RelativeVolumeAdjustment(c) => encoder.relative_volume_adjustment_content(c)?,
+        Content::EventTimingCodes(c) => encoder.event_timing_codes_content(c)?,
+        Content::Ownership(c) => encoder.ownership_content(c)?,
+        Content::Commercial(c) => encoder.commercial_content(c)?,
+        Content::TermsOfUse(c) => encoder.terms_of_use_content(c)?,
+        Content::PlayCounter(c) => encoder.play_counter_content(*c)?,
+        Content::MusicCdIdentifier(c) => encoder.music_cd_identifier_content(c)?,
+        Content::LinkedInformation(c) => encoder.linked_information_content(c)?,
+        Content::SynchronisedTempoCodes(c) => encoder.synchronised_tempo_codes_content(c)?,
+        Content::AudioSeekPointIndex(c) => encoder.audio_seek_point_index_content(c)?,
+        Content::GroupRegistration(c) => encoder.group_registration_content(c)?,
+        Content::Signature(c) => encoder.signature_content(c)?,
+        Content::LegacyVolumeAdjustment(c) => encoder.legacy_volume_adjustment_content(c)?,
+        Content::LegacyEqualisation(c) => encoder.legacy_equalisation_content(c)?,
+        Content::Equalisation(c) => encoder.equalisation_content(c)?,
+        Content::PodcastFlag(c) => encoder.uint32(*c)?,
+        Content::EncryptedMeta(c) => encoder.encrypted_meta_content(c)?,
+        Content::EncryptionMethod(c) => encoder.encryption_method_content(c)?,
+        Content::EncryptedFrame(c) => encoder.encrypted_frame_content(c)?,
+        Content::Skipped(_) => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "the content of the frame was skipped while decoding",
+            ))
+        }
         Content::Unknown(c) => encoder.bytes(&c.data)?,
     };
 
@@ -383,15 +720,29 @@ pub fn encode(
 }
 
 pub fn decode(
+    id: &str,
+    version: Version,
+    reader: impl io::Read,
+) -> crate::Result<(Content, Option<Encoding>)> {
+    decode_with_warnings(id, version, reader, &mut Vec::new())
+}
+
+/// Decodes the content of a frame like [`decode`], pushing recoverable issues with the content to
+/// `warnings`.
+pub fn decode_with_warnings(
     id: &str,
     version: Version,
     mut reader: impl io::Read,
+    warnings: &mut Vec<Warning>,
 ) -> crate::Result<(Content, Option<Encoding>)> {
     let mut data = Vec::new();
     reader.read_to_end(&mut data)?;
+    let first_warning = warnings.len();
     let decoder = Decoder {
+        start: data.as_ptr() as usize,
         r: &mut data,
         version,
+        warnings,
     };
 
     let mut encoding = None;
@@ -439,21 +790,47 @@ pub fn decode(
             decoder.text_content_multiple()
         }
         id if id.starts_with('T') => decoder.text_content(),
+        // iTunes writes the podcast feed URL as a text frame.
+        "WFED" => decoder.text_content(),
         id if id.starts_with('W') => decoder.link_content(),
-        "GRP1" => decoder.text_content(),
+        "GRP1" | "MVNM" | "MVN" | "MVIN" | "MVI" => decoder.text_content(),
         "CHAP" => decoder.chapter_content(),
         "MLLT" => decoder.mpeg_location_lookup_table_content(),
         "PRIV" => decoder.private_content(),
         "UFID" => decoder.unique_file_identifier_content(),
         "CTOC" => decoder.table_of_contents_content(),
+        "RVA2" => decoder.relative_volume_adjustment_content(),
+        "ETCO" | "ETC" => decoder.event_timing_codes_content(),
+        "OWNE" => decoder.ownership_content(),
+        "COMR" => decoder.commercial_content(),
+        "USER" => decoder.terms_of_use_content(),
+        "PCNT" | "CNT" => decoder.play_counter_content(),
+        "MCDI" | "MCI" => decoder.music_cd_identifier_content(),
+        "LINK" | "LNK" => decoder.linked_information_content(),
+        "SYTC" | "STC" => decoder.synchronised_tempo_codes_content(),
+        "ASPI" => decoder.audio_seek_point_index_content(),
+        "GRID" => decoder.group_registration_content(),
+        "SIGN" => decoder.signature_content(),
+        "RVAD" | "RVA" => decoder.legacy_volume_adjustment_content(),
+        "EQUA" | "EQU" => decoder.legacy_equalisation_content(),
+        "EQU2" => decoder.equalisation_content(),
+        "PCST" => decoder.podcast_flag_content(),
+        "CRM" => decoder.encrypted_meta_content(),
+        "ENCR" => decoder.encryption_method_content(),
         _ => Ok(Content::Unknown(Unknown { data, version })),
     }?;
+    for warning in &mut warnings[first_warning..] {
+        warning.frame_id.get_or_insert_with(|| id.to_string());
+    }
     Ok((content, encoding))
 }
 
 struct Decoder<'a> {
     r: &'a [u8],
+    /// The address of the start of the frame content, used to locate errors.
+    start: usize,
     version: Version,
+    warnings: &'a mut Vec<Warning>,
 }
 
 impl<'a> Decoder<'a> {
@@ -492,8 +869,16 @@ impl<'a> Decoder<'a> {
         Ok(u32::from_be_bytes(a))
     }
 
+    /// Decodes a string from a slice of the frame content, reporting the position of any
+    /// invalid bytes relative to the start of the content.
+    fn decode_string(&self, encoding: Encoding, bytes: &[u8]) -> crate::Result<String> {
+        encoding
+            .decode(bytes)
+            .map_err(|err| err.offset_string_range(bytes.as_ptr() as usize - self.start))
+    }
+
     fn string_until_eof(&mut self, encoding: Encoding) -> crate::Result<String> {
-        encoding.decode(self.r)
+        self.decode_string(encoding, self.r)
     }
 
     fn string_delimited(&mut self, encoding: Encoding) -> crate::Result<String> {
@@ -502,12 +887,12 @@ impl<'a> Decoder<'a> {
         let delim_len = delim_len(encoding);
         let b = self.bytes(delim)?;
         self.bytes(delim_len)?; // Skip.
-        encoding.decode(b)
+        self.decode_string(encoding, b)
     }
 
     fn string_fixed(&mut self, bytes_len: usize) -> crate::Result<String> {
         let s = self.bytes(bytes_len)?;
-        Encoding::Latin1.decode(s)
+        self.decode_string(Encoding::Latin1, s)
     }
 
     fn encoding(&mut self) -> crate::Result<Encoding> {
@@ -520,6 +905,14 @@ impl<'a> Decoder<'a> {
         }
     }
 
+    fn timestamp_format(&mut self) -> crate::Result<TimestampFormat> {
+        match self.byte()? {
+            1 => Ok(TimestampFormat::Mpeg),
+            2 => Ok(TimestampFormat::Ms),
+            _ => Err(Error::new(ErrorKind::Parsing, "invalid timestamp format")),
+        }
+    }
+
     fn text_content(mut self) -> crate::Result<Content> {
         let encoding = self.encoding()?;
         let (end, _) = match self.version {
@@ -532,7 +925,8 @@ impl<'a> Decoder<'a> {
                 None => (self.r.len(), self.r.len()),
             },
         };
-        let text = encoding.decode(self.bytes(end)?)?;
+        let bytes = self.bytes(end)?;
+        let text = self.decode_string(encoding, bytes)?;
         Ok(Content::Text(text))
     }
 
@@ -561,16 +955,17 @@ impl<'a> Decoder<'a> {
         let data = self.bytes(end)?;
 
         let mut pos = 0;
+        let mut dangling = false;
         let items = iter::repeat_with(|| {
             find_delim(encoding, data, pos)
                 .map(|next_pos| {
-                    let substr = encoding.decode(&data[pos..next_pos]);
+                    let substr = self.decode_string(encoding, &data[pos..next_pos]);
                     pos = next_pos + delim_len(encoding);
                     substr
                 })
                 .or_else(|| {
                     if pos < data.len() {
-                        let substr = encoding.decode(&data[pos..]);
+                        let substr = self.decode_string(encoding, &data[pos..]);
                         pos = data.len();
                         Some(substr)
                     } else {
@@ -598,21 +993,24 @@ impl<'a> Decoder<'a> {
             (Some(_), None) => {
                 // This can only happen if there is an uneven number of elements.
                 *last_string = None;
-                Some(Err(Error::new(
-                    ErrorKind::Parsing,
-                    "uneven number of IPLS strings",
-                )))
+                dangling = true;
+                Some(Ok(None))
             }
             (None, None) => None,
         })
         .filter_map(|item| item.transpose())
         .collect::<crate::Result<Vec<InvolvedPeopleListItem>>>()?;
+        if dangling {
+            self.warnings.push(Warning::new(
+                "uneven number of strings in the involved people list, the last one is ignored",
+            ));
+        }
 
         Ok(Content::InvolvedPeopleList(InvolvedPeopleList { items }))
     }
 
     fn link_content(self) -> crate::Result<Content> {
-        Ok(Content::Link(String::from_utf8(self.r.to_vec())?))
+        Ok(Content::Link(self.decode_string(Encoding::UTF8, self.r)?))
     }
 
     fn picture_type(&mut self) -> crate::Result<PictureType> {
@@ -763,20 +1161,13 @@ impl<'a> Decoder<'a> {
         let (encoding, text_delim) = match self.byte()? {
             0 => (Encoding::Latin1, &[0][..]),
             1 => (Encoding::UTF16, &[0, 0][..]),
+            2 if self.version == Version::Id3v24 => (Encoding::UTF16BE, &[0, 0][..]),
+            3 if self.version == Version::Id3v24 => (Encoding::UTF8, &[0][..]),
             _ => return Err(Error::new(ErrorKind::Parsing, "invalid SYLT encoding")),
         };
 
         let lang = self.string_fixed(3)?;
-        let timestamp_format = match self.byte()? {
-            1 => TimestampFormat::Mpeg,
-            2 => TimestampFormat::Ms,
-            _ => {
-                return Err(Error::new(
-                    ErrorKind::Parsing,
-                    "invalid SYLT timestamp format",
-                ))
-            }
-        };
+        let timestamp_format = self.timestamp_format()?;
         let content_type = match self.byte()? {
             0 => SynchronisedLyricsType::Other,
             1 => SynchronisedLyricsType::Lyrics,
@@ -796,7 +1187,7 @@ impl<'a> Decoder<'a> {
             .position(|w| w == text_delim)
         {
             let i = i * text_delim.len();
-            let text = encoding.decode(&self.r[..i])?;
+            let text = self.decode_string(encoding, &self.r[..i])?;
 
             self.r = &self.r[i + text_delim.len()..];
 
@@ -805,6 +1196,10 @@ impl<'a> Decoder<'a> {
                 description = Some(text);
                 continue;
             }
+            // The content may be terminated by a null byte.
+            if text.is_empty() && self.r.len() < 4 {
+                break;
+            }
 
             let timestamp = self.uint32()?;
             content.push((timestamp, text));
@@ -940,6 +1335,301 @@ impl<'a> Decoder<'a> {
         }))
     }
 
+    fn relative_volume_adjustment_content(mut self) -> crate::Result<Content> {
+        let identification = self.string_delimited(Encoding::Latin1)?;
+        let mut adjustments = Vec::new();
+        while !self.r.is_empty() {
+            let channel_type = self.byte()?.into();
+            let volume_adjustment = self.uint16()? as i16;
+            let bits_representing_peak = self.byte()?;
+            let peak_len = (usize::from(bits_representing_peak) + 7) / 8;
+            let peak_volume = self.bytes(peak_len)?.to_vec();
+            adjustments.push(VolumeAdjustment {
+                channel_type,
+                volume_adjustment,
+                bits_representing_peak,
+                peak_volume,
+            });
+        }
+        Ok(Content::RelativeVolumeAdjustment(
+            RelativeVolumeAdjustment {
+                identification,
+                adjustments,
+            },
+        ))
+    }
+
+    fn legacy_volume_adjustment_content(mut self) -> crate::Result<Content> {
+        let increments = self.byte()?;
+        let bits_used = legacy_bits(self.byte()?, ErrorKind::Parsing)?;
+        let mut adjustments = Vec::new();
+        // The changes and peaks are stored per group of channels, of which only the first is
+        // required.
+        for group in [0..2, 2..4, 4..5, 5..6] {
+            if self.r.is_empty() {
+                break;
+            }
+            let changes = group
+                .clone()
+                .map(|_| self.legacy_value(bits_used))
+                .collect::<crate::Result<Vec<_>>>()?;
+            let peaks = group
+                .clone()
+                .map(|_| self.legacy_value(bits_used))
+                .collect::<crate::Result<Vec<_>>>()?;
+            for ((i, change), peak) in group.zip(changes).zip(peaks) {
+                let change = change as i64;
+                adjustments.push(LegacyChannelVolume {
+                    channel_type: LegacyVolumeAdjustment::CHANNELS[i],
+                    volume_change: if increments & 1 << i != 0 {
+                        change
+                    } else {
+                        -change
+                    },
+                    peak_volume: peak as u32,
+                });
+            }
+        }
+        Ok(Content::LegacyVolumeAdjustment(LegacyVolumeAdjustment {
+            bits_used,
+            adjustments,
+        }))
+    }
+
+    fn legacy_equalisation_content(mut self) -> crate::Result<Content> {
+        let adjustment_bits = legacy_bits(self.byte()?, ErrorKind::Parsing)?;
+        let mut bands = Vec::new();
+        while !self.r.is_empty() {
+            let frequency = self.uint16()?;
+            let change = self.legacy_value(adjustment_bits)? as i64;
+            let volume_change = if frequency & 0x8000 != 0 {
+                change
+            } else {
+                -change
+            };
+            bands.push((frequency & 0x7fff, volume_change));
+        }
+        Ok(Content::LegacyEqualisation(LegacyEqualisation {
+            adjustment_bits,
+            bands,
+        }))
+    }
+
+    /// Reads a value of an RVAD or EQUA frame that is stored using `ceil(bits / 8)` bytes.
+    fn legacy_value(&mut self, bits: u8) -> crate::Result<u64> {
+        let len = (usize::from(bits) + 7) / 8;
+        let b = self.bytes(len)?;
+        Ok(b.iter().fold(0, |acc, b| acc << 8 | u64::from(*b)))
+    }
+
+    fn equalisation_content(mut self) -> crate::Result<Content> {
+        let interpolation_method = self.byte()?.into();
+        let identification = self.string_delimited(Encoding::Latin1)?;
+        let mut adjustment_points = Vec::new();
+        while !self.r.is_empty() {
+            let frequency = self.uint16()?;
+            let adjustment = self.uint16()? as i16;
+            adjustment_points.push((frequency, adjustment));
+        }
+        Ok(Content::Equalisation(Equalisation {
+            interpolation_method,
+            identification,
+            adjustment_points,
+        }))
+    }
+
+    fn event_timing_codes_content(mut self) -> crate::Result<Content> {
+        let timestamp_format = self.timestamp_format()?;
+        let mut events = Vec::new();
+        while !self.r.is_empty() {
+            let event_type = self.byte()?.into();
+            let timestamp = self.uint32()?;
+            events.push((event_type, timestamp));
+        }
+        Ok(Content::EventTimingCodes(EventTimingCodes {
+            timestamp_format,
+            events,
+        }))
+    }
+
+    fn synchronised_tempo_codes_content(mut self) -> crate::Result<Content> {
+        let timestamp_format = self.timestamp_format()?;
+        let mut tempos = Vec::new();
+        while !self.r.is_empty() {
+            let tempo = match self.byte()? {
+                0xff => 0xff + u16::from(self.byte()?),
+                b => u16::from(b),
+            };
+            let timestamp = self.uint32()?;
+            tempos.push((tempo, timestamp));
+        }
+        Ok(Content::SynchronisedTempoCodes(SynchronisedTempoCodes {
+            timestamp_format,
+            tempos,
+        }))
+    }
+
+    fn audio_seek_point_index_content(mut self) -> crate::Result<Content> {
+        let indexed_data_start = self.uint32()?;
+        let indexed_data_length = self.uint32()?;
+        let num_points = self.uint16()?;
+        let bits_per_index_point = self.byte()?;
+        let index_points = (0..num_points)
+            .map(|_| match bits_per_index_point {
+                8 => self.byte().map(u16::from),
+                16 => self.uint16(),
+                _ => Err(Error::new(
+                    ErrorKind::Parsing,
+                    "ASPI bits per index point must be 8 or 16",
+                )),
+            })
+            .collect::<crate::Result<_>>()?;
+        Ok(Content::AudioSeekPointIndex(AudioSeekPointIndex {
+            indexed_data_start,
+            indexed_data_length,
+            bits_per_index_point,
+            index_points,
+        }))
+    }
+
+    fn ownership_content(mut self) -> crate::Result<Content> {
+        let encoding = self.encoding()?;
+        let price_paid = self.string_delimited(Encoding::Latin1)?;
+        let date_of_purchase = self.string_fixed(8)?;
+        let seller = self.string_until_eof(encoding)?;
+        Ok(Content::Ownership(Ownership {
+            price_paid,
+            date_of_purchase,
+            seller,
+        }))
+    }
+
+    fn commercial_content(mut self) -> crate::Result<Content> {
+        let encoding = self.encoding()?;
+        let price = self.string_delimited(Encoding::Latin1)?;
+        let valid_until = self.string_fixed(8)?;
+        let contact_url = self.string_delimited(Encoding::Latin1)?;
+        let received_as = self.byte()?.into();
+        let seller_name = self.string_delimited(encoding)?;
+        // Some writers omit the terminator of the description if no logo follows.
+        let description = if find_delim(encoding, self.r, 0).is_some() {
+            self.string_delimited(encoding)?
+        } else {
+            let description = self.string_until_eof(encoding)?;
+            self.r = &[];
+            description
+        };
+        let seller_logo = if self.r.is_empty() {
+            None
+        } else {
+            let mime_type = self.string_delimited(Encoding::Latin1)?;
+            let data = self.r.to_vec();
+            Some(SellerLogo { mime_type, data })
+        };
+        Ok(Content::Commercial(Commercial {
+            price,
+            valid_until,
+            contact_url,
+            received_as,
+            seller_name,
+            description,
+            seller_logo,
+        }))
+    }
+
+    fn terms_of_use_content(mut self) -> crate::Result<Content> {
+        let encoding = self.encoding()?;
+        let lang = self.string_fixed(3)?;
+        let text = self.string_until_eof(encoding)?;
+        Ok(Content::TermsOfUse(TermsOfUse { lang, text }))
+    }
+
+    fn play_counter_content(self) -> crate::Result<Content> {
+        let significant = &self.r[self.r.iter().take_while(|&&b| b == 0).count()..];
+        if significant.len() > size_of::<u64>() {
+            return Err(Error::new(
+                ErrorKind::Parsing,
+                "PCNT counter does not fit in 64 bits",
+            ));
+        }
+        let count = significant
+            .iter()
+            .fold(0u64, |acc, &b| (acc << 8) | u64::from(b));
+        Ok(Content::PlayCounter(count))
+    }
+
+    fn podcast_flag_content(self) -> crate::Result<Content> {
+        // The flag is written as four bytes, but readers should not depend on that.
+        if self.r.len() > 4 {
+            return Err(Error::new(
+                ErrorKind::Parsing,
+                "PCST frame is longer than four bytes",
+            ));
+        }
+        let flag = self.r.iter().fold(0, |acc, b| acc << 8 | u32::from(*b));
+        Ok(Content::PodcastFlag(flag))
+    }
+
+    fn music_cd_identifier_content(self) -> crate::Result<Content> {
+        Ok(Content::MusicCdIdentifier(MusicCdIdentifier {
+            toc: self.r.to_vec(),
+        }))
+    }
+
+    fn linked_information_content(mut self) -> crate::Result<Content> {
+        let frame_identifier = match self.version {
+            Version::Id3v22 => {
+                let id = self.string_fixed(3)?;
+                crate::frame::convert_id_2_to_3(&id).map_or(id, |id| id.to_string())
+            }
+            Version::Id3v23 | Version::Id3v24 => self.string_fixed(4)?,
+        };
+        let url = self.string_delimited(Encoding::Latin1)?;
+        Ok(Content::LinkedInformation(LinkedInformation {
+            frame_identifier,
+            url,
+            additional_data: self.r.to_vec(),
+        }))
+    }
+
+    fn group_registration_content(mut self) -> crate::Result<Content> {
+        let owner_identifier = self.string_delimited(Encoding::Latin1)?;
+        let group_symbol = self.byte()?;
+        Ok(Content::GroupRegistration(GroupRegistration {
+            owner_identifier,
+            group_symbol,
+            data: self.r.to_vec(),
+        }))
+    }
+
+    fn signature_content(mut self) -> crate::Result<Content> {
+        let group_symbol = self.byte()?;
+        Ok(Content::Signature(Signature {
+            group_symbol,
+            signature: self.r.to_vec(),
+        }))
+    }
+
+    fn encryption_method_content(mut self) -> crate::Result<Content> {
+        let owner_identifier = self.string_delimited(Encoding::Latin1)?;
+        let method_symbol = self.byte()?;
+        Ok(Content::EncryptionMethod(EncryptionMethod {
+            owner_identifier,
+            method_symbol,
+            encryption_data: self.r.to_vec(),
+        }))
+    }
+
+    fn encrypted_meta_content(mut self) -> crate::Result<Content> {
+        let owner_identifier = self.string_delimited(Encoding::Latin1)?;
+        let content_description = self.string_delimited(Encoding::Latin1)?;
+        Ok(Content::EncryptedMeta(EncryptedMeta {
+            owner_identifier,
+            content_description,
+            encrypted_data: self.r.to_vec(),
+        }))
+    }
+
     fn table_of_contents_content(mut self) -> crate::Result<Content> {
         let element_id = self.string_delimited(Encoding::Latin1)?;
         let flags = self.byte()?;
@@ -964,6 +1654,17 @@ impl<'a> Decoder<'a> {
     }
 }
 
+/// Checks the number of bits used by the values of an RVAD or EQUA frame.
+fn legacy_bits(bits: u8, kind: ErrorKind) -> crate::Result<u8> {
+    if !(1..=32).contains(&bits) {
+        return Err(Error::new(
+            kind,
+            "the number of bits of RVAD and EQUA values must be between 1 and 32",
+        ));
+    }
+    Ok(bits)
+}
+
 /// Returns the index of the first delimiter for the specified encoding.
 fn find_delim(encoding: Encoding, data: &[u8], index: usize) -> Option<usize> {
     let mut i = index;
@@ -1050,7 +1751,9 @@ fn delim_len(encoding: Encoding) -> usize {
 mod tests {
     use super::*;
     use crate::frame::Content;
-    use crate::frame::{self, Picture, PictureType};
+    use crate::frame::{
+        self, ChannelType, EventType, InterpolationMethod, Picture, PictureType, ReceivedAs,
+    };
     use std::collections::HashMap;
     use std::io::Cursor;
 
@@ -1091,8 +1794,8 @@ mod tests {
                     data.push(*encoding as u8);
                     data.extend(format.bytes());
                     data.push(picture_type.into());
-                    data.extend(bytes_for_encoding(description, *encoding).into_iter());
-                    data.extend(delim_for_encoding(*encoding).into_iter());
+                    data.extend(bytes_for_encoding(description, *encoding));
+                    data.extend(delim_for_encoding(*encoding));
                     data.extend(picture_data.iter().cloned());
 
                     assert_eq!(
@@ -1148,8 +1851,8 @@ mod tests {
                     data.extend(mime_type.bytes());
                     data.push(0x0);
                     data.push(picture_type.into());
-                    data.extend(bytes_for_encoding(description, *encoding).into_iter());
-                    data.extend(delim_for_encoding(*encoding).into_iter());
+                    data.extend(bytes_for_encoding(description, *encoding));
+                    data.extend(delim_for_encoding(*encoding));
                     data.extend(picture_data.iter().cloned());
 
                     assert_eq!(
@@ -1191,9 +1894,9 @@ mod tests {
                     let mut data = Vec::new();
                     data.push(*encoding as u8);
                     data.extend(b"eng".iter().cloned());
-                    data.extend(bytes_for_encoding(description, *encoding).into_iter());
-                    data.extend(delim_for_encoding(*encoding).into_iter());
-                    data.extend(bytes_for_encoding(comment, *encoding).into_iter());
+                    data.extend(bytes_for_encoding(description, *encoding));
+                    data.extend(delim_for_encoding(*encoding));
+                    data.extend(bytes_for_encoding(comment, *encoding));
 
                     let content = frame::Comment {
                         lang: "eng".to_string(),
@@ -1234,8 +1937,8 @@ mod tests {
             let mut data = Vec::new();
             data.push(*encoding as u8);
             data.extend(b"eng".iter().cloned());
-            data.extend(bytes_for_encoding(description, *encoding).into_iter());
-            data.extend(bytes_for_encoding(comment, *encoding).into_iter());
+            data.extend(bytes_for_encoding(description, *encoding));
+            data.extend(bytes_for_encoding(comment, *encoding));
             assert!(decode("COMM", Version::Id3v23, &data[..]).is_err());
         }
         println!("Empty description");
@@ -1251,7 +1954,7 @@ mod tests {
             data.push(*encoding as u8);
             data.extend(b"eng".iter().cloned());
             data.extend(delim_for_encoding(*encoding));
-            data.extend(bytes_for_encoding(comment, *encoding).into_iter());
+            data.extend(bytes_for_encoding(comment, *encoding));
             let content = frame::Comment {
                 lang: "eng".to_string(),
                 description: "".to_string(),
@@ -1295,6 +1998,531 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_etco() {
+        let etco = Content::EventTimingCodes(EventTimingCodes {
+            timestamp_format: TimestampFormat::Ms,
+            events: vec![
+                (EventType::EndOfInitialSilence, 500),
+                (EventType::Sync(3), 0x01020304),
+                (EventType::Reserved(0x20), 0),
+                (EventType::AudioEnd, 0xffffffff),
+            ],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &etco, Version::Id3v24, Encoding::UTF8).unwrap();
+        let expect_data =
+            b"\x02\x01\x00\x00\x01\xf4\xe3\x01\x02\x03\x04\x20\x00\x00\x00\x00\xfd\xff\xff\xff\xff";
+        assert_eq!(format!("{:x?}", data_out), format!("{:x?}", expect_data));
+        let etco_decoded = decode("ETCO", Version::Id3v24, &*data_out).unwrap().0;
+        assert_eq!(etco, etco_decoded);
+
+        assert!(decode("ETCO", Version::Id3v24, &b"\x03"[..]).is_err());
+        assert!(decode("ETCO", Version::Id3v24, &b"\x01\x02\x00"[..]).is_err());
+    }
+
+    #[test]
+    fn test_sytc() {
+        let sytc = Content::SynchronisedTempoCodes(SynchronisedTempoCodes {
+            timestamp_format: TimestampFormat::Mpeg,
+            tempos: vec![
+                (0, 0),
+                (120, 1000),
+                (254, 2000),
+                (255, 3000),
+                (510, 0x01020304),
+            ],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &sytc, Version::Id3v24, Encoding::UTF8).unwrap();
+        let expect_data = b"\x01\x00\x00\x00\x00\x00\x78\x00\x00\x03\xe8\xfe\x00\x00\x07\xd0\xff\x00\x00\x00\x0b\xb8\xff\xff\x01\x02\x03\x04";
+        assert_eq!(format!("{:x?}", data_out), format!("{:x?}", expect_data));
+        assert_eq!(decode("SYTC", Version::Id3v24, &*data_out).unwrap().0, sytc);
+        assert_eq!(decode("STC", Version::Id3v22, &*data_out).unwrap().0, sytc);
+
+        let too_fast = Content::SynchronisedTempoCodes(SynchronisedTempoCodes {
+            timestamp_format: TimestampFormat::Ms,
+            tempos: vec![(511, 0)],
+        });
+        assert!(encode(&mut Vec::new(), &too_fast, Version::Id3v24, Encoding::UTF8).is_err());
+        assert!(decode("SYTC", Version::Id3v24, &b"\x02\xff"[..]).is_err());
+    }
+
+    #[test]
+    fn test_aspi() {
+        let aspi8 = Content::AudioSeekPointIndex(AudioSeekPointIndex {
+            indexed_data_start: 0x100,
+            indexed_data_length: 0x10000,
+            bits_per_index_point: 8,
+            index_points: vec![0, 0x40, 0xff],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &aspi8, Version::Id3v24, Encoding::UTF8).unwrap();
+        let expect_data = b"\x00\x00\x01\x00\x00\x01\x00\x00\x00\x03\x08\x00\x40\xff";
+        assert_eq!(format!("{:x?}", data_out), format!("{:x?}", expect_data));
+        assert_eq!(
+            decode("ASPI", Version::Id3v24, &*data_out).unwrap().0,
+            aspi8
+        );
+
+        let aspi16 = Content::AudioSeekPointIndex(AudioSeekPointIndex {
+            indexed_data_start: 0,
+            indexed_data_length: 1000,
+            bits_per_index_point: 16,
+            index_points: vec![0, 0x8000, 0xffff],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &aspi16, Version::Id3v24, Encoding::UTF8).unwrap();
+        assert_eq!(&data_out[10..], b"\x10\x00\x00\x80\x00\xff\xff");
+        let decoded = decode("ASPI", Version::Id3v24, &*data_out).unwrap().0;
+        assert_eq!(decoded, aspi16);
+        let aspi = decoded.audio_seek_point_index().unwrap();
+        assert_eq!(aspi.fraction_at(1), Some(0.5));
+        assert_eq!(aspi.offset_at(1), Some(500));
+
+        let mut invalid = aspi8.audio_seek_point_index().unwrap().clone();
+        invalid.index_points.push(0x100);
+        let invalid = Content::AudioSeekPointIndex(invalid);
+        assert!(encode(&mut Vec::new(), &invalid, Version::Id3v24, Encoding::UTF8).is_err());
+        let bad_bits = b"\x00\x00\x00\x00\x00\x00\x00\x00\x00\x01\x04\x00";
+        assert!(decode("ASPI", Version::Id3v24, &bad_bits[..]).is_err());
+        assert!(decode("ASPI", Version::Id3v24, &expect_data[..12]).is_err());
+    }
+
+    #[test]
+    fn test_owne() {
+        let owne = Content::Ownership(Ownership {
+            price_paid: "EUR9.99".to_string(),
+            date_of_purchase: "20240131".to_string(),
+            seller: "Plattenladen Müller".to_string(),
+        });
+        for encoding in [Encoding::Latin1, Encoding::UTF16, Encoding::UTF8] {
+            let mut data_out = Vec::new();
+            encode(&mut data_out, &owne, Version::Id3v24, encoding).unwrap();
+            let mut expect_data = vec![encoding as u8];
+            expect_data.extend(b"EUR9.99\x0020240131");
+            expect_data.extend(bytes_for_encoding("Plattenladen Müller", encoding));
+            assert_eq!(data_out, expect_data);
+            let owne_decoded = decode("OWNE", Version::Id3v24, &*data_out).unwrap().0;
+            assert_eq!(owne, owne_decoded);
+        }
+
+        let invalid = Content::Ownership(Ownership {
+            price_paid: "EUR9.99".to_string(),
+            date_of_purchase: "2024-01-31".to_string(),
+            seller: String::new(),
+        });
+        assert!(encode(&mut Vec::new(), &invalid, Version::Id3v24, Encoding::UTF8).is_err());
+    }
+
+    #[test]
+    fn test_comr() {
+        let mut comr = Commercial {
+            price: "USD1.29/EUR1.19".to_string(),
+            valid_until: "20301231".to_string(),
+            contact_url: "https://example.com".to_string(),
+            received_as: ReceivedAs::FileOverInternet,
+            seller_name: "Store".to_string(),
+            description: "Single".to_string(),
+            seller_logo: None,
+        };
+        let mut expect_data =
+            b"\x00USD1.29/EUR1.19\x0020301231https://example.com\x00\x03Store\x00Single\x00"
+                .to_vec();
+        let mut data_out = Vec::new();
+        let content = Content::Commercial(comr.clone());
+        encode(&mut data_out, &content, Version::Id3v24, Encoding::Latin1).unwrap();
+        assert_eq!(data_out, expect_data);
+        assert_eq!(
+            decode("COMR", Version::Id3v24, &*data_out).unwrap().0,
+            content
+        );
+
+        // A missing terminator after the description is tolerated.
+        let unterminated = &data_out[..data_out.len() - 1];
+        assert_eq!(
+            decode("COMR", Version::Id3v24, unterminated).unwrap().0,
+            content
+        );
+
+        comr.seller_logo = Some(SellerLogo {
+            mime_type: "image/png".to_string(),
+            data: vec![0x89, 0x50, 0x4e, 0x47],
+        });
+        expect_data.extend(b"image/png\x00\x89PNG");
+        let mut data_out = Vec::new();
+        let content = Content::Commercial(comr);
+        encode(&mut data_out, &content, Version::Id3v24, Encoding::Latin1).unwrap();
+        assert_eq!(data_out, expect_data);
+        assert_eq!(
+            decode("COMR", Version::Id3v24, &*data_out).unwrap().0,
+            content
+        );
+
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &content, Version::Id3v24, Encoding::UTF16).unwrap();
+        assert_eq!(
+            decode("COMR", Version::Id3v24, &*data_out).unwrap().0,
+            content
+        );
+    }
+
+    #[test]
+    fn test_user() {
+        let user = Content::TermsOfUse(TermsOfUse {
+            lang: "en".to_string(),
+            text: "All rights reserved".to_string(),
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &user, Version::Id3v24, Encoding::Latin1).unwrap();
+        assert_eq!(data_out, b"\x00en All rights reserved");
+        let user_decoded = decode("USER", Version::Id3v24, &*data_out).unwrap().0;
+        assert_eq!(
+            user_decoded,
+            Content::TermsOfUse(TermsOfUse {
+                lang: "en ".to_string(),
+                text: "All rights reserved".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_pcnt() {
+        for (count, expect_data) in [
+            (0, &b"\x00\x00\x00\x00"[..]),
+            (7, &b"\x00\x00\x00\x07"[..]),
+            (0x1_0000_0000, &b"\x01\x00\x00\x00\x00"[..]),
+            (u64::MAX, &b"\xff\xff\xff\xff\xff\xff\xff\xff"[..]),
+        ] {
+            let pcnt = Content::PlayCounter(count);
+            let mut data_out = Vec::new();
+            encode(&mut data_out, &pcnt, Version::Id3v24, Encoding::UTF8).unwrap();
+            assert_eq!(data_out, expect_data);
+            assert_eq!(decode("PCNT", Version::Id3v24, &*data_out).unwrap().0, pcnt);
+        }
+        let short = decode("PCNT", Version::Id3v24, &b"\x01\x02"[..]).unwrap().0;
+        assert_eq!(short.play_counter(), Some(0x102));
+        assert!(decode("PCNT", Version::Id3v24, &[1; 9][..]).is_err());
+    }
+
+    #[test]
+    fn test_podcast_frames() {
+        let pcst = Content::PodcastFlag(0);
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &pcst, Version::Id3v23, Encoding::UTF8).unwrap();
+        assert_eq!(data_out, b"\x00\x00\x00\x00");
+        assert_eq!(decode("PCST", Version::Id3v23, &*data_out).unwrap().0, pcst);
+        let pcst = decode("PCST", Version::Id3v23, &b"\x01"[..]).unwrap().0;
+        assert_eq!(pcst.podcast_flag(), Some(1));
+        assert!(decode("PCST", Version::Id3v23, &[0; 5][..]).is_err());
+
+        let wfed = decode(
+            "WFED",
+            Version::Id3v23,
+            &b"\x00https://example.com/feed"[..],
+        )
+        .unwrap();
+        assert_eq!(wfed.0.text(), Some("https://example.com/feed"));
+        for id in ["TDES", "TGID", "TCAT", "TKWD"] {
+            let content = decode(id, Version::Id3v24, &b"\x03value"[..]).unwrap().0;
+            assert_eq!(content.text(), Some("value"));
+        }
+    }
+
+    #[test]
+    fn test_mcdi() {
+        let toc = vec![
+            0x00, 0x12, 0x01, 0x01, 0x00, 0x14, 0x01, 0x00, 0x00, 0x00, 0x00, 0x96,
+        ];
+        let mcdi = Content::MusicCdIdentifier(MusicCdIdentifier { toc: toc.clone() });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &mcdi, Version::Id3v24, Encoding::UTF8).unwrap();
+        assert_eq!(data_out, toc);
+        assert_eq!(decode("MCDI", Version::Id3v24, &*toc).unwrap().0, mcdi);
+        assert_eq!(decode("MCI", Version::Id3v22, &*toc).unwrap().0, mcdi);
+    }
+
+    #[test]
+    fn test_link() {
+        let link = Content::LinkedInformation(LinkedInformation {
+            frame_identifier: "COMM".to_string(),
+            url: "http://example.com/album.id3".to_string(),
+            additional_data: b"engliner notes".to_vec(),
+        });
+        for (version, id) in [
+            (Version::Id3v22, &b"COM"[..]),
+            (Version::Id3v23, &b"COMM"[..]),
+            (Version::Id3v24, &b"COMM"[..]),
+        ] {
+            let mut data = id.to_vec();
+            data.extend_from_slice(b"http://example.com/album.id3\0engliner notes");
+            let mut data_out = Vec::new();
+            encode(&mut data_out, &link, version, Encoding::UTF8).unwrap();
+            assert_eq!(data_out, data);
+            let frame_id = if version == Version::Id3v22 {
+                "LNK"
+            } else {
+                "LINK"
+            };
+            assert_eq!(decode(frame_id, version, &*data).unwrap().0, link);
+        }
+
+        let v22_link = Content::LinkedInformation(LinkedInformation {
+            frame_identifier: "TT2".to_string(),
+            url: String::new(),
+            additional_data: Vec::new(),
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &v22_link, Version::Id3v24, Encoding::UTF8).unwrap();
+        assert_eq!(data_out, b"TIT2\0");
+
+        let unknown_link = Content::LinkedInformation(LinkedInformation {
+            frame_identifier: "XYZW".to_string(),
+            url: String::new(),
+            additional_data: Vec::new(),
+        });
+        assert!(encode(
+            &mut Vec::new(),
+            &unknown_link,
+            Version::Id3v22,
+            Encoding::UTF8
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_grid_sign() {
+        let grid = Content::GroupRegistration(GroupRegistration {
+            owner_identifier: "http://example.com/groups".to_string(),
+            group_symbol: 0x80,
+            data: vec![1, 2, 3],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &grid, Version::Id3v24, Encoding::UTF8).unwrap();
+        assert_eq!(data_out, b"http://example.com/groups\0\x80\x01\x02\x03");
+        assert_eq!(decode("GRID", Version::Id3v24, &*data_out).unwrap().0, grid);
+
+        let sign = Content::Signature(Signature {
+            group_symbol: 0xf0,
+            signature: vec![0xde, 0xad],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &sign, Version::Id3v24, Encoding::UTF8).unwrap();
+        assert_eq!(data_out, b"\xf0\xde\xad");
+        assert_eq!(decode("SIGN", Version::Id3v24, &*data_out).unwrap().0, sign);
+
+        let invalid = Content::Signature(Signature {
+            group_symbol: 0x7f,
+            signature: Vec::new(),
+        });
+        assert!(encode(&mut Vec::new(), &invalid, Version::Id3v24, Encoding::UTF8).is_err());
+        assert!(decode("SIGN", Version::Id3v24, &b""[..]).is_err());
+    }
+
+    #[test]
+    fn test_sylt() {
+        let content = Content::SynchronisedLyrics(SynchronisedLyrics {
+            lang: "eng".to_string(),
+            timestamp_format: TimestampFormat::Ms,
+            content_type: SynchronisedLyricsType::Lyrics,
+            content: vec![(1000, "Hëllo".to_string()), (2000, "wörld".to_string())],
+            description: "description".to_string(),
+        });
+        for encoding in [
+            Encoding::Latin1,
+            Encoding::UTF8,
+            Encoding::UTF16,
+            Encoding::UTF16BE,
+        ] {
+            let mut data_out = Vec::new();
+            encode(&mut data_out, &content, Version::Id3v24, encoding).unwrap();
+            assert_eq!(data_out[0], encoding as u8);
+            assert_eq!(
+                decode("SYLT", Version::Id3v24, &data_out[..]).unwrap().0,
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn test_encr() {
+        let encr = Content::EncryptionMethod(EncryptionMethod {
+            owner_identifier: "http://example.com/crypt".to_string(),
+            method_symbol: 0x81,
+            encryption_data: vec![4, 2],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &encr, Version::Id3v23, Encoding::UTF16).unwrap();
+        assert_eq!(data_out, b"http://example.com/crypt\0\x81\x04\x02");
+        assert_eq!(decode("ENCR", Version::Id3v23, &*data_out).unwrap().0, encr);
+
+        let invalid = Content::EncryptionMethod(EncryptionMethod {
+            owner_identifier: String::new(),
+            method_symbol: 0x10,
+            encryption_data: Vec::new(),
+        });
+        assert!(encode(&mut Vec::new(), &invalid, Version::Id3v24, Encoding::UTF8).is_err());
+    }
+
+    #[test]
+    fn test_rvad() {
+        let channel = |channel_type, volume_change, peak_volume| LegacyChannelVolume {
+            channel_type,
+            volume_change,
+            peak_volume,
+        };
+        let rvad = Content::LegacyVolumeAdjustment(LegacyVolumeAdjustment {
+            bits_used: 16,
+            adjustments: vec![
+                channel(ChannelType::FrontRight, 0x100, 0x7fff),
+                channel(ChannelType::FrontLeft, -0x80, 0x4000),
+            ],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &rvad, Version::Id3v23, Encoding::UTF8).unwrap();
+        let expect_data = b"\x01\x10\x01\x00\x00\x80\x7f\xff\x40\x00";
+        assert_eq!(format!("{:x?}", data_out), format!("{:x?}", expect_data));
+        assert_eq!(decode("RVAD", Version::Id3v23, &*data_out).unwrap().0, rvad);
+        assert_eq!(decode("RVA", Version::Id3v22, &*data_out).unwrap().0, rvad);
+
+        let surround = Content::LegacyVolumeAdjustment(LegacyVolumeAdjustment {
+            bits_used: 8,
+            adjustments: vec![
+                channel(ChannelType::FrontRight, 1, 2),
+                channel(ChannelType::FrontLeft, 3, 4),
+                channel(ChannelType::BackRight, -5, 6),
+                channel(ChannelType::BackLeft, 7, 8),
+                channel(ChannelType::FrontCentre, 9, 10),
+                channel(ChannelType::Subwoofer, -11, 12),
+            ],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &surround, Version::Id3v23, Encoding::UTF8).unwrap();
+        let expect_data = b"\x1b\x08\x01\x03\x02\x04\x05\x07\x06\x08\x09\x0a\x0b\x0c";
+        assert_eq!(format!("{:x?}", data_out), format!("{:x?}", expect_data));
+        assert_eq!(
+            decode("RVAD", Version::Id3v23, &*data_out).unwrap().0,
+            surround
+        );
+
+        for invalid in [
+            LegacyVolumeAdjustment {
+                bits_used: 8,
+                adjustments: vec![channel(ChannelType::FrontRight, 0x100, 0)],
+            },
+            LegacyVolumeAdjustment {
+                bits_used: 8,
+                adjustments: vec![
+                    channel(ChannelType::FrontLeft, 0, 0),
+                    channel(ChannelType::FrontRight, 0, 0),
+                ],
+            },
+            LegacyVolumeAdjustment {
+                bits_used: 8,
+                adjustments: vec![
+                    channel(ChannelType::FrontRight, 0x100, 0),
+                    channel(ChannelType::FrontLeft, 0, 0),
+                ],
+            },
+        ] {
+            let invalid = Content::LegacyVolumeAdjustment(invalid);
+            assert!(encode(&mut Vec::new(), &invalid, Version::Id3v23, Encoding::UTF8).is_err());
+        }
+        assert!(decode("RVAD", Version::Id3v23, &b"\x00\x00"[..]).is_err());
+        assert!(decode("RVAD", Version::Id3v23, &b"\x00\x10\x00\x01"[..]).is_err());
+    }
+
+    #[test]
+    fn test_equa() {
+        let equa = Content::LegacyEqualisation(LegacyEqualisation {
+            adjustment_bits: 16,
+            bands: vec![(100, 0x200), (1000, 0), (10000, -0x100)],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &equa, Version::Id3v23, Encoding::UTF8).unwrap();
+        let expect_data = b"\x10\x80\x64\x02\x00\x03\xe8\x00\x00\x27\x10\x01\x00";
+        assert_eq!(format!("{:x?}", data_out), format!("{:x?}", expect_data));
+        assert_eq!(decode("EQUA", Version::Id3v23, &*data_out).unwrap().0, equa);
+        assert_eq!(decode("EQU", Version::Id3v22, &*data_out).unwrap().0, equa);
+
+        let invalid = Content::LegacyEqualisation(LegacyEqualisation {
+            adjustment_bits: 16,
+            bands: vec![(0x8000, 0)],
+        });
+        assert!(encode(&mut Vec::new(), &invalid, Version::Id3v23, Encoding::UTF8).is_err());
+    }
+
+    #[test]
+    fn test_equ2() {
+        let equ2 = Content::Equalisation(Equalisation {
+            interpolation_method: InterpolationMethod::Linear,
+            identification: "living room".to_string(),
+            adjustment_points: vec![(200, 1024), (2000, 0), (20000, -512)],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &equ2, Version::Id3v24, Encoding::UTF8).unwrap();
+        let expect_data = b"\x01living room\x00\x00\xc8\x04\x00\x07\xd0\x00\x00\x4e\x20\xfe\x00";
+        assert_eq!(format!("{:x?}", data_out), format!("{:x?}", expect_data));
+        assert_eq!(decode("EQU2", Version::Id3v24, &*data_out).unwrap().0, equ2);
+    }
+
+    #[test]
+    fn test_crm() {
+        let crm = Content::EncryptedMeta(EncryptedMeta {
+            owner_identifier: "mailto:crypto@example.com".to_string(),
+            content_description: "Lyrics".to_string(),
+            encrypted_data: vec![0x13, 0x37],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &crm, Version::Id3v22, Encoding::UTF16).unwrap();
+        assert_eq!(data_out, b"mailto:crypto@example.com\0Lyrics\0\x13\x37");
+        assert_eq!(decode("CRM", Version::Id3v22, &*data_out).unwrap().0, crm);
+        assert!(decode("CRM", Version::Id3v22, &b"owner\0"[..]).is_err());
+    }
+
+    #[test]
+    fn test_rva2() {
+        let rva2 = Content::RelativeVolumeAdjustment(RelativeVolumeAdjustment {
+            identification: "track".to_string(),
+            adjustments: vec![
+                VolumeAdjustment {
+                    channel_type: ChannelType::MasterVolume,
+                    volume_adjustment: -1024,
+                    bits_representing_peak: 16,
+                    peak_volume: vec![0x80, 0x00],
+                },
+                VolumeAdjustment {
+                    channel_type: ChannelType::Subwoofer,
+                    volume_adjustment: 512,
+                    bits_representing_peak: 0,
+                    peak_volume: Vec::new(),
+                },
+            ],
+        });
+        let mut data_out = Vec::new();
+        encode(&mut data_out, &rva2, Version::Id3v24, Encoding::UTF8).unwrap();
+        let expect_data = b"track\x00\x01\xfc\x00\x10\x80\x00\x08\x02\x00\x00";
+        assert_eq!(format!("{:x?}", data_out), format!("{:x?}", expect_data));
+        let rva2_decoded = decode("RVA2", Version::Id3v24, &*data_out).unwrap().0;
+        assert_eq!(rva2, rva2_decoded);
+
+        let adjustment = rva2
+            .relative_volume_adjustment()
+            .unwrap()
+            .adjustment(ChannelType::MasterVolume)
+            .unwrap();
+        assert_eq!(adjustment.gain_db(), -2.0);
+        assert_eq!(adjustment.peak(), Some(1.0));
+
+        // Truncated peak
+        assert!(decode(
+            "RVA2",
+            Version::Id3v24,
+            &b"track\x00\x01\xfc\x00\x10\x80"[..]
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_text() {
         assert!(decode("TALB", Version::Id3v23, &[][..]).is_err());
@@ -1309,7 +2537,7 @@ mod tests {
                 println!("`{}`, `{:?}`", text, *encoding);
                 let mut data = Vec::new();
                 data.push(*encoding as u8);
-                data.extend(bytes_for_encoding(text, *encoding).into_iter());
+                data.extend(bytes_for_encoding(text, *encoding));
 
                 assert_eq!(
                     decode("TALB", Version::Id3v23, &data[..])
@@ -1345,7 +2573,7 @@ mod tests {
             println!("`{}`, `{:?}`", text, encoding);
             let mut data = Vec::new();
             data.push(*encoding as u8);
-            data.extend(bytes_for_encoding(text, *encoding).into_iter());
+            data.extend(bytes_for_encoding(text, *encoding));
 
             assert_eq!(
                 decode("TALB", Version::Id3v24, &data[..])
@@ -1380,7 +2608,7 @@ mod tests {
             println!("`{}`, `{:?}`", text, encoding);
             let mut data = Vec::new();
             data.push(*encoding as u8);
-            data.extend(bytes_for_encoding(text, *encoding).into_iter());
+            data.extend(bytes_for_encoding(text, *encoding));
 
             assert_eq!(
                 decode("TALB", Version::Id3v24, &data[..])
@@ -1418,9 +2646,9 @@ mod tests {
                     println!("{:?}", encoding);
                     let mut data = Vec::new();
                     data.push(*encoding as u8);
-                    data.extend(bytes_for_encoding(key, *encoding).into_iter());
-                    data.extend(delim_for_encoding(*encoding).into_iter());
-                    data.extend(bytes_for_encoding(value, *encoding).into_iter());
+                    data.extend(bytes_for_encoding(key, *encoding));
+                    data.extend(delim_for_encoding(*encoding));
+                    data.extend(bytes_for_encoding(value, *encoding));
 
                     let content = frame::ExtendedText {
                         description: key.to_string(),
@@ -1459,8 +2687,8 @@ mod tests {
             println!("`{:?}`", encoding);
             let mut data = Vec::new();
             data.push(*encoding as u8);
-            data.extend(bytes_for_encoding(key, *encoding).into_iter());
-            data.extend(bytes_for_encoding(value, *encoding).into_iter());
+            data.extend(bytes_for_encoding(key, *encoding));
+            data.extend(bytes_for_encoding(value, *encoding));
             assert!(decode("TXXX", Version::Id3v23, &data[..]).is_err());
         }
     }
@@ -1507,9 +2735,9 @@ mod tests {
                     println!("`{}`, `{}`, `{:?}`", description, link, encoding);
                     let mut data = Vec::new();
                     data.push(*encoding as u8);
-                    data.extend(bytes_for_encoding(description, *encoding).into_iter());
-                    data.extend(delim_for_encoding(*encoding).into_iter());
-                    data.extend(bytes_for_encoding(link, Encoding::Latin1).into_iter());
+                    data.extend(bytes_for_encoding(description, *encoding));
+                    data.extend(delim_for_encoding(*encoding));
+                    data.extend(bytes_for_encoding(link, Encoding::Latin1));
 
                     let content = frame::ExtendedLink {
                         description: description.to_string(),
@@ -1548,8 +2776,8 @@ mod tests {
             println!("`{:?}`", encoding);
             let mut data = Vec::new();
             data.push(*encoding as u8);
-            data.extend(bytes_for_encoding(description, *encoding).into_iter());
-            data.extend(bytes_for_encoding(link, Encoding::Latin1).into_iter());
+            data.extend(bytes_for_encoding(description, *encoding));
+            data.extend(bytes_for_encoding(link, Encoding::Latin1));
             assert!(decode("WXXX", Version::Id3v23, &data[..]).is_err());
         }
     }
@@ -1571,9 +2799,9 @@ mod tests {
                     let mut data = Vec::new();
                     data.push(*encoding as u8);
                     data.extend(b"eng".iter().cloned());
-                    data.extend(bytes_for_encoding(description, *encoding).into_iter());
-                    data.extend(delim_for_encoding(*encoding).into_iter());
-                    data.extend(bytes_for_encoding(text, *encoding).into_iter());
+                    data.extend(bytes_for_encoding(description, *encoding));
+                    data.extend(delim_for_encoding(*encoding));
+                    data.extend(bytes_for_encoding(text, *encoding));
 
                     let content = frame::Lyrics {
                         lang: "eng".to_string(),
@@ -1614,8 +2842,8 @@ mod tests {
             let mut data = Vec::new();
             data.push(*encoding as u8);
             data.extend(b"eng".iter().cloned());
-            data.extend(bytes_for_encoding(description, *encoding).into_iter());
-            data.extend(bytes_for_encoding(lyrics, *encoding).into_iter());
+            data.extend(bytes_for_encoding(description, *encoding));
+            data.extend(bytes_for_encoding(lyrics, *encoding));
             assert!(decode("USLT", Version::Id3v23, &data[..]).is_err());
         }
     }
@@ -1659,10 +2887,10 @@ mod tests {
                 let mut data = Vec::new();
                 data.push(*encoding as u8);
                 for (involvement, involvee) in people_list {
-                    data.extend(bytes_for_encoding(&involvement, *encoding).into_iter());
-                    data.extend(delim_for_encoding(*encoding).into_iter());
-                    data.extend(bytes_for_encoding(&involvee, *encoding).into_iter());
-                    data.extend(delim_for_encoding(*encoding).into_iter());
+                    data.extend(bytes_for_encoding(involvement, *encoding));
+                    data.extend(delim_for_encoding(*encoding));
+                    data.extend(bytes_for_encoding(involvee, *encoding));
+                    data.extend(delim_for_encoding(*encoding));
                 }
 
                 let content = frame::InvolvedPeopleList {
@@ -1685,7 +2913,7 @@ mod tests {
                 let mut data_out = Vec::new();
                 encode(
                     &mut data_out,
-                    &&Content::InvolvedPeopleList(content),
+                    &Content::InvolvedPeopleList(content),
                     Version::Id3v23,
                     *encoding,
                 )
@@ -1694,7 +2922,7 @@ mod tests {
             }
         }
 
-        println!("invalid");
+        println!("dangling");
         for encoding in &[
             Encoding::Latin1,
             Encoding::UTF8,
@@ -1704,14 +2932,25 @@ mod tests {
             println!("`{:?}`", encoding);
             let mut data = Vec::new();
             data.push(*encoding as u8);
-            data.extend(bytes_for_encoding("involvement", *encoding).into_iter());
-            data.extend(delim_for_encoding(*encoding).into_iter());
-            data.extend(bytes_for_encoding("involvee", *encoding).into_iter());
-            data.extend(delim_for_encoding(*encoding).into_iter());
-            data.extend(bytes_for_encoding("other involvement", *encoding).into_iter());
-            data.extend(delim_for_encoding(*encoding).into_iter());
+            data.extend(bytes_for_encoding("involvement", *encoding));
+            data.extend(delim_for_encoding(*encoding));
+            data.extend(bytes_for_encoding("involvee", *encoding));
+            data.extend(delim_for_encoding(*encoding));
+            data.extend(bytes_for_encoding("other involvement", *encoding));
+            data.extend(delim_for_encoding(*encoding));
             // involveee missing here
-            assert!(decode(frame_id, version, &data[..]).is_err());
+            let mut warnings = Vec::new();
+            let (content, _) =
+                decode_with_warnings(frame_id, version, &data[..], &mut warnings).unwrap();
+            assert_eq!(
+                content.involved_people_list().unwrap().items,
+                vec![InvolvedPeopleListItem {
+                    involvement: "involvement".to_string(),
+                    involvee: "involvee".to_string(),
+                }]
+            );
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(warnings[0].frame_id.as_deref(), Some(frame_id));
         }
     }
 
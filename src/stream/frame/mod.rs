@@ -1,10 +1,12 @@
 use crate::frame::Content;
 use crate::frame::Frame;
+use crate::frame::{Skipped, Unknown};
 use crate::stream::encoding::Encoding;
 use crate::stream::unsynch;
 use crate::tag::Version;
+use crate::{Error, ErrorKind, Warning};
 use flate2::read::ZlibDecoder;
-use std::io;
+use std::io::{self, Read};
 use std::str;
 
 pub mod content;
@@ -14,12 +16,182 @@ pub mod v4;
 
 pub fn decode(reader: impl io::Read, version: Version) -> crate::Result<Option<(usize, Frame)>> {
     match version {
-        Version::Id3v22 => unimplemented!(),
-        Version::Id3v23 => v3::decode(reader),
-        Version::Id3v24 => v4::decode(reader),
+        Version::Id3v22 => match v2::decode(reader, &mut Vec::new(), None, None, &[])? {
+            Some((size, decoded)) => Ok(Some((size, decoded.into_result()?))),
+            None => Ok(None),
+        },
+        Version::Id3v23 => v3::decode(reader, &mut Vec::new()),
+        Version::Id3v24 => v4::decode(reader, &mut Vec::new()),
     }
 }
 
+/// The outcome of decoding a single frame.
+pub enum DecodedFrame {
+    /// The frame was decoded successfully.
+    Frame(Frame),
+    /// The frame header could be read, but the content could not be decoded. The frame holds the
+    /// raw content as [`Content::Unknown`], or a [`Content::Skipped`] placeholder if the content
+    /// was too large to be read.
+    Stub(Frame, crate::Error),
+}
+
+impl DecodedFrame {
+    fn stub(id: &str, version: Version, data: Vec<u8>, err: crate::Error) -> DecodedFrame {
+        let frame = Frame::with_content(id, Content::Unknown(Unknown { data, version }));
+        DecodedFrame::Stub(frame, err.with_frame_id(id))
+    }
+
+    /// Sets the alter preservation flags that were read from the frame header.
+    fn with_alter_preservation(mut self, tag: bool, file: bool) -> DecodedFrame {
+        let (DecodedFrame::Frame(frame) | DecodedFrame::Stub(frame, _)) = &mut self;
+        frame.set_tag_alter_preservation(tag);
+        frame.set_file_alter_preservation(file);
+        self
+    }
+
+    /// Converts a stub into the error that prevented its content from being decoded.
+    pub fn into_result(self) -> crate::Result<Frame> {
+        match self {
+            DecodedFrame::Frame(frame) => Ok(frame),
+            DecodedFrame::Stub(_, err) => Err(err),
+        }
+    }
+}
+
+/// Skips over the content of a frame that is larger than `max_size`, returning a stub without
+/// content.
+fn skip_oversized(
+    reader: impl io::Read,
+    id: &str,
+    version: Version,
+    size: u64,
+    max_size: usize,
+) -> crate::Result<DecodedFrame> {
+    skip(reader, size)?;
+    Ok(oversized_stub(id, version, size, max_size))
+}
+
+/// Returns the stub of a frame of which the content is larger than `max_size`. It holds a
+/// [`Content::Skipped`] placeholder, so the frame can not be written back without its content.
+/// The offset of the placeholder is filled in by the caller.
+fn oversized_stub(id: &str, version: Version, size: u64, max_size: usize) -> DecodedFrame {
+    let placeholder = Skipped {
+        size,
+        offset: 0,
+        version,
+    };
+    let frame = Frame::with_content(id, Content::Skipped(placeholder));
+    DecodedFrame::Stub(frame, oversized_error(max_size).with_frame_id(id))
+}
+
+/// Returns whether the content of the frame is to be skipped. The IDs of ID3v2.2 frames are
+/// compared using their ID3v2.3 equivalents.
+fn skips_content(id: &str, skip_content_of: &[String]) -> bool {
+    let id = match id.len() {
+        3 => crate::frame::convert_id_2_to_3(id).unwrap_or(id),
+        _ => id,
+    };
+    skip_content_of.iter().any(|skip| skip == id)
+}
+
+/// Skips over the content of a frame, returning a placeholder. The offset of the placeholder is
+/// filled in by the caller.
+fn skip_content(
+    reader: impl io::Read,
+    id: &str,
+    version: Version,
+    size: u64,
+) -> crate::Result<DecodedFrame> {
+    skip(reader, size)?;
+    let placeholder = Skipped {
+        size,
+        offset: 0,
+        version,
+    };
+    Ok(DecodedFrame::Frame(Frame::with_content(
+        id,
+        Content::Skipped(placeholder),
+    )))
+}
+
+/// Reads and discards `size` bytes.
+fn skip(reader: impl io::Read, size: u64) -> crate::Result<()> {
+    let skipped = io::copy(&mut reader.take(size), &mut io::sink())?;
+    if skipped < size {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(())
+}
+
+/// Returns an error if a frame spanning `size` bytes, including its header, does not fit in the
+/// `remaining` bytes that may still be decoded. This is checked before the content is read.
+fn check_remaining(id: &str, size: u64, remaining: Option<u64>) -> crate::Result<()> {
+    match remaining {
+        Some(remaining) if size > remaining => Err(Error::new(
+            ErrorKind::LimitExceeded,
+            format!(
+                "frame of {} bytes exceeds the remaining {} bytes of the maximum tag size",
+                size, remaining
+            ),
+        )
+        .with_frame_id(id)),
+        _ => Ok(()),
+    }
+}
+
+fn oversized_error(max_size: usize) -> Error {
+    Error::new(
+        ErrorKind::LimitExceeded,
+        format!(
+            "frame content exceeds the maximum frame size of {} bytes",
+            max_size
+        ),
+    )
+}
+
+/// Decodes the content of a frame, falling back to a stub holding the raw content if the content
+/// is invalid. `size` is the size of the content as stored in the tag.
+#[allow(clippy::too_many_arguments)]
+fn decode_frame(
+    reader: impl io::Read,
+    warnings: &mut Vec<Warning>,
+    version: Version,
+    id: &str,
+    compression: bool,
+    unsynchronisation: bool,
+    size: u64,
+    max_size: Option<usize>,
+) -> crate::Result<DecodedFrame> {
+    // Undo unsynchronisation and compression up front, so a stub holds the plain content.
+    let reader: Box<dyn io::Read + '_> = if unsynchronisation {
+        Box::new(unsynch::Reader::new(reader))
+    } else {
+        Box::new(reader)
+    };
+    let reader: Box<dyn io::Read + '_> = if compression {
+        Box::new(ZlibDecoder::new(reader))
+    } else {
+        reader
+    };
+    // Read one byte more than allowed to detect content that decompresses beyond the limit.
+    let limit = max_size.map_or(u64::MAX, |max_size| max_size as u64 + 1);
+    let mut data = Vec::new();
+    reader.take(limit).read_to_end(&mut data)?;
+    if let Some(max_size) = max_size {
+        if data.len() > max_size {
+            return Ok(oversized_stub(id, version, size, max_size));
+        }
+    }
+
+    match content::decode_with_warnings(id, version, &data[..], warnings) {
+        Ok((content, encoding)) => Ok(DecodedFrame::Frame(
+            Frame::with_content(id, content).set_encoding(encoding),
+        )),
+        Err(err) => Ok(DecodedFrame::stub(id, version, data, err)),
+    }
+}
+
+#[cfg(test)]
 fn decode_content(
     reader: impl io::Read,
     version: Version,
@@ -41,12 +213,42 @@ fn decode_content(
     }
 }
 
+/// Returns the encoding in which the text of a frame is written. Frames without an encoding of
+/// their own use UTF-16 in ID3v2.2 and ID3v2.3 and UTF-8 in ID3v2.4. UTF-16BE and UTF-8 were only
+/// introduced in ID3v2.4, so earlier versions fall back to UTF-16.
+pub(crate) fn content_encoding(frame: &Frame, version: Version) -> Encoding {
+    match (version, frame.encoding()) {
+        (Version::Id3v24, encoding) => encoding.unwrap_or(Encoding::UTF8),
+        (_, Some(Encoding::Latin1)) => Encoding::Latin1,
+        (_, _) => Encoding::UTF16,
+    }
+}
+
 pub fn encode(
     writer: impl io::Write,
     frame: &Frame,
     version: Version,
     unsynchronization: bool,
 ) -> crate::Result<usize> {
+    encode_with_compression(writer, frame, version, unsynchronization, None)
+}
+
+/// Encodes a frame like [`encode`], compressing its content using zlib if the content is at least
+/// `compression_threshold` bytes long. ID3v2.2 frames are never compressed.
+pub fn encode_with_compression(
+    writer: impl io::Write,
+    frame: &Frame,
+    version: Version,
+    unsynchronization: bool,
+    compression_threshold: Option<usize>,
+) -> crate::Result<usize> {
+    let compression = match (version, compression_threshold) {
+        (Version::Id3v22, _) | (_, None) => false,
+        (_, Some(threshold)) => {
+            let encoding = content_encoding(frame, version);
+            content::encode(io::sink(), frame.content(), version, encoding)? >= threshold
+        }
+    };
     match version {
         Version::Id3v22 => v2::encode(writer, frame),
         Version::Id3v23 => {
@@ -59,6 +261,7 @@ pub fn encode(
                 v3::Flags::FILE_ALTER_PRESERVATION,
                 frame.file_alter_preservation(),
             );
+            flags.set(v3::Flags::COMPRESSION, compression);
             v3::encode(writer, frame, flags)
         }
         Version::Id3v24 => {
@@ -72,6 +275,9 @@ pub fn encode(
                 v4::Flags::FILE_ALTER_PRESERVATION,
                 frame.file_alter_preservation(),
             );
+            // Compressed frames must have a data length indicator.
+            flags.set(v4::Flags::COMPRESSION, compression);
+            flags.set(v4::Flags::DATA_LENGTH_INDICATOR, compression);
             v4::encode(writer, frame, flags)
         }
     }
@@ -80,12 +286,10 @@ pub fn encode(
 /// Helper for str::from_utf8 that preserves any problematic pattern if applicable.
 pub fn str_from_utf8(b: &[u8]) -> crate::Result<&str> {
     str::from_utf8(b).map_err(|err| {
-        let bad = b[err.valid_up_to()..].to_vec();
-        crate::Error {
-            kind: crate::ErrorKind::StringDecoding(bad.to_vec()),
-            description: "data is not valid utf-8".to_string(),
-            partial_tag: None,
-        }
+        let start = err.valid_up_to();
+        let end = err.error_len().map(|len| start + len).unwrap_or(b.len());
+        crate::Error::string_decoding(b, Encoding::UTF8, start..end, "data is not valid utf-8")
+            .with_cause(err)
     })
 }
 
@@ -96,6 +300,32 @@ mod tests {
     use crate::stream::encoding::Encoding;
     use crate::stream::unsynch;
 
+    #[test]
+    fn test_decode_v2() {
+        let mut bytes = b"TT2\x00\x00\x06\x00title".to_vec();
+        bytes.extend(b"TAL\x00\x00\x06\x00album");
+        let mut reader = &bytes[..];
+        let (size, frame) = decode(&mut reader, Version::Id3v22).unwrap().unwrap();
+        assert_eq!(size, 12);
+        assert_eq!(frame.id(), "TIT2");
+        assert_eq!(frame.content().text(), Some("title"));
+        let (_, frame) = decode(&mut reader, Version::Id3v22).unwrap().unwrap();
+        assert_eq!(frame.id(), "TALB");
+        assert!(decode(&mut reader, Version::Id3v22).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_decode_v2_nested() {
+        // A chapter holding ID3v2.2 frames must decode without panicking.
+        let mut data = b"ch1\x00".to_vec();
+        data.extend([0; 16]);
+        data.extend(b"TT2\x00\x00\x06\x00title");
+        let (content, _) = content::decode("CHAP", Version::Id3v22, &data[..]).unwrap();
+        let chapter = content.chapter().unwrap();
+        assert_eq!(chapter.frames.len(), 1);
+        assert_eq!(chapter.frames[0].content().text(), Some("title"));
+    }
+
     fn u32_to_bytes(n: u32) -> Vec<u8> {
         vec![
             ((n & 0xFF00_0000) >> 24) as u8,
@@ -113,7 +343,7 @@ mod tests {
 
         let mut data = Vec::new();
         data.push(encoding as u8);
-        data.extend(Encoding::UTF16.encode(text).into_iter());
+        data.extend(Encoding::UTF16.encode(text));
 
         let content = decode_content(&data[..], Version::Id3v22, id, false, false)
             .unwrap()
@@ -123,7 +353,7 @@ mod tests {
         let mut bytes = Vec::new();
         bytes.extend(id.bytes());
         bytes.extend((u32_to_bytes(data.len() as u32)[1..]).iter().cloned());
-        bytes.extend(data.into_iter());
+        bytes.extend(data);
 
         let mut writer = Vec::new();
         encode(&mut writer, &frame, Version::Id3v22, false).unwrap();
@@ -138,7 +368,7 @@ mod tests {
 
         let mut data = Vec::new();
         data.push(encoding as u8);
-        data.extend(Encoding::UTF16.encode(text).into_iter());
+        data.extend(Encoding::UTF16.encode(text));
 
         let content = decode_content(&data[..], Version::Id3v23, id, false, false)
             .unwrap()
@@ -147,9 +377,9 @@ mod tests {
 
         let mut bytes = Vec::new();
         bytes.extend(id.bytes());
-        bytes.extend(u32_to_bytes(data.len() as u32).into_iter());
+        bytes.extend(u32_to_bytes(data.len() as u32));
         bytes.extend([0x00, 0x00].iter().cloned());
-        bytes.extend(data.into_iter());
+        bytes.extend(data);
 
         let mut writer = Vec::new();
         encode(&mut writer, &frame, Version::Id3v23, false).unwrap();
@@ -175,12 +405,37 @@ mod tests {
 
         let mut bytes = Vec::new();
         bytes.extend(id.bytes());
-        bytes.extend(u32_to_bytes(unsynch::encode_u32(data.len() as u32)).into_iter());
+        bytes.extend(u32_to_bytes(unsynch::encode_u32(data.len() as u32)));
         bytes.extend([0x60, 0x00].iter().cloned());
-        bytes.extend(data.into_iter());
+        bytes.extend(data);
 
         let mut writer = Vec::new();
         encode(&mut writer, &frame, Version::Id3v24, false).unwrap();
         assert_eq!(writer, bytes);
     }
+
+    #[test]
+    fn test_encode_explicit_encoding() {
+        let comment = Frame::with_content(
+            "COMM",
+            Content::Comment(crate::frame::Comment {
+                lang: "eng".to_string(),
+                description: "description".to_string(),
+                text: "text".to_string(),
+            }),
+        );
+        for (version, encoding, expected) in [
+            (Version::Id3v24, Encoding::Latin1, Encoding::Latin1),
+            (Version::Id3v24, Encoding::UTF16BE, Encoding::UTF16BE),
+            (Version::Id3v23, Encoding::Latin1, Encoding::Latin1),
+            (Version::Id3v23, Encoding::UTF8, Encoding::UTF16),
+            (Version::Id3v22, Encoding::UTF16BE, Encoding::UTF16),
+        ] {
+            let frame = comment.clone().set_encoding(Some(encoding));
+            let mut writer = Vec::new();
+            encode(&mut writer, &frame, version, false).unwrap();
+            let header_size = if version == Version::Id3v22 { 6 } else { 10 };
+            assert_eq!(writer[header_size], expected as u8);
+        }
+    }
 }
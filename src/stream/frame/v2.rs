@@ -1,12 +1,20 @@
 use crate::frame::Frame;
-use crate::stream::encoding::Encoding;
-use crate::stream::frame;
+use crate::stream::frame::{self, DecodedFrame};
 use crate::tag::Version;
-use crate::{Error, ErrorKind};
+use crate::{Error, ErrorKind, Warning};
 use byteorder::{BigEndian, WriteBytesExt};
 use std::io;
 
-pub fn decode(mut reader: impl io::Read) -> crate::Result<Option<(usize, Frame)>> {
+/// Decodes a frame, returning frames of which the content could not be decoded or is larger than
+/// `max_size` as a stub. The content of frames listed in `skip_content_of` is skipped. Frames that
+/// would take up more than the `remaining` bytes of the tag result in an error.
+pub fn decode(
+    mut reader: impl io::Read,
+    warnings: &mut Vec<Warning>,
+    max_size: Option<usize>,
+    remaining: Option<u64>,
+    skip_content_of: &[String],
+) -> crate::Result<Option<(usize, DecodedFrame)>> {
     let mut frame_header = [0; 6];
     let nread = reader.read(&mut frame_header)?;
     if nread < frame_header.len() || frame_header[0] == 0x00 {
@@ -16,10 +24,30 @@ pub fn decode(mut reader: impl io::Read) -> crate::Result<Option<(usize, Frame)>
     let sizebytes = &frame_header[3..6];
     let read_size =
         (u32::from(sizebytes[0]) << 16) | (u32::from(sizebytes[1]) << 8) | u32::from(sizebytes[2]);
-    let (content, encoding) =
-        super::content::decode(id, Version::Id3v22, reader.take(u64::from(read_size)))?;
-    let frame = Frame::with_content(id, content).set_encoding(encoding);
-    Ok(Some((6 + read_size as usize, frame)))
+    frame::check_remaining(id, 6 + u64::from(read_size), remaining)?;
+    if frame::skips_content(id, skip_content_of) {
+        let placeholder = frame::skip_content(reader, id, Version::Id3v22, u64::from(read_size))
+            .map_err(|err| err.with_frame_id(id))?;
+        return Ok(Some((6 + read_size as usize, placeholder)));
+    }
+    if let Some(max_size) = max_size.filter(|max_size| read_size as usize > *max_size) {
+        let stub =
+            super::skip_oversized(reader, id, Version::Id3v22, u64::from(read_size), max_size)
+                .map_err(|err| err.with_frame_id(id))?;
+        return Ok(Some((6 + read_size as usize, stub)));
+    }
+    let decoded = super::decode_frame(
+        reader.take(u64::from(read_size)),
+        warnings,
+        Version::Id3v22,
+        id,
+        false,
+        false,
+        u64::from(read_size),
+        max_size,
+    )
+    .map_err(|err| err.with_frame_id(id))?;
+    Ok(Some((6 + read_size as usize, decoded)))
 }
 
 pub fn encode(mut writer: impl io::Write, frame: &Frame) -> crate::Result<usize> {
@@ -28,7 +56,7 @@ pub fn encode(mut writer: impl io::Write, frame: &Frame) -> crate::Result<usize>
         &mut content_buf,
         frame.content(),
         Version::Id3v22,
-        frame.encoding().unwrap_or(Encoding::UTF16),
+        frame::content_encoding(frame, Version::Id3v22),
     )?;
     assert_ne!(0, content_buf.len());
     let id = frame.id_for_version(Version::Id3v22).ok_or_else(|| {
@@ -1,8 +1,7 @@
-use crate::frame::Frame;
-use crate::stream::encoding::Encoding;
-use crate::stream::frame;
+use crate::frame::{Content, EncryptedFrame, Frame};
+use crate::stream::frame::{self, DecodedFrame};
 use crate::tag::Version;
-use crate::{Error, ErrorKind};
+use crate::{Error, ErrorKind, Warning};
 use bitflags::bitflags;
 use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
 use flate2::write::ZlibEncoder;
@@ -20,7 +19,26 @@ bitflags! {
     }
 }
 
-pub fn decode(mut reader: impl io::Read) -> crate::Result<Option<(usize, Frame)>> {
+pub fn decode(
+    reader: impl io::Read,
+    warnings: &mut Vec<Warning>,
+) -> crate::Result<Option<(usize, Frame)>> {
+    match decode_or_stub(reader, warnings, None, None, &[])? {
+        Some((size, decoded)) => Ok(Some((size, decoded.into_result()?))),
+        None => Ok(None),
+    }
+}
+
+/// Like [`decode`], but returns frames of which the content could not be decoded or is larger than
+/// `max_size` as a stub. The content of frames listed in `skip_content_of` is skipped. Frames that
+/// would take up more than the `remaining` bytes of the tag result in an error.
+pub fn decode_or_stub(
+    mut reader: impl io::Read,
+    warnings: &mut Vec<Warning>,
+    max_size: Option<usize>,
+    remaining: Option<u64>,
+    skip_content_of: &[String],
+) -> crate::Result<Option<(usize, DecodedFrame)>> {
     let mut frame_header = [0; 10];
     let nread = reader.read(&mut frame_header)?;
     if nread < frame_header.len() || frame_header[0] == 0x00 {
@@ -29,17 +47,64 @@ pub fn decode(mut reader: impl io::Read) -> crate::Result<Option<(usize, Frame)>
     let id = frame::str_from_utf8(&frame_header[0..4])?;
 
     let content_size = BigEndian::read_u32(&frame_header[4..8]) as usize;
-    let flags = Flags::from_bits_truncate(BigEndian::read_u16(&frame_header[8..10]));
-    if flags.contains(Flags::ENCRYPTION) {
-        return Err(Error::new(
-            ErrorKind::UnsupportedFeature,
-            "encryption is not supported",
-        ));
+    frame::check_remaining(id, 10 + content_size as u64, remaining)?;
+    let raw_flags = BigEndian::read_u16(&frame_header[8..10]);
+    let flags = Flags::from_bits_truncate(raw_flags);
+    let tag_alter = flags.contains(Flags::TAG_ALTER_PRESERVATION);
+    let file_alter = flags.contains(Flags::FILE_ALTER_PRESERVATION);
+    if flags.bits() != raw_flags {
+        warnings.push(
+            Warning::new(format!("unknown frame flags are set: {:#06x}", raw_flags))
+                .with_frame_id(id),
+        );
+    }
+    let unsupported = if flags.contains(Flags::ENCRYPTION) {
+        None
     } else if flags.contains(Flags::GROUPING_IDENTITY) {
-        return Err(Error::new(
-            ErrorKind::UnsupportedFeature,
-            "grouping identity is not supported",
-        ));
+        Some("grouping identity is not supported")
+    } else {
+        None
+    };
+    if frame::skips_content(id, skip_content_of) {
+        let placeholder = frame::skip_content(reader, id, Version::Id3v23, content_size as u64)
+            .map_err(|err| err.with_frame_id(id))?;
+        return Ok(Some((
+            10 + content_size,
+            placeholder.with_alter_preservation(tag_alter, file_alter),
+        )));
+    }
+    if let Some(max_size) = max_size.filter(|max_size| content_size > *max_size) {
+        let stub =
+            frame::skip_oversized(reader, id, Version::Id3v23, content_size as u64, max_size)
+                .map_err(|err| err.with_frame_id(id))?;
+        return Ok(Some((
+            10 + content_size,
+            stub.with_alter_preservation(tag_alter, file_alter),
+        )));
+    }
+    if let Some(description) = unsupported {
+        let mut content_buf = vec![0; content_size];
+        reader
+            .read_exact(&mut content_buf)
+            .map_err(|err| Error::from(err).with_frame_id(id))?;
+        let err = Error::new(ErrorKind::UnsupportedFeature, description);
+        let stub = DecodedFrame::stub(id, Version::Id3v23, content_buf, err);
+        return Ok(Some((
+            10 + content_size,
+            stub.with_alter_preservation(tag_alter, file_alter),
+        )));
+    }
+
+    if flags.contains(Flags::ENCRYPTION) {
+        let mut content_buf = vec![0; content_size];
+        reader
+            .read_exact(&mut content_buf)
+            .map_err(|err| Error::from(err).with_frame_id(id))?;
+        let decoded = decode_encrypted(id, content_buf, &flags);
+        return Ok(Some((
+            10 + content_size,
+            decoded.with_alter_preservation(tag_alter, file_alter),
+        )));
     }
 
     let read_size = if flags.contains(Flags::COMPRESSION) {
@@ -49,26 +114,105 @@ pub fn decode(mut reader: impl io::Read) -> crate::Result<Option<(usize, Frame)>
         content_size
     };
     let mut content_buf = vec![0; read_size];
-    reader.read_exact(&mut content_buf)?;
-    let (content, encoding) = super::decode_content(
+    reader
+        .read_exact(&mut content_buf)
+        .map_err(|err| Error::from(err).with_frame_id(id))?;
+    let decoded = super::decode_frame(
         &content_buf[..],
+        warnings,
         Version::Id3v23,
         id,
         flags.contains(Flags::COMPRESSION),
         false,
-    )?;
-    let frame = Frame::with_content(id, content).set_encoding(encoding);
-    Ok(Some((10 + content_size, frame)))
+        content_size as u64,
+        max_size,
+    )
+    .map_err(|err| err.with_frame_id(id))?;
+    Ok(Some((
+        10 + content_size,
+        decoded.with_alter_preservation(tag_alter, file_alter),
+    )))
+}
+
+/// Decodes the content of an encrypted frame, which is preceded by the decompressed size, the
+/// encryption method and the group symbol, if their flags are set.
+fn decode_encrypted(id: &str, data: Vec<u8>, flags: &Flags) -> DecodedFrame {
+    let compressed = flags.contains(Flags::COMPRESSION);
+    let grouped = flags.contains(Flags::GROUPING_IDENTITY);
+    let prefix_size = if compressed { 4 } else { 0 } + 1 + usize::from(grouped);
+    if data.len() < prefix_size {
+        let err = Error::new(ErrorKind::Parsing, "encrypted frame is too short");
+        return DecodedFrame::stub(id, Version::Id3v23, data, err);
+    }
+    let (data_length, rest) = if compressed {
+        (Some(BigEndian::read_u32(&data[0..4])), &data[4..])
+    } else {
+        (None, &data[..])
+    };
+    let encrypted = EncryptedFrame {
+        method_symbol: rest[0],
+        group_symbol: if grouped { Some(rest[1]) } else { None },
+        compressed,
+        unsynchronised: false,
+        data_length,
+        data: data[prefix_size..].to_vec(),
+    };
+    DecodedFrame::Frame(Frame::with_content(id, Content::EncryptedFrame(encrypted)))
+}
+
+/// Encodes an encrypted frame, writing the header fields that describe the content.
+fn encode_encrypted(
+    mut writer: impl io::Write,
+    frame: &Frame,
+    encrypted: &EncryptedFrame,
+    mut flags: Flags,
+) -> crate::Result<usize> {
+    if encrypted.unsynchronised {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "unsynchronised encrypted frames can only be written to ID3v2.4 tags",
+        )
+        .with_frame_id(frame.id()));
+    }
+    let mut prefix = Vec::new();
+    if encrypted.compressed {
+        let data_length = encrypted.data_length.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "compressed encrypted frames require a data length",
+            )
+            .with_frame_id(frame.id())
+        })?;
+        flags.insert(Flags::COMPRESSION);
+        prefix.extend_from_slice(&data_length.to_be_bytes());
+    }
+    flags.insert(Flags::ENCRYPTION);
+    prefix.push(encrypted.method_symbol);
+    if let Some(group_symbol) = encrypted.group_symbol {
+        flags.insert(Flags::GROUPING_IDENTITY);
+        prefix.push(group_symbol);
+    }
+
+    writer.write_all(frame.id().as_bytes())?;
+    writer.write_u32::<BigEndian>((prefix.len() + encrypted.data.len()) as u32)?;
+    writer.write_u16::<BigEndian>(flags.bits())?;
+    writer.write_all(&prefix)?;
+    writer.write_all(&encrypted.data)?;
+    Ok(10 + prefix.len() + encrypted.data.len())
 }
 
 pub fn encode(mut writer: impl io::Write, frame: &Frame, flags: Flags) -> crate::Result<usize> {
+    if let Content::EncryptedFrame(encrypted) = frame.content() {
+        let flags = flags & (Flags::TAG_ALTER_PRESERVATION | Flags::FILE_ALTER_PRESERVATION);
+        return encode_encrypted(writer, frame, encrypted, flags);
+    }
     let (content_buf, comp_hint_delta, decompressed_size) = if flags.contains(Flags::COMPRESSION) {
         let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
         let content_size = frame::content::encode(
             &mut encoder,
             frame.content(),
             Version::Id3v23,
-            frame.encoding().unwrap_or(Encoding::UTF16),
+            frame::content_encoding(frame, Version::Id3v23),
         )?;
         let content_buf = encoder.finish()?;
         (content_buf, 4, Some(content_size))
@@ -78,7 +222,7 @@ pub fn encode(mut writer: impl io::Write, frame: &Frame, flags: Flags) -> crate:
             &mut content_buf,
             frame.content(),
             Version::Id3v23,
-            frame.encoding().unwrap_or(Encoding::UTF16),
+            frame::content_encoding(frame, Version::Id3v23),
         )?;
         (content_buf, 0, None)
     };
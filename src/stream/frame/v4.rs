@@ -1,13 +1,13 @@
-use crate::frame::Frame;
-use crate::stream::encoding::Encoding;
-use crate::stream::{frame, unsynch};
+use crate::frame::{Content, EncryptedFrame, Frame};
+use crate::stream::frame::{self, DecodedFrame};
+use crate::stream::unsynch;
 use crate::tag::Version;
-use crate::{Error, ErrorKind};
+use crate::{Error, ErrorKind, Warning};
 use bitflags::bitflags;
 use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
-use std::io;
+use std::io::{self, Write};
 
 bitflags! {
     pub struct Flags: u16 {
@@ -22,7 +22,26 @@ bitflags! {
     }
 }
 
-pub fn decode(mut reader: impl io::Read) -> crate::Result<Option<(usize, Frame)>> {
+pub fn decode(
+    reader: impl io::Read,
+    warnings: &mut Vec<Warning>,
+) -> crate::Result<Option<(usize, Frame)>> {
+    match decode_or_stub(reader, warnings, None, None, &[])? {
+        Some((size, decoded)) => Ok(Some((size, decoded.into_result()?))),
+        None => Ok(None),
+    }
+}
+
+/// Like [`decode`], but returns frames of which the content could not be decoded or is larger than
+/// `max_size` as a stub. The content of frames listed in `skip_content_of` is skipped. Frames that
+/// would take up more than the `remaining` bytes of the tag result in an error.
+pub fn decode_or_stub(
+    mut reader: impl io::Read,
+    warnings: &mut Vec<Warning>,
+    max_size: Option<usize>,
+    remaining: Option<u64>,
+    skip_content_of: &[String],
+) -> crate::Result<Option<(usize, DecodedFrame)>> {
     let mut frame_header = [0; 10];
     let nread = reader.read(&mut frame_header)?;
     if nread < frame_header.len() || frame_header[0] == 0x00 {
@@ -30,17 +49,64 @@ pub fn decode(mut reader: impl io::Read) -> crate::Result<Option<(usize, Frame)>
     }
     let id = frame::str_from_utf8(&frame_header[0..4])?;
     let content_size = unsynch::decode_u32(BigEndian::read_u32(&frame_header[4..8])) as usize;
-    let flags = Flags::from_bits_truncate(BigEndian::read_u16(&frame_header[8..10]));
-    if flags.contains(Flags::ENCRYPTION) {
-        return Err(Error::new(
-            ErrorKind::UnsupportedFeature,
-            "encryption is not supported",
-        ));
+    frame::check_remaining(id, 10 + content_size as u64, remaining)?;
+    let raw_flags = BigEndian::read_u16(&frame_header[8..10]);
+    let flags = Flags::from_bits_truncate(raw_flags);
+    let tag_alter = flags.contains(Flags::TAG_ALTER_PRESERVATION);
+    let file_alter = flags.contains(Flags::FILE_ALTER_PRESERVATION);
+    if flags.bits() != raw_flags {
+        warnings.push(
+            Warning::new(format!("unknown frame flags are set: {:#06x}", raw_flags))
+                .with_frame_id(id),
+        );
+    }
+    let unsupported = if flags.contains(Flags::ENCRYPTION) {
+        None
     } else if flags.contains(Flags::GROUPING_IDENTITY) {
-        return Err(Error::new(
-            ErrorKind::UnsupportedFeature,
-            "grouping identity is not supported",
-        ));
+        Some("grouping identity is not supported")
+    } else {
+        None
+    };
+    if frame::skips_content(id, skip_content_of) {
+        let placeholder = frame::skip_content(reader, id, Version::Id3v24, content_size as u64)
+            .map_err(|err| err.with_frame_id(id))?;
+        return Ok(Some((
+            10 + content_size,
+            placeholder.with_alter_preservation(tag_alter, file_alter),
+        )));
+    }
+    if let Some(max_size) = max_size.filter(|max_size| content_size > *max_size) {
+        let stub =
+            frame::skip_oversized(reader, id, Version::Id3v24, content_size as u64, max_size)
+                .map_err(|err| err.with_frame_id(id))?;
+        return Ok(Some((
+            10 + content_size,
+            stub.with_alter_preservation(tag_alter, file_alter),
+        )));
+    }
+    if let Some(description) = unsupported {
+        let mut content_buf = vec![0; content_size];
+        reader
+            .read_exact(&mut content_buf)
+            .map_err(|err| Error::from(err).with_frame_id(id))?;
+        let err = Error::new(ErrorKind::UnsupportedFeature, description);
+        let stub = DecodedFrame::stub(id, Version::Id3v24, content_buf, err);
+        return Ok(Some((
+            10 + content_size,
+            stub.with_alter_preservation(tag_alter, file_alter),
+        )));
+    }
+
+    if flags.contains(Flags::ENCRYPTION) {
+        let mut content_buf = vec![0; content_size];
+        reader
+            .read_exact(&mut content_buf)
+            .map_err(|err| Error::from(err).with_frame_id(id))?;
+        let decoded = decode_encrypted(id, content_buf, &flags);
+        return Ok(Some((
+            10 + content_size,
+            decoded.with_alter_preservation(tag_alter, file_alter),
+        )));
     }
 
     let read_size = if flags.contains(Flags::DATA_LENGTH_INDICATOR) {
@@ -50,47 +116,130 @@ pub fn decode(mut reader: impl io::Read) -> crate::Result<Option<(usize, Frame)>
         content_size
     };
 
-    let (content, encoding) = super::decode_content(
+    let decoded = super::decode_frame(
         reader.take(read_size as u64),
+        warnings,
         Version::Id3v24,
         id,
         flags.contains(Flags::COMPRESSION),
         flags.contains(Flags::UNSYNCHRONISATION),
-    )?;
-    let frame = Frame::with_content(id, content).set_encoding(encoding);
-    Ok(Some((10 + content_size, frame)))
+        content_size as u64,
+        max_size,
+    )
+    .map_err(|err| err.with_frame_id(id))?;
+    Ok(Some((
+        10 + content_size,
+        decoded.with_alter_preservation(tag_alter, file_alter),
+    )))
+}
+
+/// Decodes the content of an encrypted frame, which is preceded by the group symbol, the
+/// encryption method and the data length indicator, if their flags are set.
+fn decode_encrypted(id: &str, data: Vec<u8>, flags: &Flags) -> DecodedFrame {
+    let grouped = flags.contains(Flags::GROUPING_IDENTITY);
+    let has_data_length = flags.contains(Flags::DATA_LENGTH_INDICATOR);
+    let prefix_size = usize::from(grouped) + 1 + if has_data_length { 4 } else { 0 };
+    if data.len() < prefix_size {
+        let err = Error::new(ErrorKind::Parsing, "encrypted frame is too short");
+        return DecodedFrame::stub(id, Version::Id3v24, data, err);
+    }
+    let (group_symbol, rest) = if grouped {
+        (Some(data[0]), &data[1..])
+    } else {
+        (None, &data[..])
+    };
+    let data_length = if has_data_length {
+        Some(unsynch::decode_u32(BigEndian::read_u32(&rest[1..5])))
+    } else {
+        None
+    };
+    let encrypted = EncryptedFrame {
+        method_symbol: rest[0],
+        group_symbol,
+        compressed: flags.contains(Flags::COMPRESSION),
+        unsynchronised: flags.contains(Flags::UNSYNCHRONISATION),
+        data_length,
+        data: data[prefix_size..].to_vec(),
+    };
+    DecodedFrame::Frame(Frame::with_content(id, Content::EncryptedFrame(encrypted)))
+}
+
+/// Encodes an encrypted frame, writing the header fields that describe the content.
+fn encode_encrypted(
+    mut writer: impl io::Write,
+    frame: &Frame,
+    encrypted: &EncryptedFrame,
+    mut flags: Flags,
+) -> crate::Result<usize> {
+    if encrypted.compressed && encrypted.data_length.is_none() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "compressed encrypted frames require a data length",
+        )
+        .with_frame_id(frame.id()));
+    }
+    let mut prefix = Vec::new();
+    if let Some(group_symbol) = encrypted.group_symbol {
+        flags.insert(Flags::GROUPING_IDENTITY);
+        prefix.push(group_symbol);
+    }
+    flags.insert(Flags::ENCRYPTION);
+    prefix.push(encrypted.method_symbol);
+    if let Some(data_length) = encrypted.data_length {
+        flags.insert(Flags::DATA_LENGTH_INDICATOR);
+        prefix.extend_from_slice(&unsynch::encode_u32(data_length).to_be_bytes());
+    }
+    flags.set(Flags::COMPRESSION, encrypted.compressed);
+    flags.set(Flags::UNSYNCHRONISATION, encrypted.unsynchronised);
+
+    let id = frame.id().as_bytes();
+    if id.len() != 4 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Frame ID must be 4 bytes long",
+        ));
+    }
+    writer.write_all(id)?;
+    writer.write_u32::<BigEndian>(unsynch::encode_u32(
+        (prefix.len() + encrypted.data.len()) as u32,
+    ))?;
+    writer.write_u16::<BigEndian>(flags.bits())?;
+    writer.write_all(&prefix)?;
+    writer.write_all(&encrypted.data)?;
+    Ok(10 + prefix.len() + encrypted.data.len())
 }
 
 pub fn encode(mut writer: impl io::Write, frame: &Frame, flags: Flags) -> crate::Result<usize> {
-    let (mut content_buf, comp_hint_delta, decompressed_size) =
-        if flags.contains(Flags::COMPRESSION) {
-            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
-            let content_size = frame::content::encode(
-                &mut encoder,
-                frame.content(),
-                Version::Id3v24,
-                frame.encoding().unwrap_or(Encoding::UTF8),
-            )?;
-            let content_buf = encoder.finish()?;
-            let cd = if flags.contains(Flags::DATA_LENGTH_INDICATOR) {
-                4
-            } else {
-                0
-            };
-            (content_buf, cd, Some(content_size))
-        } else {
-            let mut content_buf = Vec::new();
-            frame::content::encode(
-                &mut content_buf,
-                frame.content(),
-                Version::Id3v24,
-                frame.encoding().unwrap_or(Encoding::UTF8),
-            )?;
-            (content_buf, 0, None)
-        };
+    if let Content::EncryptedFrame(encrypted) = frame.content() {
+        let flags = flags & (Flags::TAG_ALTER_PRESERVATION | Flags::FILE_ALTER_PRESERVATION);
+        return encode_encrypted(writer, frame, encrypted, flags);
+    }
+    // The data length indicator is required for compressed and unsynchronised frames.
+    let mut flags = flags;
+    if flags.intersects(Flags::COMPRESSION | Flags::UNSYNCHRONISATION) {
+        flags.insert(Flags::DATA_LENGTH_INDICATOR);
+    }
+
+    let mut content_buf = Vec::new();
+    let data_length = frame::content::encode(
+        &mut content_buf,
+        frame.content(),
+        Version::Id3v24,
+        frame::content_encoding(frame, Version::Id3v24),
+    )?;
+    if flags.contains(Flags::COMPRESSION) {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&content_buf)?;
+        content_buf = encoder.finish()?;
+    }
     if flags.contains(Flags::UNSYNCHRONISATION) {
         unsynch::encode_vec(&mut content_buf);
     }
+    let dli_size = if flags.contains(Flags::DATA_LENGTH_INDICATOR) {
+        4
+    } else {
+        0
+    };
 
     writer.write_all({
         let id = frame.id().as_bytes();
@@ -102,17 +251,14 @@ pub fn encode(mut writer: impl io::Write, frame: &Frame, flags: Flags) -> crate:
         }
         id
     })?;
-    writer.write_u32::<BigEndian>(unsynch::encode_u32(
-        (content_buf.len() + comp_hint_delta) as u32,
-    ))?;
+    writer.write_u32::<BigEndian>(unsynch::encode_u32((content_buf.len() + dli_size) as u32))?;
     writer.write_u16::<BigEndian>(flags.bits())?;
-    if let Some(s) = decompressed_size {
-        if flags.contains(Flags::DATA_LENGTH_INDICATOR) {
-            writer.write_u32::<BigEndian>(unsynch::encode_u32(s as u32))?;
-        }
+    if flags.contains(Flags::DATA_LENGTH_INDICATOR) {
+        // The length of the frame data with compression and unsynchronisation undone.
+        writer.write_u32::<BigEndian>(unsynch::encode_u32(data_length as u32))?;
     }
     writer.write_all(&content_buf)?;
-    Ok(10 + comp_hint_delta + content_buf.len())
+    Ok(10 + dli_size + content_buf.len())
 }
 
 #[cfg(test)]
@@ -136,6 +282,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_data_length_indicator() {
+        let frame = Frame::with_content(
+            "PRIV",
+            Content::Private(crate::frame::Private {
+                owner_identifier: "example.com".to_string(),
+                private_data: vec![0xff, 0xe0, 0xff, 0x00, 0xff],
+            }),
+        );
+        for flags in [
+            Flags::UNSYNCHRONISATION,
+            Flags::COMPRESSION,
+            Flags::UNSYNCHRONISATION | Flags::COMPRESSION,
+        ] {
+            let mut buf = Vec::new();
+            let size = encode(&mut buf, &frame, Flags::from_bits_truncate(flags.bits())).unwrap();
+            assert_eq!(size, buf.len());
+            let raw_flags = Flags::from_bits_truncate(BigEndian::read_u16(&buf[8..10]));
+            assert!(raw_flags.contains(flags | Flags::DATA_LENGTH_INDICATOR));
+            let content_size = unsynch::decode_u32(BigEndian::read_u32(&buf[4..8])) as usize;
+            assert_eq!(content_size, buf.len() - 10);
+            // "example.com", its terminator and the private data.
+            let data_length = unsynch::decode_u32(BigEndian::read_u32(&buf[10..14]));
+            assert_eq!(data_length, 12 + 5);
+
+            let (_, decoded) = decode(&buf[..], &mut Vec::new()).unwrap().unwrap();
+            assert_eq!(decoded, frame);
+        }
+
+        let mut buf = Vec::new();
+        encode(&mut buf, &frame, Flags::empty()).unwrap();
+        assert_eq!(BigEndian::read_u16(&buf[8..10]), 0);
+    }
+
     #[test]
     fn test_decode_with_underflow() {
         // Create a frame header with DATA_LENGTH_INDICATOR flag set and a content size of 3
@@ -152,7 +332,7 @@ mod tests {
         let mut reader = Cursor::new(data);
 
         // Attempt to decode the frame
-        let result = decode(&mut reader);
+        let result = decode(&mut reader, &mut Vec::new());
 
         // Ensure that the result is an error due to underflow
         assert!(result.is_err());
@@ -1,4 +1,5 @@
 pub mod encoding;
 pub mod frame;
+pub mod mpeg;
 pub mod tag;
 pub mod unsynch;
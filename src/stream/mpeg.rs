@@ -0,0 +1,111 @@
+//! Parsing of MPEG audio frame headers, which is needed both to scan the audio of a file and to
+//! tell ID3v2 tags apart from audio data.
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum MpegVersion {
+    Mpeg1,
+    Mpeg2,
+    Mpeg25,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Layer {
+    Layer1,
+    Layer2,
+    Layer3,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FrameHeader {
+    pub(crate) version: MpegVersion,
+    pub(crate) layer: Layer,
+    /// The bitrate in bits per second.
+    pub(crate) bitrate: u32,
+    pub(crate) sample_rate: u32,
+    pub(crate) padding: bool,
+    pub(crate) mono: bool,
+}
+
+const BITRATES_V1_L1: [u32; 15] = [
+    0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+];
+const BITRATES_V1_L2: [u32; 15] = [
+    0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+];
+const BITRATES_V1_L3: [u32; 15] = [
+    0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+];
+const BITRATES_V2_L1: [u32; 15] = [
+    0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+];
+const BITRATES_V2_L23: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+impl FrameHeader {
+    pub(crate) fn parse(b: [u8; 4]) -> Option<FrameHeader> {
+        if b[0] != 0xff || b[1] & 0xe0 != 0xe0 {
+            return None;
+        }
+        let version = match (b[1] >> 3) & 0x3 {
+            0 => MpegVersion::Mpeg25,
+            2 => MpegVersion::Mpeg2,
+            3 => MpegVersion::Mpeg1,
+            _ => return None,
+        };
+        let layer = match (b[1] >> 1) & 0x3 {
+            1 => Layer::Layer3,
+            2 => Layer::Layer2,
+            3 => Layer::Layer1,
+            _ => return None,
+        };
+        let bitrate_index = (b[2] >> 4) as usize;
+        // Free format streams (index 0) can not be sized from the header alone.
+        if bitrate_index == 0 || bitrate_index == 15 {
+            return None;
+        }
+        let bitrates = match (version, layer) {
+            (MpegVersion::Mpeg1, Layer::Layer1) => &BITRATES_V1_L1,
+            (MpegVersion::Mpeg1, Layer::Layer2) => &BITRATES_V1_L2,
+            (MpegVersion::Mpeg1, Layer::Layer3) => &BITRATES_V1_L3,
+            (_, Layer::Layer1) => &BITRATES_V2_L1,
+            (_, _) => &BITRATES_V2_L23,
+        };
+        let sample_rate = match ((b[2] >> 2) & 0x3, version) {
+            (3, _) => return None,
+            (i, MpegVersion::Mpeg1) => [44100, 48000, 32000][i as usize],
+            (i, MpegVersion::Mpeg2) => [22050, 24000, 16000][i as usize],
+            (i, MpegVersion::Mpeg25) => [11025, 12000, 8000][i as usize],
+        };
+        Some(FrameHeader {
+            version,
+            layer,
+            bitrate: bitrates[bitrate_index] * 1000,
+            sample_rate,
+            padding: b[2] & 0x2 != 0,
+            mono: b[3] >> 6 == 3,
+        })
+    }
+
+    /// The number of samples per channel in the frame.
+    pub(crate) fn samples(&self) -> u32 {
+        match (self.layer, self.version) {
+            (Layer::Layer1, _) => 384,
+            (Layer::Layer2, _) | (Layer::Layer3, MpegVersion::Mpeg1) => 1152,
+            (Layer::Layer3, _) => 576,
+        }
+    }
+
+    /// The size of the frame in bytes, including the header.
+    pub(crate) fn size(&self) -> usize {
+        let size = match self.layer {
+            Layer::Layer1 => (12 * self.bitrate / self.sample_rate + u32::from(self.padding)) * 4,
+            _ => self.samples() / 8 * self.bitrate / self.sample_rate + u32::from(self.padding),
+        };
+        size as usize
+    }
+}
+
+/// Returns the size of the MPEG audio frame of which the header starts `buf`, if it does.
+pub(crate) fn frame_size(buf: &[u8]) -> Option<usize> {
+    let header = buf.get(..4)?.try_into().ok()?;
+    FrameHeader::parse(header).map(|header| header.size())
+}
@@ -1,11 +1,21 @@
 use crate::chunk;
-use crate::storage::{plain::PlainStorage, Format, Storage, StorageFile};
-use crate::stream::{frame, unsynch};
-use crate::tag::{Tag, Version};
+use crate::frame::{Content, Frame};
+use crate::riff_info;
+use crate::storage::{self, plain::PlainStorage, Format, Storage, StorageFile};
+#[cfg(feature = "tokio")]
+use crate::storage::{plain::AsyncPlainStorage, AsyncStorageFile};
+use crate::stream::encoding::Encoding;
+use crate::stream::frame::{self, DecodedFrame};
+use crate::stream::mpeg;
+use crate::stream::unsynch;
+#[cfg(feature = "picture-info")]
+use crate::tag::ImageSizeRestriction;
+use crate::tag::{Tag, TagRestrictions, Version};
 use crate::taglike::TagLike;
-use crate::{Error, ErrorKind};
+use crate::{Error, ErrorKind, Warning};
 use bitflags::bitflags;
-use byteorder::{BigEndian, ByteOrder, WriteBytesExt};
+use byteorder::{BigEndian, ByteOrder};
+use std::borrow::Cow;
 use std::cmp;
 use std::fs;
 use std::io::{self, Read, Write};
@@ -16,6 +26,12 @@ static DEFAULT_FILE_DISCARD: &[&str] = &[
     "AENC", "ETCO", "EQUA", "MLLT", "POSS", "SYLT", "SYTC", "RVAD", "TENC", "TLEN", "TSIZ",
 ];
 
+/// Returns whether the content of the frame is not understood. The alter preservation flags only
+/// apply to such frames.
+fn is_unknown(frame: &Frame) -> bool {
+    matches!(frame.content(), Content::Unknown(_))
+}
+
 bitflags! {
     struct Flags: u8 {
         const UNSYNCHRONISATION = 0x80; // All versions
@@ -40,13 +56,29 @@ struct HeaderBuilder {
 }
 
 impl HeaderBuilder {
-    fn with_ext_header(self, size: u32) -> Header {
+    fn with_ext_header(self, size: u32, ext: ExtHeader) -> Header {
         Header {
             version: self.version,
             flags: self.flags,
             tag_size: self.tag_size,
             ext_header_size: size,
+            ext,
+        }
+    }
+
+    /// Reads the extended header, if the header indicates that there is one.
+    fn decode_ext_header(self, reader: impl io::Read) -> crate::Result<Header> {
+        if !self.flags.contains(Flags::EXTENDED_HEADER) {
+            return Ok(self.with_ext_header(0, ExtHeader::default()));
         }
+        // In ID3v2.3, the extended header is subject to unsynchronisation.
+        let unsynchronised =
+            self.version == Version::Id3v23 && self.flags.contains(Flags::UNSYNCHRONISATION);
+        // An empty tag of the right version is all that can be salvaged from a broken extended
+        // header.
+        let (size, ext) = ExtHeader::decode(reader, self.version, unsynchronised)
+            .map_err(|err| err.with_tag(Tag::with_version(self.version)))?;
+        Ok(self.with_ext_header(size, ext))
     }
 }
 
@@ -55,8 +87,8 @@ struct Header {
     flags: Flags,
     tag_size: u32,
 
-    // TODO: Extended header.
     ext_header_size: u32,
+    ext: ExtHeader,
 }
 
 impl Header {
@@ -69,84 +101,192 @@ impl Header {
     }
 
     fn tag_size(&self) -> u64 {
-        self.size() + self.frame_bytes()
+        let footer_size = if self.flags.contains(Flags::FOOTER) {
+            10
+        } else {
+            0
+        };
+        self.size() + u64::from(self.tag_size) + footer_size
     }
 }
 
-impl Header {
-    fn decode(mut reader: impl io::Read) -> crate::Result<Header> {
-        let mut header = [0; 10];
-        let nread = reader.read(&mut header)?;
-        let base_header = Self::decode_base_header(&header[..nread])?;
+/// The contents of the extended header.
+#[derive(Clone, Copy, Debug, Default)]
+struct ExtHeader {
+    /// The CRC-32 of the tag data.
+    crc: Option<u32>,
+    /// The size of the padding. Only present in ID3v2.3.
+    padding_size: u32,
+    /// The restrictions of the tag. Only present in ID3v2.4.
+    restrictions: Option<TagRestrictions>,
+    /// Whether the tag is an update of an earlier tag. Only present in ID3v2.4.
+    is_update: bool,
+}
 
-        // TODO: actually use the extended header data.
-        let ext_header_size = if base_header.flags.contains(Flags::EXTENDED_HEADER) {
-            let mut ext_header = [0; 6];
-            reader.read_exact(&mut ext_header)?;
-            let ext_size = unsynch::decode_u32(BigEndian::read_u32(&ext_header[0..4]));
-            // The extended header size includes itself and always has at least 2 bytes following.
-            if ext_size < 6 {
-                return Err(Error::new(
-                    ErrorKind::Parsing,
-                    "Extended header requires has a minimum size of 6",
-                ));
-            }
+impl ExtHeader {
+    /// Decodes the extended header, returning it together with the number of bytes it occupies.
+    fn decode(
+        mut reader: impl io::Read,
+        version: Version,
+        unsynchronised: bool,
+    ) -> crate::Result<(u32, ExtHeader)> {
+        match version {
+            Version::Id3v22 => Ok((0, ExtHeader::default())),
+            Version::Id3v23 => {
+                let mut raw_len = 0;
+                let mut prev = 0;
+                // Reads bytes from the header, removing the bytes that were inserted by the
+                // unsynchronisation scheme.
+                let mut read_bytes = |buf: &mut [u8]| -> io::Result<()> {
+                    for b in buf.iter_mut() {
+                        let mut byte = [0];
+                        reader.read_exact(&mut byte)?;
+                        raw_len += 1;
+                        if unsynchronised && prev == 0xff && byte[0] == 0x00 {
+                            reader.read_exact(&mut byte)?;
+                            raw_len += 1;
+                        }
+                        prev = byte[0];
+                        *b = byte[0];
+                    }
+                    Ok(())
+                };
 
-            let _ext_flags = ExtFlags::from_bits_truncate(ext_header[5]);
+                let mut ext_header = [0; 14];
+                read_bytes(&mut ext_header[..6])?;
+                // The size excludes the size field itself.
+                let ext_size = BigEndian::read_u32(&ext_header[0..4]);
+                if ext_size != 6 && ext_size != 10 {
+                    return Err(Error::new(
+                        ErrorKind::Parsing,
+                        "Extended header must have a size of 6 or 10",
+                    ));
+                }
+                read_bytes(&mut ext_header[6..4 + ext_size as usize])?;
+                let crc_present = ext_header[4] & 0x80 != 0 && ext_size == 10;
+                let ext = ExtHeader {
+                    crc: Some(BigEndian::read_u32(&ext_header[10..14])).filter(|_| crc_present),
+                    padding_size: BigEndian::read_u32(&ext_header[6..10]),
+                    ..ExtHeader::default()
+                };
+                Ok((raw_len, ext))
+            }
+            Version::Id3v24 => {
+                let mut ext_header = [0; 6];
+                reader.read_exact(&mut ext_header)?;
+                let ext_size = unsynch::decode_u32(BigEndian::read_u32(&ext_header[0..4]));
+                // The extended header size includes itself and always has at least 2 bytes
+                // following.
+                if ext_size < 6 {
+                    return Err(Error::new(
+                        ErrorKind::Parsing,
+                        "Extended header requires has a minimum size of 6",
+                    ));
+                }
 
-            let ext_remaining_size = ext_size - ext_header.len() as u32;
-            let mut ext_header = Vec::with_capacity(cmp::min(ext_remaining_size as usize, 0xffff));
-            reader
-                .by_ref()
-                .take(ext_remaining_size as u64)
-                .read_to_end(&mut ext_header)?;
+                let ext_flags = ExtFlags::from_bits_truncate(ext_header[5]);
 
-            ext_size
-        } else {
-            0
-        };
+                let ext_remaining_size = ext_size - ext_header.len() as u32;
+                let mut flag_data =
+                    Vec::with_capacity(cmp::min(ext_remaining_size as usize, 0xffff));
+                reader
+                    .take(ext_remaining_size as u64)
+                    .read_to_end(&mut flag_data)?;
 
-        Ok(base_header.with_ext_header(ext_header_size))
+                // The data of each set flag follows in the order of the flags, prefixed by its
+                // length. Malformed flag data is ignored.
+                let mut flag_data = &flag_data[..];
+                let mut ext = ExtHeader::default();
+                if ext_flags.contains(ExtFlags::TAG_IS_UPDATE) {
+                    ext.is_update = true;
+                    take_flag_data(&mut flag_data);
+                }
+                if ext_flags.contains(ExtFlags::CRC_DATA_PRESENT) {
+                    // The CRC is stored as a 35 bit synchsafe integer.
+                    ext.crc = take_flag_data(&mut flag_data)
+                        .filter(|data| data.len() == 5)
+                        .map(|data| {
+                            data.iter()
+                                .fold(0, |crc, b| (crc << 7) | u32::from(b & 0x7f))
+                        });
+                }
+                if ext_flags.contains(ExtFlags::TAG_RESTRICTIONS) {
+                    ext.restrictions = take_flag_data(&mut flag_data)
+                        .and_then(|data| data.first())
+                        .map(|b| TagRestrictions::from_byte(*b));
+                }
+                Ok((ext_size, ext))
+            }
+        }
     }
 
-    #[cfg(feature = "tokio")]
-    async fn async_decode(
-        mut reader: impl tokio::io::AsyncRead + std::marker::Unpin,
-    ) -> crate::Result<Header> {
-        use tokio::io::AsyncReadExt;
-
-        let mut header = [0; 10];
-        let nread = reader.read(&mut header).await?;
-        let base_header = Self::decode_base_header(&header[..nread])?;
+    /// Returns true if the extended header has any contents that can be written to a tag of the
+    /// specified version.
+    fn is_needed(&self, version: Version) -> bool {
+        match version {
+            Version::Id3v22 => false,
+            Version::Id3v23 => self.crc.is_some(),
+            Version::Id3v24 => self.is_update || self.crc.is_some() || self.restrictions.is_some(),
+        }
+    }
 
-        // TODO: actually use the extended header data.
-        let ext_header_size = if base_header.flags.contains(Flags::EXTENDED_HEADER) {
-            let mut ext_header = [0; 6];
-            reader.read_exact(&mut ext_header).await?;
-            let ext_size = unsynch::decode_u32(BigEndian::read_u32(&ext_header[0..4]));
-            // The extended header size includes itself and always has at least 2 bytes following.
-            if ext_size < 6 {
-                return Err(Error::new(
-                    ErrorKind::Parsing,
-                    "Extended header requires has a minimum size of 6",
-                ));
+    /// Encodes the extended header.
+    fn encode(&self, version: Version) -> Vec<u8> {
+        let mut ext_header = Vec::new();
+        match version {
+            Version::Id3v22 => {}
+            Version::Id3v23 => {
+                let crc = self.crc.unwrap_or(0);
+                ext_header.extend_from_slice(&10u32.to_be_bytes());
+                ext_header.extend_from_slice(&[0x80, 0x00]);
+                ext_header.extend_from_slice(&self.padding_size.to_be_bytes());
+                ext_header.extend_from_slice(&crc.to_be_bytes());
             }
+            Version::Id3v24 => {
+                let mut ext_flags = ExtFlags::empty();
+                let mut flag_data = Vec::new();
+                if self.is_update {
+                    ext_flags.insert(ExtFlags::TAG_IS_UPDATE);
+                    flag_data.push(0);
+                }
+                if let Some(crc) = self.crc {
+                    ext_flags.insert(ExtFlags::CRC_DATA_PRESENT);
+                    // The CRC is stored as a 35 bit synchsafe integer.
+                    flag_data.push(5);
+                    flag_data.extend((0..5).rev().map(|i| (crc >> (i * 7)) as u8 & 0x7f));
+                }
+                if let Some(restrictions) = self.restrictions {
+                    ext_flags.insert(ExtFlags::TAG_RESTRICTIONS);
+                    flag_data.extend_from_slice(&[1, restrictions.to_byte()]);
+                }
+                let size = 6 + flag_data.len() as u32;
+                ext_header.extend_from_slice(&unsynch::encode_u32(size).to_be_bytes());
+                ext_header.extend_from_slice(&[1, ext_flags.bits()]);
+                ext_header.extend(flag_data);
+            }
+        }
+        ext_header
+    }
+}
 
-            let _ext_flags = ExtFlags::from_bits_truncate(ext_header[5]);
-
-            let ext_remaining_size = ext_size - ext_header.len() as u32;
-            let mut ext_header = Vec::with_capacity(cmp::min(ext_remaining_size as usize, 0xffff));
-            reader
-                .take(ext_remaining_size as u64)
-                .read_to_end(&mut ext_header)
-                .await?;
-
-            ext_size
-        } else {
-            0
-        };
+/// Takes the data of the next extended header flag. Returns None if the data is truncated.
+fn take_flag_data<'a>(data: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let (&len, rest) = data.split_first()?;
+    if rest.len() < len as usize {
+        *data = &[];
+        return None;
+    }
+    let (flag_data, rest) = rest.split_at(len as usize);
+    *data = rest;
+    Some(flag_data)
+}
 
-        Ok(base_header.with_ext_header(ext_header_size))
+impl Header {
+    fn decode(mut reader: impl io::Read) -> crate::Result<Header> {
+        let mut header = [0; 10];
+        let nread = reader.read(&mut header)?;
+        let base_header = Self::decode_base_header(&header[..nread])?;
+        base_header.decode_ext_header(reader)
     }
 
     fn decode_base_header(header: &[u8]) -> crate::Result<HeaderBuilder> {
@@ -171,7 +311,10 @@ impl Header {
             (4, _) => Version::Id3v24,
             (_, _) => {
                 return Err(Error::new(
-                    ErrorKind::UnsupportedFeature,
+                    ErrorKind::UnsupportedVersion {
+                        major: ver_major,
+                        minor: ver_minor,
+                    },
                     format!(
                         "Unsupported id3 tag version: v2.{}.{}",
                         ver_major, ver_minor
@@ -199,34 +342,480 @@ impl Header {
     }
 }
 
-pub fn decode(mut reader: impl io::Read) -> crate::Result<Tag> {
+pub fn decode(reader: impl io::Read) -> crate::Result<Tag> {
+    Decoder::new().decode(reader)
+}
+
+/// Decodes a tag, collecting any recoverable issues into `warnings`.
+pub fn decode_with_warnings(
+    reader: impl io::Read,
+    warnings: &mut Vec<Warning>,
+) -> crate::Result<Tag> {
+    Decoder::new().decode_with_warnings(reader, warnings)
+}
+
+/// Decodes the tag at the start of the reader like [`decode_with_warnings`], falling back to a tag
+/// that is appended to the end of the reader. If the tag has a SEEK frame, the tag it points to is
+/// decoded as well and merged into it, with the frames of the latter taking precedence.
+pub fn decode_seekable_with_warnings(
+    mut reader: impl io::Read + io::Seek,
+    warnings: &mut Vec<Warning>,
+) -> crate::Result<Tag> {
+    let mut start = reader.stream_position()?;
+    let mut tag = match decode_with_warnings(&mut reader, warnings) {
+        Err(Error {
+            kind: ErrorKind::NoTag,
+            ..
+        }) => {
+            let location = locate_id3v2_footer(&mut reader)
+                .map_err(|_| Error::new(ErrorKind::NoTag, "reader does not contain an id3 tag"))?;
+            start = location.start;
+            reader.seek(io::SeekFrom::Start(start))?;
+            decode_with_warnings((&mut reader).take(location.end - location.start), warnings)?
+        }
+        result => result?,
+    };
+
+    // The offset of the SEEK frame is counted from the end of the tag.
+    let offset = match tag.get("SEEK").map(|frame| frame.content()) {
+        Some(Content::Unknown(unknown)) if unknown.data.len() == 4 => {
+            u64::from(BigEndian::read_u32(&unknown.data))
+        }
+        _ => return Ok(tag),
+    };
+    reader.seek(io::SeekFrom::Start(start))?;
+    let mut header = [0; 10];
+    reader.read_exact(&mut header)?;
+    let tag_size = Header::decode_base_header(&header)?
+        .with_ext_header(0, ExtHeader::default())
+        .tag_size();
+    reader.seek(io::SeekFrom::Start(start + tag_size + offset))?;
+    match decode_with_warnings(&mut reader, warnings) {
+        Ok(next) => {
+            tag.remove("SEEK");
+            Ok(Tag::merged([tag, next]))
+        }
+        Err(_) => {
+            warnings
+                .push(Warning::new("the SEEK frame does not point to a tag").with_frame_id("SEEK"));
+            Ok(tag)
+        }
+    }
+}
+
+/// The `Decoder` may be used to decode tags with custom settings.
+#[derive(Clone, Debug, Default)]
+pub struct Decoder {
+    max_frame_size: Option<usize>,
+    max_tag_size: Option<u64>,
+    max_frames: Option<usize>,
+    skip_content_of: Vec<String>,
+}
+
+impl Decoder {
+    /// Constructs a new `Decoder` with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size of the content of a single frame, after decompression. Larger frames
+    /// are skipped without being read into memory.
+    ///
+    /// A skipped frame is kept in the tag as a [`Content::Skipped`] placeholder, like with
+    /// [`Decoder::skip_content_of`], so a tag holding it can not be written back. Decoding results
+    /// in an error of kind [`ErrorKind::LimitExceeded`] that holds the partial tag. Use
+    /// [`partial_tag_ok`](crate::partial_tag_ok) to accept such tags.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{Picture, PictureType};
+    /// use id3::{partial_tag_ok, Decoder, ErrorKind, Tag, TagLike, Version};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// tag.add_frame(Picture {
+    ///     mime_type: "image/png".to_string(),
+    ///     picture_type: PictureType::CoverFront,
+    ///     description: "".to_string(),
+    ///     data: vec![0; 4096],
+    /// });
+    /// let mut buf = Vec::new();
+    /// tag.write_to(&mut buf, Version::Id3v24)?;
+    ///
+    /// let decoder = Decoder::new().max_frame_size(1024);
+    /// let err = decoder.decode(&buf[..]).unwrap_err();
+    /// assert!(matches!(err.kind, ErrorKind::LimitExceeded));
+    /// let tag = partial_tag_ok(decoder.decode(&buf[..]))?;
+    /// assert_eq!(tag.title(), Some("Title"));
+    /// assert_eq!(tag.pictures().count(), 0);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = Some(max_frame_size);
+        self
+    }
+
+    /// Sets the maximum number of bytes of the tag that are decoded, including the header.
+    ///
+    /// Decoding stops before the first frame that ends beyond the limit, as declared by its header,
+    /// so its content is never read. The result is an error of
+    /// kind [`ErrorKind::LimitExceeded`] that holds the frames decoded so far as the partial tag.
+    /// Combine this with [`Decoder::max_frame_size`] to also bound the size of the frame that
+    /// exceeds the limit.
+    pub fn max_tag_size(mut self, max_tag_size: u64) -> Self {
+        self.max_tag_size = Some(max_tag_size);
+        self
+    }
+
+    /// Sets the maximum number of frames that are decoded.
+    ///
+    /// Like with [`Decoder::max_tag_size`], decoding stops when the limit is exceeded and the
+    /// frames decoded so far are returned as a partial tag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{partial_tag_ok, Decoder, ErrorKind, Tag, TagLike, Version};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// tag.set_artist("Artist");
+    /// tag.set_album("Album");
+    /// let mut buf = Vec::new();
+    /// tag.write_to(&mut buf, Version::Id3v24)?;
+    ///
+    /// let decoder = Decoder::new().max_frames(2);
+    /// let err = decoder.decode(&buf[..]).unwrap_err();
+    /// assert!(matches!(err.kind, ErrorKind::LimitExceeded));
+    /// assert_eq!(err.partial_tag.map(|tag| tag.frames().count()), Some(2));
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn max_frames(mut self, max_frames: usize) -> Self {
+        self.max_frames = Some(max_frames);
+        self
+    }
+
+    /// Skips the content of the frames with the given IDs, which saves the time and memory needed
+    /// to decode large frames like pictures when only the text frames are of interest.
+    ///
+    /// A skipped frame is kept in the tag as a [`Content::Skipped`] placeholder that holds the size
+    /// and the offset of the frame. Frames of ID3v2.2 tags are matched by their ID3v2.3 IDs. As the
+    /// content is not available, tags holding placeholders can not be written.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{Picture, PictureType};
+    /// use id3::{Decoder, Tag, TagLike, Version};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// tag.add_frame(Picture {
+    ///     mime_type: "image/png".to_string(),
+    ///     picture_type: PictureType::CoverFront,
+    ///     description: "".to_string(),
+    ///     data: vec![0; 4096],
+    /// });
+    /// let mut buf = Vec::new();
+    /// tag.write_to(&mut buf, Version::Id3v24)?;
+    ///
+    /// let tag = Decoder::new().skip_content_of(&["APIC", "GEOB"]).decode(&buf[..])?;
+    /// assert_eq!(tag.title(), Some("Title"));
+    /// let skipped = tag.get("APIC").and_then(|frame| frame.content().skipped()).unwrap();
+    /// assert!(skipped.size > 4096);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn skip_content_of(mut self, ids: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.skip_content_of
+            .extend(ids.into_iter().map(|id| id.as_ref().to_string()));
+        self
+    }
+
+    /// Returns the number of bytes that may still be decoded from byte `offset` of the tag on, if
+    /// the size of the tag is limited. Frames are checked against it before their content is read.
+    fn remaining_tag_size(&self, offset: u64) -> Option<u64> {
+        self.max_tag_size.map(|max| max.saturating_sub(offset))
+    }
+
+    /// Checks whether decoding may continue after `frames` frames have been read.
+    fn check_limits(&self, frames: usize) -> crate::Result<()> {
+        if let Some(max_frames) = self.max_frames.filter(|max| frames > *max) {
+            return Err(Error::new(
+                ErrorKind::LimitExceeded,
+                format!("tag exceeds the maximum of {} frames", max_frames),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Decodes a tag from the reader using the settings set in the [`Decoder`].
+    pub fn decode(&self, reader: impl io::Read) -> crate::Result<Tag> {
+        self.decode_with_warnings(reader, &mut Vec::new())
+    }
+
+    /// Decodes a tag like [`Decoder::decode`], collecting any recoverable issues into
+    /// `warnings`.
+    pub fn decode_with_warnings(
+        &self,
+        mut reader: impl io::Read,
+        warnings: &mut Vec<Warning>,
+    ) -> crate::Result<Tag> {
+        let header = Header::decode(&mut reader)?;
+
+        decode_remaining(reader, header, self, warnings)
+    }
+}
+
+/// Provides access to the data of an encapsulated object (GEOB) frame without loading it into
+/// memory. Created by [`Tag::read_encapsulated_object`].
+///
+/// The object data is read from the source the tag was read from. If the data can not be read
+/// directly because it is compressed or unsynchronised, or because the tag is ID3v2.2, it is
+/// buffered in memory instead.
+pub struct EncapsulatedObjectReader<R> {
+    /// The MIME type of the data.
+    pub mime_type: String,
+    /// The filename of the object.
+    pub filename: String,
+    /// The description of the object.
+    pub description: String,
+    data: ObjectData<R>,
+}
+
+enum ObjectData<R> {
+    Source(io::Take<R>),
+    Buffered(io::Cursor<Vec<u8>>),
+}
+
+impl<R: io::Read> EncapsulatedObjectReader<R> {
+    /// Returns the number of bytes of object data that have not been read yet.
+    pub fn remaining(&self) -> u64 {
+        match &self.data {
+            ObjectData::Source(r) => r.limit(),
+            ObjectData::Buffered(r) => r.get_ref().len() as u64 - r.position(),
+        }
+    }
+}
+
+impl<R: io::Read> io::Read for EncapsulatedObjectReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.data {
+            ObjectData::Source(r) => r.read(buf),
+            ObjectData::Buffered(r) => r.read(buf),
+        }
+    }
+}
+
+/// Locates the first GEOB frame with the specified description by skipping over the content of
+/// all other frames. The reader must be positioned at the start of the tag.
+pub fn read_encapsulated_object<R: io::Read + io::Seek>(
+    mut reader: R,
+    description: &str,
+) -> crate::Result<Option<EncapsulatedObjectReader<R>>> {
+    let start = reader.stream_position()?;
     let header = Header::decode(&mut reader)?;
+    let mut streamable =
+        header.version != Version::Id3v22 && !header.flags.contains(Flags::UNSYNCHRONISATION);
+
+    let mut offset = 0;
+    while streamable && offset + 10 <= header.frame_bytes() {
+        let mut frame_header = [0; 10];
+        reader.read_exact(&mut frame_header)?;
+        if frame_header[0] == 0x00 {
+            return Ok(None);
+        }
+        let raw_size = BigEndian::read_u32(&frame_header[4..8]);
+        let raw_flags = BigEndian::read_u16(&frame_header[8..10]);
+        let (size, plain) = match header.version {
+            Version::Id3v24 => {
+                let flags = frame::v4::Flags::from_bits_truncate(raw_flags);
+                let encoded = frame::v4::Flags::GROUPING_IDENTITY
+                    | frame::v4::Flags::COMPRESSION
+                    | frame::v4::Flags::ENCRYPTION
+                    | frame::v4::Flags::UNSYNCHRONISATION
+                    | frame::v4::Flags::DATA_LENGTH_INDICATOR;
+                (unsynch::decode_u32(raw_size), !flags.intersects(encoded))
+            }
+            _ => {
+                let flags = frame::v3::Flags::from_bits_truncate(raw_flags);
+                let encoded = frame::v3::Flags::COMPRESSION
+                    | frame::v3::Flags::ENCRYPTION
+                    | frame::v3::Flags::GROUPING_IDENTITY;
+                (raw_size, !flags.intersects(encoded))
+            }
+        };
+        offset += 10 + u64::from(size);
+        if &frame_header[0..4] != b"GEOB" {
+            reader.seek(io::SeekFrom::Current(i64::from(size)))?;
+            continue;
+        }
+        if !plain {
+            streamable = false;
+            break;
+        }
+
+        if size == 0 {
+            return Err(Error::new(ErrorKind::Parsing, "frame is empty"));
+        }
+        let mut encoding = [0; 1];
+        reader.read_exact(&mut encoding)?;
+        let mut limit = u64::from(size) - 1;
+        let encoding = match encoding[0] {
+            0 => Encoding::Latin1,
+            1 => Encoding::UTF16,
+            2 => Encoding::UTF16BE,
+            3 => Encoding::UTF8,
+            _ => return Err(Error::new(ErrorKind::Parsing, "unknown encoding")),
+        };
+        let mime_type = read_delimited(&mut reader, Encoding::Latin1, usize::MAX, &mut limit)?;
+        let filename = read_delimited(&mut reader, encoding, usize::MAX, &mut limit)?;
+        let object_description = read_delimited(&mut reader, encoding, usize::MAX, &mut limit)?;
+        if encoding.decode(&object_description)? != description {
+            reader.seek(io::SeekFrom::Current(limit as i64))?;
+            continue;
+        }
+        return Ok(Some(EncapsulatedObjectReader {
+            mime_type: Encoding::Latin1.decode(mime_type)?,
+            filename: encoding.decode(filename)?,
+            description: description.to_string(),
+            data: ObjectData::Source(reader.take(limit)),
+        }));
+    }
+    if streamable {
+        return Ok(None);
+    }
+
+    reader.seek(io::SeekFrom::Start(start))?;
+    let tag = decode(&mut reader)?;
+    let object = tag
+        .encapsulated_objects()
+        .find(|object| object.description == description);
+    Ok(object.map(|object| EncapsulatedObjectReader {
+        mime_type: object.mime_type.clone(),
+        filename: object.filename.clone(),
+        description: object.description.clone(),
+        data: ObjectData::Buffered(io::Cursor::new(object.data.clone())),
+    }))
+}
 
-    decode_remaining(reader, header)
+/// Reads bytes up to and excluding the delimiter of the encoding, or until `max_len` bytes have
+/// been read. `limit` is the number of bytes that remain in the frame and is updated.
+fn read_delimited(
+    mut reader: impl io::Read,
+    encoding: Encoding,
+    max_len: usize,
+    limit: &mut u64,
+) -> crate::Result<Vec<u8>> {
+    let unit = match encoding {
+        Encoding::Latin1 | Encoding::UTF8 => 1,
+        Encoding::UTF16 | Encoding::UTF16BE => 2,
+    };
+    let mut bytes = Vec::new();
+    let mut buf = [0; 2];
+    while bytes.len() < max_len {
+        if *limit < unit as u64 {
+            return Err(Error::new(ErrorKind::Parsing, "delimiter not found"));
+        }
+        reader.read_exact(&mut buf[..unit])?;
+        *limit -= unit as u64;
+        if buf[..unit].iter().all(|b| *b == 0) {
+            break;
+        }
+        bytes.extend_from_slice(&buf[..unit]);
+    }
+    Ok(bytes)
 }
 
 #[cfg(feature = "tokio")]
 pub async fn async_decode(
+    reader: impl tokio::io::AsyncRead + std::marker::Unpin,
+) -> crate::Result<Tag> {
+    async_decode_with_warnings(reader, &mut Vec::new()).await
+}
+
+/// Decodes a tag via Tokio, collecting any recoverable issues into `warnings`.
+#[cfg(feature = "tokio")]
+pub async fn async_decode_with_warnings(
     mut reader: impl tokio::io::AsyncRead + std::marker::Unpin,
+    warnings: &mut Vec<Warning>,
 ) -> crate::Result<Tag> {
-    let header = Header::async_decode(&mut reader).await?;
+    use tokio::io::AsyncReadExt;
 
-    let reader = {
-        use tokio::io::AsyncReadExt;
+    let mut header = [0; 10];
+    let nread = reader.read(&mut header).await?;
+    let base_header = Header::decode_base_header(&header[..nread])?;
 
-        let mut buf = Vec::new();
+    // Read the rest of the tag into memory so the extended header and frames can be decoded using
+    // the synchronous decoder.
+    let mut buf = Vec::new();
+    reader
+        .take(u64::from(base_header.tag_size))
+        .read_to_end(&mut buf)
+        .await?;
+    let mut reader = std::io::Cursor::new(buf);
+    let header = base_header.decode_ext_header(&mut reader)?;
 
-        reader
-            .take(header.frame_bytes())
-            .read_to_end(&mut buf)
-            .await?;
-        std::io::Cursor::new(buf)
-    };
+    decode_remaining(reader, header, &Decoder::new(), warnings)
+}
 
-    decode_remaining(reader, header)
+fn decode_remaining(
+    mut reader: impl io::Read,
+    header: Header,
+    decoder: &Decoder,
+    warnings: &mut Vec<Warning>,
+) -> crate::Result<Tag> {
+    let ext = header.ext;
+    let Some(expected_crc) = header.ext.crc else {
+        return decode_frames(reader, header, decoder, warnings).map(|mut tag| {
+            tag.set_restrictions(ext.restrictions);
+            tag.set_is_update(ext.is_update);
+            tag
+        });
+    };
+    // The frame data needs to be buffered to verify the CRC before decoding it.
+    let mut data = Vec::new();
+    (&mut reader)
+        .take(header.frame_bytes())
+        .read_to_end(&mut data)?;
+    let crc_data = match header.version {
+        // In ID3v2.3, the CRC is calculated over the frames before unsynchronisation, excluding
+        // the padding.
+        Version::Id3v23 => {
+            let mut crc_data = if header.flags.contains(Flags::UNSYNCHRONISATION) {
+                let mut decoded = Vec::new();
+                unsynch::Reader::new(&data[..]).read_to_end(&mut decoded)?;
+                decoded
+            } else {
+                data.clone()
+            };
+            let len = crc_data
+                .len()
+                .saturating_sub(header.ext.padding_size as usize);
+            crc_data.truncate(len);
+            Cow::Owned(crc_data)
+        }
+        // In ID3v2.4, the CRC is calculated over the frames and padding. ID3v2.2 has no extended
+        // header.
+        Version::Id3v22 | Version::Id3v24 => Cow::Borrowed(&data[..]),
+    };
+    let mut crc = flate2::Crc::new();
+    crc.update(&crc_data);
+    if crc.sum() != expected_crc {
+        warnings.push(Warning::new(
+            "the CRC-32 of the tag does not match its data",
+        ));
+    }
+    decode_frames(io::Cursor::new(data), header, decoder, warnings).map(|mut tag| {
+        tag.set_restrictions(ext.restrictions);
+        tag.set_is_update(ext.is_update);
+        tag
+    })
 }
 
-fn decode_remaining(mut reader: impl io::Read, header: Header) -> crate::Result<Tag> {
+fn decode_frames(
+    mut reader: impl io::Read,
+    header: Header,
+    decoder: &Decoder,
+    warnings: &mut Vec<Warning>,
+) -> crate::Result<Tag> {
     match header.version {
         Version::Id3v22 => {
             // Limit the reader only to the given tag_size, don't return any more bytes after that.
@@ -234,9 +823,9 @@ fn decode_remaining(mut reader: impl io::Read, header: Header) -> crate::Result<
 
             if header.flags.contains(Flags::UNSYNCHRONISATION) {
                 // Unwrap all 'unsynchronized' bytes in the tag before parsing frames.
-                decode_v2_frames(unsynch::Reader::new(v2_reader))
+                decode_v2_frames(unsynch::Reader::new(v2_reader), decoder, warnings)
             } else {
-                decode_v2_frames(v2_reader)
+                decode_v2_frames(v2_reader, decoder, warnings)
             }
         }
         Version::Id3v23 => {
@@ -247,69 +836,359 @@ fn decode_remaining(mut reader: impl io::Read, header: Header) -> crate::Result<
                 Box::new(reader)
             };
 
+            let frames_start = header.size() + u64::from(header.ext_header_size);
             let mut offset = 0;
             let mut tag = Tag::with_version(header.version);
+            let mut first_error = None;
+            let mut frames = 0;
             while offset < header.frame_bytes() {
-                let v = match frame::v3::decode(&mut reader) {
+                let first_warning = warnings.len();
+                let v = match frame::v3::decode_or_stub(
+                    &mut reader,
+                    warnings,
+                    decoder.max_frame_size,
+                    decoder.remaining_tag_size(frames_start + offset),
+                    &decoder.skip_content_of,
+                ) {
                     Ok(v) => v,
-                    Err(err) => return Err(err.with_tag(tag)),
+                    Err(err) => return Err(err.with_offset(frames_start + offset).with_tag(tag)),
                 };
-                let (bytes_read, frame) = match v {
+                let (bytes_read, decoded) = match v {
                     Some(v) => v,
                     None => break, // Padding.
                 };
-                tag.add_frame(frame);
+                frames += 1;
+                if let Err(err) = decoder.check_limits(frames) {
+                    return Err(err.with_offset(frames_start + offset).with_tag(tag));
+                }
+                add_decoded_frame(
+                    &mut tag,
+                    decoded,
+                    frames_start + offset,
+                    warnings,
+                    first_warning,
+                    &mut first_error,
+                );
                 offset += bytes_read as u64;
             }
-            Ok(tag)
+            finish_decoding(tag, first_error)
         }
         Version::Id3v24 => {
+            let frames_start = header.size() + u64::from(header.ext_header_size);
             let mut offset = 0;
             let mut tag = Tag::with_version(header.version);
+            let mut first_error = None;
+            let mut frames = 0;
 
             while offset < header.frame_bytes() {
-                let v = match frame::v4::decode(&mut reader) {
+                let first_warning = warnings.len();
+                let v = match frame::v4::decode_or_stub(
+                    &mut reader,
+                    warnings,
+                    decoder.max_frame_size,
+                    decoder.remaining_tag_size(frames_start + offset),
+                    &decoder.skip_content_of,
+                ) {
                     Ok(v) => v,
-                    Err(err) => return Err(err.with_tag(tag)),
+                    Err(err) => return Err(err.with_offset(frames_start + offset).with_tag(tag)),
                 };
-                let (bytes_read, frame) = match v {
+                let (bytes_read, decoded) = match v {
                     Some(v) => v,
                     None => break, // Padding.
                 };
-                tag.add_frame(frame);
+                frames += 1;
+                if let Err(err) = decoder.check_limits(frames) {
+                    return Err(err.with_offset(frames_start + offset).with_tag(tag));
+                }
+                add_decoded_frame(
+                    &mut tag,
+                    decoded,
+                    frames_start + offset,
+                    warnings,
+                    first_warning,
+                    &mut first_error,
+                );
                 offset += bytes_read as u64;
             }
-            Ok(tag)
+            finish_decoding(tag, first_error)
         }
     }
 }
 
-pub fn decode_v2_frames(mut reader: impl io::Read) -> crate::Result<Tag> {
+pub fn decode_v2_frames(
+    mut reader: impl io::Read,
+    decoder: &Decoder,
+    warnings: &mut Vec<Warning>,
+) -> crate::Result<Tag> {
     let mut tag = Tag::with_version(Version::Id3v22);
+    // The ID3v2.2 header has no extended header, so frames start right after it.
+    let mut offset = 10;
+    let mut first_error = None;
+    let mut frames = 0;
     // Add all frames, until either an error is thrown or there are no more frames to parse
     // (because of EOF or a Padding).
     loop {
-        let v = match frame::v2::decode(&mut reader) {
+        let first_warning = warnings.len();
+        let v = match frame::v2::decode(
+            &mut reader,
+            warnings,
+            decoder.max_frame_size,
+            decoder.remaining_tag_size(offset),
+            &decoder.skip_content_of,
+        ) {
             Ok(v) => v,
-            Err(err) => return Err(err.with_tag(tag)),
+            Err(err) => return Err(err.with_offset(offset).with_tag(tag)),
         };
         match v {
-            Some((_bytes_read, frame)) => {
-                tag.add_frame(frame);
+            Some((bytes_read, decoded)) => {
+                frames += 1;
+                if let Err(err) = decoder.check_limits(frames) {
+                    return Err(err.with_offset(offset).with_tag(tag));
+                }
+                add_decoded_frame(
+                    &mut tag,
+                    decoded,
+                    offset,
+                    warnings,
+                    first_warning,
+                    &mut first_error,
+                );
+                offset += bytes_read as u64;
+            }
+            None => break finish_decoding(tag, first_error),
+        }
+    }
+}
+
+/// Returns the decoded tag, or the first frame error along with everything that was salvaged.
+fn finish_decoding(tag: Tag, first_error: Option<Error>) -> crate::Result<Tag> {
+    match first_error {
+        Some(err) => Err(err.with_tag(tag)),
+        None => Ok(tag),
+    }
+}
+
+/// Adds a freshly decoded frame to the tag, recording warnings for frames that can not be
+/// interpreted or that replace an earlier frame. Warnings from `first_warning` onwards were
+/// raised while decoding this frame and receive its offset.
+///
+/// Stubs of frames of which the content could not be decoded are kept, so they can be salvaged
+/// using the partial tag of the error. The error of the first of such frames is stored in
+/// `first_error`.
+fn add_decoded_frame(
+    tag: &mut Tag,
+    mut decoded: DecodedFrame,
+    offset: u64,
+    warnings: &mut Vec<Warning>,
+    first_warning: usize,
+    first_error: &mut Option<Error>,
+) {
+    let (DecodedFrame::Frame(frame) | DecodedFrame::Stub(frame, _)) = &mut decoded;
+    if let Content::Skipped(skipped) = frame.content_mut() {
+        skipped.offset = offset;
+    }
+    let frame = match decoded {
+        DecodedFrame::Frame(frame) => frame,
+        DecodedFrame::Stub(frame, err) => {
+            first_error.get_or_insert(err.with_offset(offset));
+            tag.add_frame(frame);
+            for warning in &mut warnings[first_warning..] {
+                warning.offset = warning.offset.or(Some(offset));
+            }
+            return;
+        }
+    };
+    let id = frame.id().to_string();
+    if let Content::Unknown(_) = frame.content() {
+        warnings.push(
+            Warning::new("frame is not supported and is kept as raw data").with_frame_id(&id),
+        );
+    }
+    if tag.add_frame(frame).is_some() {
+        warnings.push(Warning::new("frame replaces an earlier duplicate").with_frame_id(&id));
+    }
+    for warning in &mut warnings[first_warning..] {
+        warning.offset = warning.offset.or(Some(offset));
+    }
+}
+
+/// Describes what happened on disk when a tag was written to a file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WriteOutcome {
+    /// The number of bytes of tag data that were written, including headers and padding.
+    pub bytes_written: u64,
+    /// Whether the data following the tag had to be moved because the new tag did not fit in the
+    /// space of the old one. If false, the tag was updated in place.
+    pub rewritten: bool,
+    /// The amount of padding that remains after the frames of the tag.
+    pub padding: usize,
+    /// Whether an ID3v1 tag was written or removed.
+    pub v1_touched: bool,
+}
+
+/// Presets for [`Encoder::file_altered`] and the alter preservation flags of the encoded frames.
+///
+/// See [`Encoder::profile`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AlterProfile {
+    /// Only the tag is changed, the audio is left untouched.
+    ///
+    /// All frames are kept. Frames that depend on the audio contents (AENC, ETCO, EQUA, MLLT,
+    /// POSS, SYLT, SYTC, RVAD, TENC, TLEN, TSIZ) get their File Alter Preservation flag set, so
+    /// that tools that later change the audio know to discard them.
+    MetadataEdit,
+    /// The audio has been changed, for example because it was transcoded.
+    ///
+    /// Frames that depend on the audio contents are discarded, as are unknown frames that have
+    /// their File Alter Preservation flag set.
+    Transcode,
+}
+
+/// How the [`Encoder`] handles UFID frames that violate the specification, see
+/// [`Encoder::ufid_policy`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum UfidPolicy {
+    /// The frame is written as is and a warning is reported.
+    Warn,
+    /// Identifiers that are too long are truncated to 64 bytes and a warning is reported. A frame
+    /// with an empty owner can not be repaired and results in an error.
+    Truncate,
+    /// Encoding fails with [`ErrorKind::InvalidInput`].
+    Error,
+}
+
+/// How the [`Encoder`] chooses the encoding of the text of frames that do not have an encoding of
+/// their own, see [`Encoder::encoding_policy`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum EncodingPolicy {
+    /// Text is written in the default encoding of the version, UTF-16 for ID3v2.2 and ID3v2.3
+    /// and UTF-8 for ID3v2.4.
+    #[default]
+    Default,
+    /// Text is written as Latin1 when it can be represented in it and in the default encoding of
+    /// the version otherwise, which minimizes the size of the tag.
+    Smallest,
+    /// Text is written in the given encoding. Frames fall back to the default encoding of the
+    /// version if their text can not be represented in it or if the encoding is not supported
+    /// by the version: UTF-16BE and UTF-8 are only available in ID3v2.4.
+    Always(Encoding),
+}
+
+impl EncodingPolicy {
+    /// Returns the encoding to write the frame in, or `None` to use the default encoding.
+    fn encoding_for(self, frame: &Frame, version: Version) -> Option<Encoding> {
+        let encoding = match self {
+            EncodingPolicy::Default => return None,
+            EncodingPolicy::Smallest => Encoding::Latin1,
+            EncodingPolicy::Always(encoding) => encoding,
+        };
+        Some(encoding).filter(|&encoding| can_encode_text(frame, version, encoding))
+    }
+}
+
+/// How much padding the [`Encoder`] writes after the frames of a tag, see
+/// [`Encoder::padding_policy`].
+///
+/// Padding reserves space in a file, so a tag that grows a little can be written without moving
+/// the data that follows it. When writing to a file of which the existing tag has enough space
+/// for the new tag, the existing space is reused and all of the space that remains becomes
+/// padding, regardless of the policy. The policy decides the padding when the tag does not fit.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PaddingPolicy {
+    /// No padding is written.
+    #[default]
+    None,
+    /// The given number of bytes of padding is written.
+    Fixed(usize),
+    /// Padding of the given percentage of the size of the frames is written.
+    Percentage(u32),
+    /// The given number of bytes of padding is written. The space of an existing tag is only
+    /// reused if at least this much padding remains, so that there is always room for the tag
+    /// to grow.
+    AtLeast(usize),
+}
+
+impl PaddingPolicy {
+    /// Returns the amount of padding to write after frames of the given size.
+    fn padding(self, frames_size: usize) -> usize {
+        match self {
+            PaddingPolicy::None => 0,
+            PaddingPolicy::Fixed(padding) | PaddingPolicy::AtLeast(padding) => padding,
+            PaddingPolicy::Percentage(percentage) => {
+                (frames_size as u64 * u64::from(percentage) / 100) as usize
             }
-            None => break Ok(tag),
         }
     }
 }
 
+/// The ID of the chunk that holds the tag in WAV, AIFF and DSDIFF files, see
+/// [`Encoder::chunk_id`].
+///
+/// Chunk IDs are matched case insensitively when reading, so tags in chunks of any of these IDs
+/// are found regardless of this setting.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ChunkId {
+    /// The ID of an existing chunk is kept, new chunks are written as `ID3 `.
+    #[default]
+    Preserve,
+    /// `ID3 `, which is the most common.
+    Id3,
+    /// `id3 `, as written by some Windows software.
+    Id3Lowercase,
+    /// `ID32`, as written by some older software.
+    Id32,
+}
+
+impl ChunkId {
+    /// Returns the ID to write, or `None` if the existing one must be kept.
+    pub(crate) fn tag(self) -> Option<&'static [u8; 4]> {
+        match self {
+            ChunkId::Preserve => None,
+            ChunkId::Id3 => Some(b"ID3 "),
+            ChunkId::Id3Lowercase => Some(b"id3 "),
+            ChunkId::Id32 => Some(b"ID32"),
+        }
+    }
+}
+
+/// Where the tag is written in files that store it as a header, like MP3 files, see
+/// [`Encoder::location`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Placement {
+    /// The tag is written to the start of the file.
+    #[default]
+    Prepend,
+    /// The tag is appended to the end of the file, before any ID3v1 tag. Appended tags are
+    /// written with a footer, which requires ID3v2.4.
+    Append,
+}
+
 /// The `Encoder` may be used to encode tags with custom settings.
 #[derive(Clone, Debug)]
 pub struct Encoder {
     version: Version,
     unsynchronisation: bool,
+    auto_unsynchronisation: bool,
     compression: bool,
     file_altered: bool,
-    padding: Option<usize>,
+    mark_file_dependent: bool,
+    ufid_policy: UfidPolicy,
+    padding: PaddingPolicy,
+    write_empty: bool,
+    convert_legacy_frames: bool,
+    footer: bool,
+    crc: bool,
+    enforce_restrictions: bool,
+    compression_threshold: usize,
+    encoding_policy: EncodingPolicy,
+    atomic: bool,
+    preserve_mtime: bool,
+    chunk_id: ChunkId,
+    sync_riff_info: bool,
+    location: Placement,
 }
 
 impl Encoder {
@@ -317,208 +1196,1722 @@ impl Encoder {
     ///
     /// * [`Version`] is ID3v2.4
     /// * Unsynchronization is disabled due to compatibility issues
-    /// * No compression
+    /// * No compression, frames of at least 1024 bytes are compressed when enabled
     /// * File is not marked as altered
+    /// * No padding
+    /// * UFID frames that violate the specification are written with a warning
+    /// * Tags without frames are not written to files
+    /// * ID3v2.3 RVAD and EQUA frames are converted when encoding ID3v2.4
+    /// * No footer
+    /// * No CRC
+    /// * Tag restrictions are not enforced
+    /// * Text is written in the default encoding of the version, UTF-16 for ID3v2.2 and ID3v2.3
+    ///   and UTF-8 for ID3v2.4
+    /// * Files are modified in place
+    /// * The modification time of files is updated
+    /// * The ID of an existing ID3 chunk is kept, new chunks are written as `ID3 `
+    /// * The INFO list of WAV files is left as is
+    /// * Tags are written to the start of MP3 files
     pub fn new() -> Self {
         Self {
             version: Version::Id3v24,
             unsynchronisation: false,
+            auto_unsynchronisation: false,
             compression: false,
             file_altered: false,
-            padding: None,
+            mark_file_dependent: false,
+            ufid_policy: UfidPolicy::Warn,
+            padding: PaddingPolicy::None,
+            write_empty: false,
+            convert_legacy_frames: true,
+            footer: false,
+            crc: false,
+            enforce_restrictions: false,
+            compression_threshold: 1024,
+            encoding_policy: EncodingPolicy::Default,
+            atomic: false,
+            preserve_mtime: false,
+            chunk_id: ChunkId::Preserve,
+            sync_riff_info: false,
+            location: Placement::Prepend,
         }
     }
 
-    /// Sets the padding that is written after the tag.
+    /// Sets whether a tag without frames is written to files.
     ///
-    /// Should be only used when writing to a MP3 file
-    pub fn padding(mut self, padding: usize) -> Self {
-        self.padding = Some(padding);
+    /// By default, writing a tag without frames to an MP3 file removes any existing tag instead
+    /// of writing a tag header without content. WAV and AIFF files always get an ID3 chunk.
+    pub fn write_empty(mut self, write_empty: bool) -> Self {
+        self.write_empty = write_empty;
         self
     }
 
-    /// Sets the ID3 version.
-    pub fn version(mut self, version: Version) -> Self {
-        self.version = version;
+    /// Sets whether the ID3v2.3 relative volume adjustment (RVAD) and equalisation (EQUA) frames
+    /// are converted to their ID3v2.4 replacements RVA2 and EQU2 when encoding an ID3v2.4 tag.
+    ///
+    /// Enabled by default. When disabled, the frames are written as is, which ID3v2.4 readers may
+    /// not understand.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::{ChannelType, LegacyChannelVolume, LegacyVolumeAdjustment};
+    /// use id3::{Encoder, Tag, TagLike, Version};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(LegacyVolumeAdjustment {
+    ///     bits_used: 16,
+    ///     adjustments: vec![
+    ///         LegacyChannelVolume {
+    ///             channel_type: ChannelType::FrontRight,
+    ///             volume_change: 0,
+    ///             peak_volume: 0,
+    ///         },
+    ///         LegacyChannelVolume {
+    ///             channel_type: ChannelType::FrontLeft,
+    ///             volume_change: 0,
+    ///             peak_volume: 0,
+    ///         },
+    ///     ],
+    /// });
+    ///
+    /// let mut buffer = Vec::new();
+    /// Encoder::new()
+    ///     .version(Version::Id3v24)
+    ///     .encode(&tag, &mut buffer)?;
+    /// let tag = Tag::read_from2(std::io::Cursor::new(&buffer))?;
+    /// assert!(tag.get("RVAD").is_none());
+    /// assert_eq!(tag.relative_volume_adjustments().count(), 1);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn convert_legacy_frames(mut self, convert_legacy_frames: bool) -> Self {
+        self.convert_legacy_frames = convert_legacy_frames;
         self
     }
 
-    /// Enables or disables the unsynchronisation scheme.
+    /// Enables or disables writing a footer after the tag.
     ///
-    /// This avoids patterns that resemble MP3-frame headers from being
-    /// encoded. If you are encoding to MP3 files and wish to be compatible
-    /// with very old tools, you probably want this enabled.
-    pub fn unsynchronisation(mut self, unsynchronisation: bool) -> Self {
-        self.unsynchronisation = unsynchronisation;
+    /// The footer is a copy of the header that allows the tag to be found when scanning a file
+    /// from the end, which is required for tags that are appended to a file. Footers only exist
+    /// in ID3v2.4, this option has no effect on other versions. A tag with a footer may not have
+    /// padding, so any padding set with [`Encoder::padding`] is not written.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Encoder, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    ///
+    /// let mut buffer = vec![0xff, 0xfb, 0x90, 0x00];
+    /// Encoder::new().footer(true).encode(&tag, &mut buffer)?;
+    /// assert_eq!(&buffer[buffer.len() - 10..buffer.len() - 7], b"3DI");
+    ///
+    /// let tag = Tag::read_from_end(std::io::Cursor::new(&buffer))?;
+    /// assert_eq!(tag.title(), Some("Title"));
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn footer(mut self, footer: bool) -> Self {
+        self.footer = footer;
         self
     }
 
-    /// Enables or disables compression.
-    pub fn compression(mut self, compression: bool) -> Self {
-        self.compression = compression;
+    /// Enables or disables writing a CRC-32 of the tag data in the extended header.
+    ///
+    /// The CRC allows readers to detect corruption of the tag. It is verified when decoding and a
+    /// mismatch is reported as a [`Warning`]. ID3v2.2 does not support this and is written without
+    /// CRC.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Encoder, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    ///
+    /// let mut buffer = Vec::new();
+    /// Encoder::new().write_crc(true).encode(&tag, &mut buffer)?;
+    /// // Corrupt the title.
+    /// let len = buffer.len();
+    /// buffer[len - 1] = b'X';
+    ///
+    /// let (tag, warnings) = Tag::read_with_warnings(std::io::Cursor::new(&buffer))?;
+    /// assert_eq!(tag.title(), Some("TitlX"));
+    /// assert_eq!(warnings.len(), 1);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn write_crc(mut self, crc: bool) -> Self {
+        self.crc = crc;
         self
     }
 
-    /// Informs the encoder whether the file this tag belongs to has been changed.
+    /// Enables or disables enforcing the [`TagRestrictions`] of the tag.
     ///
-    /// This subsequently discards any tags that have their File Alter Preservation bits set and
-    /// that have a relation to the file contents:
+    /// When enabled, encoding an ID3v2.4 tag that violates its restrictions fails with
+    /// [`ErrorKind::InvalidInput`]. Image dimensions are only checked if the `picture-info`
+    /// feature is enabled.
     ///
-    ///   AENC, ETCO, EQUA, MLLT, POSS, SYLT, SYTC, RVAD, TENC, TLEN, TSIZ
-    pub fn file_altered(mut self, file_altered: bool) -> Self {
-        self.file_altered = file_altered;
+    /// # Example
+    /// ```
+    /// use id3::{Encoder, Tag, TagLike, TagRestrictions, TextSizeRestriction};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("A title that is longer than thirty characters");
+    /// tag.set_restrictions(Some(TagRestrictions {
+    ///     text_size: TextSizeRestriction::Max30Chars,
+    ///     ..TagRestrictions::default()
+    /// }));
+    ///
+    /// assert!(Encoder::new().encode(&tag, Vec::new()).is_ok());
+    /// assert!(Encoder::new()
+    ///     .enforce_restrictions(true)
+    ///     .encode(&tag, Vec::new())
+    ///     .is_err());
+    /// ```
+    pub fn enforce_restrictions(mut self, enforce_restrictions: bool) -> Self {
+        self.enforce_restrictions = enforce_restrictions;
         self
     }
 
-    /// Encodes the specified [`Tag`] using the settings set in the [`Encoder`].
+    /// Sets the padding that is written after the tag, this is a shorthand for
+    /// [`Encoder::padding_policy`] with [`PaddingPolicy::Fixed`].
     ///
-    /// Note that the plain tag is written, regardless of the original contents. To safely encode a
-    /// tag to an MP3 file, use [`Encoder::encode_to_path`].
-    pub fn encode(&self, tag: &Tag, mut writer: impl io::Write) -> crate::Result<()> {
-        // remove frames which have the flags indicating they should be removed
-        let saved_frames = tag
-            .frames()
-            // Assert that by encoding, we are changing the tag. If the Tag Alter Preservation bit
-            // is set, discard the frame.
-            .filter(|frame| !frame.tag_alter_preservation())
-            // If the file this tag belongs to is updated, check for the File Alter Preservation
-            // bit.
-            .filter(|frame| !self.file_altered || !frame.file_alter_preservation())
-            // Check whether this frame is part of the set of frames that should always be
-            // discarded when the file is changed.
-            .filter(|frame| !self.file_altered || !DEFAULT_FILE_DISCARD.contains(&frame.id()));
+    /// Should be only used when writing to a MP3 file
+    pub fn padding(self, padding: usize) -> Self {
+        self.padding_policy(PaddingPolicy::Fixed(padding))
+    }
+
+    /// Sets how much padding is written after the tag.
+    ///
+    /// Should be only used when writing to a MP3 file. A tag with a footer may not have padding,
+    /// see [`Encoder::footer`].
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Encoder, PaddingPolicy, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    ///
+    /// let mut buffer = Vec::new();
+    /// Encoder::new()
+    ///     .padding_policy(PaddingPolicy::Percentage(50))
+    ///     .encode(&tag, &mut buffer)?;
+    /// // The TIT2 frame takes 16 bytes.
+    /// assert_eq!(buffer.len(), 10 + 16 + 8);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn padding_policy(mut self, padding_policy: PaddingPolicy) -> Self {
+        self.padding = padding_policy;
+        self
+    }
+
+    /// Sets whether [`Encoder::write_to_path`] writes atomically.
+    ///
+    /// When enabled, the tag is written to a copy of the file in the same directory, which then
+    /// replaces the original file by renaming it. If writing fails or the process is interrupted,
+    /// the original file is left intact. A symbolic link is followed, so the file it points to is
+    /// replaced.
+    ///
+    /// As the original file is replaced with a new one, it is not the same inode afterwards: hard
+    /// links to the original keep referring to the old data, and the new file is owned by the
+    /// user running the process. The copy retains the permissions of the original file, but not
+    /// its ownership or other metadata.
+    ///
+    /// Writing atomically always copies the whole file, even if the tag could be updated in
+    /// place.
+    pub fn atomic(mut self, atomic: bool) -> Self {
+        self.atomic = atomic;
+        self
+    }
+
+    /// Sets whether [`Encoder::write_to_path`] restores the modification time of the file after
+    /// writing the tag.
+    ///
+    /// Media libraries often treat a changed modification time as a changed file. Only the
+    /// modification time is restored, the access time is left as is. Restoring the modification
+    /// time is only supported on Unix platforms, writing fails with
+    /// [`std::io::ErrorKind::Unsupported`] on other platforms.
+    pub fn preserve_mtime(mut self, preserve_mtime: bool) -> Self {
+        self.preserve_mtime = preserve_mtime;
+        self
+    }
+
+    /// Sets the ID of the chunk that holds the tag when writing to WAV, AIFF and DSDIFF files.
+    ///
+    /// Some players only recognize one of the IDs that are in use. By default, the ID of an
+    /// existing chunk is kept.
+    pub fn chunk_id(mut self, chunk_id: ChunkId) -> Self {
+        self.chunk_id = chunk_id;
+        self
+    }
+
+    /// Sets whether the INFO list of WAV files is updated to match the tag when writing.
+    ///
+    /// Windows Explorer and many other programs only show the INFO list. The entries that map to
+    /// ID3 frames are replaced, other entries are kept. The INFO list is removed if no entries
+    /// remain. See [`riff_info::Info`](crate::riff_info::Info).
+    pub fn sync_riff_info(mut self, sync_riff_info: bool) -> Self {
+        self.sync_riff_info = sync_riff_info;
+        self
+    }
+
+    /// Sets where [`Encoder::write_to_file`] writes the tag in MP3 and other files that store the
+    /// tag as a header. Chunk based formats like WAV and AIFF, and DSF files, are not affected.
+    ///
+    /// When appending, a tag at the start of the file is removed, so readers that look at the
+    /// start first don't find a stale tag. Appending fails with [`ErrorKind::InvalidInput`] if
+    /// the version is not ID3v2.4.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Encoder, Placement, Tag, TagLike};
+    /// use std::io::Cursor;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    ///
+    /// let mut file = Cursor::new(vec![0xff, 0xfb, 0x90, 0x00]);
+    /// Encoder::new()
+    ///     .location(Placement::Append)
+    ///     .write_to_file(&tag, &mut file)?;
+    /// assert_eq!(&file.get_ref()[..4], &[0xff, 0xfb, 0x90, 0x00]);
+    ///
+    /// file.set_position(0);
+    /// assert_eq!(Tag::read_from2(&mut file)?.title(), Some("Title"));
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn location(mut self, location: Placement) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// Sets the ID3 version.
+    pub fn version(mut self, version: Version) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Enables or disables the unsynchronisation scheme.
+    ///
+    /// This avoids patterns that resemble MP3-frame headers from being
+    /// encoded. If you are encoding to MP3 files and wish to be compatible
+    /// with very old tools, you probably want this enabled.
+    pub fn unsynchronisation(mut self, unsynchronisation: bool) -> Self {
+        self.unsynchronisation = unsynchronisation;
+        self
+    }
+
+    /// Enables or disables automatic unsynchronisation.
+    ///
+    /// Unsynchronisation is only applied where the tag contains patterns that resemble MP3-frame
+    /// headers. For ID3v2.4, this is decided for each frame separately, so frames without such
+    /// patterns are left untouched. For older versions, unsynchronisation applies to the whole
+    /// tag.
+    ///
+    /// Has no effect if unsynchronisation is enabled using [`Encoder::unsynchronisation`].
+    pub fn auto_unsynchronisation(mut self, auto_unsynchronisation: bool) -> Self {
+        self.auto_unsynchronisation = auto_unsynchronisation;
+        self
+    }
+
+    /// Enables or disables compression.
+    ///
+    /// For ID3v2.3 and ID3v2.4, the content of each frame that is at least as large as the
+    /// [compression threshold](Encoder::compression_threshold) is compressed using zlib. For
+    /// ID3v2.2, the compression bit of the tag header is set.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Encoder, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// tag.add_frame(id3::frame::Lyrics {
+    ///     lang: "eng".to_string(),
+    ///     description: String::new(),
+    ///     text: "La la la. ".repeat(1000),
+    /// });
+    ///
+    /// let mut uncompressed = Vec::new();
+    /// Encoder::new().encode(&tag, &mut uncompressed)?;
+    /// let mut compressed = Vec::new();
+    /// Encoder::new().compression(true).encode(&tag, &mut compressed)?;
+    /// assert!(compressed.len() < uncompressed.len() / 10);
+    ///
+    /// assert_eq!(Tag::from_bytes(&compressed)?, tag);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the minimum size in bytes of the content of a frame for it to be compressed when
+    /// [compression](Encoder::compression) is enabled. Smaller frames do not benefit from
+    /// compression and are written uncompressed.
+    ///
+    /// Defaults to 1024.
+    pub fn compression_threshold(mut self, compression_threshold: usize) -> Self {
+        self.compression_threshold = compression_threshold;
+        self
+    }
+
+    /// Sets the encoding in which the text of the frames is written, this is a shorthand for
+    /// [`Encoder::encoding_policy`] with [`EncodingPolicy::Always`].
+    ///
+    /// Frames that have an encoding of their own keep it, see [`Frame::set_encoding`]. A frame
+    /// falls back to the default encoding of the version if its text can not be represented in
+    /// the chosen encoding or if the encoding is not supported by the version: UTF-16BE and UTF-8
+    /// are only available in ID3v2.4.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Encoder, Encoding, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// tag.set_artist("Motörhead");
+    /// tag.set_album("東京");
+    ///
+    /// let mut buffer = Vec::new();
+    /// Encoder::new()
+    ///     .text_encoding(Encoding::Latin1)
+    ///     .encode(&tag, &mut buffer)?;
+    /// assert_eq!(Tag::read_from2(std::io::Cursor::new(&buffer))?, tag);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn text_encoding(self, text_encoding: Encoding) -> Self {
+        self.encoding_policy(EncodingPolicy::Always(text_encoding))
+    }
+
+    /// Sets how the encoding of the text of the frames is chosen.
+    ///
+    /// Frames that have an encoding of their own keep it, see [`Frame::set_encoding`].
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Encoder, EncodingPolicy, Tag, TagLike, Version};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// tag.set_album("東京");
+    ///
+    /// let mut default = Vec::new();
+    /// Encoder::new()
+    ///     .version(Version::Id3v23)
+    ///     .encode(&tag, &mut default)?;
+    /// let mut smallest = Vec::new();
+    /// Encoder::new()
+    ///     .version(Version::Id3v23)
+    ///     .encoding_policy(EncodingPolicy::Smallest)
+    ///     .encode(&tag, &mut smallest)?;
+    /// assert!(smallest.len() < default.len());
+    /// assert_eq!(Tag::read_from2(std::io::Cursor::new(&smallest))?, tag);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn encoding_policy(mut self, encoding_policy: EncodingPolicy) -> Self {
+        self.encoding_policy = encoding_policy;
+        self
+    }
+
+    /// Informs the encoder whether the file this tag belongs to has been changed.
+    ///
+    /// This subsequently discards unknown frames that have their File Alter Preservation bit set
+    /// and the frames that have a relation to the file contents:
+    ///
+    ///   AENC, ETCO, EQUA, MLLT, POSS, SYLT, SYTC, RVAD, TENC, TLEN, TSIZ
+    pub fn file_altered(mut self, file_altered: bool) -> Self {
+        self.file_altered = file_altered;
+        self
+    }
+
+    /// Sets how UFID frames that violate the specification are handled.
+    ///
+    /// The identifier of a UFID frame may be at most 64 bytes long and its owner must not be
+    /// empty.
+    pub fn ufid_policy(mut self, ufid_policy: UfidPolicy) -> Self {
+        self.ufid_policy = ufid_policy;
+        self
+    }
+
+    /// Applies an [`AlterProfile`], which sets [`Encoder::file_altered`] and decides which frames
+    /// get their File Alter Preservation flag set.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{AlterProfile, Encoder, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// tag.set_duration(1337);
+    ///
+    /// let mut buffer = Vec::new();
+    /// Encoder::new()
+    ///     .profile(AlterProfile::MetadataEdit)
+    ///     .encode(&tag, &mut buffer)?;
+    /// let tag = Tag::read_from2(std::io::Cursor::new(&buffer))?;
+    /// assert!(tag.get("TLEN").unwrap().file_alter_preservation());
+    ///
+    /// let mut buffer = Vec::new();
+    /// Encoder::new()
+    ///     .profile(AlterProfile::Transcode)
+    ///     .encode(&tag, &mut buffer)?;
+    /// let tag = Tag::read_from2(std::io::Cursor::new(&buffer))?;
+    /// assert_eq!(tag.duration(), None);
+    /// assert_eq!(tag.title(), Some("Title"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn profile(mut self, profile: AlterProfile) -> Self {
+        match profile {
+            AlterProfile::MetadataEdit => {
+                self.file_altered = false;
+                self.mark_file_dependent = true;
+            }
+            AlterProfile::Transcode => {
+                self.file_altered = true;
+                self.mark_file_dependent = false;
+            }
+        }
+        self
+    }
+
+    /// Encodes the specified [`Tag`] using the settings set in the [`Encoder`].
+    ///
+    /// Note that the plain tag is written, regardless of the original contents. To safely encode a
+    /// tag to an MP3 file, use [`Encoder::encode_to_path`].
+    ///
+    /// The header and frames are written as they are encoded, the tag as a whole is never held in
+    /// memory. Use [`Encoder::encoded_size`] to learn the size of the tag in advance.
+    pub fn encode(&self, tag: &Tag, writer: impl io::Write) -> crate::Result<()> {
+        self.encode_with_warnings(tag, writer, &mut Vec::new())
+    }
+
+    /// Encodes a tag like [`Encoder::encode`], collecting any issues with the frames that did not
+    /// prevent encoding into `warnings`.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::UniqueFileIdentifier;
+    /// use id3::{Encoder, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(UniqueFileIdentifier {
+    ///     owner_identifier: "example.com".to_string(),
+    ///     identifier: vec![b'x'; 100],
+    /// });
+    ///
+    /// let mut warnings = Vec::new();
+    /// Encoder::new().encode_with_warnings(&tag, Vec::new(), &mut warnings)?;
+    /// assert_eq!(warnings.len(), 1);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn encode_with_warnings(
+        &self,
+        tag: &Tag,
+        writer: impl io::Write,
+        warnings: &mut Vec<Warning>,
+    ) -> crate::Result<()> {
+        self.encode_tag(tag, writer, warnings).map(|_| ())
+    }
+
+    /// Encodes a tag like [`Encoder::encode_with_warnings`] and returns the amount of padding
+    /// that was written.
+    fn encode_tag(
+        &self,
+        tag: &Tag,
+        mut writer: impl io::Write,
+        warnings: &mut Vec<Warning>,
+    ) -> crate::Result<usize> {
+        let prepared = self.prepare(tag, warnings)?;
+        let mut header = [0; 10];
+        header[0..3].copy_from_slice(b"ID3");
+        header[3] = self.version.minor();
+        header[5] = prepared.flags.bits();
+        BigEndian::write_u32(
+            &mut header[6..10],
+            unsynch::encode_u32(prepared.tag_size as u32),
+        );
+        writer.write_all(&header)?;
+        self.write_data(&prepared, &mut writer)?;
+
+        io::copy(
+            &mut io::repeat(0).take(prepared.padding_size as u64),
+            &mut writer,
+        )?;
+        if prepared.flags.contains(Flags::FOOTER) {
+            // The footer is a copy of the header with a different identifier.
+            header[0..3].copy_from_slice(b"3DI");
+            writer.write_all(&header)?;
+        }
+        Ok(prepared.padding_size)
+    }
+
+    /// Returns the exact number of bytes [`Encoder::encode`] writes for the specified [`Tag`],
+    /// including the header and footer.
+    ///
+    /// Frames are encoded and written one at a time, so combined with this method a tag can be
+    /// streamed to a writer that needs to know the size up front, like an HTTP request body,
+    /// without holding the whole encoded tag in memory.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Encoder, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    ///
+    /// let encoder = Encoder::new().padding(64);
+    /// let size = encoder.encoded_size(&tag)?;
+    /// let mut buf = Vec::new();
+    /// encoder.encode(&tag, &mut buf)?;
+    /// assert_eq!(buf.len() as u64, size);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn encoded_size(&self, tag: &Tag) -> crate::Result<u64> {
+        let prepared = self.prepare(tag, &mut Vec::new())?;
+        let footer_size = if prepared.flags.contains(Flags::FOOTER) {
+            10
+        } else {
+            0
+        };
+        Ok(10 + prepared.tag_size as u64 + footer_size)
+    }
+
+    /// Validates the frames of a tag and measures the encoded tag without keeping the encoded
+    /// frames in memory.
+    fn prepare<'a>(
+        &self,
+        tag: &'a Tag,
+        warnings: &mut Vec<Warning>,
+    ) -> crate::Result<PreparedTag<'a>> {
+        // remove frames which have the flags indicating they should be removed
+        let saved_frames = tag
+            .frames()
+            // Assert that by encoding, we are changing the tag. If the Tag Alter Preservation bit
+            // is set, discard the frame. The flag only applies to frames that are not understood.
+            .filter(|frame| !(is_unknown(frame) && frame.tag_alter_preservation()))
+            // If the file this tag belongs to is updated, check for the File Alter Preservation
+            // bit, which likewise only applies to frames that are not understood.
+            .filter(|frame| {
+                !(self.file_altered && is_unknown(frame) && frame.file_alter_preservation())
+            })
+            // Check whether this frame is part of the set of frames that should always be
+            // discarded when the file is changed.
+            .filter(|frame| !self.file_altered || !DEFAULT_FILE_DISCARD.contains(&frame.id()));
 
         let mut flags = Flags::empty();
         flags.set(Flags::UNSYNCHRONISATION, self.unsynchronisation);
         if self.version == Version::Id3v22 {
             flags.set(Flags::COMPRESSION, self.compression);
         }
+        let footer = self.footer && self.version == Version::Id3v24;
+        flags.set(Flags::FOOTER, footer);
+        let auto_unsynchronisation = self.auto_unsynchronisation && !self.unsynchronisation;
+        let restrictions = tag
+            .restrictions()
+            .filter(|_| self.enforce_restrictions && self.version == Version::Id3v24);
+        let mut num_frames = 0;
 
-        let mut frame_data = Vec::new();
+        let mut frames = Vec::new();
+        let mut measure = Measure::new(self.crc);
+        let mut frame_buf = Vec::new();
+        let mut has_mcdi = false;
         for frame in saved_frames {
-            frame.validate()?;
-            frame::encode(&mut frame_data, frame, self.version, self.unsynchronisation)?;
+            // ID3v2.2 frames without an equivalent in later versions can only be written to
+            // ID3v2.2 tags.
+            if self.version != Version::Id3v22 && frame.id().len() != 4 {
+                warnings.push(
+                    Warning::new("frame can only be written to ID3v2.2 tags and is skipped")
+                        .with_frame_id(frame.id()),
+                );
+                continue;
+            }
+            let mut frame = frame.validate(self.ufid_policy, warnings)?;
+            if frame.id() == "MCDI" {
+                if has_mcdi {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "a tag may only contain one MCDI frame",
+                    ));
+                }
+                has_mcdi = true;
+            }
+            if self.mark_file_dependent
+                && !frame.file_alter_preservation()
+                && DEFAULT_FILE_DISCARD.contains(&frame.id())
+            {
+                frame.to_mut().set_file_alter_preservation(true);
+            }
+            if self.convert_legacy_frames && self.version == Version::Id3v24 {
+                if let Some(upgraded) = frame.upgrade_legacy() {
+                    frame = Cow::Owned(upgraded);
+                }
+            }
+            if frame.encoding().is_none() {
+                if let Some(encoding) = self.encoding_policy.encoding_for(&frame, self.version) {
+                    frame = Cow::Owned(frame.into_owned().set_encoding(Some(encoding)));
+                }
+            }
+            if let Some(restrictions) = restrictions {
+                check_frame_restrictions(&frame, restrictions)?;
+            }
+            num_frames += 1;
+            let mut unsynchronisation = self.unsynchronisation;
+            self.encode_frame(&mut frame_buf, &frame, unsynchronisation)?;
+            if auto_unsynchronisation
+                && self.version == Version::Id3v24
+                && unsynch::has_false_sync(&frame_buf[10..])
+            {
+                // Only unsynchronise the frames that need it, skipping the frame header.
+                unsynchronisation = true;
+                self.encode_frame(&mut frame_buf, &frame, true)?;
+            }
+            measure.write_all(&frame_buf)?;
+            frames.push((frame, unsynchronisation));
+        }
+        let padding_size = if footer {
+            0
+        } else {
+            self.padding.padding(measure.len)
+        };
+        let mut ext = ExtHeader {
+            crc: None,
+            padding_size: padding_size as u32,
+            restrictions: tag.restrictions(),
+            is_update: tag.is_update(),
+        };
+        if let Some(mut crc) = measure.crc.take() {
+            if self.version == Version::Id3v24 {
+                crc.update(&vec![0; padding_size]);
+            }
+            ext.crc = Some(crc.sum());
+        }
+        let mut ext_header = Vec::new();
+        if ext.is_needed(self.version) {
+            ext_header = ext.encode(self.version);
+            flags.insert(Flags::EXTENDED_HEADER);
         }
         // In ID3v2.2/ID3v2.3, Unsynchronization is applied to the whole tag data at once, not for
         // each frame separately.
-        if self.unsynchronisation {
-            match self.version {
-                Version::Id3v22 | Version::Id3v23 => unsynch::encode_vec(&mut frame_data),
-                Version::Id3v24 => {}
-            };
+        if auto_unsynchronisation && self.version != Version::Id3v24 {
+            let mut head = ext_header.clone();
+            head.extend(measure.first);
+            if unsynch::has_false_sync(&head) || measure.has_false_sync() {
+                flags.insert(Flags::UNSYNCHRONISATION);
+            }
         }
-        let tag_size = frame_data.len() + self.padding.unwrap_or(0);
-        writer.write_all(b"ID3")?;
-        writer.write_all(&[self.version.minor(), 0])?;
-        writer.write_u8(flags.bits())?;
-        writer.write_u32::<BigEndian>(unsynch::encode_u32(tag_size as u32))?;
-        writer.write_all(&frame_data[..])?;
-
-        if let Some(padding) = self.padding {
-            writer.write_all(&vec![0; padding])?;
+        let mut prepared = PreparedTag {
+            frames,
+            flags,
+            ext_header,
+            padding_size,
+            tag_size: 0,
+        };
+        let data_size = if prepared.unsynchronise_data(self.version) {
+            // The size after unsynchronisation can only be known by encoding the data once more.
+            let mut measure = Measure::new(false);
+            self.write_data(&prepared, &mut measure)?;
+            measure.len
+        } else {
+            prepared.ext_header.len() + measure.len
+        };
+        let tag_size = data_size + padding_size;
+        if let Some(restrictions) = restrictions {
+            let max_frames = restrictions.tag_size.max_frames();
+            if num_frames > max_frames {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "the tag restrictions allow at most {} frames, the tag has {}",
+                        max_frames, num_frames
+                    ),
+                ));
+            }
+            let max_size = restrictions.tag_size.max_size();
+            let total_size = 10 + tag_size as u64 + if footer { 10 } else { 0 };
+            if total_size > max_size {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "the tag restrictions allow at most {} bytes, the tag has {}",
+                        max_size, total_size
+                    ),
+                ));
+            }
         }
+        prepared.tag_size = tag_size;
+        Ok(prepared)
+    }
+
+    /// Encodes a single frame into the buffer, replacing its contents.
+    fn encode_frame(
+        &self,
+        buf: &mut Vec<u8>,
+        frame: &Frame,
+        unsynchronisation: bool,
+    ) -> crate::Result<()> {
+        let compression_threshold = Some(self.compression_threshold).filter(|_| self.compression);
+        buf.clear();
+        frame::encode_with_compression(
+            buf,
+            frame,
+            self.version,
+            unsynchronisation,
+            compression_threshold,
+        )?;
         Ok(())
     }
 
-    /// Encodes a [`Tag`] and replaces any existing tag in the file.
-    pub fn write_to_file(&self, tag: &Tag, mut file: impl StorageFile) -> crate::Result<()> {
-        let mut probe = [0; 12];
+    /// Writes the extended header and the frames of a prepared tag, one frame at a time.
+    fn write_data(&self, prepared: &PreparedTag, writer: impl io::Write) -> crate::Result<()> {
+        fn write_frames(
+            encoder: &Encoder,
+            prepared: &PreparedTag,
+            mut writer: impl io::Write,
+        ) -> crate::Result<()> {
+            writer.write_all(&prepared.ext_header)?;
+            let mut frame_buf = Vec::new();
+            for (frame, unsynchronisation) in &prepared.frames {
+                encoder.encode_frame(&mut frame_buf, frame, *unsynchronisation)?;
+                writer.write_all(&frame_buf)?;
+            }
+            Ok(())
+        }
+
+        if prepared.unsynchronise_data(self.version) {
+            let mut writer = unsynch::Writer::new(writer);
+            write_frames(self, prepared, &mut writer)?;
+            writer.finish()?;
+            Ok(())
+        } else {
+            write_frames(self, prepared, writer)
+        }
+    }
+
+    /// Encodes a [`Tag`] and replaces any existing tag in the file. Tags that are stacked directly
+    /// after an existing tag at the start of the file are replaced as well.
+    ///
+    /// If the tag has no frames, any existing tag is removed instead unless
+    /// [`Encoder::write_empty`] is enabled.
+    pub fn write_to_file(
+        &self,
+        tag: &Tag,
+        mut file: impl StorageFile,
+    ) -> crate::Result<WriteOutcome> {
+        let mut probe = [0; 16];
         let nread = file.read(&mut probe)?;
         file.seek(io::SeekFrom::Start(0))?;
         let storage_format = Format::magic(&probe[..nread]);
 
-        match storage_format {
+        let location = match storage_format {
             Some(Format::Aiff) => {
-                chunk::write_id3_chunk_file::<chunk::AiffFormat>(file, tag, self.version)?;
+                return chunk::write_id3_chunk_file::<chunk::AiffFormat>(
+                    file,
+                    tag,
+                    self.version,
+                    self.chunk_id,
+                );
             }
             Some(Format::Wav) => {
-                chunk::write_id3_chunk_file::<chunk::WavFormat>(file, tag, self.version)?;
-            }
-            Some(Format::Header) => {
-                let location = locate_id3v2(&mut file)?;
-                let mut storage = PlainStorage::new(file, location);
-                let mut w = storage.writer()?;
-                self.encode(tag, &mut w)?;
-                w.flush()?;
+                let outcome = chunk::write_id3_chunk_file::<chunk::WavFormat>(
+                    &mut file,
+                    tag,
+                    self.version,
+                    self.chunk_id,
+                )?;
+                if self.sync_riff_info {
+                    riff_info::sync_file(&mut file, tag)?;
+                }
+                return Ok(outcome);
             }
-            None => {
-                let mut storage = PlainStorage::new(file, 0..0);
-                let mut w = storage.writer()?;
-                self.encode(tag, &mut w)?;
-                w.flush()?;
+            Some(Format::Dsdiff) => {
+                return chunk::write_id3_chunk_file::<chunk::DsdiffFormat>(
+                    file,
+                    tag,
+                    self.version,
+                    self.chunk_id,
+                );
             }
+            Some(Format::Dsf) => return self.write_to_dsf_file(tag, file),
+            Some(Format::Header) => locate_stacked_id3v2(&mut file)?,
+            None => 0..0,
         };
+        if self.location == Placement::Append {
+            return self.write_appended(tag, file, location);
+        }
 
-        Ok(())
+        let (buf, padding) = self.encode_for_region(tag, &location)?;
+        let mut storage = PlainStorage::new(file, location.clone());
+        let mut w = storage.writer()?;
+        w.write_all(&buf)?;
+        w.flush()?;
+
+        Ok(WriteOutcome {
+            bytes_written: buf.len() as u64,
+            rewritten: buf.len() as u64 != location.end - location.start,
+            padding,
+            v1_touched: false,
+        })
     }
 
-    /// Encodes a [`Tag`] and replaces any existing tag in the file.
-    #[deprecated(note = "Use write_to_file")]
-    pub fn encode_to_file(&self, tag: &Tag, file: &mut fs::File) -> crate::Result<()> {
-        self.write_to_file(tag, file)
+    /// Replaces the tag that is appended to the file, or appends one, and removes the tag at
+    /// `prepended`.
+    fn write_appended(
+        &self,
+        tag: &Tag,
+        mut file: impl StorageFile,
+        prepended: Range<u64>,
+    ) -> crate::Result<WriteOutcome> {
+        let location = locate_appended_id3v2(&mut file)?;
+        let buf = self.encode_appended(tag)?;
+        {
+            let mut storage = PlainStorage::new(&mut file, location.clone());
+            let mut w = storage.writer()?;
+            w.write_all(&buf)?;
+            w.flush()?;
+        }
+        // The data before the appended tag is not affected by the write above.
+        if !prepended.is_empty() {
+            let mut storage = PlainStorage::new(&mut file, prepended.clone());
+            storage.writer()?.flush()?;
+        }
+
+        Ok(WriteOutcome {
+            bytes_written: buf.len() as u64,
+            rewritten: buf.len() as u64 != location.end - location.start || !prepended.is_empty(),
+            padding: 0,
+            v1_touched: false,
+        })
     }
 
-    /// Encodes a [`Tag`] and replaces any existing tag in the file pointed to by the specified path.
-    pub fn write_to_path(&self, tag: &Tag, path: impl AsRef<Path>) -> crate::Result<()> {
-        let mut file = fs::OpenOptions::new().read(true).write(true).open(path)?;
-        self.write_to_file(tag, &mut file)?;
-        file.flush()?;
-        Ok(())
+    /// Encodes a tag to be appended to a file, which requires a footer.
+    fn encode_appended(&self, tag: &Tag) -> crate::Result<Vec<u8>> {
+        if self.version != Version::Id3v24 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "appended tags require a footer, which is only supported by ID3v2.4",
+            ));
+        }
+        let mut buf = Vec::new();
+        if !tag.is_empty() || self.write_empty {
+            self.clone()
+                .footer(true)
+                .padding_policy(PaddingPolicy::None)
+                .encode_tag(tag, &mut buf, &mut Vec::new())?;
+        }
+        Ok(buf)
     }
 
-    /// Encodes a [`Tag`] and replaces any existing tag in the file pointed to by the specified path.
-    #[deprecated(note = "Use write_to_path")]
-    pub fn encode_to_path(&self, tag: &Tag, path: impl AsRef<Path>) -> crate::Result<()> {
-        self.write_to_path(tag, path)
+    /// Replaces the tag at the end of a DSF file and updates the metadata pointer in its header.
+    fn write_to_dsf_file(
+        &self,
+        tag: &Tag,
+        mut file: impl StorageFile,
+    ) -> crate::Result<WriteOutcome> {
+        let (mut header, location) = storage::dsf::read_region(&mut file)?;
+        let (buf, padding) = self.encode_for_region(tag, &location)?;
+        {
+            let mut storage = PlainStorage::new(&mut file, location.clone());
+            let mut w = storage.writer()?;
+            w.write_all(&buf)?;
+            w.flush()?;
+        }
+        header.update(location.start, buf.len() as u64);
+        storage::dsf::write_header(&mut file, &header)?;
+
+        Ok(WriteOutcome {
+            bytes_written: buf.len() as u64,
+            rewritten: buf.len() as u64 != location.end - location.start,
+            padding,
+            v1_touched: false,
+        })
     }
-}
 
-impl Default for Encoder {
-    fn default() -> Self {
-        Self::new()
+    /// Encodes a tag that replaces the existing tag at `location`, returning the encoded bytes
+    /// and the amount of padding. An empty buffer is returned if the existing tag should be
+    /// removed.
+    fn encode_for_region(
+        &self,
+        tag: &Tag,
+        location: &Range<u64>,
+    ) -> crate::Result<(Vec<u8>, usize)> {
+        let mut buf = Vec::new();
+        let mut padding = 0;
+        if !tag.is_empty() || self.write_empty {
+            padding = self.encode_tag(tag, &mut buf, &mut Vec::new())?;
+            // If the tag fits in the space of the existing tag, the remaining space is filled
+            // with padding. Only the existing tag is overwritten and the data following it is
+            // not moved. A tag with a footer can not have padding.
+            let available = location.end - location.start;
+            let unpadded = (buf.len() - padding) as u64;
+            let min_padding = match self.padding {
+                PaddingPolicy::AtLeast(min_padding) => min_padding as u64,
+                _ => 0,
+            };
+            let footer = self.footer && self.version == Version::Id3v24;
+            if !footer && buf.len() as u64 != available && unpadded + min_padding <= available {
+                buf.clear();
+                padding = self
+                    .clone()
+                    .padding((available - unpadded) as usize)
+                    .encode_tag(tag, &mut buf, &mut Vec::new())?;
+            }
+        }
+        Ok((buf, padding))
     }
-}
 
-pub fn locate_id3v2(mut reader: impl io::Read + io::Seek) -> crate::Result<Range<u64>> {
-    let header = Header::decode(&mut reader)?;
+    /// Encodes the specified [`Tag`] via Tokio, like [`Encoder::encode`].
+    ///
+    /// The tag is encoded in memory before it is written.
+    #[cfg(feature = "tokio")]
+    pub async fn async_encode(
+        &self,
+        tag: &Tag,
+        mut writer: impl tokio::io::AsyncWrite + std::marker::Unpin,
+    ) -> crate::Result<()> {
+        use tokio::io::AsyncWriteExt;
 
-    let tag_size = header.tag_size();
-    reader.seek(io::SeekFrom::Start(tag_size))?;
-    let num_padding = reader
-        .bytes()
-        .take_while(|rs| rs.as_ref().map(|b| *b == 0x00).unwrap_or(false))
-        .count();
-    Ok(0..tag_size + num_padding as u64)
-}
+        let mut buf = Vec::new();
+        self.encode(tag, &mut buf)?;
+        writer.write_all(&buf).await?;
+        Ok(())
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::frame::{
-        Chapter, Content, EncapsulatedObject, Frame, MpegLocationLookupTable,
-        MpegLocationLookupTableReference, Picture, PictureType, Popularimeter, Private,
-        SynchronisedLyrics, SynchronisedLyricsType, TableOfContents, TimestampFormat,
-        UniqueFileIdentifier, Unknown,
-    };
-    use std::fs::{self};
-    use std::io::{self, Read};
+    /// Encodes a [`Tag`] and replaces any existing tag in the file via Tokio, like
+    /// [`Encoder::write_to_file`].
+    #[cfg(feature = "tokio")]
+    pub async fn async_write_to_file(
+        &self,
+        tag: &Tag,
+        mut file: impl AsyncStorageFile,
+    ) -> crate::Result<WriteOutcome> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
 
-    fn make_tag(version: Version) -> Tag {
-        let mut tag = Tag::new();
-        tag.set_title("Title");
-        tag.set_artist("Artist");
-        tag.set_genre("Genre");
-        tag.add_frame(Frame::with_content(
-            "TPE1",
-            Content::new_text_values(["artist 1", "artist 2", "artist 3"]),
-        ));
-        tag.set_duration(1337);
-        tag.add_frame(EncapsulatedObject {
-            mime_type: "Some Object".to_string(),
-            filename: "application/octet-stream".to_string(),
-            description: "".to_string(),
-            data: b"\xC0\xFF\xEE\x00".to_vec(),
-        });
+        let mut probe = [0; 16];
+        let nread = file.read(&mut probe).await?;
+        file.seek(io::SeekFrom::Start(0)).await?;
+        let storage_format = Format::magic(&probe[..nread]);
+
+        let location = match storage_format {
+            Some(Format::Aiff) => {
+                return chunk::async_write_id3_chunk_file::<chunk::AiffFormat>(
+                    file,
+                    tag,
+                    self.version,
+                    self.chunk_id,
+                )
+                .await;
+            }
+            Some(Format::Wav) => {
+                let outcome = chunk::async_write_id3_chunk_file::<chunk::WavFormat>(
+                    &mut file,
+                    tag,
+                    self.version,
+                    self.chunk_id,
+                )
+                .await?;
+                if self.sync_riff_info {
+                    riff_info::async_sync_file(&mut file, tag).await?;
+                }
+                return Ok(outcome);
+            }
+            Some(Format::Dsdiff) => {
+                return chunk::async_write_id3_chunk_file::<chunk::DsdiffFormat>(
+                    file,
+                    tag,
+                    self.version,
+                    self.chunk_id,
+                )
+                .await;
+            }
+            Some(Format::Dsf) => return self.async_write_to_dsf_file(tag, file).await,
+            Some(Format::Header) => async_locate_id3v2(&mut file).await?,
+            None => 0..0,
+        };
+        if self.location == Placement::Append {
+            return self.async_write_appended(tag, file, location).await;
+        }
+
+        let (buf, padding) = self.encode_for_region(tag, &location)?;
+        AsyncPlainStorage::new(file, location.clone())
+            .write(&buf)
+            .await?;
+
+        Ok(WriteOutcome {
+            bytes_written: buf.len() as u64,
+            rewritten: buf.len() as u64 != location.end - location.start,
+            padding,
+            v1_touched: false,
+        })
+    }
+
+    /// Writes an appended tag via Tokio, like [`Encoder::write_appended`].
+    #[cfg(feature = "tokio")]
+    async fn async_write_appended(
+        &self,
+        tag: &Tag,
+        mut file: impl AsyncStorageFile,
+        prepended: Range<u64>,
+    ) -> crate::Result<WriteOutcome> {
+        let location = async_locate_appended_id3v2(&mut file).await?;
+        let buf = self.encode_appended(tag)?;
+        AsyncPlainStorage::new(&mut file, location.clone())
+            .write(&buf)
+            .await?;
+        if !prepended.is_empty() {
+            AsyncPlainStorage::new(&mut file, prepended.clone())
+                .write(&[])
+                .await?;
+        }
+
+        Ok(WriteOutcome {
+            bytes_written: buf.len() as u64,
+            rewritten: buf.len() as u64 != location.end - location.start || !prepended.is_empty(),
+            padding: 0,
+            v1_touched: false,
+        })
+    }
+
+    /// Replaces the tag at the end of a DSF file via Tokio, like [`Encoder::write_to_dsf_file`].
+    #[cfg(feature = "tokio")]
+    async fn async_write_to_dsf_file(
+        &self,
+        tag: &Tag,
+        mut file: impl AsyncStorageFile,
+    ) -> crate::Result<WriteOutcome> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+
+        let mut header = [0; storage::dsf::HEADER_LEN];
+        file.read_exact(&mut header).await?;
+        let mut header = storage::dsf::DsdHeader::parse(&header)?;
+        let file_end = file.seek(io::SeekFrom::End(0)).await?;
+        let location = header.metadata_region(file_end)?;
+
+        let (buf, padding) = self.encode_for_region(tag, &location)?;
+        AsyncPlainStorage::new(&mut file, location.clone())
+            .write(&buf)
+            .await?;
+        header.update(location.start, buf.len() as u64);
+        file.seek(io::SeekFrom::Start(0)).await?;
+        file.write_all(&header.encode()).await?;
+        file.flush().await?;
+
+        Ok(WriteOutcome {
+            bytes_written: buf.len() as u64,
+            rewritten: buf.len() as u64 != location.end - location.start,
+            padding,
+            v1_touched: false,
+        })
+    }
+
+    /// Encodes a [`Tag`] and replaces any existing tag in the file pointed to by the specified
+    /// path via Tokio, like [`Encoder::write_to_path`].
+    #[cfg(feature = "tokio")]
+    pub async fn async_write_to_path(
+        &self,
+        tag: &Tag,
+        path: impl AsRef<Path>,
+    ) -> crate::Result<WriteOutcome> {
+        let path = path.as_ref();
+        let modified = if self.preserve_mtime {
+            Some(tokio::fs::metadata(path).await?.modified()?)
+        } else {
+            None
+        };
+        if !self.atomic {
+            let mut file = tokio::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(path)
+                .await?;
+            return self
+                .async_write_to_open_file(tag, &mut file, modified)
+                .await;
+        }
+
+        // Like `storage::write_atomically`, replace the file a symbolic link points to.
+        let path = &tokio::fs::canonicalize(path).await?;
+        let copy_path = path.clone();
+        let (tmp_path, file) =
+            tokio::task::spawn_blocking(move || storage::create_temp_copy(&copy_path))
+                .await
+                .map_err(io::Error::from)??;
+        let mut file = tokio::fs::File::from_std(file);
+        let result = async {
+            let outcome = self
+                .async_write_to_open_file(tag, &mut file, modified)
+                .await?;
+            file.sync_all().await?;
+            drop(file);
+            tokio::fs::rename(&tmp_path, path).await?;
+            Ok(outcome)
+        }
+        .await;
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+        }
+        result
+    }
+
+    /// Writes the tag to an opened file and restores its modification time, if requested.
+    #[cfg(feature = "tokio")]
+    async fn async_write_to_open_file(
+        &self,
+        tag: &Tag,
+        file: &mut tokio::fs::File,
+        modified: Option<std::time::SystemTime>,
+    ) -> crate::Result<WriteOutcome> {
+        use tokio::io::AsyncWriteExt;
+
+        let outcome = self.async_write_to_file(tag, &mut *file).await?;
+        file.flush().await?;
+        if let Some(modified) = modified {
+            let std_file = file.try_clone().await?.into_std().await;
+            storage::set_modified(&std_file, modified)?;
+        }
+        Ok(outcome)
+    }
+
+    /// Encodes a [`Tag`] and replaces any existing tag in the file.
+    #[deprecated(note = "Use write_to_file")]
+    pub fn encode_to_file(&self, tag: &Tag, file: &mut fs::File) -> crate::Result<()> {
+        self.write_to_file(tag, file)?;
+        Ok(())
+    }
+
+    /// Encodes a [`Tag`] and replaces any existing tag in the file pointed to by the specified path.
+    ///
+    /// See [`Encoder::atomic`] to protect the file against interrupted writes.
+    pub fn write_to_path(&self, tag: &Tag, path: impl AsRef<Path>) -> crate::Result<WriteOutcome> {
+        let modified = if self.preserve_mtime {
+            Some(fs::metadata(path.as_ref())?.modified()?)
+        } else {
+            None
+        };
+        let write = |file: &mut fs::File| {
+            let outcome = self.write_to_file(tag, &mut *file)?;
+            file.flush()?;
+            if let Some(modified) = modified {
+                storage::set_modified(file, modified)?;
+            }
+            Ok(outcome)
+        };
+        if self.atomic {
+            return storage::write_atomically(path.as_ref(), write);
+        }
+        let mut file = fs::OpenOptions::new().read(true).write(true).open(path)?;
+        write(&mut file)
+    }
+
+    /// Encodes a [`Tag`] and replaces any existing tag in the file pointed to by the specified path.
+    #[deprecated(note = "Use write_to_path")]
+    pub fn encode_to_path(&self, tag: &Tag, path: impl AsRef<Path>) -> crate::Result<()> {
+        self.write_to_path(tag, path)?;
+        Ok(())
+    }
+}
+
+impl Default for Encoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns whether all text of the frame can be written in the encoding in the given version.
+fn can_encode_text(frame: &Frame, version: Version, encoding: Encoding) -> bool {
+    if encoding != Encoding::Latin1 {
+        return true;
+    }
+    // Latin1 can not represent all characters, check whether the content survives a round trip.
+    let mut buf = Vec::new();
+    frame::content::encode(&mut buf, frame.content(), version, encoding).is_ok()
+        && frame::content::decode(frame.id(), version, &buf[..])
+            .is_ok_and(|(content, _)| content == *frame.content())
+}
+
+/// Returns an error if the frame violates the restrictions of its tag.
+fn check_frame_restrictions(frame: &Frame, restrictions: TagRestrictions) -> crate::Result<()> {
+    let violation = |description: &str| {
+        Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("the tag restrictions require that {}", description),
+        )
+        .with_frame_id(frame.id()))
+    };
+    if restrictions.latin1_or_utf8_only
+        && matches!(frame.encoding(), Some(Encoding::UTF16 | Encoding::UTF16BE))
+    {
+        return violation("text is encoded as ISO-8859-1 or UTF-8");
+    }
+    if let (Some(max_chars), Some(text)) =
+        (restrictions.text_size.max_chars(), frame.content().text())
+    {
+        if text
+            .split('\0')
+            .any(|value| value.chars().count() > max_chars)
+        {
+            return violation(&format!("text is at most {} characters long", max_chars));
+        }
+    }
+    if let Some(picture) = frame.content().picture() {
+        if restrictions.png_or_jpeg_only
+            && !matches!(picture.mime_type.as_str(), "image/png" | "image/jpeg")
+        {
+            return violation("images are PNG or JPEG");
+        }
+        #[cfg(feature = "picture-info")]
+        if let Some(info) = picture.image_info() {
+            let fits = match restrictions.image_size {
+                ImageSizeRestriction::None => true,
+                ImageSizeRestriction::Max256x256 => info.width <= 256 && info.height <= 256,
+                ImageSizeRestriction::Max64x64 => info.width <= 64 && info.height <= 64,
+                ImageSizeRestriction::Exactly64x64 => info.width == 64 && info.height == 64,
+            };
+            if !fits {
+                return violation("images fit the size restriction");
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn locate_id3v2(mut reader: impl io::Read + io::Seek) -> crate::Result<Range<u64>> {
+    let header = Header::decode(&mut reader)?;
+
+    let tag_size = header.tag_size();
+    reader.seek(io::SeekFrom::Start(tag_size))?;
+    let num_padding = io::BufReader::new(reader)
+        .bytes()
+        .take_while(|rs| rs.as_ref().map(|b| *b == 0x00).unwrap_or(false))
+        .count();
+    Ok(0..tag_size + num_padding as u64)
+}
+
+/// Locates the ID3v2 tags at the start of the reader, like [`locate_id3v2`], but also includes any
+/// tags that are stacked directly after the first one.
+///
+/// Some programs prepend a new tag instead of replacing the existing one. Writing or removing the
+/// tag over the whole range ensures no stale duplicate is left behind.
+pub fn locate_stacked_id3v2(mut reader: impl io::Read + io::Seek) -> crate::Result<Range<u64>> {
+    let mut end = locate_id3v2(&mut reader)?.end;
+    while let Some(next_end) = tag_end_at(&mut reader, end)? {
+        end = next_end;
+    }
+    Ok(0..end)
+}
+
+/// Returns the end of the tag that starts at `start`, including the null bytes directly following
+/// it, or `None` if there is no tag at that position.
+pub fn tag_end_at(mut reader: impl io::Read + io::Seek, start: u64) -> crate::Result<Option<u64>> {
+    reader.seek(io::SeekFrom::Start(start))?;
+    let mut header = Vec::with_capacity(10);
+    (&mut reader).take(10).read_to_end(&mut header)?;
+    let header = match Header::decode_base_header(&header) {
+        Ok(header) => header.with_ext_header(0, ExtHeader::default()),
+        Err(_) => return Ok(None),
+    };
+    let end = start + header.tag_size();
+    reader.seek(io::SeekFrom::Start(end))?;
+    let num_padding = io::BufReader::new(reader)
+        .bytes()
+        .take_while(|rs| rs.as_ref().map(|b| *b == 0x00).unwrap_or(false))
+        .count();
+    Ok(Some(end + num_padding as u64))
+}
+
+/// Returns the version of the tag of which the header starts at `start`.
+pub fn version_at(mut reader: impl io::Read + io::Seek, start: u64) -> crate::Result<Version> {
+    reader.seek(io::SeekFrom::Start(start))?;
+    let mut header = Vec::with_capacity(10);
+    (&mut reader).take(10).read_to_end(&mut header)?;
+    Ok(Header::decode_base_header(&header)?.version)
+}
+
+/// Locates the ID3v2 tags at the start of the reader via Tokio, like [`locate_stacked_id3v2`].
+#[cfg(feature = "tokio")]
+async fn async_locate_id3v2(
+    mut reader: impl tokio::io::AsyncRead + tokio::io::AsyncSeek + std::marker::Unpin,
+) -> crate::Result<Range<u64>> {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt};
+
+    let mut end = 0;
+    loop {
+        reader.seek(io::SeekFrom::Start(end)).await?;
+        let mut header = Vec::with_capacity(10);
+        (&mut reader).take(10).read_to_end(&mut header).await?;
+        let header = match Header::decode_base_header(&header) {
+            Ok(header) => header.with_ext_header(0, ExtHeader::default()),
+            Err(err) if end == 0 => return Err(err),
+            Err(_) => break,
+        };
+
+        end += header.tag_size();
+        reader.seek(io::SeekFrom::Start(end)).await?;
+        let mut reader = tokio::io::BufReader::new(&mut reader);
+        loop {
+            let buf = reader.fill_buf().await?;
+            let zeros = buf.iter().take_while(|b| **b == 0x00).count();
+            let done = buf.is_empty() || zeros < buf.len();
+            reader.consume(zeros);
+            end += zeros as u64;
+            if done {
+                break;
+            }
+        }
+    }
+    Ok(0..end)
+}
+
+/// Returns the amount of padding of the ID3v2 tag at the start of the reader: the space after the
+/// frames of the tag and any null bytes directly following the tag.
+pub fn padding_size(mut reader: impl io::Read + io::Seek) -> crate::Result<u64> {
+    let location = locate_id3v2(&mut reader)?;
+    reader.seek(io::SeekFrom::Start(0))?;
+    let header = Header::decode(&mut reader)?;
+    let mut data = Vec::new();
+    (&mut reader)
+        .take(header.frame_bytes())
+        .read_to_end(&mut data)?;
+    if header.version != Version::Id3v24 && header.flags.contains(Flags::UNSYNCHRONISATION) {
+        let mut decoded = Vec::new();
+        unsynch::Reader::new(&data[..]).read_to_end(&mut decoded)?;
+        data = decoded;
+    }
+
+    // Skip over the frame headers to find the end of the frames.
+    let header_size = match header.version {
+        Version::Id3v22 => 6,
+        Version::Id3v23 | Version::Id3v24 => 10,
+    };
+    let mut frames_end = 0;
+    while frames_end + header_size <= data.len() && data[frames_end] != 0 {
+        let size = match header.version {
+            Version::Id3v22 => BigEndian::read_u24(&data[frames_end + 3..frames_end + 6]),
+            Version::Id3v23 => BigEndian::read_u32(&data[frames_end + 4..frames_end + 8]),
+            Version::Id3v24 => {
+                unsynch::decode_u32(BigEndian::read_u32(&data[frames_end + 4..frames_end + 8]))
+            }
+        };
+        frames_end += header_size + size as usize;
+    }
+    let padding = data.len().saturating_sub(frames_end) as u64;
+    Ok(padding + (location.end - header.tag_size()))
+}
+
+/// Locates an ID3v2 tag that is appended to the end of the reader using its footer. An ID3v1 tag
+/// following the ID3v2 tag is skipped.
+pub fn locate_id3v2_footer(mut reader: impl io::Read + io::Seek) -> crate::Result<Range<u64>> {
+    let mut end = reader.seek(io::SeekFrom::End(0))?;
+    if end >= 128 && crate::v1::Tag::is_candidate(&mut reader)? {
+        end -= 128;
+    }
+    locate_id3v2_footer_at(reader, end)
+}
+
+/// Locates the tag of which the footer ends at `end`, like [`locate_id3v2_footer`].
+pub fn locate_id3v2_footer_at(
+    mut reader: impl io::Read + io::Seek,
+    end: u64,
+) -> crate::Result<Range<u64>> {
+    if end < 10 {
+        return Err(Error::new(
+            ErrorKind::NoTag,
+            "reader is not large enough to contain a id3 footer",
+        ));
+    }
+    reader.seek(io::SeekFrom::Start(end - 10))?;
+    let mut footer = [0; 10];
+    reader.read_exact(&mut footer)?;
+    decode_footer(footer, end)
+}
+
+/// Locates the tag that is appended to the end of the reader, like [`locate_id3v2_footer`]. If
+/// there is none, an empty range is returned at the position where a tag would be appended.
+fn locate_appended_id3v2(mut reader: impl io::Read + io::Seek) -> crate::Result<Range<u64>> {
+    match locate_id3v2_footer(&mut reader) {
+        Err(Error {
+            kind: ErrorKind::NoTag,
+            ..
+        }) => {}
+        result => return result,
+    }
+    let mut end = reader.seek(io::SeekFrom::End(0))?;
+    if end >= 128 && crate::v1::Tag::is_candidate(&mut reader)? {
+        end -= 128;
+    }
+    Ok(end..end)
+}
+
+/// Locates the tag that is appended to the end of the reader via Tokio, like
+/// [`locate_appended_id3v2`].
+#[cfg(feature = "tokio")]
+async fn async_locate_appended_id3v2(
+    mut reader: impl tokio::io::AsyncRead + tokio::io::AsyncSeek + std::marker::Unpin,
+) -> crate::Result<Range<u64>> {
+    use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+    let mut end = reader.seek(io::SeekFrom::End(0)).await?;
+    if end >= 128 {
+        reader.seek(io::SeekFrom::Start(end - 128)).await?;
+        let mut id = [0; 3];
+        reader.read_exact(&mut id).await?;
+        if &id == b"TAG" {
+            end -= 128;
+        }
+    }
+    if end < 10 {
+        return Ok(end..end);
+    }
+    reader.seek(io::SeekFrom::Start(end - 10)).await?;
+    let mut footer = [0; 10];
+    reader.read_exact(&mut footer).await?;
+    match decode_footer(footer, end) {
+        Err(Error {
+            kind: ErrorKind::NoTag,
+            ..
+        }) => Ok(end..end),
+        result => result,
+    }
+}
+
+/// Decodes the footer of a tag that ends at `end`, returning the location of the tag.
+fn decode_footer(mut footer: [u8; 10], end: u64) -> crate::Result<Range<u64>> {
+    if &footer[0..3] != b"3DI" {
+        return Err(Error::new(
+            ErrorKind::NoTag,
+            "reader does not contain an id3 footer",
+        ));
+    }
+    // Apart from the identifier, the footer is a copy of the header.
+    footer[0..3].copy_from_slice(b"ID3");
+    let header = Header::decode_base_header(&footer)?;
+    if !header.flags.contains(Flags::FOOTER) {
+        return Err(Error::new(
+            ErrorKind::Parsing,
+            "the id3 footer does not have the footer flag set",
+        ));
+    }
+    let tag_size = 20 + u64::from(header.tag_size);
+    if tag_size > end {
+        return Err(Error::new(
+            ErrorKind::Parsing,
+            "the id3 footer points beyond the start of the reader",
+        ));
+    }
+    Ok(end - tag_size..end)
+}
+
+/// Scans the reader for every ID3v2 tag it contains and decodes them, returning each tag together
+/// with the byte offset of its header. `len` is the number of bytes left in the reader.
+///
+/// MPEG audio frames are skipped as a whole, so the magic is only looked for between them.
+/// Candidate headers that turn out not to be decodable, e.g. because the magic occurs by chance in
+/// other data, or that claim to extend beyond the end of the stream are skipped. A tag of which
+/// some frames could not be decoded is returned with the frames that could.
+pub fn decode_all(mut reader: impl io::Read, len: u64) -> crate::Result<Vec<(u64, Tag)>> {
+    let mut tags = Vec::new();
+    // Data that has been read but not scanned yet. The first byte is at `offset` in the stream.
+    let mut pending = Vec::new();
+    let mut offset = 0;
+    let mut eof = false;
+    let mut chunk = vec![0; 0x10000];
+    loop {
+        match find_tag_header(&pending, eof) {
+            Ok(start) => {
+                let header = Header::decode_base_header(&pending[start..start + 10])?;
+                let mut tag_size = 10 + u64::from(header.tag_size);
+                if header.flags.contains(Flags::FOOTER) {
+                    tag_size += 10;
+                }
+                if offset + start as u64 + tag_size > len {
+                    // Not a tag after all, resume scanning right after the magic.
+                    offset += start as u64 + 1;
+                    pending.drain(..start + 1);
+                    continue;
+                }
+                let end = cmp::min(pending.len() as u64, start as u64 + tag_size) as usize;
+                let rest = pending.split_off(end);
+                let mut data = pending.split_off(start);
+                (&mut reader)
+                    .take(tag_size - data.len() as u64)
+                    .read_to_end(&mut data)?;
+
+                let decoded = match Decoder::new().max_tag_size(tag_size).decode(&data[..]) {
+                    Ok(tag) => Some(tag),
+                    Err(Error {
+                        partial_tag: Some(tag),
+                        ..
+                    }) if tag.frames().next().is_some() => Some(tag),
+                    Err(_) => None,
+                };
+                match decoded {
+                    Some(tag) => {
+                        tags.push((offset + start as u64, tag));
+                        offset += (start + data.len()) as u64;
+                        pending = rest;
+                    }
+                    None => {
+                        offset += start as u64 + 1;
+                        data.remove(0);
+                        data.extend(rest);
+                        pending = data;
+                    }
+                }
+                continue;
+            }
+            Err(keep_from) => {
+                offset += keep_from as u64;
+                pending.drain(..keep_from);
+            }
+        }
+
+        if eof {
+            break;
+        }
+        match reader.read(&mut chunk) {
+            Ok(0) => eof = true,
+            Ok(n) => pending.extend_from_slice(&chunk[..n]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Ok(tags)
+}
+
+/// Searches `buf` for a plausible tag header and returns its position. If there is none, the
+/// position from which the data must be retained to find a header that is cut off by the end of
+/// the buffer is returned as the error.
+///
+/// An MPEG audio frame that is followed by another frame or by a tag is skipped as a whole. Frames
+/// that are cut off by the end of the buffer are retained to be verified later, unless `eof` is
+/// set.
+fn find_tag_header(buf: &[u8], eof: bool) -> Result<usize, usize> {
+    let mut start = 0;
+    while start < buf.len() {
+        let candidate = &buf[start..];
+        if b"ID3".starts_with(&candidate[..cmp::min(candidate.len(), 3)]) {
+            if candidate.len() < 10 {
+                return Err(start);
+            }
+            let plausible = candidate[3] != 0xff
+                && candidate[4] != 0xff
+                && candidate[6..10].iter().all(|b| b & 0x80 == 0)
+                && Header::decode_base_header(&candidate[..10]).is_ok();
+            if plausible {
+                return Ok(start);
+            }
+        }
+        if let Some(size) = mpeg::frame_size(candidate) {
+            match candidate.get(size..size + 4) {
+                Some(next) if next.starts_with(b"ID3") || mpeg::frame_size(next).is_some() => {
+                    start += size;
+                    continue;
+                }
+                None if !eof => return Err(start),
+                _ => {}
+            }
+        }
+        start += 1;
+    }
+    Err(buf.len())
+}
+
+/// A tag of which the frames have been validated and measured, but not yet written.
+struct PreparedTag<'a> {
+    frames: Vec<(Cow<'a, Frame>, bool)>,
+    flags: Flags,
+    ext_header: Vec<u8>,
+    padding_size: usize,
+    /// The size of the tag, excluding the header and footer.
+    tag_size: usize,
+}
+
+impl PreparedTag<'_> {
+    /// Returns whether unsynchronisation is applied to the tag data as a whole, which is the case
+    /// for ID3v2.2 and ID3v2.3 tags.
+    fn unsynchronise_data(&self, version: Version) -> bool {
+        self.flags.contains(Flags::UNSYNCHRONISATION) && version != Version::Id3v24
+    }
+}
+
+/// A writer discarding the data written to it, keeping track of its size, CRC and whether it
+/// contains a false synchronisation.
+struct Measure {
+    len: usize,
+    crc: Option<flate2::Crc>,
+    first: Option<u8>,
+    last: Option<u8>,
+    false_sync: bool,
+}
+
+impl Measure {
+    fn new(crc: bool) -> Self {
+        Self {
+            len: 0,
+            crc: if crc { Some(flate2::Crc::new()) } else { None },
+            first: None,
+            last: None,
+            false_sync: false,
+        }
+    }
+
+    /// Like [`unsynch::has_false_sync`] for the concatenation of all written data.
+    fn has_false_sync(&self) -> bool {
+        self.false_sync || self.last == Some(0xFF)
+    }
+}
+
+impl io::Write for Measure {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let (Some(&first), Some(&last)) = (buf.first(), buf.last()) else {
+            return Ok(0);
+        };
+        self.len += buf.len();
+        if let Some(crc) = &mut self.crc {
+            crc.update(buf);
+        }
+        self.false_sync = self.false_sync
+            || (self.last == Some(0xFF) && first & 0xE0 == 0xE0)
+            || buf.windows(2).any(|w| w[0] == 0xFF && w[1] & 0xE0 == 0xE0);
+        self.first.get_or_insert(first);
+        self.last = Some(last);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::{
+        ChannelType, Chapter, Comment, Content, EncapsulatedObject, EncryptedMeta,
+        EncryptionMethod, ExtendedText, Frame, LegacyChannelVolume, LegacyEqualisation,
+        LegacyVolumeAdjustment, MpegLocationLookupTable, MpegLocationLookupTableReference,
+        MusicCdIdentifier, Picture, PictureType, Popularimeter, Private, SynchronisedLyrics,
+        SynchronisedLyricsType, TableOfContents, TimestampFormat, UniqueFileIdentifier, Unknown,
+    };
+    use std::fs::{self};
+    use std::io::{self, Read};
+
+    fn make_tag(version: Version) -> Tag {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_artist("Artist");
+        tag.set_genre("Genre");
+        tag.add_frame(Frame::with_content(
+            "TPE1",
+            Content::new_text_values(["artist 1", "artist 2", "artist 3"]),
+        ));
+        tag.set_duration(1337);
+        tag.add_frame(EncapsulatedObject {
+            mime_type: "Some Object".to_string(),
+            filename: "application/octet-stream".to_string(),
+            description: "".to_string(),
+            data: b"\xC0\xFF\xEE\x00".to_vec(),
+        });
         let mut image_data = Vec::new();
         fs::File::open("testdata/image.jpg")
             .unwrap()
@@ -754,142 +3147,1334 @@ mod tests {
             println!("{:?}", x);
         }
 
-        let ctoc = tag.tables_of_contents().last().unwrap();
+        let ctoc = tag.tables_of_contents().last().unwrap();
+
+        assert_eq!(ctoc.element_id, "toc");
+        assert!(ctoc.top_level);
+        assert!(ctoc.ordered);
+        assert_eq!(
+            ctoc.elements,
+            &["chp0", "chp1", "chp2", "chp3", "chp4", "chp5", "chp6"]
+        );
+        assert!(ctoc.frames.is_empty());
+    }
+
+    #[test]
+    fn read_id3v24() {
+        let mut file = fs::File::open("testdata/id3v24.id3").unwrap();
+        let tag = decode(&mut file).unwrap();
+        assert_eq!("Title", tag.title().unwrap());
+        assert_eq!(1, tag.disc().unwrap());
+        assert_eq!(1, tag.total_discs().unwrap());
+        if cfg!(feature = "decode_picture") {
+            assert_eq!(
+                PictureType::CoverFront,
+                tag.pictures().next().unwrap().picture_type
+            );
+        }
+    }
+
+    #[test]
+    fn read_id3v24_extended() {
+        let mut file = fs::File::open("testdata/id3v24_ext.id3").unwrap();
+        let tag = decode(&mut file).unwrap();
+        assert_eq!("Title", tag.title().unwrap());
+        assert_eq!("Genre", tag.genre().unwrap());
+        assert_eq!("Artist", tag.artist().unwrap());
+        assert_eq!("Album", tag.album().unwrap());
+        assert_eq!(2, tag.track().unwrap());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn read_id3v24_extended_tokio() {
+        let mut file = tokio::fs::File::open("testdata/id3v24_ext.id3")
+            .await
+            .unwrap();
+        let tag = async_decode(&mut file).await.unwrap();
+        assert_eq!("Title", tag.title().unwrap());
+        assert_eq!("Genre", tag.genre().unwrap());
+        assert_eq!("Artist", tag.artist().unwrap());
+        assert_eq!("Album", tag.album().unwrap());
+        assert_eq!(2, tag.track().unwrap());
+    }
+
+    #[test]
+    fn read_error_context() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_artist("Artist");
+        let mut buf = Vec::new();
+        Encoder::new()
+            .version(Version::Id3v24)
+            .encode(&tag, &mut buf)
+            .unwrap();
+
+        // Corrupt the UTF-8 text of the artist frame.
+        let offset = buf.windows(4).position(|w| w == b"TPE1").unwrap();
+        buf[offset + 11] = 0xff;
+        buf[offset + 12] = 0xfe;
+
+        let err = decode(&buf[..]).unwrap_err();
+        match err.kind {
+            ErrorKind::StringDecoding(ref bytes) => assert_eq!(bytes, b"\xff\xfetist"),
+            ref kind => panic!("unexpected error kind: {:?}", kind),
+        }
+        assert_eq!(err.string_encoding(), Some(crate::Encoding::UTF8));
+        assert_eq!(err.string_range(), Some(1..2));
+        assert_eq!(err.frame_id(), Some("TPE1"));
+        assert_eq!(err.offset(), Some(offset as u64));
+        assert_eq!(err.partial_tag.unwrap().title(), Some("Title"));
+    }
+
+    #[test]
+    fn read_salvage() {
+        let frame = |id: &[u8], flags: u16, data: &[u8]| {
+            let mut buf = id.to_vec();
+            buf.extend((data.len() as u32).to_be_bytes());
+            buf.extend(flags.to_be_bytes());
+            buf.extend(data);
+            buf
+        };
+        let mut frames = Vec::new();
+        frames.extend(frame(b"TIT2", 0, b"\x03Title"));
+        frames.extend(frame(b"TPE1", 0, b"\x03\xff\xfe"));
+        frames.extend(frame(b"TALB", 0x0004, b"\x01encrypted"));
+        frames.extend(frame(b"TCON", 0, b"\x03Genre"));
+        let mut buf = b"ID3\x04\x00\x00".to_vec();
+        buf.extend(unsynch::encode_u32(frames.len() as u32).to_be_bytes());
+        buf.extend(&frames);
+
+        let err = decode(&buf[..]).unwrap_err();
+        assert_eq!(err.frame_id(), Some("TPE1"));
+        let tag = crate::partial_tag_ok(Err(err)).unwrap();
+        assert_eq!(tag.title(), Some("Title"));
+        assert_eq!(tag.genre(), Some("Genre"));
+        assert_eq!(
+            tag.get("TPE1")
+                .unwrap()
+                .content()
+                .to_unknown()
+                .unwrap()
+                .data,
+            b"\x03\xff\xfe"
+        );
+        let encrypted = tag
+            .get("TALB")
+            .unwrap()
+            .content()
+            .encrypted_frame()
+            .unwrap();
+        assert_eq!(encrypted.method_symbol, 0x01);
+        assert_eq!(encrypted.data, b"encrypted");
+
+        // A broken extended header still yields a tag of the right version.
+        let buf = b"ID3\x04\x00\x40\x00\x00\x00\x06\x00\x00\x00\x01\x01\x00";
+        let err = decode(&buf[..]).unwrap_err();
+        assert_eq!(err.partial_tag.unwrap().version(), Version::Id3v24);
+    }
+
+    #[test]
+    fn read_max_frame_size() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.add_frame(EncapsulatedObject {
+            mime_type: "application/octet-stream".to_string(),
+            filename: "".to_string(),
+            description: "".to_string(),
+            data: vec![0; 4096],
+        });
+        tag.set_artist("Artist");
+        let decoder = Decoder::new().max_frame_size(1024);
+        for (version, compression) in [
+            (Version::Id3v22, false),
+            (Version::Id3v23, false),
+            (Version::Id3v24, false),
+            // The zeroes compress to less than the limit, but the content is still too large.
+            (Version::Id3v23, true),
+            (Version::Id3v24, true),
+        ] {
+            let mut buf = Vec::new();
+            Encoder::new()
+                .version(version)
+                .compression(compression)
+                .encode(&tag, &mut buf)
+                .unwrap();
+            assert!(Decoder::new().max_frame_size(8192).decode(&buf[..]).is_ok());
+
+            let err = decoder.decode(&buf[..]).unwrap_err();
+            assert!(matches!(err.kind, ErrorKind::LimitExceeded));
+            let tag = crate::partial_tag_ok(Err(err)).unwrap();
+            assert_eq!(tag.title(), Some("Title"));
+            assert_eq!(tag.artist(), Some("Artist"));
+            let stub = tag.frames().find(|f| f.id().starts_with("GEO")).unwrap();
+            let skipped = stub.content().skipped().unwrap();
+            assert_eq!(skipped.version, version);
+            assert!(skipped.offset > 0);
+            let err = Encoder::new()
+                .version(version)
+                .encode(&tag, &mut Vec::new())
+                .unwrap_err();
+            assert!(matches!(err.kind, ErrorKind::InvalidInput));
+        }
+    }
+
+    #[test]
+    fn read_skip_content_of() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.add_frame(EncapsulatedObject {
+            mime_type: "application/octet-stream".to_string(),
+            filename: "".to_string(),
+            description: "".to_string(),
+            data: vec![0; 4096],
+        });
+        tag.set_artist("Artist");
+        let decoder = Decoder::new().skip_content_of(["GEOB"]);
+        for version in [Version::Id3v22, Version::Id3v23, Version::Id3v24] {
+            let mut buf = Vec::new();
+            Encoder::new()
+                .version(version)
+                .encode(&tag, &mut buf)
+                .unwrap();
+
+            let mut warnings = Vec::new();
+            let read = decoder
+                .decode_with_warnings(&buf[..], &mut warnings)
+                .unwrap();
+            assert!(warnings.is_empty(), "{:?}", warnings);
+            assert_eq!(read.title(), Some("Title"));
+            assert_eq!(read.artist(), Some("Artist"));
+            let frame = read.frames().find(|f| f.id().starts_with("GEO")).unwrap();
+            let skipped = frame.content().skipped().unwrap();
+            assert_eq!(skipped.version, version);
+            assert!(skipped.size > 4096);
+            let offset = skipped.offset as usize;
+            assert_eq!(&buf[offset..offset + 3], b"GEO");
+
+            let err = Encoder::new().encode(&read, &mut Vec::new()).unwrap_err();
+            assert!(matches!(err.kind, ErrorKind::InvalidInput));
+        }
+    }
+
+    #[test]
+    fn read_limits() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_artist("Artist");
+        tag.set_album("Album");
+        for version in [Version::Id3v22, Version::Id3v23, Version::Id3v24] {
+            let mut buf = Vec::new();
+            Encoder::new()
+                .version(version)
+                .padding(32)
+                .encode(&tag, &mut buf)
+                .unwrap();
+            let frames_end = buf.len() as u64 - 32;
+            let decoder = Decoder::new().max_tag_size(frames_end).max_frames(3);
+            assert_eq!(decoder.decode(&buf[..]).unwrap().frames().count(), 3);
+
+            let err = Decoder::new()
+                .max_tag_size(frames_end - 1)
+                .decode(&buf[..])
+                .unwrap_err();
+            assert!(matches!(err.kind, ErrorKind::LimitExceeded));
+            let tag = err.partial_tag.unwrap();
+            assert_eq!(tag.title(), Some("Title"));
+            assert_eq!(tag.artist(), Some("Artist"));
+            assert_eq!(tag.album(), None);
+
+            let err = Decoder::new().max_frames(1).decode(&buf[..]).unwrap_err();
+            assert!(matches!(err.kind, ErrorKind::LimitExceeded));
+            assert_eq!(err.partial_tag.unwrap().frames().count(), 1);
+        }
+
+        // A frame that declares a huge size is rejected before its content is read.
+        let mut frames = b"TIT2\x00\x00\x00\x06\x00\x00\x03Title".to_vec();
+        frames.extend(b"APIC\xff\xff\xff\xf0\x00\x00\x00");
+        let mut buf = b"ID3\x03\x00\x00".to_vec();
+        buf.extend(unsynch::encode_u32(frames.len() as u32).to_be_bytes());
+        buf.extend(&frames);
+        let err = Decoder::new()
+            .max_tag_size(1024)
+            .decode(&buf[..])
+            .unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::LimitExceeded));
+        assert_eq!(err.frame_id(), Some("APIC"));
+        assert_eq!(err.partial_tag.unwrap().title(), Some("Title"));
+    }
+
+    #[test]
+    fn read_unsupported_version() {
+        let buf = b"ID3\x05\x01\x00\x00\x00\x00\x00";
+        let err = decode(&buf[..]).unwrap_err();
+        assert!(matches!(
+            err.kind,
+            ErrorKind::UnsupportedVersion { major: 5, minor: 1 }
+        ));
+    }
+
+    #[test]
+    fn read_warnings() {
+        let frame = |id: &[u8], flags: u16, data: &[u8]| {
+            let mut buf = id.to_vec();
+            buf.extend((data.len() as u32).to_be_bytes());
+            buf.extend(flags.to_be_bytes());
+            buf.extend(data);
+            buf
+        };
+        let mut frames = Vec::new();
+        frames.extend(frame(b"TIT2", 0, b"\x03One"));
+        frames.extend(frame(b"XYZW", 0, b"raw"));
+        frames.extend(frame(b"TIT2", 0x8000, b"\x03Two"));
+        let mut buf = b"ID3\x04\x00\x00".to_vec();
+        buf.extend(unsynch::encode_u32(frames.len() as u32).to_be_bytes());
+        buf.extend(&frames);
+
+        let mut warnings = Vec::new();
+        let tag = decode_with_warnings(&buf[..], &mut warnings).unwrap();
+        assert_eq!(tag.title(), Some("Two"));
+        let warnings = warnings
+            .iter()
+            .map(|w| (w.frame_id.as_deref().unwrap(), w.offset.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(warnings, [("XYZW", 24), ("TIT2", 37), ("TIT2", 37)]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn read_warnings_tokio() {
+        let data = b"\x00guitar\x00Jimmy\x00drums\x00";
+        let mut buf = b"ID3\x03\x00\x00".to_vec();
+        buf.extend(unsynch::encode_u32(10 + data.len() as u32).to_be_bytes());
+        buf.extend(b"IPLS");
+        buf.extend((data.len() as u32).to_be_bytes());
+        buf.extend([0, 0]);
+        buf.extend(data);
+
+        let mut warnings = Vec::new();
+        let tag = async_decode_with_warnings(&buf[..], &mut warnings)
+            .await
+            .unwrap();
+        let people = tag.get("IPLS").unwrap().content().involved_people_list();
+        assert_eq!(people.unwrap().items.len(), 1);
+        let warnings = warnings
+            .iter()
+            .map(|w| (w.frame_id.as_deref().unwrap(), w.offset.unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(warnings, [("IPLS", 10)]);
+    }
+
+    #[test]
+    fn write_id3v22() {
+        if !cfg!(feature = "decode_picture") {
+            return;
+        }
+
+        let tag = make_tag(Version::Id3v22);
+        let mut buffer = Vec::new();
+        Encoder::new()
+            .version(Version::Id3v22)
+            .encode(&tag, &mut buffer)
+            .unwrap();
+        let tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
+        assert_eq!(tag, tag_read);
+    }
+
+    #[test]
+    fn write_id3v22_unsynch() {
+        if !cfg!(feature = "decode_picture") {
+            return;
+        }
+
+        let tag = make_tag(Version::Id3v22);
+        let mut buffer = Vec::new();
+        Encoder::new()
+            .unsynchronisation(true)
+            .version(Version::Id3v22)
+            .encode(&tag, &mut buffer)
+            .unwrap();
+        let tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
+        assert_eq!(tag, tag_read);
+    }
+
+    #[test]
+    fn write_id3v22_invalid_id() {
+        if !cfg!(feature = "decode_picture") {
+            return;
+        }
+
+        let mut tag = make_tag(Version::Id3v22);
+        tag.add_frame(Frame::with_content(
+            "XXX",
+            Content::Unknown(Unknown {
+                version: Version::Id3v22,
+                data: vec![1, 2, 3],
+            }),
+        ));
+        tag.add_frame(Frame::with_content(
+            "YYY",
+            Content::Unknown(Unknown {
+                version: Version::Id3v22,
+                data: vec![4, 5, 6],
+            }),
+        ));
+        tag.add_frame(Frame::with_content(
+            "ZZZ",
+            Content::Unknown(Unknown {
+                version: Version::Id3v22,
+                data: vec![7, 8, 9],
+            }),
+        ));
+        let mut buffer = Vec::new();
+        Encoder::new()
+            .version(Version::Id3v22)
+            .encode(&tag, &mut buffer)
+            .unwrap();
+        let tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
+        assert_eq!(tag, tag_read);
+    }
+
+    #[test]
+    fn write_id3v23() {
+        if !cfg!(feature = "decode_picture") {
+            return;
+        }
+
+        let tag = make_tag(Version::Id3v23);
+        let mut buffer = Vec::new();
+        Encoder::new()
+            .version(Version::Id3v23)
+            .encode(&tag, &mut buffer)
+            .unwrap();
+        let tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
+        assert_eq!(tag, tag_read);
+    }
+
+    #[test]
+    fn write_preserves_frame_order() {
+        for version in [Version::Id3v22, Version::Id3v23, Version::Id3v24] {
+            let mut tag = Tag::new();
+            tag.set_artist("Artist");
+            tag.add_frame(EncapsulatedObject {
+                mime_type: "application/octet-stream".to_string(),
+                filename: "".to_string(),
+                description: "".to_string(),
+                data: b"\xC0\xFF\xEE".to_vec(),
+            });
+            tag.set_title("Title");
+            tag.add_frame(Comment {
+                lang: "eng".to_string(),
+                description: "".to_string(),
+                text: "Comment".to_string(),
+            });
+            tag.add_frame(ExtendedText {
+                description: "key".to_string(),
+                value: "value".to_string(),
+            });
+            tag.set_album("Album");
+            let ids = |tag: &Tag| tag.frames().map(|f| f.id().to_string()).collect::<Vec<_>>();
+
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            let mut tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
+            assert_eq!(ids(&tag), ids(&tag_read), "{}", version);
+
+            // Replacing a frame keeps its position.
+            tag_read.set_title("Other Title");
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .encode(&tag_read, &mut buffer)
+                .unwrap();
+            let tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
+            assert_eq!(ids(&tag), ids(&tag_read), "{}", version);
+            assert_eq!(tag_read.title(), Some("Other Title"));
+        }
+    }
+
+    #[test]
+    fn write_legacy_frames() {
+        let mut tag = Tag::new();
+        let mut rvad = Frame::from(LegacyVolumeAdjustment {
+            bits_used: 16,
+            adjustments: vec![
+                LegacyChannelVolume {
+                    channel_type: ChannelType::FrontRight,
+                    volume_change: 0x8000,
+                    peak_volume: 0x1234,
+                },
+                LegacyChannelVolume {
+                    channel_type: ChannelType::FrontLeft,
+                    volume_change: 0,
+                    peak_volume: 0,
+                },
+            ],
+        });
+        rvad.set_file_alter_preservation(true);
+        tag.add_frame(rvad);
+        tag.add_frame(LegacyEqualisation {
+            adjustment_bits: 16,
+            bands: vec![(1000, 0)],
+        });
+        let encode = |version, convert| {
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .convert_legacy_frames(convert)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            decode(io::Cursor::new(buffer)).unwrap()
+        };
+
+        let upgraded = encode(Version::Id3v24, true);
+        assert!(upgraded.get("RVAD").is_none());
+        assert!(upgraded.get("EQUA").is_none());
+        let rva2 = upgraded.get("RVA2").unwrap();
+        assert!(rva2.file_alter_preservation());
+        let rva2 = rva2.content().relative_volume_adjustment().unwrap();
+        let right = rva2.adjustment(ChannelType::FrontRight).unwrap();
+        assert_eq!(right.volume_adjustment, 1803);
+        assert_eq!(right.peak_volume, vec![0x12, 0x34]);
+        let equ2 = upgraded.get("EQU2").unwrap().content().equalisation();
+        assert_eq!(equ2.unwrap().adjustment_points, vec![(2000, 0)]);
+
+        let kept = encode(Version::Id3v24, false);
+        assert!(kept.get("RVAD").is_some());
+        assert!(kept.get("EQUA").is_some());
+        let kept = encode(Version::Id3v23, true);
+        assert!(kept.get("RVAD").is_some());
+        assert!(kept.get("RVA2").is_none());
+    }
+
+    #[test]
+    fn write_podcast_frames() {
+        let mut tag = Tag::new();
+        tag.set_podcast(true);
+        tag.set_podcast_description("An episode about tags");
+        tag.set_podcast_id("https://example.com/episodes/1");
+        tag.set_podcast_category("Technology");
+        tag.set_podcast_keywords("id3,metadata");
+        tag.set_podcast_feed_url("https://example.com/feed.xml");
+        for version in [Version::Id3v23, Version::Id3v24] {
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            let decoded = decode(io::Cursor::new(buffer)).unwrap();
+            assert!(decoded.is_podcast());
+            assert_eq!(decoded.podcast_description(), Some("An episode about tags"));
+            assert_eq!(decoded.podcast_id(), Some("https://example.com/episodes/1"));
+            assert_eq!(decoded.podcast_category(), Some("Technology"));
+            assert_eq!(decoded.podcast_keywords(), Some("id3,metadata"));
+            assert_eq!(
+                decoded.podcast_feed_url(),
+                Some("https://example.com/feed.xml")
+            );
+        }
+        tag.set_podcast(false);
+        assert!(!tag.is_podcast());
+    }
+
+    #[test]
+    fn write_movement_frames() {
+        let mut tag = Tag::new();
+        tag.set_movement("Adagio");
+        tag.set_movement_number(2);
+        tag.set_total_movements(3);
+        for version in [Version::Id3v22, Version::Id3v23, Version::Id3v24] {
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            if version == Version::Id3v22 {
+                assert!(buffer.windows(3).any(|id| id == b"MVN"));
+            }
+            let decoded = decode(io::Cursor::new(buffer)).unwrap();
+            assert_eq!(decoded.movement(), Some("Adagio"));
+            assert_eq!(decoded.movement_number(), Some(2));
+            assert_eq!(decoded.total_movements(), Some(3));
+        }
+    }
+
+    #[test]
+    fn write_compilation() {
+        let mut tag = Tag::new();
+        tag.set_compilation(true);
+        for (version, id) in [(Version::Id3v22, &b"TCP"[..]), (Version::Id3v24, b"TCMP")] {
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            assert_eq!(&buffer[10..10 + id.len()], id);
+            assert!(decode(io::Cursor::new(buffer)).unwrap().is_compilation());
+        }
+    }
+
+    #[test]
+    fn write_encrypted_meta() {
+        let mut tag = Tag::with_version(Version::Id3v22);
+        tag.set_title("Title");
+        tag.add_frame(EncryptedMeta {
+            owner_identifier: "mailto:crypto@example.com".to_string(),
+            content_description: "Lyrics".to_string(),
+            encrypted_data: vec![0x13, 0x37],
+        });
+
+        let mut buffer = Vec::new();
+        Encoder::new()
+            .version(Version::Id3v22)
+            .encode(&tag, &mut buffer)
+            .unwrap();
+        let decoded = decode(io::Cursor::new(buffer)).unwrap();
+        let crm = decoded
+            .get("CRM")
+            .unwrap()
+            .content()
+            .encrypted_meta()
+            .unwrap();
+        assert_eq!(crm.encrypted_data, vec![0x13, 0x37]);
+
+        let mut buffer = Vec::new();
+        let mut warnings = Vec::new();
+        Encoder::new()
+            .version(Version::Id3v24)
+            .encode_with_warnings(&decoded, &mut buffer, &mut warnings)
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].frame_id.as_deref(), Some("CRM"));
+        let decoded = decode(io::Cursor::new(buffer)).unwrap();
+        assert!(decoded.get("CRM").is_none());
+        assert_eq!(decoded.title(), Some("Title"));
+    }
+
+    #[test]
+    fn write_footer() {
+        if !cfg!(feature = "decode_picture") {
+            return;
+        }
+
+        let tag = make_tag(Version::Id3v24);
+        let mut buffer = Vec::new();
+        Encoder::new()
+            .footer(true)
+            .padding(100)
+            .encode(&tag, &mut buffer)
+            .unwrap();
+        let header = Header::decode(&buffer[..]).unwrap();
+        assert!(header.flags.contains(Flags::FOOTER));
+        assert_eq!(header.tag_size(), buffer.len() as u64);
+        assert_eq!(&buffer[..3], b"ID3");
+        assert_eq!(&buffer[buffer.len() - 10..buffer.len() - 7], b"3DI");
+        assert_eq!(buffer[3..10], buffer[buffer.len() - 7..]);
+        assert_eq!(decode(&buffer[..]).unwrap(), tag);
+
+        // The tag is appended to some audio and followed by an ID3v1 tag.
+        let mut file = vec![0xaa; 1000];
+        file.extend_from_slice(&buffer);
+        file.extend_from_slice(&fs::read("testdata/id3v1.id3").unwrap());
+        let range = locate_id3v2_footer(io::Cursor::new(&file)).unwrap();
+        assert_eq!(range, 1000..1000 + buffer.len() as u64);
+        assert_eq!(Tag::read_from_end(io::Cursor::new(&file)).unwrap(), tag);
+
+        assert!(Tag::read_from_end(io::Cursor::new(&file[..1000])).is_err());
+
+        // Footers do not exist in ID3v2.3.
+        let mut buffer = Vec::new();
+        Encoder::new()
+            .version(Version::Id3v23)
+            .footer(true)
+            .encode(&make_tag(Version::Id3v23), &mut buffer)
+            .unwrap();
+        assert!(!Header::decode(&buffer[..])
+            .unwrap()
+            .flags
+            .contains(Flags::FOOTER));
+        assert!(matches!(
+            locate_id3v2_footer(io::Cursor::new(&buffer)),
+            Err(Error {
+                kind: ErrorKind::NoTag,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn read_appended() {
+        if !cfg!(feature = "decode_picture") {
+            return;
+        }
+
+        let tag = make_tag(Version::Id3v24);
+        let mut file = vec![0xaa; 1000];
+        Encoder::new().footer(true).encode(&tag, &mut file).unwrap();
+        assert_eq!(Tag::read_from2(io::Cursor::new(&file)).unwrap(), tag);
+
+        file.truncate(1000);
+        let error = Tag::read_from2(io::Cursor::new(&file)).unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::NoTag));
+    }
+
+    #[test]
+    fn read_seek() {
+        let mut appended = Tag::new();
+        appended.set_title("Appended");
+        appended.set_album("Album");
+        let mut appended_buf = Vec::new();
+        Encoder::new()
+            .footer(true)
+            .encode(&appended, &mut appended_buf)
+            .unwrap();
+
+        // The SEEK frame points to the appended tag, past the audio.
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_artist("Artist");
+        tag.add_frame(Frame::with_content(
+            "SEEK",
+            Content::Unknown(Unknown {
+                data: 1000u32.to_be_bytes().to_vec(),
+                version: Version::Id3v24,
+            }),
+        ));
+        let mut file = Vec::new();
+        Encoder::new().encode(&tag, &mut file).unwrap();
+        file.extend_from_slice(&[0xaa; 1000]);
+        file.extend_from_slice(&appended_buf);
+
+        let points_nowhere = |warnings: &[Warning]| {
+            warnings
+                .iter()
+                .any(|warning| warning.description == "the SEEK frame does not point to a tag")
+        };
+        let (read, warnings) = Tag::read_with_warnings(io::Cursor::new(&file)).unwrap();
+        assert!(!points_nowhere(&warnings));
+        assert!(read.get("SEEK").is_none());
+        assert_eq!(read.title(), Some("Appended"));
+        assert_eq!(read.artist(), Some("Artist"));
+        assert_eq!(read.album(), Some("Album"));
+
+        // A SEEK frame that points nowhere is reported.
+        file.truncate(file.len() - appended_buf.len());
+        let (read, warnings) = Tag::read_with_warnings(io::Cursor::new(&file)).unwrap();
+        assert_eq!(read.title(), Some("Title"));
+        assert!(points_nowhere(&warnings));
+    }
+
+    #[test]
+    fn stacked_tags() {
+        let mut outer = Tag::new();
+        outer.set_title("Outer");
+        outer.set_artist("Artist");
+        let mut inner = Tag::with_version(Version::Id3v23);
+        inner.set_title("Inner");
+        inner.set_album("Album");
+
+        let mut data = Vec::new();
+        Encoder::new()
+            .padding(16)
+            .encode(&outer, &mut data)
+            .unwrap();
+        Encoder::new()
+            .version(Version::Id3v23)
+            .encode(&inner, &mut data)
+            .unwrap();
+        let tags_end = data.len() as u64;
+        data.extend_from_slice(&[0xaa; 100]);
+
+        assert_eq!(
+            locate_stacked_id3v2(io::Cursor::new(&data)).unwrap(),
+            0..tags_end
+        );
+        // Only the first tag is read, unless the tags are merged explicitly.
+        let read = Tag::read_from2(io::Cursor::new(&data)).unwrap();
+        assert_eq!(read, outer);
+        let all = Tag::read_all_from(io::Cursor::new(&data)).unwrap();
+        let read = Tag::merged(all.into_iter().rev().map(|(_, tag)| tag));
+        assert_eq!(read.title(), Some("Outer"));
+        assert_eq!(read.artist(), Some("Artist"));
+        assert_eq!(read.album(), Some("Album"));
 
-        assert_eq!(ctoc.element_id, "toc");
-        assert!(ctoc.top_level);
-        assert!(ctoc.ordered);
+        // Writing replaces both tags.
+        let mut file = io::Cursor::new(data.clone());
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        Encoder::new().write_to_file(&tag, &mut file).unwrap();
+        let written = file.into_inner();
+        assert_eq!(Tag::read_from2(io::Cursor::new(&written)).unwrap(), tag);
         assert_eq!(
-            ctoc.elements,
-            &["chp0", "chp1", "chp2", "chp3", "chp4", "chp5", "chp6"]
+            Tag::read_all_from(io::Cursor::new(&written))
+                .unwrap()
+                .into_iter()
+                .map(|(_, tag)| tag)
+                .collect::<Vec<_>>(),
+            vec![tag]
         );
-        assert!(ctoc.frames.is_empty());
+        assert_eq!(&written[written.len() - 100..], &[0xaa; 100][..]);
+
+        // Removing removes both tags.
+        let mut file = io::Cursor::new(data);
+        assert!(Tag::remove_from_file(&mut file).unwrap());
+        assert_eq!(file.into_inner(), vec![0xaa; 100]);
     }
 
     #[test]
-    fn read_id3v24() {
-        let mut file = fs::File::open("testdata/id3v24.id3").unwrap();
-        let tag = decode(&mut file).unwrap();
-        assert_eq!("Title", tag.title().unwrap());
-        assert_eq!(1, tag.disc().unwrap());
-        assert_eq!(1, tag.total_discs().unwrap());
-        if cfg!(feature = "decode_picture") {
-            assert_eq!(
-                PictureType::CoverFront,
-                tag.pictures().next().unwrap().picture_type
-            );
+    fn write_appended() {
+        let mut original = Vec::new();
+        Encoder::new()
+            .encode(&make_tag(Version::Id3v24), &mut original)
+            .unwrap();
+        original.extend_from_slice(&[0xaa; 1000]);
+        let id3v1 = fs::read("testdata/id3v1.id3").unwrap();
+        original.extend_from_slice(&id3v1);
+
+        let mut file = io::Cursor::new(original);
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_album("A longer album title");
+        let encoder = Encoder::new().location(Placement::Append);
+        for _ in 0..2 {
+            file.set_position(0);
+            encoder.write_to_file(&tag, &mut file).unwrap();
+            let data = file.get_ref();
+            // The tag at the start is removed and the ID3v1 tag is kept at the end.
+            assert_eq!(&data[..1000], &[0xaa; 1000][..]);
+            assert_eq!(&data[data.len() - id3v1.len()..], &id3v1[..]);
+            let location = locate_id3v2_footer(io::Cursor::new(data)).unwrap();
+            assert_eq!(location.start, 1000);
+            assert_eq!(Tag::read_from2(io::Cursor::new(data)).unwrap(), tag);
+            tag.remove_album();
         }
-    }
 
-    #[test]
-    fn read_id3v24_extended() {
-        let mut file = fs::File::open("testdata/id3v24_ext.id3").unwrap();
-        let tag = decode(&mut file).unwrap();
-        assert_eq!("Title", tag.title().unwrap());
-        assert_eq!("Genre", tag.genre().unwrap());
-        assert_eq!("Artist", tag.artist().unwrap());
-        assert_eq!("Album", tag.album().unwrap());
-        assert_eq!(2, tag.track().unwrap());
+        // Appended tags require a footer.
+        let error = encoder
+            .version(Version::Id3v23)
+            .write_to_file(&tag, &mut file)
+            .unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::InvalidInput));
     }
 
     #[cfg(feature = "tokio")]
     #[tokio::test]
-    async fn read_id3v24_extended_tokio() {
-        let mut file = tokio::fs::File::open("testdata/id3v24_ext.id3")
+    async fn async_write_appended() {
+        let mut original = Vec::new();
+        Encoder::new()
+            .encode(&make_tag(Version::Id3v24), &mut original)
+            .unwrap();
+        original.extend_from_slice(&[0xaa; 1000]);
+
+        let encoder = Encoder::new().location(Placement::Append);
+        let tag = make_tag(Version::Id3v24);
+        let mut file = io::Cursor::new(original.clone());
+        encoder.write_to_file(&tag, &mut file).unwrap();
+        let mut async_file = io::Cursor::new(original);
+        encoder
+            .async_write_to_file(&tag, &mut async_file)
             .await
             .unwrap();
-        let tag = async_decode(&mut file).await.unwrap();
-        assert_eq!("Title", tag.title().unwrap());
-        assert_eq!("Genre", tag.genre().unwrap());
-        assert_eq!("Artist", tag.artist().unwrap());
-        assert_eq!("Album", tag.album().unwrap());
-        assert_eq!(2, tag.track().unwrap());
+        assert_eq!(file.get_ref(), async_file.get_ref());
     }
 
     #[test]
-    fn write_id3v22() {
+    fn write_crc() {
         if !cfg!(feature = "decode_picture") {
             return;
         }
 
-        let tag = make_tag(Version::Id3v22);
+        for version in [Version::Id3v23, Version::Id3v24] {
+            for unsynchronisation in [false, true] {
+                let tag = make_tag(version);
+                let mut buffer = Vec::new();
+                Encoder::new()
+                    .version(version)
+                    .unsynchronisation(unsynchronisation)
+                    .padding(20)
+                    .write_crc(true)
+                    .encode(&tag, &mut buffer)
+                    .unwrap();
+                let header = Header::decode(&buffer[..]).unwrap();
+                assert!(header.flags.contains(Flags::EXTENDED_HEADER));
+                assert!(header.ext.crc.is_some());
+                assert_eq!(header.tag_size(), buffer.len() as u64);
+
+                let mut warnings = Vec::new();
+                let tag_read = decode_with_warnings(&buffer[..], &mut warnings).unwrap();
+                assert_eq!(tag_read, tag);
+                assert!(warnings.is_empty(), "{:?}", warnings);
+
+                // Flip a bit in the data of the first frame.
+                let ext_header_size = header.ext_header_size as usize;
+                buffer[10 + ext_header_size + 11] ^= 0x01;
+                let mut warnings = Vec::new();
+                decode_with_warnings(&buffer[..], &mut warnings).unwrap();
+                assert_eq!(warnings.len(), 1, "{:?}", warnings);
+            }
+        }
+    }
+
+    #[test]
+    fn read_restrictions() {
+        let file = fs::File::open("testdata/id3v24_ext.id3").unwrap();
+        let tag = decode(file).unwrap();
+        assert_eq!(tag.restrictions(), Some(TagRestrictions::default()));
+
+        let tag = decode(fs::File::open("testdata/id3v24.id3").unwrap()).unwrap();
+        assert_eq!(tag.restrictions(), None);
+
+        for b in 0..=255 {
+            assert_eq!(TagRestrictions::from_byte(b).to_byte(), b);
+        }
+    }
+
+    #[test]
+    fn write_restrictions() {
+        use crate::tag::{ImageSizeRestriction, TagSizeRestriction, TextSizeRestriction};
+
+        let mut tag = make_tag(Version::Id3v24);
+        let restrictions = TagRestrictions {
+            tag_size: TagSizeRestriction::Max32Frames4KiB,
+            latin1_or_utf8_only: true,
+            text_size: TextSizeRestriction::Max128Chars,
+            png_or_jpeg_only: true,
+            image_size: ImageSizeRestriction::Max64x64,
+        };
+        tag.set_restrictions(Some(restrictions));
+        for crc in [false, true] {
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .write_crc(crc)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            let tag_read = decode(&buffer[..]).unwrap();
+            assert_eq!(tag_read.restrictions(), Some(restrictions));
+        }
+
+        // Restrictions only exist in ID3v2.4.
         let mut buffer = Vec::new();
         Encoder::new()
-            .version(Version::Id3v22)
+            .version(Version::Id3v23)
             .encode(&tag, &mut buffer)
             .unwrap();
-        let tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
-        assert_eq!(tag, tag_read);
+        assert_eq!(decode(&buffer[..]).unwrap().restrictions(), None);
+
+        let encoder = Encoder::new().enforce_restrictions(true);
+        let mut tag = Tag::new();
+        tag.set_restrictions(Some(restrictions));
+        tag.set_title("Title");
+        encoder.encode(&tag, Vec::new()).unwrap();
+
+        let mut tag_long = tag.clone();
+        tag_long.set_album("x".repeat(129));
+        assert!(encoder.encode(&tag_long, Vec::new()).is_err());
+
+        let mut tag_utf16 = tag.clone();
+        tag_utf16.add_frame(Frame::text("TPE1", "Artist").set_encoding(Some(Encoding::UTF16)));
+        assert!(encoder.encode(&tag_utf16, Vec::new()).is_err());
+
+        let mut tag_gif = tag.clone();
+        tag_gif.add_frame(Picture {
+            mime_type: "image/gif".to_string(),
+            picture_type: PictureType::CoverFront,
+            description: String::new(),
+            data: vec![0; 16],
+        });
+        assert!(encoder.encode(&tag_gif, Vec::new()).is_err());
+
+        let mut tag_many = tag.clone();
+        for i in 0..32 {
+            tag_many.add_frame(ExtendedText {
+                description: i.to_string(),
+                value: String::new(),
+            });
+        }
+        assert!(encoder.encode(&tag_many, Vec::new()).is_err());
+
+        let mut tag_large = tag;
+        tag_large.add_frame(Private {
+            owner_identifier: "example.com".to_string(),
+            private_data: vec![0; 4096],
+        });
+        assert!(encoder.encode(&tag_large, Vec::new()).is_err());
     }
 
     #[test]
-    fn write_id3v22_unsynch() {
+    fn write_is_update() {
         if !cfg!(feature = "decode_picture") {
             return;
         }
 
-        let tag = make_tag(Version::Id3v22);
+        let mut tag = make_tag(Version::Id3v24);
+        tag.set_is_update(true);
+        tag.set_restrictions(Some(TagRestrictions::default()));
         let mut buffer = Vec::new();
         Encoder::new()
-            .unsynchronisation(true)
-            .version(Version::Id3v22)
+            .write_crc(true)
             .encode(&tag, &mut buffer)
             .unwrap();
-        let tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
-        assert_eq!(tag, tag_read);
+        let mut warnings = Vec::new();
+        let tag_read = decode_with_warnings(&buffer[..], &mut warnings).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert!(tag_read.is_update());
+        assert_eq!(tag_read.restrictions(), Some(TagRestrictions::default()));
+
+        assert!(!decode(fs::File::open("testdata/id3v24_ext.id3").unwrap())
+            .unwrap()
+            .is_update());
     }
 
     #[test]
-    fn write_id3v22_invalid_id() {
+    fn write_compressed_frames() {
         if !cfg!(feature = "decode_picture") {
             return;
         }
 
-        let mut tag = make_tag(Version::Id3v22);
-        tag.add_frame(Frame::with_content(
-            "XXX",
-            Content::Unknown(Unknown {
-                version: Version::Id3v22,
-                data: vec![1, 2, 3],
-            }),
-        ));
-        tag.add_frame(Frame::with_content(
-            "YYY",
-            Content::Unknown(Unknown {
-                version: Version::Id3v22,
-                data: vec![4, 5, 6],
-            }),
-        ));
-        tag.add_frame(Frame::with_content(
-            "ZZZ",
-            Content::Unknown(Unknown {
-                version: Version::Id3v22,
-                data: vec![7, 8, 9],
-            }),
-        ));
+        for version in [Version::Id3v23, Version::Id3v24] {
+            let mut tag = make_tag(version);
+            tag.set_title("Title");
+            tag.add_frame(Frame::with_content(
+                "TXXX",
+                Content::ExtendedText(ExtendedText {
+                    description: "long".to_string(),
+                    value: "0123456789".repeat(100),
+                }),
+            ));
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .compression(true)
+                .compression_threshold(500)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            assert_eq!(decode(&buffer[..]).unwrap(), tag);
+
+            // Only the large frame is compressed.
+            let compressed = |id: &[u8]| {
+                let start = buffer.windows(4).position(|w| w == id).unwrap();
+                let flags = BigEndian::read_u16(&buffer[start + 8..start + 10]);
+                match version {
+                    Version::Id3v23 => flags == frame::v3::Flags::COMPRESSION.bits(),
+                    _ => {
+                        flags
+                            == (frame::v4::Flags::COMPRESSION
+                                | frame::v4::Flags::DATA_LENGTH_INDICATOR)
+                                .bits()
+                    }
+                }
+            };
+            assert!(compressed(b"TXXX"));
+            assert!(!compressed(b"TIT2"));
+        }
+    }
+
+    #[test]
+    fn write_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quiet.mp3");
+        fs::copy("testdata/quiet.mp3", &path).unwrap();
+        let encoder = Encoder::new().atomic(true);
+
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        let outcome = encoder.write_to_path(&tag, &path).unwrap();
+        assert!(outcome.rewritten);
+        assert_eq!(Tag::read_from_path(&path).unwrap(), tag);
+
+        // A failed write leaves the file untouched.
+        let data = fs::read(&path).unwrap();
+        let mut invalid = tag.clone();
+        invalid.add_frame(EncryptionMethod {
+            owner_identifier: "example.com".to_string(),
+            method_symbol: 0x10,
+            encryption_data: Vec::new(),
+        });
+        assert!(encoder.write_to_path(&invalid, &path).is_err());
+        assert_eq!(fs::read(&path).unwrap(), data);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_symlink() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quiet.mp3");
+        let link = dir.path().join("link.mp3");
+        fs::copy("testdata/quiet.mp3", &path).unwrap();
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        Encoder::new()
+            .atomic(true)
+            .write_to_path(&tag, &link)
+            .unwrap();
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(Tag::read_from_path(&path).unwrap(), tag);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_preserve_mtime() {
+        use std::time::{Duration, SystemTime};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quiet.mp3");
+        fs::copy("testdata/quiet.mp3", &path).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        storage::set_modified(&fs::File::open(&path).unwrap(), modified).unwrap();
+
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        for atomic in [false, true] {
+            let encoder = Encoder::new().atomic(atomic).preserve_mtime(true);
+            encoder.write_to_path(&tag, &path).unwrap();
+            assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), modified);
+        }
+        assert_eq!(Tag::read_from_path(&path).unwrap(), tag);
+    }
+
+    #[test]
+    fn write_text_encoding() {
+        let encoded = |version: Version, text_encoding: Encoding| {
+            let mut tag = Tag::with_version(version);
+            tag.set_title("Title");
+            tag.set_album("東京");
+            tag.add_frame(
+                Frame::with_content("TPE1", Content::Text("Artist".to_string()))
+                    .set_encoding(Some(Encoding::UTF16)),
+            );
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .text_encoding(text_encoding)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            let tag = decode(&buffer[..]).unwrap();
+            assert_eq!(tag.title(), Some("Title"));
+            assert_eq!(tag.album(), Some("東京"));
+            let encoding_byte = |id: &[u8]| {
+                let start = buffer.windows(4).position(|w| w == id).unwrap();
+                buffer[start + 10]
+            };
+            [
+                encoding_byte(b"TIT2"),
+                encoding_byte(b"TALB"),
+                encoding_byte(b"TPE1"),
+            ]
+        };
+        assert_eq!(encoded(Version::Id3v24, Encoding::Latin1), [0, 3, 1]);
+        assert_eq!(encoded(Version::Id3v24, Encoding::UTF16BE), [2, 2, 1]);
+        assert_eq!(encoded(Version::Id3v23, Encoding::Latin1), [0, 1, 1]);
+        assert_eq!(encoded(Version::Id3v23, Encoding::UTF8), [1, 1, 1]);
+    }
+
+    #[test]
+    fn write_encoding_policy_smallest() {
+        for (version, fallback) in [(Version::Id3v23, 1), (Version::Id3v24, 3)] {
+            let mut tag = Tag::with_version(version);
+            tag.set_title("Motörhead");
+            tag.set_album("東京");
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .encoding_policy(EncodingPolicy::Smallest)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            assert_eq!(decode(&buffer[..]).unwrap(), tag);
+            let encoding_byte = |id: &[u8]| {
+                let start = buffer.windows(4).position(|w| w == id).unwrap();
+                buffer[start + 10]
+            };
+            assert_eq!(encoding_byte(b"TIT2"), 0);
+            assert_eq!(encoding_byte(b"TALB"), fallback);
+        }
+    }
+
+    #[test]
+    fn read_write_encrypted_frames() {
+        let encr = b"ENCR\x00\x00\x00\x0a\x00\x00example\0\x81\x00";
+        // Compressed, encrypted and grouped: decompressed size, method and group symbol.
+        let v3_frame = b"TIT2\x00\x00\x00\x0a\x00\xe0\x00\x00\x00\x20\x81\x90\xde\xad\xbe\xef";
+        // Grouped, encrypted and unsynchronised with a data length indicator: group symbol,
+        // method and data length.
+        let v4_frame = b"TIT2\x00\x00\x00\x0a\x00\x47\x90\x81\x00\x00\x01\x00\xde\xad\xbe\xef";
+        for (version, frame) in [
+            (Version::Id3v23, &v3_frame[..]),
+            (Version::Id3v24, &v4_frame[..]),
+        ] {
+            let mut data = Vec::new();
+            data.extend_from_slice(encr);
+            data.extend_from_slice(frame);
+            let mut tag_bytes = vec![b'I', b'D', b'3', version.minor(), 0, 0];
+            tag_bytes.extend_from_slice(&unsynch::encode_u32(data.len() as u32).to_be_bytes());
+            tag_bytes.extend_from_slice(&data);
+
+            let tag = decode(&tag_bytes[..]).unwrap();
+            let encrypted = tag
+                .get("TIT2")
+                .unwrap()
+                .content()
+                .encrypted_frame()
+                .unwrap();
+            assert_eq!(encrypted.method_symbol, 0x81);
+            assert_eq!(encrypted.group_symbol, Some(0x90));
+            assert_eq!(encrypted.data, b"\xde\xad\xbe\xef");
+            let method = tag
+                .get("ENCR")
+                .unwrap()
+                .content()
+                .encryption_method()
+                .unwrap();
+            assert_eq!(method.method_symbol, encrypted.method_symbol);
+
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .version(version)
+                .encode(&tag, &mut buffer)
+                .unwrap();
+            assert_eq!(buffer, tag_bytes);
+        }
+    }
+
+    #[test]
+    fn write_single_mcdi() {
+        let mcdi = |toc: &[u8]| Frame::from(MusicCdIdentifier { toc: toc.to_vec() });
+        let mut tag = Tag::new();
+        tag.add_frame(mcdi(&[1]));
+        tag.add_frame(mcdi(&[2]));
+        assert_eq!(tag.frames().count(), 1);
+        let mut buffer = Vec::new();
+        Encoder::new().encode(&tag, &mut buffer).unwrap();
+        let decoded = decode(io::Cursor::new(buffer)).unwrap();
+        assert_eq!(decoded.music_cd_identifier().unwrap().toc, vec![2]);
+
+        let tag: Tag = vec![mcdi(&[1]), mcdi(&[2])].into_iter().collect();
+        let err = Encoder::new().encode(&tag, &mut Vec::new()).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidInput));
+    }
+
+    #[test]
+    fn write_ufid_policy() {
+        let mut tag = Tag::new();
+        tag.add_frame(UniqueFileIdentifier {
+            owner_identifier: "example.com".to_string(),
+            identifier: vec![b'x'; 65],
+        });
+        let encode = |tag: &Tag, policy| {
+            let mut buffer = Vec::new();
+            let mut warnings = Vec::new();
+            Encoder::new()
+                .ufid_policy(policy)
+                .encode_with_warnings(tag, &mut buffer, &mut warnings)
+                .map(|_| (decode(io::Cursor::new(buffer)).unwrap(), warnings))
+        };
+        let ufid_len = |tag: &Tag| {
+            tag.unique_file_identifiers()
+                .next()
+                .map(|ufid| ufid.identifier.len())
+        };
+
+        let (decoded, warnings) = encode(&tag, UfidPolicy::Warn).unwrap();
+        assert_eq!(ufid_len(&decoded), Some(65));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].frame_id.as_deref(), Some("UFID"));
+
+        let (decoded, warnings) = encode(&tag, UfidPolicy::Truncate).unwrap();
+        assert_eq!(ufid_len(&decoded), Some(64));
+        assert_eq!(warnings.len(), 1);
+
+        let err = encode(&tag, UfidPolicy::Error).unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidInput));
+
+        let mut tag = Tag::new();
+        tag.add_frame(UniqueFileIdentifier {
+            owner_identifier: "".to_string(),
+            identifier: b"id".to_vec(),
+        });
+        let (_, warnings) = encode(&tag, UfidPolicy::Warn).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(encode(&tag, UfidPolicy::Truncate).is_err());
+        assert!(encode(&tag, UfidPolicy::Error).is_err());
+    }
+
+    #[test]
+    fn write_alter_profile() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_duration(1337);
+        let mut flagged = Frame::text("TPUB", "Publisher");
+        flagged.set_file_alter_preservation(true);
+        tag.add_frame(flagged);
+
         let mut buffer = Vec::new();
         Encoder::new()
-            .version(Version::Id3v22)
+            .profile(AlterProfile::MetadataEdit)
             .encode(&tag, &mut buffer)
             .unwrap();
-        let tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
-        assert_eq!(tag, tag_read);
+        let tag = decode(io::Cursor::new(buffer)).unwrap();
+        assert_eq!(tag.frames().count(), 3);
+        assert!(!tag.get("TIT2").unwrap().file_alter_preservation());
+        assert!(tag.get("TLEN").unwrap().file_alter_preservation());
+        assert!(tag.get("TPUB").unwrap().file_alter_preservation());
+
+        let mut buffer = Vec::new();
+        Encoder::new()
+            .profile(AlterProfile::Transcode)
+            .encode(&tag, &mut buffer)
+            .unwrap();
+        let tag = decode(io::Cursor::new(buffer)).unwrap();
+        let ids: Vec<_> = tag.frames().map(|f| f.id()).collect();
+        assert_eq!(ids, ["TIT2", "TPUB"]);
     }
 
     #[test]
-    fn write_id3v23() {
-        if !cfg!(feature = "decode_picture") {
-            return;
-        }
+    fn write_alter_preservation_known_frames() {
+        let frame = |id: &[u8], flags: u16, data: &[u8]| {
+            let mut buf = id.to_vec();
+            buf.extend(unsynch::encode_u32(data.len() as u32).to_be_bytes());
+            buf.extend(flags.to_be_bytes());
+            buf.extend(data);
+            buf
+        };
+        let mut frames = Vec::new();
+        frames.extend(frame(b"TIT2", 0x4000, b"\x03Title"));
+        frames.extend(frame(b"XTST", 0x4000, b"data"));
+        frames.extend(frame(b"XFIL", 0x2000, b"data"));
+        frames.extend(frame(b"TPE1", 0, b"\x03Artist"));
+        let mut buf = b"ID3\x04\x00\x00".to_vec();
+        buf.extend(unsynch::encode_u32(frames.len() as u32).to_be_bytes());
+        buf.extend(&frames);
 
-        let tag = make_tag(Version::Id3v23);
+        // Read, modify and write the tag. Only the unknown frame with the Tag Alter Preservation
+        // flag is discarded.
+        let mut tag = decode(&buf[..]).unwrap();
+        tag.set_artist("Other");
+        let mut buffer = Vec::new();
+        Encoder::new().encode(&tag, &mut buffer).unwrap();
+        let tag = decode(&buffer[..]).unwrap();
+        assert_eq!(tag.title(), Some("Title"));
+        assert_eq!(tag.artist(), Some("Other"));
+        assert!(tag.get("XTST").is_none());
+        assert!(tag.get("XFIL").is_some());
+
+        // The unknown frame with the File Alter Preservation flag is discarded on a transcode.
         let mut buffer = Vec::new();
         Encoder::new()
-            .version(Version::Id3v23)
+            .profile(AlterProfile::Transcode)
             .encode(&tag, &mut buffer)
             .unwrap();
-        let tag_read = decode(&mut io::Cursor::new(buffer)).unwrap();
-        assert_eq!(tag, tag_read);
+        let tag = decode(&buffer[..]).unwrap();
+        assert_eq!(tag.title(), Some("Title"));
+        assert!(tag.get("XFIL").is_none());
     }
 
     #[test]
@@ -976,6 +4561,147 @@ mod tests {
         assert_eq!(tag, tag_read);
     }
 
+    #[test]
+    fn write_auto_unsynch() {
+        // UTF-16 text starts with a byte order mark that is a false sync, so use Latin-1.
+        let mut tag = Tag::new();
+        tag.add_frame(Frame::text("TIT2", "Title").set_encoding(Some(Encoding::Latin1)));
+        let encode = |tag: &Tag, version| {
+            let mut buffer = Vec::new();
+            Encoder::new()
+                .auto_unsynchronisation(true)
+                .version(version)
+                .encode(tag, &mut buffer)
+                .unwrap();
+            assert_eq!(&decode(&buffer[..]).unwrap(), tag);
+            crate::inspect::inspect(&buffer[..]).unwrap()
+        };
+
+        for version in [Version::Id3v23, Version::Id3v24] {
+            let report = encode(&tag, version);
+            assert!(!report.unsynchronised);
+            assert!(!report.frames[0].unsynchronised);
+        }
+
+        tag.add_frame(Private {
+            owner_identifier: "owner".to_string(),
+            private_data: vec![0xff, 0xfb, 0x90, 0x00, 0xff],
+        });
+        let report = encode(&tag, Version::Id3v23);
+        assert!(report.unsynchronised);
+        let report = encode(&tag, Version::Id3v24);
+        assert!(!report.unsynchronised);
+        assert!(!report.frames[0].unsynchronised);
+        assert!(report.frames[1].unsynchronised);
+    }
+
+    #[test]
+    fn write_encoded_size() {
+        for version in [Version::Id3v22, Version::Id3v23, Version::Id3v24] {
+            let mut tag = make_tag(version);
+            if version != Version::Id3v22 {
+                tag.add_frame(Private {
+                    owner_identifier: "owner".to_string(),
+                    private_data: vec![0xff, 0xfb, 0x90, 0x00, 0xff],
+                });
+            }
+            let encoders = [
+                Encoder::new(),
+                Encoder::new().padding(100),
+                Encoder::new().auto_unsynchronisation(true),
+                Encoder::new().unsynchronisation(true),
+                Encoder::new().compression(true).compression_threshold(0),
+                Encoder::new()
+                    .write_crc(true)
+                    .padding_policy(PaddingPolicy::Percentage(10)),
+                Encoder::new().footer(true),
+            ];
+            for encoder in encoders {
+                let encoder = encoder.version(version);
+                let mut buf = Vec::new();
+                encoder.encode(&tag, &mut buf).unwrap();
+                assert_eq!(encoder.encoded_size(&tag).unwrap(), buf.len() as u64);
+            }
+            let buf = tag.to_bytes(version).unwrap();
+            assert_eq!(tag.encoded_size(version).unwrap(), buf.len() as u64);
+        }
+    }
+
+    #[test]
+    fn decode_all_concatenated() {
+        let mut stream = Vec::new();
+        fs::File::open("testdata/quiet.mp3")
+            .unwrap()
+            .read_to_end(&mut stream)
+            .unwrap();
+
+        let mut first = Tag::new();
+        first.set_title("First");
+        let first_offset = stream.len() as u64;
+        Encoder::new()
+            .version(Version::Id3v23)
+            .encode(&first, &mut stream)
+            .unwrap();
+
+        // Magic that is not followed by a valid header must be skipped.
+        stream.extend_from_slice(b"xxID3\x04\x00\x00\xff\xff\xff\xff");
+        stream.extend_from_slice(b"ID3\x09\x00\x00\x00\x00\x00\x00");
+
+        // Place the second tag across the boundary of the chunks that are read.
+        stream.resize(0x10000 - 15, 0xaa);
+        let mut second = Tag::new();
+        second.set_title("Second");
+        second.set_artist("Artist");
+        let second_offset = stream.len() as u64;
+        Encoder::new()
+            .version(Version::Id3v24)
+            .encode(&second, &mut stream)
+            .unwrap();
+        stream.extend_from_slice(&[0xaa; 100]);
+
+        let tags = decode_all(&stream[..], stream.len() as u64).unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0], (first_offset, first));
+        assert_eq!(tags[1], (second_offset, second));
+
+        let merged = Tag::merged(tags.into_iter().map(|(_, tag)| tag));
+        assert_eq!(merged.title(), Some("Second"));
+        assert_eq!(merged.artist(), Some("Artist"));
+        assert_eq!(merged.version(), Version::Id3v23);
+    }
+
+    #[test]
+    fn decode_all_skips_audio() {
+        let audio_frame = |body: &[u8]| {
+            let mut frame = vec![0xff, 0xfb, 0x90, 0x00];
+            frame.extend(body);
+            frame.resize(417, 0);
+            frame
+        };
+        let mut hidden = Tag::new();
+        hidden.set_title("Hidden");
+        let mut stream = audio_frame(&hidden.to_bytes(Version::Id3v24).unwrap());
+        stream.extend(audio_frame(&[]));
+
+        // A tag with a frame that can not be decoded is returned with the other frames.
+        let mut frames = b"TIT2\x00\x00\x00\x05\x00\x00\x03Real".to_vec();
+        frames.extend(b"TALB\x00\x00\x00\x04\x00\x00\x09bad");
+        let offset = stream.len() as u64;
+        stream.extend(b"ID3\x04\x00\x00");
+        stream.extend(unsynch::encode_u32(frames.len() as u32).to_be_bytes());
+        stream.extend(&frames);
+        stream.extend(audio_frame(&[]));
+
+        // A header that claims more data than the stream holds is skipped.
+        stream.extend(b"ID3\x04\x00\x00\x7f\x7f\x7f\x7f");
+
+        let tags = decode_all(&stream[..], stream.len() as u64).unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].0, offset);
+        assert_eq!(tags[0].1.title(), Some("Real"));
+        assert_eq!(tags[0].1.album(), None);
+    }
+
     #[test]
     fn write_id3v24_alter_file() {
         if !cfg!(feature = "decode_picture") {
@@ -1099,6 +4825,7 @@ mod tests {
             flags: Flags::empty(),
             tag_size: 10,
             ext_header_size: 20,
+            ext: ExtHeader::default(),
         };
 
         // Without saturating_sub, this would underflow and cause a panic.
@@ -4,7 +4,19 @@
 //! with tags in MPEG 1/2 layer I, II and III, MPEG 2.5 and AAC files.
 use std::io;
 
-/// Returns the synchsafe variant of a `u32` value.
+/// Returns the synchsafe variant of a `u32` value. In a synchsafe integer, the most significant
+/// bit of every byte is zero, so only 28 bits are available.
+///
+/// # Panics
+/// Panics if the value does not fit in 28 bits.
+///
+/// # Example
+/// ```
+/// use id3::util;
+///
+/// assert_eq!(util::encode_u32(0x0fff_ffff), 0x7f7f_7f7f);
+/// assert_eq!(util::encode_u32(128), 0x0100);
+/// ```
 pub fn encode_u32(n: u32) -> u32 {
     assert!(n < 0x1000_0000);
     let mut x: u32 = n & 0x7F | (n & 0xFFFF_FF80) << 1;
@@ -13,7 +25,16 @@ pub fn encode_u32(n: u32) -> u32 {
     x
 }
 
-/// Returns the unsynchsafe varaiant of a `u32` value.
+/// Returns the plain variant of a synchsafe `u32` value. The most significant bit of every byte is
+/// ignored.
+///
+/// # Example
+/// ```
+/// use id3::util;
+///
+/// assert_eq!(util::decode_u32(0x7f7f_7f7f), 0x0fff_ffff);
+/// assert_eq!(util::decode_u32(0x0100), 128);
+/// ```
 pub fn decode_u32(n: u32) -> u32 {
     n & 0xFF | (n & 0xFF00) >> 1 | (n & 0xFF_0000) >> 2 | (n & 0xFF00_0000) >> 3
 }
@@ -21,6 +42,21 @@ pub fn decode_u32(n: u32) -> u32 {
 /// Decoder for an unsynchronized stream of bytes.
 ///
 /// The decoder has an internal buffer.
+///
+/// # Example
+/// ```
+/// use id3::util;
+/// use std::io::Read;
+///
+/// let mut data = vec![0x42, 0xff, 0x00, 0x42];
+/// util::encode_vec(&mut data);
+/// assert_eq!(data, [0x42, 0xff, 0x00, 0x00, 0x42]);
+///
+/// let mut decoded = Vec::new();
+/// util::Reader::new(&data[..]).read_to_end(&mut decoded)?;
+/// assert_eq!(decoded, [0x42, 0xff, 0x00, 0x42]);
+/// # Ok::<(), std::io::Error>(())
+/// ```
 pub struct Reader<R>
 where
     R: io::Read,
@@ -36,6 +72,7 @@ impl<R> Reader<R>
 where
     R: io::Read,
 {
+    /// Creates a decoder that reads the unsynchronized bytes from `reader`.
     pub fn new(reader: R) -> Reader<R> {
         Reader {
             reader,
@@ -84,18 +121,86 @@ where
     }
 }
 
+/// Encoder applying the unsynchronization scheme to a stream of bytes.
+///
+/// Produces the same output as [`encode_vec`] on the concatenation of all written bytes once
+/// [`Writer::finish`] has been called.
+pub(crate) struct Writer<W>
+where
+    W: io::Write,
+{
+    writer: W,
+    pending_ff: bool,
+}
+
+impl<W> Writer<W>
+where
+    W: io::Write,
+{
+    /// Creates a new unsynchronization encoder writing to the specified writer.
+    pub(crate) fn new(writer: W) -> Writer<W> {
+        Writer {
+            writer,
+            pending_ff: false,
+        }
+    }
+
+    /// Terminates the stream, which inserts a zero byte if the last byte written was `0xFF`.
+    pub(crate) fn finish(mut self) -> io::Result<W> {
+        if self.pending_ff {
+            self.writer.write_all(&[0x00])?;
+        }
+        Ok(self.writer)
+    }
+}
+
+impl<W> io::Write for Writer<W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut start = 0;
+        for (i, &b) in buf.iter().enumerate() {
+            if self.pending_ff && (b == 0x00 || b & 0xE0 == 0xE0) {
+                self.writer.write_all(&buf[start..i])?;
+                self.writer.write_all(&[0x00])?;
+                start = i;
+            }
+            self.pending_ff = b == 0xFF;
+        }
+        self.writer.write_all(&buf[start..])?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
 /// Applies the unsynchronization scheme to a byte buffer.
+///
+/// A zero byte is inserted after every `0xFF` byte that is followed by a zero byte or a byte of
+/// which the three most significant bits are set, and after a `0xFF` byte at the end of the
+/// buffer.
 pub fn encode_vec(buffer: &mut Vec<u8>) {
-    let mut repeat_next_null_byte = false;
-    let mut i = 0;
-    while i < buffer.len() {
-        if buffer[i] == 0x00 && repeat_next_null_byte {
-            buffer.insert(i, 0);
-            i += 1;
+    let mut out = Vec::with_capacity(buffer.len());
+    for (i, &b) in buffer.iter().enumerate() {
+        out.push(b);
+        if b == 0xFF
+            && buffer
+                .get(i + 1)
+                .map_or(true, |&next| next == 0x00 || next & 0xE0 == 0xE0)
+        {
+            out.push(0x00);
         }
-        repeat_next_null_byte = buffer[i] == 0xFF;
-        i += 1;
     }
+    *buffer = out;
+}
+
+/// Returns whether the data contains a false synchronisation: a `0xFF` byte followed by a byte of
+/// which the three most significant bits are set, or a `0xFF` byte at the end of the data.
+pub fn has_false_sync(data: &[u8]) -> bool {
+    data.last() == Some(&0xFF) || data.windows(2).any(|w| w[0] == 0xFF && w[1] & 0xE0 == 0xE0)
 }
 
 #[cfg(test)]
@@ -128,6 +233,23 @@ mod tests {
         assert_eq!(v, [66, 0, 255, 0, 255, 0, 0, 255, 66]);
     }
 
+    #[test]
+    fn synchronization_false_sync() {
+        let orig = vec![0xff, 0xe0, 0x42, 0xff, 0xff, 0xfb, 0x90, 0xff];
+        assert!(has_false_sync(&orig));
+        let mut recoded = orig.clone();
+        encode_vec(&mut recoded);
+        assert_eq!(
+            recoded,
+            [0xff, 0x00, 0xe0, 0x42, 0xff, 0x00, 0xff, 0x00, 0xfb, 0x90, 0xff, 0x00]
+        );
+        assert!(!has_false_sync(&recoded));
+        decode_vec(&mut recoded);
+        assert_eq!(orig, recoded);
+
+        assert!(!has_false_sync(&[0xff, 0x00, 0xff, 0x42]));
+    }
+
     #[test]
     fn synchronization_jpeg() {
         let orig = vec![
@@ -153,4 +275,17 @@ mod tests {
         decode_vec(&mut recoded);
         assert_eq!(orig, recoded);
     }
+
+    #[test]
+    fn writer_matches_encode_vec() {
+        let data = [0x42, 0xff, 0x00, 0xff, 0xe0, 0xff, 0x42, 0xff];
+        for split in 0..=data.len() {
+            let mut writer = Writer::new(Vec::new());
+            io::Write::write_all(&mut writer, &data[..split]).unwrap();
+            io::Write::write_all(&mut writer, &data[split..]).unwrap();
+            let mut expected = data.to_vec();
+            encode_vec(&mut expected);
+            assert_eq!(writer.finish().unwrap(), expected);
+        }
+    }
 }
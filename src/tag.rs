@@ -1,17 +1,23 @@
 use crate::chunk;
 use crate::frame::{
-    Chapter, Comment, EncapsulatedObject, ExtendedLink, ExtendedText, Frame, InvolvedPeopleList,
-    Lyrics, Picture, SynchronisedLyrics, TableOfContents, UniqueFileIdentifier,
+    Chapter, Comment, Content, EncapsulatedObject, EventTimingCodes, ExtendedLink, ExtendedText,
+    Frame, GroupRegistration, InvolvedPeopleList, Lyrics, MusicCdIdentifier, Picture,
+    RelativeVolumeAdjustment, Signature, SynchronisedLyrics, TableOfContents, Timestamp,
+    UniqueFileIdentifier,
 };
-use crate::storage::{plain::PlainStorage, Format, Storage};
+use crate::storage::{self, dsf, plain::PlainStorage, Format, Storage};
 use crate::stream;
+use crate::tagged_file::{self, TagLocation};
 use crate::taglike::TagLike;
 use crate::v1;
+#[cfg(feature = "tokio")]
+use crate::AsyncStorageFile;
 use crate::StorageFile;
-use crate::{Error, ErrorKind};
+use crate::{ChunkId, EncapsulatedObjectReader, Error, ErrorKind, Warning, WriteOutcome};
+use std::collections::HashSet;
 use std::fmt;
 use std::fs::{self, File};
-use std::io::{self, BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, Seek, Write};
 use std::iter::{FromIterator, Iterator};
 use std::path::Path;
 
@@ -55,6 +61,152 @@ impl fmt::Display for Version {
     }
 }
 
+/// Restrictions that apply to an ID3v2.4 tag, as stored in its extended header.
+///
+/// The restrictions are meant for taggers that modify the tag, they are not enforced when
+/// decoding. See [`Encoder::enforce_restrictions`](crate::Encoder::enforce_restrictions).
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq, Hash)]
+pub struct TagRestrictions {
+    /// Limits the number of frames and the total size of the tag.
+    pub tag_size: TagSizeRestriction,
+    /// Whether text may only be encoded using ISO-8859-1 or UTF-8.
+    pub latin1_or_utf8_only: bool,
+    /// Limits the length of text fields.
+    pub text_size: TextSizeRestriction,
+    /// Whether images may only be stored as PNG or JPEG.
+    pub png_or_jpeg_only: bool,
+    /// Limits the dimensions of images.
+    pub image_size: ImageSizeRestriction,
+}
+
+impl TagRestrictions {
+    pub(crate) fn from_byte(b: u8) -> TagRestrictions {
+        TagRestrictions {
+            tag_size: match b >> 6 {
+                0 => TagSizeRestriction::Max128Frames1MiB,
+                1 => TagSizeRestriction::Max64Frames128KiB,
+                2 => TagSizeRestriction::Max32Frames40KiB,
+                _ => TagSizeRestriction::Max32Frames4KiB,
+            },
+            latin1_or_utf8_only: b & 0x20 != 0,
+            text_size: match (b >> 3) & 0x03 {
+                0 => TextSizeRestriction::None,
+                1 => TextSizeRestriction::Max1024Chars,
+                2 => TextSizeRestriction::Max128Chars,
+                _ => TextSizeRestriction::Max30Chars,
+            },
+            png_or_jpeg_only: b & 0x04 != 0,
+            image_size: match b & 0x03 {
+                0 => ImageSizeRestriction::None,
+                1 => ImageSizeRestriction::Max256x256,
+                2 => ImageSizeRestriction::Max64x64,
+                _ => ImageSizeRestriction::Exactly64x64,
+            },
+        }
+    }
+
+    pub(crate) fn to_byte(self) -> u8 {
+        let tag_size = match self.tag_size {
+            TagSizeRestriction::Max128Frames1MiB => 0,
+            TagSizeRestriction::Max64Frames128KiB => 1,
+            TagSizeRestriction::Max32Frames40KiB => 2,
+            TagSizeRestriction::Max32Frames4KiB => 3,
+        };
+        let text_size = match self.text_size {
+            TextSizeRestriction::None => 0,
+            TextSizeRestriction::Max1024Chars => 1,
+            TextSizeRestriction::Max128Chars => 2,
+            TextSizeRestriction::Max30Chars => 3,
+        };
+        let image_size = match self.image_size {
+            ImageSizeRestriction::None => 0,
+            ImageSizeRestriction::Max256x256 => 1,
+            ImageSizeRestriction::Max64x64 => 2,
+            ImageSizeRestriction::Exactly64x64 => 3,
+        };
+        tag_size << 6
+            | u8::from(self.latin1_or_utf8_only) << 5
+            | text_size << 3
+            | u8::from(self.png_or_jpeg_only) << 2
+            | image_size
+    }
+}
+
+/// Limits the number of frames and total size of a tag, see [`TagRestrictions`].
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq, Hash)]
+pub enum TagSizeRestriction {
+    /// No more than 128 frames and 1 MiB.
+    #[default]
+    Max128Frames1MiB,
+    /// No more than 64 frames and 128 KiB.
+    Max64Frames128KiB,
+    /// No more than 32 frames and 40 KiB.
+    Max32Frames40KiB,
+    /// No more than 32 frames and 4 KiB.
+    Max32Frames4KiB,
+}
+
+impl TagSizeRestriction {
+    /// Returns the maximum number of frames.
+    pub fn max_frames(self) -> usize {
+        match self {
+            TagSizeRestriction::Max128Frames1MiB => 128,
+            TagSizeRestriction::Max64Frames128KiB => 64,
+            TagSizeRestriction::Max32Frames40KiB | TagSizeRestriction::Max32Frames4KiB => 32,
+        }
+    }
+
+    /// Returns the maximum size of the tag in bytes.
+    pub fn max_size(self) -> u64 {
+        match self {
+            TagSizeRestriction::Max128Frames1MiB => 1024 * 1024,
+            TagSizeRestriction::Max64Frames128KiB => 128 * 1024,
+            TagSizeRestriction::Max32Frames40KiB => 40 * 1024,
+            TagSizeRestriction::Max32Frames4KiB => 4 * 1024,
+        }
+    }
+}
+
+/// Limits the length of text fields, see [`TagRestrictions`].
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq, Hash)]
+pub enum TextSizeRestriction {
+    /// No restriction.
+    #[default]
+    None,
+    /// No longer than 1024 characters.
+    Max1024Chars,
+    /// No longer than 128 characters.
+    Max128Chars,
+    /// No longer than 30 characters.
+    Max30Chars,
+}
+
+impl TextSizeRestriction {
+    /// Returns the maximum number of characters of a text field, if restricted.
+    pub fn max_chars(self) -> Option<usize> {
+        match self {
+            TextSizeRestriction::None => None,
+            TextSizeRestriction::Max1024Chars => Some(1024),
+            TextSizeRestriction::Max128Chars => Some(128),
+            TextSizeRestriction::Max30Chars => Some(30),
+        }
+    }
+}
+
+/// Limits the dimensions of images, see [`TagRestrictions`].
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq, Hash)]
+pub enum ImageSizeRestriction {
+    /// No restriction.
+    #[default]
+    None,
+    /// No larger than 256x256 pixels.
+    Max256x256,
+    /// No larger than 64x64 pixels.
+    Max64x64,
+    /// Exactly 64x64 pixels, unless required otherwise.
+    Exactly64x64,
+}
+
 /// An ID3 tag containing zero or more [`Frame`]s.
 #[derive(Clone, Debug, Default, Eq)]
 pub struct Tag {
@@ -62,6 +214,10 @@ pub struct Tag {
     frames: Vec<Frame>,
     /// ID3 Tag version
     version: Version,
+    /// The restrictions from the extended header.
+    restrictions: Option<TagRestrictions>,
+    /// Whether the tag is an update of an earlier tag.
+    is_update: bool,
 }
 
 impl<'a> Tag {
@@ -115,6 +271,21 @@ impl<'a> Tag {
         Ok(true)
     }
 
+    /// Returns the amount of padding of the ID3v2 tag at the start of the reader. This is the
+    /// space that is available for the tag to grow without moving the data that follows it.
+    ///
+    /// Null bytes directly following the tag are counted as padding, as they are overwritten
+    /// when the tag is written.
+    pub fn padding_from(reader: impl io::Read + io::Seek) -> crate::Result<u64> {
+        stream::tag::padding_size(reader)
+    }
+
+    /// Returns the amount of padding of the ID3v2 tag of the file at the specified path, see
+    /// [`Tag::padding_from`].
+    pub fn padding_from_path(path: impl AsRef<Path>) -> crate::Result<u64> {
+        Tag::padding_from(File::open(path)?)
+    }
+
     /// Removes an ID3v2 tag from the file at the specified path.
     ///
     /// Returns true if the file initially contained a tag.
@@ -128,11 +299,12 @@ impl<'a> Tag {
         Self::remove_from_file(&mut file)
     }
 
-    /// Removes an ID3v2 tag from the specified file.
+    /// Removes an ID3v2 tag from the specified file, along with any tags that are stacked directly
+    /// after it.
     ///
     /// Returns true if the file initially contained a tag.
     pub fn remove_from_file(mut file: impl StorageFile) -> crate::Result<bool> {
-        let location = match stream::tag::locate_id3v2(&mut file) {
+        let location = match stream::tag::locate_stacked_id3v2(&mut file) {
             Ok(l) => l,
             Err(Error {
                 kind: ErrorKind::NoTag,
@@ -159,23 +331,151 @@ impl<'a> Tag {
     ///
     /// In the case of both Aiff/Wav tags and a ID3 header being present, the header takes
     /// precense.
+    ///
+    /// If there is no tag at the start, a tag that is appended to the end is read, see
+    /// [`Tag::read_from_end`]. If the tag has a SEEK frame, the tag it points to is read as well
+    /// and merged into it. Tags that are stacked directly after the first one are not read, see
+    /// [`Tag::read_all_from`].
     pub fn read_from2(reader: impl io::Read + io::Seek) -> crate::Result<Tag> {
         let mut b = BufReader::new(reader);
         let probe = b.fill_buf()?;
 
         match Format::magic(probe) {
-            Some(Format::Header) | None => stream::tag::decode(b),
+            Some(Format::Header) | None => {
+                stream::tag::decode_seekable_with_warnings(b, &mut Vec::new())
+            }
             Some(Format::Aiff) => chunk::load_id3_chunk::<chunk::AiffFormat, _>(b),
             Some(Format::Wav) => chunk::load_id3_chunk::<chunk::WavFormat, _>(b),
+            Some(Format::Dsdiff) => chunk::load_id3_chunk::<chunk::DsdiffFormat, _>(b),
+            Some(Format::Dsf) => dsf::load_id3_with_warnings(b, &mut Vec::new()),
         }
     }
 
+    /// Attempts to read an ID3 tag from a byte slice, like [`Tag::read_from2`].
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike, Version};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// let bytes = tag.to_bytes(Version::Id3v24)?;
+    ///
+    /// let tag = Tag::from_bytes(&bytes)?;
+    /// assert_eq!(tag.title(), Some("Title"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> crate::Result<Tag> {
+        Tag::read_from2(io::Cursor::new(bytes))
+    }
+
+    /// Attempts to read an ID3 tag from the reader, like [`Tag::read_from2`], and additionally
+    /// returns any recoverable issues that were encountered while decoding.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike, Version};
+    /// use std::io::Cursor;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// let mut buf = Vec::new();
+    /// tag.write_to(&mut buf, Version::Id3v24).unwrap();
+    ///
+    /// let (tag, warnings) = Tag::read_with_warnings(Cursor::new(buf)).unwrap();
+    /// assert_eq!(tag.title(), Some("Title"));
+    /// assert!(warnings.is_empty());
+    /// ```
+    pub fn read_with_warnings(
+        reader: impl io::Read + io::Seek,
+    ) -> crate::Result<(Tag, Vec<Warning>)> {
+        let mut warnings = Vec::new();
+        let mut b = BufReader::new(reader);
+        let probe = b.fill_buf()?;
+
+        let tag = match Format::magic(probe) {
+            Some(Format::Header) | None => {
+                stream::tag::decode_seekable_with_warnings(b, &mut warnings)
+            }
+            Some(Format::Aiff) => {
+                chunk::load_id3_chunk_with_warnings::<chunk::AiffFormat, _>(b, &mut warnings)
+            }
+            Some(Format::Wav) => {
+                chunk::load_id3_chunk_with_warnings::<chunk::WavFormat, _>(b, &mut warnings)
+            }
+            Some(Format::Dsdiff) => {
+                chunk::load_id3_chunk_with_warnings::<chunk::DsdiffFormat, _>(b, &mut warnings)
+            }
+            Some(Format::Dsf) => dsf::load_id3_with_warnings(b, &mut warnings),
+        }?;
+        Ok((tag, warnings))
+    }
+
+    /// Attempts to read an ID3 tag from the file at the indicated path, like
+    /// [`Tag::read_from_path`], and additionally returns any recoverable issues that were
+    /// encountered while decoding.
+    pub fn read_with_warnings_from_path(
+        path: impl AsRef<Path>,
+    ) -> crate::Result<(Tag, Vec<Warning>)> {
+        Tag::read_with_warnings(File::open(path)?)
+    }
+
+    /// Looks up the encapsulated object (GEOB) with the specified description and returns a
+    /// reader over its data, without decoding the rest of the tag or loading the object into
+    /// memory. The reader must be positioned at the start of an ID3v2 tag.
+    ///
+    /// Returns `None` if the tag has no such object.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::frame::EncapsulatedObject;
+    /// use id3::{Tag, TagLike, Version};
+    /// use std::io::{Cursor, Read};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(EncapsulatedObject {
+    ///     mime_type: "application/octet-stream".to_string(),
+    ///     filename: "".to_string(),
+    ///     description: "Serato Analysis".to_string(),
+    ///     data: vec![1, 2, 3],
+    /// });
+    /// let mut buf = Vec::new();
+    /// tag.write_to(&mut buf, Version::Id3v24)?;
+    ///
+    /// let mut object = Tag::read_encapsulated_object(Cursor::new(buf), "Serato Analysis")?
+    ///     .expect("object not found");
+    /// assert_eq!(object.mime_type, "application/octet-stream");
+    /// let mut data = Vec::new();
+    /// object.read_to_end(&mut data)?;
+    /// assert_eq!(data, [1, 2, 3]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn read_encapsulated_object<R: io::Read + io::Seek>(
+        reader: R,
+        description: &str,
+    ) -> crate::Result<Option<EncapsulatedObjectReader<R>>> {
+        stream::tag::read_encapsulated_object(reader, description)
+    }
+
     /// Attempts to read an ID3 tag via Tokio from the reader.
+    ///
+    /// Like [`Tag::read_from2`], the tag is also read from AIFF, WAV, DSDIFF and DSF streams.
     #[cfg(feature = "tokio")]
     pub async fn async_read_from(
         reader: impl tokio::io::AsyncRead + std::marker::Unpin,
     ) -> crate::Result<Tag> {
-        stream::tag::async_decode(reader).await
+        use tokio::io::AsyncBufReadExt;
+
+        let mut b = tokio::io::BufReader::new(reader);
+        let probe = b.fill_buf().await?;
+
+        match Format::magic(probe) {
+            Some(Format::Header) | None => stream::tag::async_decode(b).await,
+            Some(Format::Aiff) => chunk::async_load_id3_chunk::<chunk::AiffFormat, _>(b).await,
+            Some(Format::Wav) => chunk::async_load_id3_chunk::<chunk::WavFormat, _>(b).await,
+            Some(Format::Dsdiff) => chunk::async_load_id3_chunk::<chunk::DsdiffFormat, _>(b).await,
+            Some(Format::Dsf) => dsf::async_load_id3(b).await,
+        }
     }
 
     /// Attempts to read an ID3 tag from the file at the indicated path.
@@ -183,11 +483,132 @@ impl<'a> Tag {
         Tag::read_from2(File::open(path)?)
     }
 
+    /// Attempts to read an ID3v2.4 tag that is appended to the end of the reader.
+    ///
+    /// Appended tags are found using their footer, see
+    /// [`Encoder::footer`](crate::Encoder::footer). An ID3v1 tag following the ID3v2 tag is
+    /// skipped. If there is no footer, an error with [`ErrorKind::NoTag`] is returned.
+    pub fn read_from_end(mut reader: impl io::Read + io::Seek) -> crate::Result<Tag> {
+        let location = stream::tag::locate_id3v2_footer(&mut reader)?;
+        reader.seek(io::SeekFrom::Start(location.start))?;
+        stream::tag::decode(reader.take(location.end - location.start))
+    }
+
+    /// Reads every ID3v2 tag from the reader, not just the one at the start. This is useful for
+    /// files that were stitched together from several tagged streams, such as podcasts or stream
+    /// rips.
+    ///
+    /// Each tag is returned together with the byte offset at which its header starts, counted from
+    /// the position of the reader. Use [`Tag::merged`] to combine them into a single tag. Taggers
+    /// that stack tags put the newest one in front, so to let its frames take precedence, merge the
+    /// tags in reverse order.
+    ///
+    /// MPEG audio frames are skipped as a whole, so "ID3" occurring by chance in the audio is not
+    /// mistaken for a tag. A tag of which some frames can not be decoded is returned with the
+    /// frames that can.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike, Version};
+    /// use std::io::Cursor;
+    ///
+    /// let mut first = Tag::new();
+    /// first.set_title("Part 1");
+    /// let mut second = Tag::new();
+    /// second.set_title("Part 2");
+    ///
+    /// let mut stream = first.to_bytes(Version::Id3v24)?;
+    /// let offset = stream.len() as u64 + 4;
+    /// stream.extend_from_slice(&[0xff, 0xfb, 0x90, 0x00]);
+    /// stream.extend(second.to_bytes(Version::Id3v24)?);
+    ///
+    /// let tags = Tag::read_all_from(Cursor::new(stream))?;
+    /// assert_eq!(tags.len(), 2);
+    /// assert_eq!(tags[0].0, 0);
+    /// assert_eq!(tags[1].0, offset);
+    /// assert_eq!(tags[1].1.title(), Some("Part 2"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn read_all_from(mut reader: impl io::Read + io::Seek) -> crate::Result<Vec<(u64, Tag)>> {
+        let start = reader.stream_position()?;
+        let len = reader.seek(io::SeekFrom::End(0))?.saturating_sub(start);
+        reader.seek(io::SeekFrom::Start(start))?;
+        stream::tag::decode_all(BufReader::new(reader), len)
+    }
+
+    /// Reads every ID3v2 tag from the file at the indicated path, like [`Tag::read_all_from`].
+    pub fn read_all_from_path(path: impl AsRef<Path>) -> crate::Result<Vec<(u64, Tag)>> {
+        Tag::read_all_from(File::open(path)?)
+    }
+
+    /// Lists every block of metadata in the reader, ordered by their position in the file.
+    ///
+    /// This reports ID3v2 tags at the start of the file, including stacked ones, ID3v2 tags
+    /// appended to the end, ID3 chunks of AIFF, WAV and DSDIFF files, and ID3v1 tags along with
+    /// their extended data. The tags themselves are not decoded, which makes this useful for
+    /// showing what a file contains without failing on broken tags.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagKind, TagLike, Version};
+    /// use std::io::Cursor;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// let mut data = tag.to_bytes(Version::Id3v23)?;
+    /// data.extend_from_slice(&[0xff; 100]);
+    /// let mut file = Cursor::new(data);
+    /// id3::v1::Tag::from(&tag).write_to_file(&mut file)?;
+    ///
+    /// let locations = Tag::locate(&mut file)?;
+    /// assert_eq!(locations.len(), 2);
+    /// assert_eq!(locations[0].kind, TagKind::Id3v2Header);
+    /// assert_eq!(locations[0].range.start, 0);
+    /// assert_eq!(locations[0].version, Some(Version::Id3v23));
+    /// assert_eq!(locations[1].kind, TagKind::Id3v1);
+    /// assert_eq!(locations[1].range.end, file.get_ref().len() as u64);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn locate(reader: impl io::Read + io::Seek) -> crate::Result<Vec<TagLocation>> {
+        tagged_file::locate(reader)
+    }
+
+    /// Merges several tags into one. The frames of the tags are added in order, so a frame of a
+    /// later tag replaces a conflicting frame of an earlier tag. The resulting tag has the version
+    /// of the first tag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut first = Tag::new();
+    /// first.set_title("Title");
+    /// first.set_artist("Artist");
+    /// let mut second = Tag::new();
+    /// second.set_title("Better Title");
+    ///
+    /// let tag = Tag::merged([first, second]);
+    /// assert_eq!(tag.title(), Some("Better Title"));
+    /// assert_eq!(tag.artist(), Some("Artist"));
+    /// ```
+    pub fn merged(tags: impl IntoIterator<Item = Tag>) -> Tag {
+        let mut tags = tags.into_iter();
+        let mut merged = match tags.next() {
+            Some(tag) => tag,
+            None => return Tag::new(),
+        };
+        for tag in tags {
+            for frame in tag.frames {
+                merged.add_frame(frame);
+            }
+        }
+        merged
+    }
+
     /// Attempts to read an ID3 tag via Tokio from the file at the indicated path.
     #[cfg(feature = "tokio")]
     pub async fn async_read_from_path(path: impl AsRef<Path>) -> crate::Result<Tag> {
-        let file = tokio::io::BufReader::new(tokio::fs::File::open(path).await?);
-        stream::tag::async_decode(file).await
+        Tag::async_read_from(tokio::fs::File::open(path).await?).await
     }
 
     /// Reads an AIFF stream and returns any present ID3 tag.
@@ -238,22 +659,164 @@ impl<'a> Tag {
             .encode(self, writer)
     }
 
+    /// Returns the exact number of bytes [`Tag::write_to`] writes for the specified version.
+    ///
+    /// Use [`Encoder::encoded_size`](crate::Encoder::encoded_size) for tags written with other
+    /// settings.
+    pub fn encoded_size(&self, version: Version) -> crate::Result<u64> {
+        stream::tag::Encoder::new()
+            .version(version)
+            .encoded_size(self)
+    }
+
+    /// Encodes the ID3 tag using the specified version and returns the encoded bytes.
+    ///
+    /// Like [`Tag::write_to`], this produces the plain tag.
+    pub fn to_bytes(&self, version: Version) -> crate::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.write_to(&mut buf, version)?;
+        Ok(buf)
+    }
+
     /// Attempts to write the ID3 tag from the file at the indicated path. If the specified path is
     /// the same path which the tag was read from, then the tag will be written to the padding if
     /// possible.
-    pub fn write_to_file(&self, file: impl StorageFile, version: Version) -> crate::Result<()> {
+    ///
+    /// The returned [`WriteOutcome`] describes whether the tag could be updated in place.
+    ///
+    /// If the tag has no frames, any existing tag is removed from MP3 files instead of writing an
+    /// empty tag. Use [`Encoder::write_empty`](crate::Encoder::write_empty) to change this.
+    pub fn write_to_file(
+        &self,
+        file: impl StorageFile,
+        version: Version,
+    ) -> crate::Result<WriteOutcome> {
         stream::tag::Encoder::new()
             .version(version)
-            .write_to_file(self, file)?;
-        Ok(())
+            .write_to_file(self, file)
     }
 
     /// Conventience function for [`write_to_file`].
-    pub fn write_to_path(&self, path: impl AsRef<Path>, version: Version) -> crate::Result<()> {
+    ///
+    /// Use [`Encoder::atomic`](crate::Encoder::atomic) to protect the file against interrupted
+    /// writes.
+    pub fn write_to_path(
+        &self,
+        path: impl AsRef<Path>,
+        version: Version,
+    ) -> crate::Result<WriteOutcome> {
         let file = fs::OpenOptions::new().read(true).write(true).open(path)?;
         self.write_to_file(file, version)
     }
 
+    /// Attempts to write the ID3 tag via Tokio to the writer using the specified version.
+    ///
+    /// Like [`Tag::write_to`], this writes the plain tag.
+    #[cfg(feature = "tokio")]
+    pub async fn async_write_to(
+        &self,
+        writer: impl tokio::io::AsyncWrite + std::marker::Unpin,
+        version: Version,
+    ) -> crate::Result<()> {
+        stream::tag::Encoder::new()
+            .version(version)
+            .async_encode(self, writer)
+            .await
+    }
+
+    /// Attempts to write the ID3 tag via Tokio to the file, like [`Tag::write_to_file`].
+    #[cfg(feature = "tokio")]
+    pub async fn async_write_to_file(
+        &self,
+        file: impl AsyncStorageFile,
+        version: Version,
+    ) -> crate::Result<WriteOutcome> {
+        stream::tag::Encoder::new()
+            .version(version)
+            .async_write_to_file(self, file)
+            .await
+    }
+
+    /// Attempts to write the ID3 tag via Tokio to the file at the indicated path, like
+    /// [`Tag::write_to_path`].
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike, Version};
+    /// use std::fs::copy;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let temp_file = std::env::temp_dir().join("async_write_to_path.mp3");
+    /// copy("testdata/quiet.mp3", &temp_file)?;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_album("Fancy Album Title");
+    /// tag.async_write_to_path(&temp_file, Version::Id3v24).await?;
+    ///
+    /// let tag = Tag::async_read_from_path(&temp_file).await?;
+    /// assert_eq!(tag.album(), Some("Fancy Album Title"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    pub async fn async_write_to_path(
+        &self,
+        path: impl AsRef<Path>,
+        version: Version,
+    ) -> crate::Result<WriteOutcome> {
+        stream::tag::Encoder::new()
+            .version(version)
+            .async_write_to_path(self, path)
+            .await
+    }
+
+    /// Reads the tag from the file at the indicated path, transforms it using `f` and writes the
+    /// result back, using the version of the returned tag.
+    ///
+    /// If the file has no tag, `f` receives an empty tag.
+    ///
+    /// The tag is written to a copy of the file which then atomically replaces the original, like
+    /// [`Encoder::atomic`](crate::Encoder::atomic). If reading or writing the tag fails, the
+    /// original file is left untouched. The copy is created in the same directory as the original,
+    /// always as a new file, so an existing file or link of the same name is never written to.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use std::fs::copy;
+    ///
+    /// let temp_file = std::env::temp_dir().join("replace_in_path.mp3");
+    /// copy("testdata/quiet.mp3", &temp_file)?;
+    ///
+    /// Tag::replace_in_path(&temp_file, |mut tag| {
+    ///     tag.set_album("Fancy Album Title");
+    ///     tag
+    /// })?;
+    /// assert_eq!(Tag::read_from_path(&temp_file)?.album(), Some("Fancy Album Title"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn replace_in_path(
+        path: impl AsRef<Path>,
+        f: impl FnOnce(Tag) -> Tag,
+    ) -> crate::Result<WriteOutcome> {
+        storage::write_atomically(path.as_ref(), |file| {
+            let tag = match Tag::read_from2(&mut *file) {
+                Ok(tag) => tag,
+                Err(Error {
+                    kind: ErrorKind::NoTag,
+                    ..
+                }) => Tag::new(),
+                Err(err) => return Err(err),
+            };
+            file.seek(io::SeekFrom::Start(0))?;
+            let tag = f(tag);
+            let outcome = tag.write_to_file(&mut *file, tag.version())?;
+            file.flush()?;
+            Ok(outcome)
+        })
+    }
+
     /// Overwrite WAV file ID3 chunk in a file
     #[deprecated(note = "use write_to_path")]
     pub fn write_to_aiff_path(
@@ -267,7 +830,12 @@ impl<'a> Tag {
             .create(false)
             .truncate(false)
             .open(path)?;
-        chunk::write_id3_chunk_file::<chunk::AiffFormat>(&mut file, self, version)?;
+        chunk::write_id3_chunk_file::<chunk::AiffFormat>(
+            &mut file,
+            self,
+            version,
+            ChunkId::Preserve,
+        )?;
         file.flush()?;
         Ok(())
     }
@@ -279,7 +847,8 @@ impl<'a> Tag {
         file: impl StorageFile,
         version: Version,
     ) -> crate::Result<()> {
-        chunk::write_id3_chunk_file::<chunk::AiffFormat>(file, self, version)
+        chunk::write_id3_chunk_file::<chunk::AiffFormat>(file, self, version, ChunkId::Preserve)?;
+        Ok(())
     }
 
     /// Overwrite WAV file ID3 chunk
@@ -291,7 +860,12 @@ impl<'a> Tag {
             .create(false)
             .truncate(false)
             .open(path)?;
-        chunk::write_id3_chunk_file::<chunk::WavFormat>(&mut file, self, version)?;
+        chunk::write_id3_chunk_file::<chunk::WavFormat>(
+            &mut file,
+            self,
+            version,
+            ChunkId::Preserve,
+        )?;
         file.flush()?;
         Ok(())
     }
@@ -299,7 +873,8 @@ impl<'a> Tag {
     /// Overwrite AIFF file ID3 chunk in a file. The file must be opened read/write.
     #[deprecated(note = "use write_to_file")]
     pub fn write_to_wav_file(&self, file: impl StorageFile, version: Version) -> crate::Result<()> {
-        chunk::write_id3_chunk_file::<chunk::WavFormat>(file, self, version)
+        chunk::write_id3_chunk_file::<chunk::WavFormat>(file, self, version, ChunkId::Preserve)?;
+        Ok(())
     }
 
     /// Returns version of the read tag.
@@ -307,6 +882,144 @@ impl<'a> Tag {
         self.version
     }
 
+    /// Returns the restrictions of the tag.
+    ///
+    /// Only ID3v2.4 tags have restrictions.
+    pub fn restrictions(&self) -> Option<TagRestrictions> {
+        self.restrictions
+    }
+
+    /// Sets the restrictions of the tag, which are written to the extended header of ID3v2.4
+    /// tags.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike, TagRestrictions, TextSizeRestriction, Version};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("Title");
+    /// tag.set_restrictions(Some(TagRestrictions {
+    ///     text_size: TextSizeRestriction::Max30Chars,
+    ///     ..TagRestrictions::default()
+    /// }));
+    ///
+    /// let tag = Tag::from_bytes(&tag.to_bytes(Version::Id3v24)?)?;
+    /// let restrictions = tag.restrictions().unwrap();
+    /// assert_eq!(restrictions.text_size.max_chars(), Some(30));
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn set_restrictions(&mut self, restrictions: Option<TagRestrictions>) {
+        self.restrictions = restrictions;
+    }
+
+    /// Returns true if the tag is an update of a tag found earlier in the file or stream, as
+    /// indicated by the extended header of ID3v2.4 tags. See [`Tag::merge_update`].
+    pub fn is_update(&self) -> bool {
+        self.is_update
+    }
+
+    /// Sets whether the tag is an update of an earlier tag. This is written to the extended
+    /// header of ID3v2.4 tags.
+    pub fn set_is_update(&mut self, is_update: bool) {
+        self.is_update = is_update;
+    }
+
+    /// Applies an update tag on top of this tag.
+    ///
+    /// Following the ID3v2.4 specification, frames of the update that may only occur once
+    /// override the corresponding frames of this tag and all other frames are added. The
+    /// restrictions of the update, if any, replace the restrictions of this tag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike, Version};
+    ///
+    /// let mut base = Tag::new();
+    /// base.set_title("Title");
+    /// base.set_artist("Artist");
+    /// base.add_comment(id3::frame::Comment {
+    ///     lang: "eng".to_string(),
+    ///     description: "first".to_string(),
+    ///     text: "First".to_string(),
+    /// });
+    ///
+    /// let mut update = Tag::new();
+    /// update.set_is_update(true);
+    /// update.set_title("Live Title");
+    /// update.add_comment(id3::frame::Comment {
+    ///     lang: "eng".to_string(),
+    ///     description: "second".to_string(),
+    ///     text: "Second".to_string(),
+    /// });
+    /// let update = Tag::from_bytes(&update.to_bytes(Version::Id3v24)?)?;
+    /// assert!(update.is_update());
+    ///
+    /// base.merge_update(update);
+    /// assert_eq!(base.title(), Some("Live Title"));
+    /// assert_eq!(base.artist(), Some("Artist"));
+    /// assert_eq!(base.comments().count(), 2);
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn merge_update(&mut self, update: Tag) {
+        for frame in update.frames {
+            self.add_frame(frame);
+        }
+        if update.restrictions.is_some() {
+            self.restrictions = update.restrictions;
+        }
+    }
+
+    /// Returns true if the tag has no frames.
+    ///
+    /// Writing an empty tag to a file removes any existing tag, see [`Tag::write_to_file`].
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(tag.is_empty());
+    /// tag.set_title("Title");
+    /// assert!(!tag.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Sets the original release date in the frame that matches the version of the tag: TDOR for
+    /// ID3v2.4 and TORY for older versions. The frame of the other version is removed.
+    ///
+    /// TORY only holds the year, so the rest of the timestamp is discarded for older versions.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike, Timestamp, Version};
+    ///
+    /// let timestamp = Timestamp::builder().year(1989).month(12).build()?;
+    ///
+    /// let mut tag = Tag::with_version(Version::Id3v23);
+    /// tag.set_original_date_released_for_version(timestamp);
+    /// assert_eq!(tag.get("TORY").and_then(|f| f.content().text()), Some("1989"));
+    /// assert_eq!(tag.original_date_released_v23().map(|t| t.year), Some(1989));
+    ///
+    /// let mut tag = Tag::with_version(Version::Id3v24);
+    /// tag.set_original_date_released_for_version(timestamp);
+    /// assert_eq!(tag.original_date_released_v23(), Some(timestamp));
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn set_original_date_released_for_version(&mut self, timestamp: Timestamp) {
+        match self.version {
+            Version::Id3v24 => {
+                self.remove("TORY");
+                self.set_original_date_released(timestamp);
+            }
+            Version::Id3v22 | Version::Id3v23 => {
+                self.remove_original_date_released();
+                self.set_text("TORY", format!("{:04}", timestamp.year));
+            }
+        }
+    }
+
     /// Returns an iterator over the all frames in the tag.
     ///
     /// # Example
@@ -460,41 +1173,326 @@ impl<'a> Tag {
         self.frames().filter_map(|frame| frame.content().chapter())
     }
 
-    /// Returns an iterator over all tables of contents (CTOC) in the tag.
+    /// Returns an iterator over all tables of contents (CTOC) in the tag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{Chapter, TableOfContents, Content, Frame};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Chapter{
+    ///     element_id: "chap01".to_string(),
+    ///     start_time: 1000,
+    ///     end_time: 2000,
+    ///     start_offset: 0xff,
+    ///     end_offset: 0xff,
+    ///     frames: Vec::new(),
+    /// });
+    /// tag.add_frame(TableOfContents{
+    ///     element_id: "internalTable01".to_string(),
+    ///     top_level: false,
+    ///     ordered: false,
+    ///     elements: Vec::new(),
+    ///     frames: Vec::new(),
+    /// });
+    /// tag.add_frame(TableOfContents{
+    ///     element_id: "01".to_string(),
+    ///     top_level: true,
+    ///     ordered: true,
+    ///     elements: vec!["internalTable01".to_string(),"chap01".to_string()],
+    ///     frames: Vec::new(),
+    /// });
+    /// assert_eq!(2, tag.tables_of_contents().count());
+    /// ```
+    pub fn tables_of_contents(&self) -> impl Iterator<Item = &TableOfContents> {
+        self.frames()
+            .filter_map(|frame| frame.content().table_of_contents())
+    }
+
+    /// Returns the chapter (CHAP) that covers the specified time in milliseconds, if any.
+    ///
+    /// A chapter covers all times from its start time up to, but not including, its end time. If
+    /// multiple chapters overlap, the one that started last is returned.
+    ///
+    /// Use [`Tag::chapter_index`] when performing many lookups on the same tag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::Chapter;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Chapter{
+    ///     element_id: "01".to_string(),
+    ///     start_time: 1000,
+    ///     end_time: 2000,
+    ///     start_offset: 0xffffffff,
+    ///     end_offset: 0xffffffff,
+    ///     frames: Vec::new(),
+    /// });
+    /// assert_eq!(tag.chapter_at(1500).unwrap().element_id, "01");
+    /// assert!(tag.chapter_at(2000).is_none());
+    /// ```
+    pub fn chapter_at(&self, millis: u32) -> Option<&Chapter> {
+        self.chapters()
+            .filter(|chapter| chapter.start_time <= millis && millis < chapter.end_time)
+            .max_by_key(|chapter| chapter.start_time)
+    }
+
+    /// Builds an index over the chapters (CHAP) of the tag that allows for fast repeated lookups
+    /// by time.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::Chapter;
+    ///
+    /// let mut tag = Tag::new();
+    /// for (i, start) in [0, 1000, 2000].into_iter().enumerate() {
+    ///     tag.add_frame(Chapter{
+    ///         element_id: format!("{:02}", i),
+    ///         start_time: start,
+    ///         end_time: start + 1000,
+    ///         start_offset: 0xffffffff,
+    ///         end_offset: 0xffffffff,
+    ///         frames: Vec::new(),
+    ///     });
+    /// }
+    /// let index = tag.chapter_index();
+    /// assert_eq!(index.chapter_at(500).unwrap().element_id, "00");
+    /// assert_eq!(index.chapter_at(2999).unwrap().element_id, "02");
+    /// assert!(index.chapter_at(3000).is_none());
+    /// ```
+    pub fn chapter_index(&self) -> ChapterIndex<'_> {
+        let mut chapters: Vec<&Chapter> = self.chapters().collect();
+        chapters.sort_by_key(|chapter| chapter.start_time);
+        ChapterIndex { chapters }
+    }
+
+    /// Returns an iterator over all relative volume adjustments (RVA2) in the tag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{ChannelType, RelativeVolumeAdjustment, VolumeAdjustment};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(RelativeVolumeAdjustment {
+    ///     identification: "track".to_string(),
+    ///     adjustments: vec![VolumeAdjustment::from_gain(ChannelType::MasterVolume, -1.0, None)],
+    /// });
+    /// tag.add_frame(RelativeVolumeAdjustment {
+    ///     identification: "album".to_string(),
+    ///     adjustments: vec![VolumeAdjustment::from_gain(ChannelType::MasterVolume, -2.0, None)],
+    /// });
+    /// assert_eq!(2, tag.relative_volume_adjustments().count());
+    /// ```
+    pub fn relative_volume_adjustments(&self) -> impl Iterator<Item = &RelativeVolumeAdjustment> {
+        self.frames()
+            .filter_map(|frame| frame.content().relative_volume_adjustment())
+    }
+
+    /// Returns the event timing codes (ETCO) of the tag, if present.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{EventTimingCodes, EventType, TimestampFormat};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(EventTimingCodes {
+    ///     timestamp_format: TimestampFormat::Ms,
+    ///     events: vec![(EventType::IntroEnd, 12000), (EventType::OutroStart, 180000)],
+    /// });
+    /// let etco = tag.event_timing_codes().unwrap();
+    /// assert_eq!(etco.events[1], (EventType::OutroStart, 180000));
+    /// ```
+    pub fn event_timing_codes(&self) -> Option<&EventTimingCodes> {
+        self.frames()
+            .find_map(|frame| frame.content().event_timing_codes())
+    }
+
+    /// Returns the music CD identifier (MCDI) of the tag, if present.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::MusicCdIdentifier;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(MusicCdIdentifier { toc: vec![0, 10, 1, 1] });
+    /// assert_eq!(tag.music_cd_identifier().unwrap().toc, vec![0, 10, 1, 1]);
+    /// ```
+    pub fn music_cd_identifier(&self) -> Option<&MusicCdIdentifier> {
+        self.frames()
+            .find_map(|frame| frame.content().music_cd_identifier())
+    }
+
+    /// Returns an iterator over all group identification registrations (GRID) in the tag.
+    pub fn group_registrations(&self) -> impl Iterator<Item = &GroupRegistration> {
+        self.frames()
+            .filter_map(|frame| frame.content().group_registration())
+    }
+
+    /// Returns an iterator over all signatures (SIGN) in the tag.
+    pub fn signatures(&self) -> impl Iterator<Item = &Signature> {
+        self.frames()
+            .filter_map(|frame| frame.content().signature())
+    }
+
+    /// Checks the integrity of the chapter structure of the tag.
+    ///
+    /// An error with [`ErrorKind::InvalidInput`] is returned if the element ID of a chapter (CHAP)
+    /// or table of contents (CTOC) is used more than once, or if a table of contents refers to an
+    /// element ID that is not present in the tag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{Chapter, TableOfContents};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Chapter{
+    ///     element_id: "chap01".to_string(),
+    ///     start_time: 1000,
+    ///     end_time: 2000,
+    ///     start_offset: 0xff,
+    ///     end_offset: 0xff,
+    ///     frames: Vec::new(),
+    /// });
+    /// tag.add_frame(TableOfContents{
+    ///     element_id: "toc".to_string(),
+    ///     top_level: true,
+    ///     ordered: true,
+    ///     elements: vec!["chap01".to_string()],
+    ///     frames: Vec::new(),
+    /// });
+    /// assert!(tag.check_chapter_references().is_ok());
+    ///
+    /// tag.remove_all_chapters();
+    /// assert!(tag.check_chapter_references().is_err());
+    /// ```
+    pub fn check_chapter_references(&self) -> crate::Result<()> {
+        let mut element_ids = HashSet::new();
+        let chapter_ids = self.chapters().map(|chapter| &chapter.element_id);
+        let toc_ids = self.tables_of_contents().map(|toc| &toc.element_id);
+        for element_id in chapter_ids.chain(toc_ids) {
+            if !element_ids.insert(element_id.as_str()) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("element ID {:?} is used more than once", element_id),
+                ));
+            }
+        }
+        for toc in self.tables_of_contents() {
+            if let Some(element) = toc
+                .elements
+                .iter()
+                .find(|element| !element_ids.contains(element.as_str()))
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "table of contents {:?} refers to missing element {:?}",
+                        toc.element_id, element
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the group symbols used in the tag are registered.
+    ///
+    /// An error with [`ErrorKind::InvalidInput`] is returned if a group symbol is registered by
+    /// more than one group identification registration (GRID), or if a signature (SIGN) refers to
+    /// a group symbol that is not registered in the tag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{GroupRegistration, Signature};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Signature {
+    ///     group_symbol: 0x80,
+    ///     signature: vec![0xde, 0xad],
+    /// });
+    /// assert!(tag.check_group_references().is_err());
+    ///
+    /// tag.add_frame(GroupRegistration {
+    ///     owner_identifier: "http://example.com/groups".to_string(),
+    ///     group_symbol: 0x80,
+    ///     data: Vec::new(),
+    /// });
+    /// assert!(tag.check_group_references().is_ok());
+    /// ```
+    pub fn check_group_references(&self) -> crate::Result<()> {
+        let mut group_symbols = HashSet::new();
+        for registration in self.group_registrations() {
+            if !group_symbols.insert(registration.group_symbol) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "group symbol {:#04x} is registered more than once",
+                        registration.group_symbol
+                    ),
+                ));
+            }
+        }
+        if let Some(signature) = self
+            .signatures()
+            .find(|signature| !group_symbols.contains(&signature.group_symbol))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "signature refers to unregistered group symbol {:#04x}",
+                    signature.group_symbol
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Removes all references from tables of contents (CTOC) to element IDs that are not present
+    /// in the tag.
+    ///
+    /// Returns the number of references that were removed.
     ///
     /// # Example
     /// ```
     /// use id3::{Tag, TagLike};
-    /// use id3::frame::{Chapter, TableOfContents, Content, Frame};
+    /// use id3::frame::TableOfContents;
     ///
     /// let mut tag = Tag::new();
-    /// tag.add_frame(Chapter{
-    ///     element_id: "chap01".to_string(),
-    ///     start_time: 1000,
-    ///     end_time: 2000,
-    ///     start_offset: 0xff,
-    ///     end_offset: 0xff,
-    ///     frames: Vec::new(),
-    /// });
-    /// tag.add_frame(TableOfContents{
-    ///     element_id: "internalTable01".to_string(),
-    ///     top_level: false,
-    ///     ordered: false,
-    ///     elements: Vec::new(),
-    ///     frames: Vec::new(),
-    /// });
     /// tag.add_frame(TableOfContents{
-    ///     element_id: "01".to_string(),
+    ///     element_id: "toc".to_string(),
     ///     top_level: true,
     ///     ordered: true,
-    ///     elements: vec!["internalTable01".to_string(),"chap01".to_string()],
+    ///     elements: vec!["chap01".to_string()],
     ///     frames: Vec::new(),
     /// });
-    /// assert_eq!(2, tag.tables_of_contents().count());
+    /// assert_eq!(tag.prune_chapter_references(), 1);
+    /// assert!(tag.check_chapter_references().is_ok());
     /// ```
-    pub fn tables_of_contents(&self) -> impl Iterator<Item = &TableOfContents> {
-        self.frames()
-            .filter_map(|frame| frame.content().table_of_contents())
+    pub fn prune_chapter_references(&mut self) -> usize {
+        let element_ids: HashSet<String> = self
+            .chapters()
+            .map(|chapter| chapter.element_id.clone())
+            .chain(self.tables_of_contents().map(|toc| toc.element_id.clone()))
+            .collect();
+        let mut num_removed = 0;
+        for frame in &mut self.frames {
+            if let Content::TableOfContents(toc) = frame.content_mut() {
+                let len = toc.elements.len();
+                toc.elements
+                    .retain(|element| element_ids.contains(element.as_str()));
+                num_removed += len - toc.elements.len();
+            }
+        }
+        num_removed
     }
 
     /// Returns an iterator over all involved people lists (`IPLS` in ID3v2.3, `TIPL` and `TMCL` in
@@ -587,6 +1585,35 @@ impl<'a> Tag {
     }
 }
 
+/// An index over the chapters of a [`Tag`], sorted by start time.
+///
+/// Created by [`Tag::chapter_index`].
+#[derive(Clone, Debug)]
+pub struct ChapterIndex<'a> {
+    chapters: Vec<&'a Chapter>,
+}
+
+impl<'a> ChapterIndex<'a> {
+    /// Returns the chapter that covers the specified time in milliseconds, if any.
+    ///
+    /// The result is the same as that of [`Tag::chapter_at`].
+    pub fn chapter_at(&self, millis: u32) -> Option<&'a Chapter> {
+        let end = self
+            .chapters
+            .partition_point(|chapter| chapter.start_time <= millis);
+        self.chapters[..end]
+            .iter()
+            .rev()
+            .find(|chapter| millis < chapter.end_time)
+            .copied()
+    }
+
+    /// Returns the indexed chapters, sorted by start time.
+    pub fn chapters(&self) -> &[&'a Chapter] {
+        &self.chapters
+    }
+}
+
 impl PartialEq for Tag {
     fn eq(&self, other: &Tag) -> bool {
         self.frames.len() == other.frames.len()
@@ -655,6 +1682,7 @@ impl From<v1::Tag> for Tag {
 mod tests {
     use super::*;
     use crate::taglike::TagLike;
+    use crate::{Encoder, PaddingPolicy};
     use std::error::Error;
     use std::fs;
     use std::process::Command;
@@ -721,6 +1749,265 @@ mod tests {
         println!("{}", output);
     }
 
+    #[test]
+    fn write_outcome() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/quiet.mp3", &tmp).unwrap();
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+
+        let outcome = tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+        assert!(outcome.rewritten);
+        assert!(!outcome.v1_touched);
+        assert_eq!(outcome.padding, 0);
+        let mut buf = Vec::new();
+        tag.write_to(&mut buf, Version::Id3v24).unwrap();
+        assert_eq!(outcome.bytes_written, buf.len() as u64);
+
+        tag.set_title("Eltit");
+        let outcome = tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+        assert!(!outcome.rewritten);
+
+        let outcome = stream::tag::Encoder::new()
+            .version(Version::Id3v24)
+            .padding_policy(PaddingPolicy::AtLeast(64))
+            .write_to_path(&tag, &tmp)
+            .unwrap();
+        assert!(outcome.rewritten);
+        assert_eq!(outcome.padding, 64);
+        assert_eq!(outcome.bytes_written, buf.len() as u64 + 64);
+    }
+
+    #[test]
+    fn write_in_place() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/quiet.mp3", &tmp).unwrap();
+        let audio = fs::read("testdata/quiet.mp3").unwrap();
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_artist("Artist");
+        tag.set_album("Album");
+        let outcome = tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+        assert!(outcome.rewritten);
+        let file_len = fs::metadata(&tmp).unwrap().len();
+
+        // A smaller tag is padded to the size of the existing one.
+        tag.remove_album();
+        let outcome = stream::tag::Encoder::new()
+            .padding(1024)
+            .write_to_path(&tag, &tmp)
+            .unwrap();
+        assert!(!outcome.rewritten);
+        assert_eq!(outcome.padding, 16);
+        assert_eq!(outcome.bytes_written, file_len - audio.len() as u64);
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+        // A tag that fits the padding is written in place.
+        tag.set_genre("Genre");
+        let outcome = tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+        assert!(!outcome.rewritten);
+        assert_eq!(outcome.padding, 0);
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+        let data = fs::read(&tmp).unwrap();
+        assert_eq!(data.len() as u64, file_len);
+        assert_eq!(&data[data.len() - audio.len()..], &audio[..]);
+    }
+
+    #[test]
+    fn write_padding_policy() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/quiet.mp3", &tmp).unwrap();
+        let encoder = stream::tag::Encoder::new().padding_policy(PaddingPolicy::AtLeast(64));
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+
+        let outcome = encoder.write_to_path(&tag, &tmp).unwrap();
+        assert!(outcome.rewritten);
+        assert_eq!(outcome.padding, 64);
+        assert_eq!(Tag::padding_from_path(&tmp).unwrap(), 64);
+        encoder
+            .clone()
+            .padding_policy(PaddingPolicy::AtLeast(128))
+            .write_to_path(&tag, &tmp)
+            .unwrap();
+        assert_eq!(Tag::padding_from_path(&tmp).unwrap(), 128);
+        let file_len = fs::metadata(&tmp).unwrap().len();
+
+        // A small edit uses the existing padding.
+        tag.set_artist("Artist");
+        let outcome = encoder.write_to_path(&tag, &tmp).unwrap();
+        assert!(!outcome.rewritten);
+        assert_eq!(outcome.padding, 128 - 17);
+        assert_eq!(Tag::padding_from_path(&tmp).unwrap(), 128 - 17);
+        assert_eq!(fs::metadata(&tmp).unwrap().len(), file_len);
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+        // A tag that leaves less padding than required is rewritten.
+        tag.set_album("Album".repeat(20));
+        let outcome = encoder.write_to_path(&tag, &tmp).unwrap();
+        assert!(outcome.rewritten);
+        assert_eq!(outcome.padding, 64);
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        for version in [Version::Id3v22, Version::Id3v23, Version::Id3v24] {
+            let mut tag = Tag::new();
+            tag.set_title("Title");
+            tag.set_artist("Artist");
+            let bytes = tag.to_bytes(version).unwrap();
+            let decoded = Tag::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.version(), version);
+            assert_eq!(decoded, tag);
+        }
+        let err = Tag::from_bytes(b"not a tag").unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::NoTag));
+    }
+
+    #[test]
+    fn replace_in_path() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/quiet.mp3", &tmp).unwrap();
+        Tag::replace_in_path(&tmp, |tag| {
+            assert!(tag.is_empty());
+            let mut tag = Tag::with_version(Version::Id3v23);
+            tag.set_title("Title");
+            tag
+        })
+        .unwrap();
+        Tag::replace_in_path(&tmp, |mut tag| {
+            assert_eq!(tag.version(), Version::Id3v23);
+            assert_eq!(tag.title(), Some("Title"));
+            tag.set_artist("Artist");
+            tag
+        })
+        .unwrap();
+        let tag = Tag::read_from_path(&tmp).unwrap();
+        assert_eq!(tag.version(), Version::Id3v23);
+        assert_eq!(tag.title(), Some("Title"));
+        assert_eq!(tag.artist(), Some("Artist"));
+
+        // A failing write leaves the original file untouched, even if the tag is streamed into
+        // the file as it is encoded.
+        fs::copy("testdata/wav/tagless.wav", &tmp).unwrap();
+        let original = fs::read(&tmp).unwrap();
+        Tag::replace_in_path(&tmp, |mut tag| {
+            tag.set_title("Other");
+            tag.add_frame(Frame::with_content(
+                "TALB",
+                Content::Skipped(crate::frame::Skipped {
+                    size: 10,
+                    offset: 0,
+                    version: Version::Id3v23,
+                }),
+            ));
+            tag
+        })
+        .unwrap_err();
+        assert_eq!(fs::read(&tmp).unwrap(), original);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replace_in_path_existing_temp_name() {
+        use std::sync::atomic::Ordering;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("song.mp3");
+        fs::copy("testdata/quiet.mp3", &path).unwrap();
+        let victim = dir.path().join("victim");
+        fs::write(&victim, b"victim").unwrap();
+        let next = storage::TEMP_COUNTER.load(Ordering::Relaxed);
+        for counter in next..next + 10 {
+            let name = format!(".song.mp3.{}-{}.tmp", std::process::id(), counter);
+            std::os::unix::fs::symlink(&victim, dir.path().join(name)).unwrap();
+        }
+
+        Tag::replace_in_path(&path, |mut tag| {
+            tag.set_title("Title");
+            tag
+        })
+        .unwrap();
+        assert_eq!(Tag::read_from_path(&path).unwrap().title(), Some("Title"));
+        assert_eq!(fs::read(&victim).unwrap(), b"victim");
+    }
+
+    #[test]
+    fn write_empty_tag() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/quiet.mp3", &tmp).unwrap();
+        let audio_len = fs::metadata(&tmp).unwrap().len();
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+
+        let outcome = Tag::new().write_to_path(&tmp, Version::Id3v24).unwrap();
+        assert_eq!(outcome.bytes_written, 0);
+        assert!(outcome.rewritten);
+        assert!(!Tag::is_candidate(File::open(&tmp).unwrap()).unwrap());
+        assert_eq!(fs::metadata(&tmp).unwrap().len(), audio_len);
+
+        let outcome = stream::tag::Encoder::new()
+            .write_empty(true)
+            .write_to_path(&Tag::new(), &tmp)
+            .unwrap();
+        assert_eq!(outcome.bytes_written, 10);
+        assert!(Tag::read_from_path(&tmp).unwrap().is_empty());
+    }
+
+    #[test]
+    fn read_encapsulated_object() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        for (description, data) in [("first", vec![1; 64]), ("second", vec![2; 100_000])] {
+            tag.add_frame(EncapsulatedObject {
+                mime_type: "application/octet-stream".to_string(),
+                filename: "file.bin".to_string(),
+                description: description.to_string(),
+                data,
+            });
+        }
+        tag.set_artist("Artist");
+
+        let encoders = [
+            stream::tag::Encoder::new().version(Version::Id3v23),
+            stream::tag::Encoder::new().version(Version::Id3v24),
+            stream::tag::Encoder::new()
+                .version(Version::Id3v23)
+                .unsynchronisation(true),
+            stream::tag::Encoder::new()
+                .version(Version::Id3v24)
+                .unsynchronisation(true),
+            stream::tag::Encoder::new()
+                .version(Version::Id3v24)
+                .compression(true),
+            stream::tag::Encoder::new().version(Version::Id3v22),
+            stream::tag::Encoder::new()
+                .version(Version::Id3v23)
+                .encoding_policy(crate::EncodingPolicy::Always(crate::Encoding::Latin1)),
+            stream::tag::Encoder::new()
+                .version(Version::Id3v24)
+                .encoding_policy(crate::EncodingPolicy::Always(crate::Encoding::Latin1)),
+        ];
+        for encoder in encoders {
+            let mut buf = Vec::new();
+            encoder.encode(&tag, &mut buf).unwrap();
+            let mut object = Tag::read_encapsulated_object(io::Cursor::new(&buf), "second")
+                .unwrap()
+                .unwrap();
+            assert_eq!(object.filename, "file.bin");
+            assert_eq!(object.remaining(), 100_000);
+            let mut data = Vec::new();
+            io::Read::read_to_end(&mut object, &mut data).unwrap();
+            assert_eq!(data, vec![2; 100_000]);
+
+            let object = Tag::read_encapsulated_object(io::Cursor::new(&buf), "third").unwrap();
+            assert!(object.is_none());
+        }
+    }
+
     #[test]
     fn github_issue_82() {
         let mut tag = Tag::new();
@@ -789,6 +2076,181 @@ mod tests {
         assert_eq!(tag.artist(), Some("TEST ARTIST"));
     }
 
+    #[test]
+    fn aifc_read() {
+        let tag = Tag::read_from_path("testdata/aiff/tagged.aifc").unwrap();
+        assert_eq!(tag.title(), Some("Title"));
+        assert_eq!(tag.artist(), Some("Artist"));
+
+        let error = Tag::read_from_path("testdata/aiff/tagless.aifc").unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::NoTag));
+    }
+
+    #[test]
+    fn aifc_write() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/aiff/tagless.aifc", &tmp).unwrap();
+        let audio = fs::read("testdata/aiff/tagless.aifc").unwrap();
+
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_album("Album");
+        for _ in 0..2 {
+            tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+            assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+            let data = fs::read(&tmp).unwrap();
+            let form_size = u32::from_be_bytes(data[4..8].try_into().unwrap());
+            assert_eq!(form_size as usize, data.len() - 8);
+            assert_eq!(&data[8..12], b"AIFC");
+            // The FVER chunk and the audio are kept as is, the tag is appended.
+            assert_eq!(&data[12..16], b"FVER");
+            assert_eq!(&data[8..audio.len()], &audio[8..]);
+            assert_eq!(&data[audio.len()..audio.len() + 4], b"ID3 ");
+            tag.remove_album();
+        }
+    }
+
+    #[test]
+    fn dsdiff_read() {
+        let tag = Tag::read_from_path("testdata/dsdiff/tagged.dff").unwrap();
+        assert_eq!(tag.title(), Some("Title"));
+        assert_eq!(tag.artist(), Some("Artist"));
+
+        let error = Tag::read_from_path("testdata/dsdiff/tagless.dff").unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::NoTag));
+    }
+
+    #[test]
+    fn dsdiff_write() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/dsdiff/tagless.dff", &tmp).unwrap();
+        let audio = fs::read("testdata/dsdiff/tagless.dff").unwrap();
+
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_album("Album");
+        for _ in 0..2 {
+            tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+            assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+            // The sizes of the form and the ID3 chunk are stored in 64 bits.
+            let data = fs::read(&tmp).unwrap();
+            let form_size = u64::from_be_bytes(data[4..12].try_into().unwrap());
+            assert_eq!(form_size, data.len() as u64 - 12);
+            let id3_chunk = &data[audio.len()..];
+            assert_eq!(&id3_chunk[..4], b"ID3 ");
+            let id3_size = u64::from_be_bytes(id3_chunk[4..12].try_into().unwrap());
+            assert_eq!(id3_size, id3_chunk.len() as u64 - 12);
+            assert_eq!(&data[16..audio.len()], &audio[16..]);
+            tag.remove_album();
+        }
+    }
+
+    #[test]
+    fn dsdiff_write_chunk_past_end() {
+        let mut data = Vec::new();
+        data.extend(b"FRM8");
+        data.extend(0x1c_e924_44e4u64.to_be_bytes());
+        data.extend(b"DSD ");
+        data.extend(b"SSND");
+        data.extend(0x29f7_f1ef_8a19_6333u64.to_be_bytes());
+        data.extend([0; 16]);
+        let mut file = io::Cursor::new(data.clone());
+
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        let error = Encoder::new().write_to_file(&tag, &mut file).unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::InvalidInput));
+        assert_eq!(file.into_inner(), data);
+    }
+
+    #[test]
+    fn dsf_read() {
+        let tag = Tag::read_from_path("testdata/dsf/tagged.dsf").unwrap();
+        assert_eq!(tag.title(), Some("Title"));
+        assert_eq!(tag.artist(), Some("Artist"));
+
+        let error = Tag::read_from_path("testdata/dsf/tagless.dsf").unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::NoTag));
+    }
+
+    #[test]
+    fn dsf_write() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/dsf/tagless.dsf", &tmp).unwrap();
+        let audio = fs::read("testdata/dsf/tagless.dsf").unwrap();
+        let check_header = |tag_len: u64| {
+            let data = fs::read(&tmp).unwrap();
+            let header = dsf::DsdHeader::read(&data[..]).unwrap();
+            assert_eq!(header.file_size, data.len() as u64);
+            assert_eq!(data.len() as u64, audio.len() as u64 + tag_len);
+            let offset = if tag_len == 0 { 0 } else { audio.len() as u64 };
+            assert_eq!(header.metadata_offset, offset);
+            assert_eq!(&data[28..audio.len()], &audio[28..]);
+        };
+
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_album("Album");
+        let outcome = tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+        assert!(outcome.rewritten);
+        assert!(!outcome.v1_touched);
+        check_header(outcome.bytes_written);
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+        // A smaller tag is padded to the size of the existing one.
+        tag.remove_album();
+        let outcome = tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+        assert!(!outcome.rewritten);
+        assert!(outcome.padding > 0);
+        check_header(outcome.bytes_written);
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+        // Writing an empty tag removes the tag.
+        let outcome = Tag::new().write_to_path(&tmp, Version::Id3v24).unwrap();
+        assert_eq!(outcome.bytes_written, 0);
+        check_header(0);
+        assert_eq!(fs::read(&tmp).unwrap(), audio);
+    }
+
+    #[test]
+    fn rf64_read() {
+        let tag = Tag::read_from_path("testdata/wav/rf64-tagged.wav").unwrap();
+        assert_eq!(tag.title(), Some("Title"));
+        assert_eq!(tag.artist(), Some("Artist"));
+
+        let error = Tag::read_from_path("testdata/wav/rf64-tagless.wav").unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::NoTag));
+    }
+
+    #[test]
+    fn rf64_write() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/wav/rf64-tagless.wav", &tmp).unwrap();
+        let audio = fs::read("testdata/wav/rf64-tagless.wav").unwrap();
+
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_album("Album");
+        for _ in 0..2 {
+            tag.write_to_path(&tmp, Version::Id3v24).unwrap();
+            assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+            // The size of the root chunk is kept in the ds64 chunk.
+            let data = fs::read(&tmp).unwrap();
+            assert_eq!(&data[4..8], &[0xff; 4]);
+            let riff_size = u64::from_le_bytes(data[20..28].try_into().unwrap());
+            assert_eq!(riff_size, data.len() as u64 - 8);
+            let id3_chunk = &data[audio.len()..];
+            assert_eq!(&id3_chunk[..4], b"ID3 ");
+            let id3_size = u32::from_le_bytes(id3_chunk[4..8].try_into().unwrap());
+            assert_eq!(id3_size as usize, id3_chunk.len() - 8);
+            assert_eq!(&data[28..audio.len()], &audio[28..]);
+            tag.remove_album();
+        }
+    }
+
     #[test]
     fn wav_read_tagless() {
         use crate::ErrorKind;
@@ -901,6 +2363,108 @@ mod tests {
         edit_and_check_wav_tag("testdata/wav/tagged-mid.wav", &tmp).unwrap();
     }
 
+    /// Returns the ID, offset and contents of the chunks in a WAV or AIFF file.
+    fn list_chunks(data: &[u8], big_endian: bool) -> Vec<([u8; 4], usize, Vec<u8>)> {
+        let read_size = |b: &[u8]| {
+            let b = b.try_into().unwrap();
+            if big_endian {
+                u32::from_be_bytes(b)
+            } else {
+                u32::from_le_bytes(b)
+            }
+        } as usize;
+        assert_eq!(read_size(&data[4..8]), data.len() - 8);
+        let mut chunks = Vec::new();
+        let mut pos = 12;
+        while pos < data.len() {
+            let id = data[pos..pos + 4].try_into().unwrap();
+            let size = read_size(&data[pos + 4..pos + 8]);
+            chunks.push((id, pos, data[pos + 8..pos + 8 + size].to_vec()));
+            pos += 8 + size + size % 2;
+        }
+        assert_eq!(pos, data.len());
+        chunks
+    }
+
+    #[test]
+    fn chunk_order_and_alignment() {
+        for (path, big_endian) in [
+            ("testdata/wav/multi-chunk.wav", false),
+            ("testdata/aiff/multi-chunk.aiff", true),
+        ] {
+            let original = list_chunks(&fs::read(path).unwrap(), big_endian);
+            let tmp = tempfile::NamedTempFile::new().unwrap();
+            fs::copy(path, &tmp).unwrap();
+
+            let mut tag = Tag::read_from_path(&tmp).unwrap();
+            tag.set_album("Album");
+            tag.set_genre("A somewhat long genre that grows the tag");
+            for _ in 0..3 {
+                tag.write_to_path(&tmp, Version::Id3v23).unwrap();
+                assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag, "{}", path);
+
+                let chunks = list_chunks(&fs::read(&tmp).unwrap(), big_endian);
+                assert_eq!(chunks.len(), original.len(), "{}", path);
+                for (chunk, original) in chunks.iter().zip(&original) {
+                    assert_eq!(chunk.0, original.0, "{}", path);
+                    assert_eq!(chunk.1 % 2, 0, "{}", path);
+                    if &chunk.0 != b"ID3 " {
+                        assert_eq!(chunk.2, original.2, "{}", path);
+                    }
+                }
+                tag.remove_genre();
+            }
+        }
+    }
+
+    #[test]
+    fn wav_read_chunk_ids() {
+        let expected = Tag::read_from_path("testdata/wav/tagged-end.wav").unwrap();
+        let mut data = fs::read("testdata/wav/tagged-end.wav").unwrap();
+        for id in [b"id3 ", b"ID32"] {
+            data[80..84].copy_from_slice(id);
+            let tag = Tag::read_from2(io::Cursor::new(&data)).unwrap();
+            assert_eq!(tag, expected);
+        }
+    }
+
+    #[test]
+    fn wav_write_chunk_id() {
+        if !cfg!(feature = "decode_picture") {
+            return;
+        }
+
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        let mut data = fs::read("testdata/wav/tagged-end.wav").unwrap();
+        data[80..84].copy_from_slice(b"id3 ");
+        fs::write(&tmp, &data).unwrap();
+        let chunk_id = || fs::read(&tmp).unwrap()[80..84].to_vec();
+
+        let mut tag = Tag::read_from_path(&tmp).unwrap();
+        tag.set_title("NewTitle");
+
+        // The existing ID is kept by default.
+        Encoder::new().write_to_path(&tag, &tmp).unwrap();
+        assert_eq!(chunk_id(), b"id3 ");
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+        Encoder::new()
+            .chunk_id(ChunkId::Id32)
+            .write_to_path(&tag, &tmp)
+            .unwrap();
+        assert_eq!(chunk_id(), b"ID32");
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+
+        // New chunks get the requested ID as well.
+        fs::copy("testdata/wav/tagless.wav", &tmp).unwrap();
+        Encoder::new()
+            .chunk_id(ChunkId::Id3Lowercase)
+            .write_to_path(&tag, &tmp)
+            .unwrap();
+        assert_eq!(chunk_id(), b"id3 ");
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+    }
+
     #[test]
     fn wav_write_trailing_data() {
         let tmp = tempfile::NamedTempFile::new().unwrap();
@@ -945,6 +2509,109 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_read_chunks() {
+        for path in [
+            "testdata/aiff/quiet.aiff",
+            "testdata/aiff/padding.aiff",
+            "testdata/wav/tagged-mid.wav",
+            "testdata/wav/tagged-end.wav",
+            "testdata/wav/rf64-tagged.wav",
+            "testdata/dsdiff/tagged.dff",
+            "testdata/dsf/tagged.dsf",
+        ] {
+            let tag = Tag::async_read_from_path(path).await.unwrap();
+            assert_eq!(tag, Tag::read_from_path(path).unwrap(), "{}", path);
+        }
+
+        // The reader does not need to be seekable.
+        let data = fs::read("testdata/wav/tagged-mid.wav").unwrap();
+        let tag = Tag::async_read_from(&data[..]).await.unwrap();
+        assert_eq!(
+            tag,
+            Tag::read_from_path("testdata/wav/tagged-mid.wav").unwrap()
+        );
+
+        let error = Tag::async_read_from_path("testdata/wav/tagless.wav")
+            .await
+            .unwrap_err();
+        assert!(matches!(error.kind, ErrorKind::NoTag));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_write_matches_write() {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_album("Album");
+        let mut buf = Vec::new();
+        tag.async_write_to(&mut buf, Version::Id3v23).await.unwrap();
+        assert_eq!(buf, tag.to_bytes(Version::Id3v23).unwrap());
+
+        for path in [
+            "testdata/quiet.mp3",
+            "testdata/aiff/quiet.aiff",
+            "testdata/aiff/tagged.aifc",
+            "testdata/wav/tagged-mid.wav",
+            "testdata/wav/tagless-trailing-data.wav",
+            "testdata/wav/rf64-tagged.wav",
+            "testdata/wav/rf64-tagless.wav",
+            "testdata/wav/multi-chunk.wav",
+            "testdata/aiff/multi-chunk.aiff",
+            "testdata/dsdiff/tagged.dff",
+            "testdata/dsdiff/tagless.dff",
+            "testdata/dsf/tagged.dsf",
+            "testdata/dsf/tagless.dsf",
+        ] {
+            let sync_tmp = tempfile::NamedTempFile::new().unwrap();
+            let async_tmp = tempfile::NamedTempFile::new().unwrap();
+            fs::copy(path, &sync_tmp).unwrap();
+            fs::copy(path, &async_tmp).unwrap();
+
+            let mut tag = tag.clone();
+            for _ in 0..2 {
+                let expected = tag.write_to_path(&sync_tmp, Version::Id3v24).unwrap();
+                let outcome = tag
+                    .async_write_to_path(&async_tmp, Version::Id3v24)
+                    .await
+                    .unwrap();
+                assert_eq!(outcome, expected, "{}", path);
+                assert_eq!(
+                    fs::read(&async_tmp).unwrap(),
+                    fs::read(&sync_tmp).unwrap(),
+                    "{}",
+                    path
+                );
+                // The second write shrinks the tag.
+                tag.remove_album();
+            }
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn async_write_to_path_atomic() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        fs::copy("testdata/quiet.mp3", &tmp).unwrap();
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+
+        stream::tag::Encoder::new()
+            .atomic(true)
+            .async_write_to_path(&tag, tmp.path())
+            .await
+            .unwrap();
+        assert_eq!(Tag::read_from_path(&tmp).unwrap(), tag);
+        let dir = tmp.path().parent().unwrap();
+        let name = tmp.path().file_name().unwrap().to_string_lossy();
+        assert!(!fs::read_dir(dir).unwrap().any(|entry| {
+            let entry_name = entry.unwrap().file_name();
+            let entry_name = entry_name.to_string_lossy();
+            entry_name.starts_with(&format!(".{}", name)) && entry_name.ends_with(".tmp")
+        }));
+    }
+
     fn edit_and_check_wav_tag(from: impl AsRef<Path>, to: impl AsRef<Path>) -> crate::Result<()> {
         let from = from.as_ref();
         let to = to.as_ref();
@@ -1036,6 +2703,40 @@ mod tests {
         assert_eq!(count, tag.encapsulated_objects().count());
     }
 
+    #[test]
+    fn test_serato_geob_edit() {
+        let mut tag = Tag::read_from_path("testdata/geob_serato.id3").unwrap();
+        let updated = tag.set_encapsulated_object_data("Serato Markers2", vec![1, 2, 3, 4]);
+        assert_eq!(updated, 2);
+
+        for version in [Version::Id3v23, Version::Id3v24] {
+            let mut buffer = Vec::new();
+            tag.write_to(&mut buffer, version).unwrap();
+            let new_tag = Tag::read_from2(io::Cursor::new(&buffer)).unwrap();
+            assert_eq!(new_tag.encapsulated_objects().count(), 14);
+
+            let encodings = new_tag
+                .frames()
+                .filter(|frame| {
+                    frame
+                        .content()
+                        .encapsulated_object()
+                        .map(|obj| obj.description == "Serato Markers2")
+                        .unwrap_or(false)
+                })
+                .map(|frame| {
+                    let obj = frame.content().encapsulated_object().unwrap();
+                    assert_eq!(obj.data, [1, 2, 3, 4]);
+                    assert_eq!(obj.mime_type, "application/octet-stream");
+                    frame.encoding()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(encodings.len(), 2, "{:?}", version);
+            assert!(encodings.contains(&Some(crate::Encoding::Latin1)));
+            assert!(encodings.contains(&Some(crate::Encoding::UTF16)));
+        }
+    }
+
     /// Read an IPLS frame with UTF-16 encording in an ID3v2.3 tag written by MusicBrainz Picard
     /// 2.12.3.
     #[test]
@@ -1148,4 +2849,129 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(&musician_credits, &new_musician_credits,);
     }
+
+    #[test]
+    fn check_chapter_references() {
+        let chapter = |id: &str| Chapter {
+            element_id: id.to_string(),
+            start_time: 0,
+            end_time: 1000,
+            start_offset: 0xffff_ffff,
+            end_offset: 0xffff_ffff,
+            frames: Vec::new(),
+        };
+        let toc = |id: &str, elements: &[&str]| TableOfContents {
+            element_id: id.to_string(),
+            top_level: true,
+            ordered: true,
+            elements: elements.iter().map(|e| e.to_string()).collect(),
+            frames: Vec::new(),
+        };
+
+        let mut tag = Tag::new();
+        tag.add_frame(chapter("chp0"));
+        tag.add_frame(chapter("chp1"));
+        tag.add_frame(toc("sub", &["chp1"]));
+        tag.add_frame(toc("toc", &["chp0", "sub", "chp2", "chp3"]));
+        let err = tag.check_chapter_references().unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidInput));
+
+        assert_eq!(tag.prune_chapter_references(), 2);
+        tag.check_chapter_references().unwrap();
+        let elements = &tag.tables_of_contents().nth(1).unwrap().elements;
+        assert_eq!(elements, &["chp0", "sub"]);
+
+        tag.add_frame(toc("chp0", &[]));
+        let err = tag.check_chapter_references().unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidInput));
+    }
+
+    #[test]
+    fn check_group_references() {
+        let grid = |owner: &str, group_symbol| {
+            Frame::from(GroupRegistration {
+                owner_identifier: owner.to_string(),
+                group_symbol,
+                data: Vec::new(),
+            })
+        };
+        let sign = |group_symbol| {
+            Frame::from(Signature {
+                group_symbol,
+                signature: vec![1, 2, 3],
+            })
+        };
+
+        let mut tag: Tag = vec![grid("a", 0x80), grid("b", 0x81), sign(0x81)]
+            .into_iter()
+            .collect();
+        tag.check_group_references().unwrap();
+
+        tag.add_frame(sign(0x82));
+        let err = tag.check_group_references().unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidInput));
+
+        let tag: Tag = vec![grid("a", 0x80), grid("b", 0x80)].into_iter().collect();
+        let err = tag.check_group_references().unwrap_err();
+        assert!(matches!(err.kind, ErrorKind::InvalidInput));
+    }
+
+    #[test]
+    fn chapter_at_overlapping() {
+        let mut tag = Tag::new();
+        for (id, start, end) in [("c", 3000, 4000), ("a", 0, 5000), ("b", 1000, 2000)] {
+            tag.add_frame(Chapter {
+                element_id: id.to_string(),
+                start_time: start,
+                end_time: end,
+                start_offset: 0xffff_ffff,
+                end_offset: 0xffff_ffff,
+                frames: Vec::new(),
+            });
+        }
+        let index = tag.chapter_index();
+        for (millis, expected) in [
+            (0, Some("a")),
+            (1500, Some("b")),
+            (2500, Some("a")),
+            (3000, Some("c")),
+            (4999, Some("a")),
+            (5000, None),
+        ] {
+            let id = |c: Option<&Chapter>| c.map(|c| c.element_id.clone());
+            assert_eq!(id(tag.chapter_at(millis)).as_deref(), expected);
+            assert_eq!(id(index.chapter_at(millis)).as_deref(), expected);
+        }
+    }
+
+    #[test]
+    fn replace_conflicting_frame() {
+        let ext = |description: &str, value: &str| ExtendedText {
+            description: description.to_string(),
+            value: value.to_string(),
+        };
+        let mut tag = Tag::new();
+        tag.add_frame(ext("TEMPO", "fast"));
+        tag.add_frame(ext("MOOD", "happy"));
+        let old = tag.replace("TXXX:MOOD", ext("TEMPO", "slow")).unwrap();
+        assert_eq!(old.content().extended_text().unwrap().value, "happy");
+        let values: Vec<_> = tag.extended_texts().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, ["slow"]);
+    }
+
+    #[test]
+    fn entry_conflicting_frame() {
+        let ext = |description: &str, value: &str| ExtendedText {
+            description: description.to_string(),
+            value: value.to_string(),
+        };
+        let mut tag = Tag::new();
+        tag.add_frame(ext("TEMPO", "fast"));
+        tag.set_title("Title");
+        let frame = tag.entry("TXXX:MOOD").or_insert(ext("TEMPO", "slow"));
+        assert_eq!(frame.content().extended_text().unwrap().value, "slow");
+        let values: Vec<_> = tag.extended_texts().map(|t| t.value.as_str()).collect();
+        assert_eq!(values, ["slow"]);
+        assert_eq!(tag.frame_at(0).unwrap().id(), "TXXX");
+    }
 }
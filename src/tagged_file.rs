@@ -0,0 +1,357 @@
+use crate::chunk;
+use crate::storage::{dsf, Format};
+use crate::stream;
+use crate::{v1, Error, ErrorKind, Tag, Version};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::ops::Range;
+use std::path::Path;
+
+/// The tags of a file together with information about the container they were read from.
+///
+/// Created by [`read_tagged_file`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaggedFile {
+    /// The container format of the file. Files without any recognized magic, like MPEG files
+    /// without an ID3v2 tag, are reported as [`Format::Header`].
+    pub format: Format,
+    /// The ID3v2 tag, if present.
+    pub id3v2: Option<Tag>,
+    /// The ID3v1 tag, if present.
+    pub id3v1: Option<v1::Tag>,
+    /// The version of the ID3v2 tag, if present.
+    pub version: Option<Version>,
+    /// The byte range that the ID3v2 tag occupies in the file, if present. For
+    /// [`Format::Header`], this includes any padding that follows the tag. For chunk based
+    /// formats, this is the data of the ID3 chunk. For [`Format::Dsf`], this is the tag at the end
+    /// of the file.
+    pub tag_range: Option<Range<u64>>,
+}
+
+impl TaggedFile {
+    /// Reads the tags and container information from the reader. See [`read_tagged_file`].
+    pub fn read_from(mut reader: impl io::Read + io::Seek) -> crate::Result<TaggedFile> {
+        let format = {
+            let mut b = BufReader::new(&mut reader);
+            Format::magic(b.fill_buf()?).unwrap_or(Format::Header)
+        };
+
+        reader.rewind()?;
+        let tag_range = optional(match format {
+            Format::Header => stream::tag::locate_id3v2(&mut reader),
+            Format::Aiff => chunk::locate_id3_chunk::<chunk::AiffFormat, _>(&mut reader),
+            Format::Wav => chunk::locate_id3_chunk::<chunk::WavFormat, _>(&mut reader),
+            Format::Dsdiff => chunk::locate_id3_chunk::<chunk::DsdiffFormat, _>(&mut reader),
+            Format::Dsf => dsf::locate_id3(&mut reader),
+        })?;
+
+        reader.rewind()?;
+        let id3v2 = match tag_range {
+            Some(_) => optional(Tag::read_from2(&mut reader))?,
+            None => None,
+        };
+        let id3v1 = optional(v1::Tag::read_from(&mut reader))?;
+
+        Ok(TaggedFile {
+            format,
+            version: id3v2.as_ref().map(|tag| tag.version()),
+            id3v2,
+            id3v1,
+            tag_range,
+        })
+    }
+}
+
+/// The kind of a block of metadata, as reported by [`Tag::locate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TagKind {
+    /// An ID3v2 tag at the start of the file, or stacked directly after another one.
+    Id3v2Header,
+    /// An ID3v2 tag at the end of the file, located through its footer or, for DSF files, through
+    /// the pointer in the header of the file.
+    Id3v2Appended,
+    /// An ID3v2 tag stored in an ID3 chunk of an AIFF, WAV or DSDIFF file.
+    Id3v2Chunk,
+    /// An ID3v1 tag in the last 128 bytes of the file.
+    Id3v1,
+    /// The extended data (`TAG+`) that precedes an ID3v1 tag.
+    Id3v1Extended,
+}
+
+/// A block of metadata found in a file, as reported by [`Tag::locate`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TagLocation {
+    /// The kind of the block.
+    pub kind: TagKind,
+    /// The byte range that the block occupies in the file. For [`TagKind::Id3v2Header`], this
+    /// includes any padding that follows the tag. For [`TagKind::Id3v2Chunk`], this is the data
+    /// of the chunk.
+    pub range: Range<u64>,
+    /// The version of an ID3v2 tag, `None` for ID3v1 tags.
+    pub version: Option<Version>,
+}
+
+/// Lists every block of metadata in the reader. See [`Tag::locate`].
+pub(crate) fn locate(mut reader: impl io::Read + io::Seek) -> crate::Result<Vec<TagLocation>> {
+    reader.rewind()?;
+    let format = {
+        let mut b = BufReader::new(&mut reader);
+        Format::magic(b.fill_buf()?)
+    };
+
+    let mut locations = Vec::new();
+    reader.rewind()?;
+    let chunk = match format {
+        Some(Format::Aiff) => {
+            optional(chunk::locate_id3_chunk::<chunk::AiffFormat, _>(&mut reader))?
+        }
+        Some(Format::Wav) => optional(chunk::locate_id3_chunk::<chunk::WavFormat, _>(&mut reader))?,
+        Some(Format::Dsdiff) => optional(chunk::locate_id3_chunk::<chunk::DsdiffFormat, _>(
+            &mut reader,
+        ))?,
+        Some(Format::Dsf) | Some(Format::Header) | None => None,
+    };
+    if let Some(range) = chunk {
+        locations.push(id3v2_location(&mut reader, TagKind::Id3v2Chunk, range)?);
+    }
+
+    let mut header_end = 0;
+    if format == Some(Format::Header) {
+        while let Some(end) = stream::tag::tag_end_at(&mut reader, header_end)? {
+            let range = header_end..end;
+            locations.push(id3v2_location(&mut reader, TagKind::Id3v2Header, range)?);
+            header_end = end;
+        }
+    }
+
+    let v1 = v1::locate(&mut reader)?;
+    reader.rewind()?;
+    let appended = match format {
+        Some(Format::Dsf) => optional(dsf::locate_id3(&mut reader))?,
+        Some(Format::Header) | None => {
+            // An appended tag is stored before the ID3v1 tag and its extended data.
+            let end = match &v1 {
+                Some(location) => location.ext.as_ref().unwrap_or(&location.tag).start,
+                None => reader.seek(io::SeekFrom::End(0))?,
+            };
+            optional(stream::tag::locate_id3v2_footer_at(&mut reader, end))?
+        }
+        Some(_) => None,
+    };
+    // A tag at the start of the file that has a footer is also found through its footer if
+    // nothing follows it.
+    if let Some(range) = appended.filter(|range| range.start >= header_end) {
+        locations.push(id3v2_location(&mut reader, TagKind::Id3v2Appended, range)?);
+    }
+
+    if let Some(location) = v1 {
+        if let Some(range) = location.ext {
+            locations.push(TagLocation {
+                kind: TagKind::Id3v1Extended,
+                range,
+                version: None,
+            });
+        }
+        locations.push(TagLocation {
+            kind: TagKind::Id3v1,
+            range: location.tag,
+            version: None,
+        });
+    }
+
+    locations.sort_by_key(|location| location.range.start);
+    Ok(locations)
+}
+
+/// Describes the ID3v2 tag in `range`, reading its version from the header.
+fn id3v2_location(
+    reader: impl io::Read + io::Seek,
+    kind: TagKind,
+    range: Range<u64>,
+) -> crate::Result<TagLocation> {
+    Ok(TagLocation {
+        kind,
+        version: Some(stream::tag::version_at(reader, range.start)?),
+        range,
+    })
+}
+
+/// Maps an [`ErrorKind::NoTag`] error to `None`.
+fn optional<T>(rs: crate::Result<T>) -> crate::Result<Option<T>> {
+    match rs {
+        Ok(v) => Ok(Some(v)),
+        Err(Error {
+            kind: ErrorKind::NoTag,
+            ..
+        }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Reads the ID3v2 and ID3v1 tags of the file at the indicated path, together with the container
+/// format and the location of the ID3v2 tag.
+///
+/// Unlike the other read functions, a missing tag is not an error, but reported as `None`.
+///
+/// # Example
+/// ```
+/// use id3::Format;
+///
+/// let file = id3::read_tagged_file("testdata/aiff/quiet.aiff")?;
+/// assert_eq!(file.format, Format::Aiff);
+/// assert!(file.id3v2.is_some());
+/// assert!(file.tag_range.is_some());
+///
+/// let file = id3::read_tagged_file("testdata/quiet.mp3")?;
+/// assert_eq!(file.format, Format::Header);
+/// assert_eq!(file.id3v2, None);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn read_tagged_file(path: impl AsRef<Path>) -> crate::Result<TaggedFile> {
+    TaggedFile::read_from(File::open(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_header() {
+        let file = read_tagged_file("testdata/id3v24.id3").unwrap();
+        assert_eq!(file.format, Format::Header);
+        assert_eq!(file.version, Some(Version::Id3v24));
+        let range = file.tag_range.unwrap();
+        assert_eq!(range.start, 0);
+        assert!(range.end > 10);
+        assert!(file.id3v1.is_none());
+    }
+
+    #[test]
+    fn read_v1() {
+        let file = read_tagged_file("testdata/id3v1.id3").unwrap();
+        assert_eq!(file.format, Format::Header);
+        assert!(file.id3v2.is_none());
+        assert!(file.version.is_none());
+        assert!(file.tag_range.is_none());
+        assert!(file.id3v1.is_some());
+    }
+
+    #[test]
+    fn read_chunks() {
+        let file = read_tagged_file("testdata/wav/tagged-mid.wav").unwrap();
+        assert_eq!(file.format, Format::Wav);
+        assert!(file.id3v2.is_some());
+        let range = file.tag_range.unwrap();
+        let data = std::fs::read("testdata/wav/tagged-mid.wav").unwrap();
+        assert_eq!(&data[range.start as usize..][..3], b"ID3");
+
+        let file = read_tagged_file("testdata/wav/tagless.wav").unwrap();
+        assert_eq!(file.format, Format::Wav);
+        assert!(file.id3v2.is_none());
+        assert!(file.tag_range.is_none());
+    }
+
+    #[test]
+    fn read_dsdiff() {
+        let file = read_tagged_file("testdata/dsdiff/tagged.dff").unwrap();
+        assert_eq!(file.format, Format::Dsdiff);
+        assert_eq!(file.version, Some(Version::Id3v23));
+        let range = file.tag_range.unwrap();
+        let data = std::fs::read("testdata/dsdiff/tagged.dff").unwrap();
+        assert_eq!(&data[range.start as usize..][..3], b"ID3");
+    }
+
+    #[test]
+    fn read_dsf() {
+        let file = read_tagged_file("testdata/dsf/tagged.dsf").unwrap();
+        assert_eq!(file.format, Format::Dsf);
+        assert_eq!(file.version, Some(Version::Id3v23));
+        let range = file.tag_range.unwrap();
+        let data = std::fs::read("testdata/dsf/tagged.dsf").unwrap();
+        assert_eq!(range.end, data.len() as u64);
+        assert_eq!(&data[range.start as usize..][..3], b"ID3");
+
+        let file = read_tagged_file("testdata/dsf/tagless.dsf").unwrap();
+        assert_eq!(file.format, Format::Dsf);
+        assert!(file.id3v2.is_none());
+        assert!(file.tag_range.is_none());
+    }
+
+    #[test]
+    fn locate_all() {
+        use crate::{Encoder, TagLike};
+
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        let mut data = Vec::new();
+        Encoder::new().padding(20).encode(&tag, &mut data).unwrap();
+        let first_end = data.len() as u64;
+        Encoder::new()
+            .version(Version::Id3v23)
+            .encode(&tag, &mut data)
+            .unwrap();
+        let second_end = data.len() as u64;
+        data.extend_from_slice(&[0xaa; 100]);
+        let appended_start = data.len() as u64;
+        Encoder::new().footer(true).encode(&tag, &mut data).unwrap();
+        let appended_end = data.len() as u64;
+        data.extend_from_slice(b"TAG+");
+        data.extend_from_slice(&[b' '; 223]);
+        let mut file = io::Cursor::new(data);
+        v1::Tag::from(&tag).write_to_file(&mut file).unwrap();
+
+        let locations = Tag::locate(&mut file).unwrap();
+        let summary: Vec<_> = locations
+            .into_iter()
+            .map(|l| (l.kind, l.range, l.version))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (TagKind::Id3v2Header, 0..first_end, Some(Version::Id3v24)),
+                (
+                    TagKind::Id3v2Header,
+                    first_end..second_end,
+                    Some(Version::Id3v23)
+                ),
+                (
+                    TagKind::Id3v2Appended,
+                    appended_start..appended_end,
+                    Some(Version::Id3v24)
+                ),
+                (
+                    TagKind::Id3v1Extended,
+                    appended_end..appended_end + 227,
+                    None
+                ),
+                (TagKind::Id3v1, appended_end + 227..appended_end + 355, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn locate_containers() {
+        for (path, kind) in [
+            ("testdata/wav/tagged-mid.wav", TagKind::Id3v2Chunk),
+            ("testdata/dsdiff/tagged.dff", TagKind::Id3v2Chunk),
+            ("testdata/dsf/tagged.dsf", TagKind::Id3v2Appended),
+        ] {
+            let file = read_tagged_file(path).unwrap();
+            let locations = Tag::locate(File::open(path).unwrap()).unwrap();
+            assert_eq!(
+                locations,
+                vec![TagLocation {
+                    kind,
+                    range: file.tag_range.unwrap(),
+                    version: file.version,
+                }],
+                "{}",
+                path
+            );
+        }
+        assert_eq!(
+            Tag::locate(File::open("testdata/wav/tagless.wav").unwrap()).unwrap(),
+            vec![]
+        );
+    }
+}
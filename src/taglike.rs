@@ -1,11 +1,18 @@
 use crate::frame::Content;
 use crate::frame::{
-    Comment, EncapsulatedObject, ExtendedText, Frame, Lyrics, Picture, PictureType,
-    SynchronisedLyrics, Timestamp,
+    ChannelType, Comment, EncapsulatedObject, ExtendedText, Frame, Lyrics, Picture, PictureType,
+    Popularimeter, RatingConvention, RelativeVolumeAdjustment, ReplayGainScope, SynchronisedLyrics,
+    TermsOfUse, Timestamp, VolumeAdjustment, ITUNES_SOUND_CHECK,
 };
+use crate::{Error, ErrorKind};
 use std::borrow::Cow;
 use std::mem::swap;
 
+const ACOUSTID_ID: &str = "Acoustid Id";
+const ACOUSTID_FINGERPRINT: &str = "Acoustid Fingerprint";
+const CATALOG_NUMBER: &str = "CATALOGNUMBER";
+const BARCODE: &str = "BARCODE";
+
 /// TagLike is a trait that provides a set of useful default methods that make manipulation of tag
 /// frames easier.
 pub trait TagLike: private::Sealed {
@@ -39,6 +46,74 @@ pub trait TagLike: private::Sealed {
             .and_then(|text| text.parse().ok())
     }
 
+    /// Returns the value of a numeric text frame, like TBPM, TLEN, TSIZ or TDLY.
+    ///
+    /// Surrounding whitespace is ignored, as are any further values and a fractional part. Returns
+    /// `None` if the frame is missing or does not start with a number that fits in a `u32`.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("TBPM", " 128.5 "));
+    /// assert_eq!(tag.get_u32("TBPM"), Some(128));
+    ///
+    /// tag.add_frame(Frame::text("TBPM", "fast"));
+    /// assert_eq!(tag.get_u32("TBPM"), None);
+    /// ```
+    fn get_u32(&self, id: &str) -> Option<u32> {
+        parse_leading_integer(self.text_for_frame_id(id)?)?
+            .try_into()
+            .ok()
+    }
+
+    /// Returns the value of a numeric text frame that may be negative.
+    ///
+    /// The text is interpreted like [`TagLike::get_u32`].
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("TDLY", "-20"));
+    /// assert_eq!(tag.get_i32("TDLY"), Some(-20));
+    /// ```
+    fn get_i32(&self, id: &str) -> Option<i32> {
+        parse_leading_integer(self.text_for_frame_id(id)?)?
+            .try_into()
+            .ok()
+    }
+
+    /// Sets a text frame to a number.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_u32("TBPM", 128);
+    /// assert_eq!(tag.get("TBPM").unwrap().content().text(), Some("128"));
+    /// ```
+    fn set_u32(&mut self, id: &str, value: u32) {
+        self.set_text(id, value.to_string());
+    }
+
+    /// Sets a text frame to a number that may be negative.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_i32("TDLY", -20);
+    /// assert_eq!(tag.get_i32("TDLY"), Some(-20));
+    /// ```
+    fn set_i32(&mut self, id: &str, value: i32) {
+        self.set_text(id, value.to_string());
+    }
+
     /// Returns the (disc, total_discs) tuple.
     #[doc(hidden)]
     fn disc_pair(&self) -> Option<(u32, Option<u32>)> {
@@ -81,6 +156,10 @@ pub trait TagLike: private::Sealed {
 
     /// Adds the frame to the tag, replacing and returning any conflicting frame.
     ///
+    /// A frame that replaces a conflicting frame takes over its position; any other frame is
+    /// appended after the existing frames. Frames are encoded in this order, so the order of a
+    /// decoded tag is preserved when it is written back.
+    ///
     /// # Example
     /// ```
     /// use id3::{Tag, TagLike, Frame, Content};
@@ -102,16 +181,131 @@ pub trait TagLike: private::Sealed {
     /// }
     /// ```
     fn add_frame(&mut self, new_frame: impl Into<Frame>) -> Option<Frame> {
+        let new_frame = new_frame.into();
+        match self
+            .frames_vec()
+            .iter()
+            .position(|frame| frame.compare(&new_frame))
+        {
+            Some(conflict_index) => Some(std::mem::replace(
+                &mut self.frames_vec_mut()[conflict_index],
+                new_frame,
+            )),
+            None => {
+                self.frames_vec_mut().push(new_frame);
+                None
+            }
+        }
+    }
+
+    /// Returns an entry for the first frame that matches `key`, for in-place manipulation.
+    ///
+    /// The key has the same form as for [`TagLike::replace`]: either a frame ID or a frame ID and
+    /// a description separated by a colon, like `TXXX:MOOD`.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::ExtendedText;
+    ///
+    /// let mut tag = Tag::new();
+    /// let mood = |value: &str| ExtendedText {
+    ///     description: "MOOD".to_string(),
+    ///     value: value.to_string(),
+    /// };
+    ///
+    /// tag.entry("TXXX:MOOD").or_insert_with(|| mood("happy"));
+    /// tag.entry("TXXX:MOOD").or_insert_with(|| mood("sad"));
+    /// assert_eq!(tag.extended_texts().next().unwrap().value, "happy");
+    ///
+    /// tag.entry("TXXX:MOOD")
+    ///     .and_modify(|frame| *frame = mood("sad").into())
+    ///     .or_insert_with(|| mood("neutral"));
+    /// assert_eq!(tag.extended_texts().next().unwrap().value, "sad");
+    ///
+    /// assert!(tag.entry("TXXX:MOOD").remove().is_some());
+    /// assert!(tag.entry("TXXX:MOOD").get().is_none());
+    /// ```
+    fn entry(&mut self, key: &str) -> FrameEntry<'_> {
+        let index = self
+            .frames_vec()
+            .iter()
+            .position(|frame| frame.matches_key(key));
+        FrameEntry {
+            frames: self.frames_vec_mut(),
+            index,
+        }
+    }
+
+    /// Returns the frame at the specified position, in the order in which frames are encoded.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_album("Album");
+    /// tag.set_title("Title");
+    /// assert_eq!(tag.frame_at(1).map(|frame| frame.id()), Some("TIT2"));
+    /// assert!(tag.frame_at(2).is_none());
+    /// ```
+    fn frame_at(&self, index: usize) -> Option<&Frame> {
+        self.frames_vec().get(index)
+    }
+
+    /// Inserts the frame at the specified position, replacing and returning any conflicting
+    /// frame like [`TagLike::add_frame`].
+    ///
+    /// A conflicting frame is removed before inserting, so the new frame always ends up at
+    /// `index`.
+    ///
+    /// # Panics
+    /// If `index` is greater than the number of frames after removing the conflicting frame.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_album("Album");
+    /// tag.set_title("Title");
+    ///
+    /// // Some players require the title to be the first frame.
+    /// let removed = tag.insert_frame_at(0, Frame::text("TIT2", "Other Title"));
+    /// assert_eq!(removed.unwrap().content().text(), Some("Title"));
+    /// assert_eq!(tag.frame_at(0).unwrap().content().text(), Some("Other Title"));
+    /// assert_eq!(tag.frame_at(1).unwrap().id(), "TALB");
+    /// ```
+    fn insert_frame_at(&mut self, index: usize, new_frame: impl Into<Frame>) -> Option<Frame> {
         let new_frame = new_frame.into();
         let removed = self
             .frames_vec()
             .iter()
             .position(|frame| frame.compare(&new_frame))
             .map(|conflict_index| self.frames_vec_mut().remove(conflict_index));
-        self.frames_vec_mut().push(new_frame);
+        self.frames_vec_mut().insert(index, new_frame);
         removed
     }
 
+    /// Swaps the positions of two frames.
+    ///
+    /// # Panics
+    /// If `a` or `b` are out of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_album("Album");
+    /// tag.set_title("Title");
+    /// tag.swap_frames(0, 1);
+    /// assert_eq!(tag.frame_at(0).unwrap().id(), "TIT2");
+    /// ```
+    fn swap_frames(&mut self, a: usize, b: usize) {
+        self.frames_vec_mut().swap(a, b);
+    }
+
     /// Adds a text frame.
     ///
     /// # Example
@@ -184,9 +378,97 @@ pub trait TagLike: private::Sealed {
         remove
     }
 
+    /// Removes the first frame that is exactly equal to `frame`, leaving other frames with the
+    /// same ID in place.
+    ///
+    /// Returns true if a frame was removed.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    /// use id3::frame::Comment;
+    ///
+    /// let mut tag = Tag::new();
+    /// let first = Comment {
+    ///     lang: "eng".to_string(),
+    ///     description: "first".to_string(),
+    ///     text: "1".to_string(),
+    /// };
+    /// let second = Comment {
+    ///     description: "second".to_string(),
+    ///     ..first.clone()
+    /// };
+    /// tag.add_frame(first.clone());
+    /// tag.add_frame(second);
+    ///
+    /// assert!(tag.remove_frame(&Frame::from(first.clone())));
+    /// assert!(!tag.remove_frame(&Frame::from(first)));
+    /// assert_eq!(tag.comments().count(), 1);
+    /// ```
+    fn remove_frame(&mut self, frame: &Frame) -> bool {
+        match self.frames_vec().iter().position(|f| f == frame) {
+            Some(index) => {
+                self.frames_vec_mut().remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the first frame that matches `key` with `new_frame`, keeping its position.
+    ///
+    /// The key is either a frame ID, like `TIT2`, or a frame ID and a description separated by a
+    /// colon, like `TXXX:MOOD` or `COMM:iTunNORM`. The description is matched against the
+    /// description of TXXX, WXXX, COMM, USLT, SYLT, APIC and GEOB frames, the user of POPM
+    /// frames, the owner of PRIV and UFID frames and the element ID of CHAP and CTOC frames.
+    ///
+    /// Returns the replaced frame. If no frame matches, the tag is left unchanged and `None` is
+    /// returned. Like with [`TagLike::add_frame`], another frame that conflicts with `new_frame`,
+    /// e.g. a TXXX frame with the same description, is removed.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::ExtendedText;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(ExtendedText {
+    ///     description: "MOOD".to_string(),
+    ///     value: "happy".to_string(),
+    /// });
+    /// tag.add_frame(ExtendedText {
+    ///     description: "TEMPO".to_string(),
+    ///     value: "fast".to_string(),
+    /// });
+    ///
+    /// let old = tag.replace("TXXX:MOOD", ExtendedText {
+    ///     description: "MOOD".to_string(),
+    ///     value: "sad".to_string(),
+    /// });
+    /// assert_eq!(old.unwrap().content().extended_text().unwrap().value, "happy");
+    /// assert_eq!(tag.extended_texts().map(|t| t.value.as_str()).collect::<Vec<_>>(), ["sad", "fast"]);
+    /// ```
+    fn replace(&mut self, key: &str, new_frame: impl Into<Frame>) -> Option<Frame> {
+        let index = self
+            .frames_vec()
+            .iter()
+            .position(|frame| frame.matches_key(key))?;
+        let frames = self.frames_vec_mut();
+        let old = std::mem::replace(&mut frames[index], new_frame.into());
+        if let Some(conflict_index) =
+            (0..frames.len()).find(|&i| i != index && frames[i].compare(&frames[index]))
+        {
+            frames.remove(conflict_index);
+        }
+        Some(old)
+    }
+
     /// Returns the year (TYER).
     /// Returns `None` if the year frame could not be found or if it could not be parsed.
     ///
+    /// The text is interpreted like [`TagLike::get_i32`], so trailing text is ignored and e.g.
+    /// "2014-05-01" is read as 2014.
+    ///
     /// # Example
     /// ```
     /// use id3::{Tag, TagLike, Frame};
@@ -198,15 +480,16 @@ pub trait TagLike: private::Sealed {
     /// tag.add_frame(Frame::text("TYER", "2014"));
     /// assert_eq!(tag.year(), Some(2014));
     ///
+    /// tag.add_frame(Frame::text("TYER", "2014-05-01"));
+    /// assert_eq!(tag.year(), Some(2014));
+    ///
     /// tag.remove("TYER");
     ///
     /// tag.add_frame(Frame::text("TYER", "nope"));
     /// assert!(tag.year().is_none());
     /// ```
     fn year(&self) -> Option<i32> {
-        self.get("TYER")
-            .and_then(|frame| frame.content().text())
-            .and_then(|text| text.trim_start_matches('0').parse().ok())
+        self.get_i32("TYER")
     }
 
     /// Sets the year (TYER).
@@ -287,6 +570,97 @@ pub trait TagLike: private::Sealed {
         self.remove("TDRC");
     }
 
+    /// Returns the recording date, falling back to assembling it from the ID3v2.3 TYER, TDAT and
+    /// TIME frames if there is no TDRC frame.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("TYER", "2014"));
+    /// tag.add_frame(Frame::text("TDAT", "0205"));
+    /// tag.add_frame(Frame::text("TIME", "0915"));
+    /// assert_eq!(
+    ///     tag.date_recorded_v23().map(|t| t.to_string()),
+    ///     Some("2014-05-02T09:15".to_string())
+    /// );
+    /// ```
+    fn date_recorded_v23(&self) -> Option<Timestamp> {
+        if let Some(timestamp) = self.date_recorded() {
+            return Some(timestamp);
+        }
+        let year = self.year()?;
+        // TDAT and TIME both consist of two pairs of digits, DDMM and HHMM respectively.
+        let pairs = |id: &str| {
+            let text = self.text_for_frame_id(id)?;
+            if text.len() != 4 || !text.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            Some((text[..2].parse().ok()?, text[2..].parse().ok()?))
+        };
+        let mut timestamp = Timestamp {
+            year,
+            month: None,
+            day: None,
+            hour: None,
+            minute: None,
+            second: None,
+        };
+        if let Some((day, month)) = pairs("TDAT") {
+            timestamp.day = Some(day);
+            timestamp.month = Some(month);
+            if let Some((hour, minute)) = pairs("TIME") {
+                timestamp.hour = Some(hour);
+                timestamp.minute = Some(minute);
+            }
+        }
+        if !timestamp.is_valid() {
+            timestamp.month = None;
+            timestamp.day = None;
+            timestamp.hour = None;
+            timestamp.minute = None;
+        }
+        Some(timestamp)
+    }
+
+    /// Sets the recording date using the ID3v2.3 TYER, TDAT and TIME frames and removes any TDRC
+    /// frame.
+    ///
+    /// ID3v2.3 can not represent a month without a day or seconds, so these are discarded.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike, Timestamp};
+    ///
+    /// let mut tag = Tag::new();
+    /// let timestamp = Timestamp::builder().year(2014).month(5).day(2).hour(9).minute(15).build()?;
+    /// tag.set_date_recorded_v23(timestamp);
+    /// assert_eq!(tag.year(), Some(2014));
+    /// assert_eq!(tag.get("TDAT").and_then(|f| f.content().text()), Some("0205"));
+    /// assert_eq!(tag.get("TIME").and_then(|f| f.content().text()), Some("0915"));
+    /// assert_eq!(tag.date_recorded_v23(), Some(timestamp));
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    fn set_date_recorded_v23(&mut self, timestamp: Timestamp) {
+        self.remove_date_recorded();
+        self.set_year(timestamp.year);
+        match (timestamp.month, timestamp.day) {
+            (Some(month), Some(day)) => self.set_text("TDAT", format!("{:02}{:02}", day, month)),
+            _ => {
+                self.remove("TDAT");
+            }
+        }
+        match (timestamp.day, timestamp.hour, timestamp.minute) {
+            (Some(_), Some(hour), Some(minute)) => {
+                self.set_text("TIME", format!("{:02}{:02}", hour, minute))
+            }
+            _ => {
+                self.remove("TIME");
+            }
+        }
+    }
+
     /// Return the content of the TDRL frame, if any
     ///
     /// # Example
@@ -379,6 +753,51 @@ pub trait TagLike: private::Sealed {
         self.remove("TDOR");
     }
 
+    /// Returns the original release date, falling back to the year of the ID3v2.3 TORY frame if
+    /// there is no TDOR frame.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("TORY", "1989"));
+    /// assert_eq!(tag.original_date_released_v23().map(|t| t.year), Some(1989));
+    /// ```
+    fn original_date_released_v23(&self) -> Option<Timestamp> {
+        if let Some(timestamp) = self.original_date_released() {
+            return Some(timestamp);
+        }
+        self.get_i32("TORY").map(|year| Timestamp {
+            year,
+            month: None,
+            day: None,
+            hour: None,
+            minute: None,
+            second: None,
+        })
+    }
+
+    /// Returns the timestamp of a timestamp frame such as TDRC, TDRL or TDOR, accepting
+    /// malformed notations that are common in the wild. Refer to [`Timestamp::parse_lenient`]
+    /// for the accepted notations and the meaning of the returned flag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("TDRC", "02.05.2014"));
+    /// let (timestamp, lossless) = tag.timestamp_lenient("TDRC").unwrap();
+    /// assert_eq!(timestamp.to_string(), "2014-05-02");
+    /// assert!(lossless);
+    /// ```
+    fn timestamp_lenient(&self, id: &str) -> Option<(Timestamp, bool)> {
+        self.get(id)
+            .and_then(|frame| frame.content().text())
+            .and_then(Timestamp::parse_lenient)
+    }
+
     /// Returns the artist (TPE1).
     ///
     /// # Example
@@ -570,6 +989,9 @@ pub trait TagLike: private::Sealed {
 
     /// Returns the duration (TLEN).
     ///
+    /// The text is interpreted like [`TagLike::get_u32`], so surrounding whitespace and a
+    /// fractional part are ignored.
+    ///
     /// # Example
     /// ```
     /// use id3::{Frame, Tag, TagLike};
@@ -579,9 +1001,12 @@ pub trait TagLike: private::Sealed {
     ///
     /// tag.add_frame(Frame::text("TLEN", "350"));
     /// assert_eq!(tag.duration(), Some(350));
+    ///
+    /// tag.add_frame(Frame::text("TLEN", " 350.7 "));
+    /// assert_eq!(tag.duration(), Some(350));
     /// ```
     fn duration(&self) -> Option<u32> {
-        self.text_for_frame_id("TLEN").and_then(|t| t.parse().ok())
+        self.get_u32("TLEN")
     }
 
     /// Sets the duration (TLEN).
@@ -650,16 +1075,92 @@ pub trait TagLike: private::Sealed {
     /// tag.set_genre("(31)");
     /// assert_eq!(tag.genre_parsed(), Some(Cow::Owned("Trance".to_string())));
     /// ```
-    fn genre_parsed(&self) -> Option<Cow<str>> {
+    fn genre_parsed(&self) -> Option<Cow<'_, str>> {
         let tcon = self.text_for_frame_id("TCON")?;
         Some(crate::tcon::Parser::parse_tcon(tcon))
     }
 
+    /// Returns the genre (TCON) with ID3v1 genre indices resolved against the specified table.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::tcon::GenreTable;
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_genre("(1)");
+    /// let table = GenreTable::new(&["Blues", "Klassischer Rock"]);
+    /// assert_eq!(tag.genre_parsed_with(table).as_deref(), Some("Klassischer Rock"));
+    /// ```
+    fn genre_parsed_with(&self, table: crate::tcon::GenreTable<'_>) -> Option<Cow<'_, str>> {
+        let tcon = self.text_for_frame_id("TCON")?;
+        Some(crate::tcon::Parser::parse_tcon_with(tcon, table))
+    }
+
     /// Returns the (potential) multiple plain genres (TCON).
     fn genres(&self) -> Option<Vec<&str>> {
         self.text_values_for_frame_id("TCON")
     }
 
+    /// Returns the (potential) multiple genres (TCON) with ID3v1 genre indices resolved.
+    ///
+    /// In addition to the references in parentheses that are resolved by
+    /// [`genre_parsed`](TagLike::genre_parsed), bare indices and the "RX" and "CR" tokens of
+    /// ID3v2.4 are resolved too.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    /// use std::borrow::Cow;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::with_content(
+    ///     "TCON",
+    ///     id3::Content::new_text_values(["(31)", "13", "RX", "Eurodisco"]),
+    /// ));
+    /// assert_eq!(
+    ///     tag.genres_parsed(),
+    ///     Some(vec![
+    ///         Cow::Owned("Trance".to_string()),
+    ///         Cow::Owned("Pop".to_string()),
+    ///         Cow::Owned("Remix".to_string()),
+    ///         Cow::Borrowed("Eurodisco"),
+    ///     ])
+    /// );
+    /// ```
+    fn genres_parsed(&self) -> Option<Vec<Cow<'_, str>>> {
+        self.genres_parsed_with(crate::tcon::GenreTable::WINAMP)
+    }
+
+    /// Returns the (potential) multiple genres (TCON) like
+    /// [`genres_parsed`](TagLike::genres_parsed), with ID3v1 genre indices resolved against the
+    /// specified table.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::tcon::GenreTable;
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::with_content(
+    ///     "TCON",
+    ///     id3::Content::new_text_values(["13", "80"]),
+    /// ));
+    /// assert_eq!(
+    ///     tag.genres_parsed_with(GenreTable::ID3V1),
+    ///     Some(vec!["Pop".into(), "(80)".into()])
+    /// );
+    /// ```
+    fn genres_parsed_with(&self, table: crate::tcon::GenreTable<'_>) -> Option<Vec<Cow<'_, str>>> {
+        let values = self.text_values_for_frame_id("TCON")?;
+        Some(
+            values
+                .into_iter()
+                .map(|value| crate::tcon::resolve(value, table))
+                .collect(),
+        )
+    }
+
     /// Sets the plain genre (TCON).
     ///
     /// No attempt is made to interpret and convert ID3v1 indices.
@@ -676,6 +1177,50 @@ pub trait TagLike: private::Sealed {
         self.set_text("TCON", genre);
     }
 
+    /// Sets the genre (TCON), writing genres that are in the ID3v1 genre list as a numeric
+    /// reference for compatibility with devices that ignore free text genres.
+    ///
+    /// Genres that are not in the list are written as is.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::tcon::Notation;
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_genre_numeric("Pop", Notation::Parenthesized);
+    /// assert_eq!(tag.genre(), Some("(13)Pop"));
+    /// tag.set_genre_numeric("Pop", Notation::Bare);
+    /// assert_eq!(tag.genre(), Some("13"));
+    /// assert_eq!(tag.genres_parsed(), Some(vec!["Pop".into()]));
+    /// ```
+    fn set_genre_numeric(&mut self, genre: &str, notation: crate::tcon::Notation) {
+        self.set_genre_numeric_with(genre, notation, crate::tcon::GenreTable::WINAMP);
+    }
+
+    /// Sets the genre (TCON) like [`set_genre_numeric`](TagLike::set_genre_numeric), but only
+    /// writes genres that are in the specified table as a numeric reference.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::tcon::{GenreTable, Notation};
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_genre_numeric_with("Folk", Notation::Bare, GenreTable::ID3V1);
+    /// assert_eq!(tag.genre(), Some("Folk"));
+    /// tag.set_genre_numeric_with("Folk", Notation::Bare, GenreTable::WINAMP);
+    /// assert_eq!(tag.genre(), Some("80"));
+    /// ```
+    fn set_genre_numeric_with(
+        &mut self,
+        genre: &str,
+        notation: crate::tcon::Notation,
+        table: crate::tcon::GenreTable<'_>,
+    ) {
+        self.set_text("TCON", crate::tcon::encode_with(genre, notation, table));
+    }
+
     /// Removes the genre (TCON).
     ///
     /// # Example
@@ -1007,20 +1552,409 @@ pub trait TagLike: private::Sealed {
         });
     }
 
-    /// Adds a picture frame (APIC).
-    /// Any other pictures with the same type will be removed from the tag.
+    /// Returns the AcoustID of the track (TXXX "Acoustid Id").
     ///
     /// # Example
     /// ```
     /// use id3::{Tag, TagLike};
-    /// use id3::frame::{Picture, PictureType};
     ///
-    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let mut tag = Tag::new();
-    ///     tag.add_picture(Picture {
-    ///         mime_type: "image/jpeg".to_string(),
-    ///         picture_type: PictureType::Other,
-    ///         description: "some image".to_string(),
+    /// let mut tag = Tag::new();
+    /// assert!(tag.acoustid_id().is_none());
+    ///
+    /// tag.set_acoustid_id("0fe4e07c-e6bd-4a43-8a56-3a1a1a4bec7f").unwrap();
+    /// assert_eq!(tag.acoustid_id(), Some("0fe4e07c-e6bd-4a43-8a56-3a1a1a4bec7f"));
+    /// ```
+    fn acoustid_id(&self) -> Option<&str> {
+        extended_text_value(self, ACOUSTID_ID)
+    }
+
+    /// Sets the AcoustID of the track (TXXX "Acoustid Id").
+    ///
+    /// The ID must be a UUID in its hyphenated form. If it is not, an error with
+    /// [`ErrorKind::InvalidInput`] is returned and the tag is left untouched.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(tag.set_acoustid_id("not an id").is_err());
+    /// assert!(tag.set_acoustid_id("0fe4e07c-e6bd-4a43-8a56-3a1a1a4bec7f").is_ok());
+    /// ```
+    fn set_acoustid_id(&mut self, id: impl Into<String>) -> crate::Result<()> {
+        let id = id.into();
+        if !is_uuid(&id) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "AcoustID must be a hyphenated UUID",
+            ));
+        }
+        self.add_frame(ExtendedText {
+            description: ACOUSTID_ID.to_string(),
+            value: id,
+        });
+        Ok(())
+    }
+
+    /// Removes the AcoustID of the track (TXXX "Acoustid Id").
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_acoustid_id("0fe4e07c-e6bd-4a43-8a56-3a1a1a4bec7f").unwrap();
+    /// assert!(tag.acoustid_id().is_some());
+    ///
+    /// tag.remove_acoustid_id();
+    /// assert!(tag.acoustid_id().is_none());
+    /// ```
+    fn remove_acoustid_id(&mut self) {
+        self.remove_extended_text(Some(ACOUSTID_ID), None);
+    }
+
+    /// Returns the Chromaprint fingerprint of the track (TXXX "Acoustid Fingerprint").
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(tag.acoustid_fingerprint().is_none());
+    ///
+    /// tag.set_acoustid_fingerprint("AQADtEmUaEkSRZEGAA").unwrap();
+    /// assert_eq!(tag.acoustid_fingerprint(), Some("AQADtEmUaEkSRZEGAA"));
+    /// ```
+    fn acoustid_fingerprint(&self) -> Option<&str> {
+        extended_text_value(self, ACOUSTID_FINGERPRINT)
+    }
+
+    /// Sets the Chromaprint fingerprint of the track (TXXX "Acoustid Fingerprint").
+    ///
+    /// The fingerprint must be in its compressed, URL-safe base64 form as produced by `fpcalc`. If
+    /// it is not, an error with [`ErrorKind::InvalidInput`] is returned and the tag is left
+    /// untouched.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(tag.set_acoustid_fingerprint("").is_err());
+    /// assert!(tag.set_acoustid_fingerprint("AQAD/tEmU").is_err());
+    /// assert!(tag.set_acoustid_fingerprint("AQADtEmUaEkSRZEGAA").is_ok());
+    /// ```
+    fn set_acoustid_fingerprint(&mut self, fingerprint: impl Into<String>) -> crate::Result<()> {
+        let fingerprint = fingerprint.into();
+        let valid = !fingerprint.is_empty()
+            && fingerprint
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+        if !valid {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "AcoustID fingerprint must be URL-safe base64",
+            ));
+        }
+        self.add_frame(ExtendedText {
+            description: ACOUSTID_FINGERPRINT.to_string(),
+            value: fingerprint,
+        });
+        Ok(())
+    }
+
+    /// Removes the Chromaprint fingerprint of the track (TXXX "Acoustid Fingerprint").
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_acoustid_fingerprint("AQADtEmUaEkSRZEGAA").unwrap();
+    /// assert!(tag.acoustid_fingerprint().is_some());
+    ///
+    /// tag.remove_acoustid_fingerprint();
+    /// assert!(tag.acoustid_fingerprint().is_none());
+    /// ```
+    fn remove_acoustid_fingerprint(&mut self) {
+        self.remove_extended_text(Some(ACOUSTID_FINGERPRINT), None);
+    }
+
+    /// Returns the catalog number assigned by the label (TXXX "CATALOGNUMBER").
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(tag.catalog_number().is_none());
+    /// tag.set_catalog_number("WARPCD92");
+    /// assert_eq!(tag.catalog_number(), Some("WARPCD92"));
+    /// ```
+    fn catalog_number(&self) -> Option<&str> {
+        extended_text_value(self, CATALOG_NUMBER)
+    }
+
+    /// Sets the catalog number assigned by the label (TXXX "CATALOGNUMBER").
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_catalog_number("WARPCD92");
+    /// tag.set_catalog_number("WARPCD92R");
+    /// assert_eq!(tag.catalog_number(), Some("WARPCD92R"));
+    /// ```
+    fn set_catalog_number(&mut self, catalog_number: impl Into<String>) {
+        self.add_frame(ExtendedText {
+            description: CATALOG_NUMBER.to_string(),
+            value: catalog_number.into(),
+        });
+    }
+
+    /// Removes the catalog number (TXXX "CATALOGNUMBER").
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_catalog_number("WARPCD92");
+    /// tag.remove_catalog_number();
+    /// assert!(tag.catalog_number().is_none());
+    /// ```
+    fn remove_catalog_number(&mut self) {
+        self.remove_extended_text(Some(CATALOG_NUMBER), None);
+    }
+
+    /// Returns the barcode of the release (TXXX "BARCODE").
+    ///
+    /// The value is returned as stored, without validation.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(tag.barcode().is_none());
+    /// tag.set_barcode("4006381333931").unwrap();
+    /// assert_eq!(tag.barcode(), Some("4006381333931"));
+    /// ```
+    fn barcode(&self) -> Option<&str> {
+        extended_text_value(self, BARCODE)
+    }
+
+    /// Sets the barcode of the release (TXXX "BARCODE").
+    ///
+    /// The barcode must be an EAN-8, UPC-A or EAN-13 code with a valid check digit, otherwise an
+    /// error is returned and the tag is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(tag.set_barcode("036000291452").is_ok());
+    /// assert!(tag.set_barcode("036000291453").is_err());
+    /// assert!(tag.set_barcode("not a barcode").is_err());
+    /// assert_eq!(tag.barcode(), Some("036000291452"));
+    /// ```
+    fn set_barcode(&mut self, barcode: impl Into<String>) -> crate::Result<()> {
+        let barcode = barcode.into();
+        if !is_valid_barcode(&barcode) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "barcode must be an EAN-8, UPC-A or EAN-13 code with a valid check digit",
+            ));
+        }
+        self.add_frame(ExtendedText {
+            description: BARCODE.to_string(),
+            value: barcode,
+        });
+        Ok(())
+    }
+
+    /// Removes the barcode of the release (TXXX "BARCODE").
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_barcode("4006381333931").unwrap();
+    /// tag.remove_barcode();
+    /// assert!(tag.barcode().is_none());
+    /// ```
+    fn remove_barcode(&mut self) {
+        self.remove_extended_text(Some(BARCODE), None);
+    }
+
+    /// Returns the ReplayGain gain in decibels and the peak amplitude for the specified scope
+    /// from the `REPLAYGAIN_*` user defined text frames (TXXX).
+    ///
+    /// Returns `None` if the gain is missing or could not be parsed. A missing or malformed peak
+    /// is returned as `None`.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{ExtendedText, ReplayGainScope};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(ExtendedText {
+    ///     description: "REPLAYGAIN_TRACK_GAIN".to_string(),
+    ///     value: "-6.50 dB".to_string(),
+    /// });
+    /// assert_eq!(tag.replay_gain(ReplayGainScope::Track), Some((-6.5, None)));
+    /// assert_eq!(tag.replay_gain(ReplayGainScope::Album), None);
+    /// ```
+    fn replay_gain(&self, scope: ReplayGainScope) -> Option<(f64, Option<f64>)> {
+        let gain = extended_text_value(self, scope.gain_description())?;
+        let gain = gain.trim();
+        let gain = match gain.len().checked_sub(2) {
+            Some(i) if gain.is_char_boundary(i) && gain[i..].eq_ignore_ascii_case("dB") => {
+                &gain[..i]
+            }
+            _ => gain,
+        };
+        let gain = gain.trim().parse().ok()?;
+        let peak = extended_text_value(self, scope.peak_description())
+            .and_then(|peak| peak.trim().parse().ok());
+        Some((gain, peak))
+    }
+
+    /// Sets the ReplayGain gain in decibels and the peak amplitude for the specified scope as
+    /// `REPLAYGAIN_*` user defined text frames (TXXX).
+    ///
+    /// If `peak` is `None`, any existing peak frame of the scope is removed.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::ReplayGainScope;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_replay_gain(ReplayGainScope::Album, 1.25, Some(0.5));
+    /// assert_eq!(tag.replay_gain(ReplayGainScope::Album), Some((1.25, Some(0.5))));
+    /// assert!(tag.extended_texts().any(|t| t.value == "+1.25 dB"));
+    /// ```
+    fn set_replay_gain(&mut self, scope: ReplayGainScope, gain_db: f64, peak: Option<f64>) {
+        self.add_frame(ExtendedText {
+            description: scope.gain_description().to_string(),
+            value: format!("{:+.2} dB", gain_db),
+        });
+        match peak {
+            Some(peak) => {
+                self.add_frame(ExtendedText {
+                    description: scope.peak_description().to_string(),
+                    value: format!("{:.6}", peak),
+                });
+            }
+            None => self.remove_extended_text(Some(scope.peak_description()), None),
+        }
+    }
+
+    /// Copies the ReplayGain values of the specified scope to the adjustment of the specified
+    /// channel in the relative volume adjustment frame (RVA2) that is identified by the scope.
+    ///
+    /// The RVA2 frame is created if it does not exist yet, adjustments for other channels are left
+    /// untouched. Returns false if the tag contains no ReplayGain values for the scope.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{ChannelType, ReplayGainScope};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_replay_gain(ReplayGainScope::Track, -3.0, Some(1.0));
+    /// assert!(tag.replay_gain_to_rva2(ReplayGainScope::Track, ChannelType::MasterVolume));
+    ///
+    /// let rva2 = tag.get("RVA2").unwrap().content().relative_volume_adjustment().unwrap();
+    /// let adjustment = rva2.adjustment(ChannelType::MasterVolume).unwrap();
+    /// assert_eq!(rva2.identification, "track");
+    /// assert_eq!(adjustment.gain_db(), -3.0);
+    /// assert_eq!(adjustment.peak(), Some(1.0));
+    /// ```
+    fn replay_gain_to_rva2(&mut self, scope: ReplayGainScope, channel_type: ChannelType) -> bool {
+        let Some((gain_db, peak)) = self.replay_gain(scope) else {
+            return false;
+        };
+        let adjustment = VolumeAdjustment::from_gain(channel_type, gain_db, peak);
+        let existing =
+            self.frames_vec_mut()
+                .iter_mut()
+                .find_map(|frame| match frame.content_mut() {
+                    Content::RelativeVolumeAdjustment(rva2)
+                        if rva2.identification == scope.identification() =>
+                    {
+                        Some(rva2)
+                    }
+                    _ => None,
+                });
+        match existing {
+            Some(rva2) => {
+                rva2.set_adjustment(adjustment);
+            }
+            None => {
+                self.add_frame(RelativeVolumeAdjustment {
+                    identification: scope.identification().to_string(),
+                    adjustments: vec![adjustment],
+                });
+            }
+        }
+        true
+    }
+
+    /// Copies the adjustment of the specified channel in the relative volume adjustment frame
+    /// (RVA2) that is identified by the scope to the ReplayGain values of that scope.
+    ///
+    /// Returns false if the tag contains no such RVA2 frame or if the frame has no adjustment for
+    /// the channel.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{ChannelType, RelativeVolumeAdjustment, ReplayGainScope, VolumeAdjustment};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(RelativeVolumeAdjustment {
+    ///     identification: "album".to_string(),
+    ///     adjustments: vec![VolumeAdjustment::from_gain(ChannelType::MasterVolume, 2.5, None)],
+    /// });
+    /// assert!(!tag.rva2_to_replay_gain(ReplayGainScope::Album, ChannelType::FrontLeft));
+    /// assert!(tag.rva2_to_replay_gain(ReplayGainScope::Album, ChannelType::MasterVolume));
+    /// assert_eq!(tag.replay_gain(ReplayGainScope::Album), Some((2.5, None)));
+    /// ```
+    fn rva2_to_replay_gain(&mut self, scope: ReplayGainScope, channel_type: ChannelType) -> bool {
+        let Some((gain_db, peak)) = self
+            .frames_vec()
+            .iter()
+            .filter_map(|frame| frame.content().relative_volume_adjustment())
+            .find(|rva2| rva2.identification == scope.identification())
+            .and_then(|rva2| rva2.adjustment(channel_type))
+            .map(|adjustment| (adjustment.gain_db(), adjustment.peak()))
+        else {
+            return false;
+        };
+        self.set_replay_gain(scope, gain_db, peak);
+        true
+    }
+
+    /// Adds a picture frame (APIC).
+    /// Any other pictures with the same type will be removed from the tag.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{Picture, PictureType};
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut tag = Tag::new();
+    ///     tag.add_picture(Picture {
+    ///         mime_type: "image/jpeg".to_string(),
+    ///         picture_type: PictureType::Other,
+    ///         description: "some image".to_string(),
     ///         data: vec![],
     ///     });
     ///     tag.add_picture(Picture {
@@ -1034,163 +1968,819 @@ pub trait TagLike: private::Sealed {
     ///     Ok(())
     /// }
     /// ```
-    #[deprecated(note = "Use add_frame(frame::Picture{ .. })")]
-    fn add_picture(&mut self, picture: Picture) {
-        self.add_frame(picture);
+    #[deprecated(note = "Use add_frame(frame::Picture{ .. })")]
+    fn add_picture(&mut self, picture: Picture) {
+        self.add_frame(picture);
+    }
+
+    /// Removes all pictures of the specified type.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{Picture, PictureType};
+    ///
+    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut tag = Tag::new();
+    ///     tag.add_picture(Picture {
+    ///         mime_type: "image/jpeg".to_string(),
+    ///         picture_type: PictureType::Other,
+    ///         description: "some image".to_string(),
+    ///         data: vec![],
+    ///     });
+    ///     tag.add_picture(Picture {
+    ///         mime_type: "image/png".to_string(),
+    ///         picture_type: PictureType::CoverFront,
+    ///         description: "some other image".to_string(),
+    ///         data: vec![],
+    ///     });
+    ///
+    ///     assert_eq!(tag.pictures().count(), 2);
+    ///     tag.remove_picture_by_type(PictureType::CoverFront);
+    ///     assert_eq!(tag.pictures().count(), 1);
+    ///     assert_eq!(tag.pictures().nth(0).ok_or("no such picture")?.picture_type, PictureType::Other);
+    ///     Ok(())
+    /// }
+    /// ```
+    fn remove_picture_by_type(&mut self, picture_type: PictureType) {
+        self.frames_vec_mut().retain(|frame| {
+            if frame.id() == "APIC" {
+                let pic = match *frame.content() {
+                    Content::Picture(ref picture) => picture,
+                    _ => return false,
+                };
+                return pic.picture_type != picture_type;
+            }
+
+            true
+        });
+    }
+
+    /// Removes all pictures.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{Picture, PictureType};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_picture(Picture {
+    ///     mime_type: "image/jpeg".to_string(),
+    ///     picture_type: PictureType::Other,
+    ///     description: "some image".to_string(),
+    ///     data: vec![],
+    /// });
+    /// tag.add_picture(Picture {
+    ///     mime_type: "image/png".to_string(),
+    ///     picture_type: PictureType::CoverFront,
+    ///     description: "some other image".to_string(),
+    ///     data: vec![],
+    /// });
+    ///
+    /// assert_eq!(tag.pictures().count(), 2);
+    /// tag.remove_all_pictures();
+    /// assert_eq!(tag.pictures().count(), 0);
+    /// ```
+    fn remove_all_pictures(&mut self) {
+        self.frames_vec_mut().retain(|frame| frame.id() != "APIC");
+    }
+
+    /// Adds a comment (COMM).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::Comment;
+    ///
+    /// let mut tag = Tag::new();
+    ///
+    /// let com1 = Comment {
+    ///     lang: "eng".to_string(),
+    ///     description: "key1".to_string(),
+    ///     text: "value1".to_string(),
+    /// };
+    /// let com2 = Comment {
+    ///     lang: "eng".to_string(),
+    ///     description: "key2".to_string(),
+    ///     text: "value2".to_string(),
+    /// };
+    /// tag.add_comment(com1.clone());
+    /// tag.add_comment(com2.clone());
+    ///
+    /// assert_eq!(tag.comments().count(), 2);
+    /// assert_ne!(None, tag.comments().position(|c| *c == com1));
+    /// assert_ne!(None, tag.comments().position(|c| *c == com2));
+    /// ```
+    #[deprecated(note = "Use add_frame(frame::Comment{ .. })")]
+    fn add_comment(&mut self, comment: Comment) {
+        self.add_frame(comment);
+    }
+
+    /// Removes the comment (COMM) with the specified key and value.
+    ///
+    /// A key or value may be `None` to specify a wildcard value.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::Comment;
+    ///
+    /// let mut tag = Tag::new();
+    ///
+    /// tag.add_comment(Comment {
+    ///     lang: "eng".to_string(),
+    ///     description: "key1".to_string(),
+    ///     text: "value1".to_string(),
+    /// });
+    /// tag.add_comment(Comment {
+    ///     lang: "eng".to_string(),
+    ///     description: "key2".to_string(),
+    ///     text: "value2".to_string(),
+    /// });
+    /// assert_eq!(tag.comments().count(), 2);
+    ///
+    /// tag.remove_comment(Some("key1"), None);
+    /// assert_eq!(tag.comments().count(), 1);
+    ///
+    /// tag.remove_comment(None, Some("value2"));
+    /// assert_eq!(tag.comments().count(), 0);
+    /// ```
+    fn remove_comment(&mut self, description: Option<&str>, text: Option<&str>) {
+        self.frames_vec_mut().retain(|frame| {
+            if frame.id() == "COMM" {
+                match *frame.content() {
+                    Content::Comment(ref com) => {
+                        let descr_match = description.map(|v| v == com.description).unwrap_or(true);
+                        let text_match = text.map(|v| v == com.text).unwrap_or(true);
+                        // True if we want to keep the frame.
+                        !(descr_match && text_match)
+                    }
+                    _ => {
+                        // A COMM frame must always have content of the Comment type. Remove frames
+                        // that do not fit this requirement.
+                        false
+                    }
+                }
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Returns the linear gain from the iTunes Sound Check comment (COMM "iTunNORM").
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(tag.sound_check().is_none());
+    ///
+    /// tag.set_sound_check(0.5, None);
+    /// assert_eq!(tag.sound_check(), Some(0.5));
+    /// ```
+    fn sound_check(&self) -> Option<f64> {
+        self.frames_vec()
+            .iter()
+            .filter(|frame| frame.id() == "COMM")
+            .filter_map(|frame| frame.content().comment())
+            .find(|comment| comment.description == ITUNES_SOUND_CHECK)
+            .and_then(|comment| comment.sound_check())
+    }
+
+    /// Sets the iTunes Sound Check comment (COMM "iTunNORM") from a linear gain and an optional
+    /// peak amplitude relative to full scale, replacing any existing Sound Check comment.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_sound_check(2.0, Some(0.9));
+    /// tag.set_sound_check(0.25, None);
+    /// assert_eq!(tag.comments().count(), 1);
+    /// assert_eq!(tag.sound_check(), Some(0.25));
+    /// ```
+    fn set_sound_check(&mut self, gain: f64, peak: Option<f64>) {
+        self.remove_sound_check();
+        self.add_frame(Comment::from_sound_check(gain, peak));
+    }
+
+    /// Removes the iTunes Sound Check comment (COMM "iTunNORM").
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_sound_check(0.5, None);
+    /// tag.remove_sound_check();
+    /// assert!(tag.sound_check().is_none());
+    /// ```
+    fn remove_sound_check(&mut self) {
+        self.remove_comment(Some(ITUNES_SOUND_CHECK), None);
+    }
+
+    /// Returns the number of times the file was played.
+    ///
+    /// The play counter (PCNT) and the counters of all popularimeter frames (POPM) are
+    /// considered, of which the highest count is returned. Returns `None` if none of these frames
+    /// are present.
+    ///
+    /// Applications that count plays, such as scrobblers, should use this method together with
+    /// [`TagLike::set_plays`] and [`TagLike::increment_plays`]. Players keep their counts in
+    /// either frame, so these keep all counters in agreement.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::Popularimeter;
+    ///
+    /// let mut tag = Tag::new();
+    /// assert_eq!(tag.plays(), None);
+    ///
+    /// tag.add_frame(Popularimeter {
+    ///     user: "user@example.com".to_string(),
+    ///     rating: 0,
+    ///     counter: 12,
+    /// });
+    /// assert_eq!(tag.plays(), Some(12));
+    /// ```
+    fn plays(&self) -> Option<u64> {
+        let play_counter = self
+            .frames_vec()
+            .iter()
+            .find_map(|frame| frame.content().play_counter());
+        let popularimeters = self
+            .frames_vec()
+            .iter()
+            .filter(|frame| frame.id() == "POPM")
+            .filter_map(|frame| frame.content().popularimeter())
+            .map(|popm| popm.counter);
+        play_counter.into_iter().chain(popularimeters).max()
+    }
+
+    /// Sets the number of times the file was played.
+    ///
+    /// The play counter (PCNT) is replaced and the counters of all popularimeter frames (POPM)
+    /// are updated so that all of them agree on the count.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::Popularimeter;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Popularimeter {
+    ///     user: "user@example.com".to_string(),
+    ///     rating: 0,
+    ///     counter: 3,
+    /// });
+    /// tag.set_plays(7);
+    /// assert_eq!(tag.plays(), Some(7));
+    /// assert_eq!(tag.get("POPM").unwrap().content().popularimeter().unwrap().counter, 7);
+    /// assert_eq!(tag.get("PCNT").unwrap().content().play_counter(), Some(7));
+    /// ```
+    fn set_plays(&mut self, count: u64) {
+        for frame in self.frames_vec_mut() {
+            if frame.id() != "POPM" {
+                continue;
+            }
+            if let Content::Popularimeter(ref mut popm) = *frame.content_mut() {
+                popm.counter = count;
+            }
+        }
+
+        self.add_frame(Frame::with_content("PCNT", Content::PlayCounter(count)));
+    }
+
+    /// Increments the number of times the file was played by one and returns the new count.
+    ///
+    /// See [`TagLike::set_plays`] for how the count is stored.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert_eq!(tag.increment_plays(), 1);
+    /// assert_eq!(tag.increment_plays(), 2);
+    /// assert_eq!(tag.plays(), Some(2));
+    /// ```
+    fn increment_plays(&mut self) -> u64 {
+        let count = self.plays().unwrap_or(0).saturating_add(1);
+        self.set_plays(count);
+        count
+    }
+
+    /// Returns the count of the play counter (PCNT).
+    ///
+    /// Unlike [`TagLike::plays`], the counters of popularimeter frames (POPM) are not considered.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert_eq!(tag.play_count(), None);
+    /// tag.set_play_count(42);
+    /// assert_eq!(tag.play_count(), Some(42));
+    /// ```
+    #[deprecated(note = "use plays")]
+    fn play_count(&self) -> Option<u64> {
+        self.frames_vec()
+            .iter()
+            .find_map(|frame| frame.content().play_counter())
+    }
+
+    /// Sets the count of the play counter (PCNT), leaving the counters of popularimeter frames
+    /// (POPM) untouched.
+    #[deprecated(note = "use set_plays")]
+    fn set_play_count(&mut self, count: u64) {
+        self.add_frame(Frame::with_content("PCNT", Content::PlayCounter(count)));
+    }
+
+    /// Increments the play counter (PCNT) by one and returns the new count. A missing play
+    /// counter is created with a count of one. The counters of popularimeter frames (POPM) are left
+    /// untouched.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert_eq!(tag.increment_play_count(), 1);
+    /// assert_eq!(tag.increment_play_count(), 2);
+    /// ```
+    #[deprecated(note = "use increment_plays")]
+    fn increment_play_count(&mut self) -> u64 {
+        #[allow(deprecated)]
+        let count = self.play_count().unwrap_or(0).saturating_add(1);
+        #[allow(deprecated)]
+        self.set_play_count(count);
+        count
+    }
+
+    /// Returns whether the file is marked as a podcast episode by the iTunes podcast flag (PCST).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(!tag.is_podcast());
+    /// tag.set_podcast(true);
+    /// assert!(tag.is_podcast());
+    /// ```
+    fn is_podcast(&self) -> bool {
+        self.get("PCST").is_some()
+    }
+
+    /// Adds or removes the iTunes podcast flag (PCST).
+    fn set_podcast(&mut self, podcast: bool) {
+        if podcast {
+            self.add_frame(Frame::with_content("PCST", Content::PodcastFlag(0)));
+        } else {
+            self.remove("PCST");
+        }
+    }
+
+    /// Returns the podcast description (TDES).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("TDES", "An episode about tags"));
+    /// assert_eq!(tag.podcast_description(), Some("An episode about tags"));
+    /// ```
+    fn podcast_description(&self) -> Option<&str> {
+        self.text_for_frame_id("TDES")
+    }
+
+    /// Sets the podcast description (TDES).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_description("An episode about tags");
+    /// assert_eq!(tag.podcast_description(), Some("An episode about tags"));
+    /// ```
+    fn set_podcast_description(&mut self, description: impl Into<String>) {
+        self.set_text("TDES", description);
+    }
+
+    /// Removes the podcast description (TDES).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_description("An episode about tags");
+    /// tag.remove_podcast_description();
+    /// assert!(tag.podcast_description().is_none());
+    /// ```
+    fn remove_podcast_description(&mut self) {
+        self.remove("TDES");
+    }
+
+    /// Returns the podcast identifier (TGID).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("TGID", "https://example.com/episodes/1"));
+    /// assert_eq!(tag.podcast_id(), Some("https://example.com/episodes/1"));
+    /// ```
+    fn podcast_id(&self) -> Option<&str> {
+        self.text_for_frame_id("TGID")
+    }
+
+    /// Sets the podcast identifier (TGID).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_id("https://example.com/episodes/1");
+    /// assert_eq!(tag.podcast_id(), Some("https://example.com/episodes/1"));
+    /// ```
+    fn set_podcast_id(&mut self, id: impl Into<String>) {
+        self.set_text("TGID", id);
+    }
+
+    /// Removes the podcast identifier (TGID).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_id("https://example.com/episodes/1");
+    /// tag.remove_podcast_id();
+    /// assert!(tag.podcast_id().is_none());
+    /// ```
+    fn remove_podcast_id(&mut self) {
+        self.remove("TGID");
+    }
+
+    /// Returns the podcast category (TCAT).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("TCAT", "Technology"));
+    /// assert_eq!(tag.podcast_category(), Some("Technology"));
+    /// ```
+    fn podcast_category(&self) -> Option<&str> {
+        self.text_for_frame_id("TCAT")
+    }
+
+    /// Sets the podcast category (TCAT).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_category("Technology");
+    /// assert_eq!(tag.podcast_category(), Some("Technology"));
+    /// ```
+    fn set_podcast_category(&mut self, category: impl Into<String>) {
+        self.set_text("TCAT", category);
+    }
+
+    /// Removes the podcast category (TCAT).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_category("Technology");
+    /// tag.remove_podcast_category();
+    /// assert!(tag.podcast_category().is_none());
+    /// ```
+    fn remove_podcast_category(&mut self) {
+        self.remove("TCAT");
+    }
+
+    /// Returns the podcast keywords (TKWD).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("TKWD", "id3,metadata"));
+    /// assert_eq!(tag.podcast_keywords(), Some("id3,metadata"));
+    /// ```
+    fn podcast_keywords(&self) -> Option<&str> {
+        self.text_for_frame_id("TKWD")
+    }
+
+    /// Sets the podcast keywords (TKWD).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_keywords("id3,metadata");
+    /// assert_eq!(tag.podcast_keywords(), Some("id3,metadata"));
+    /// ```
+    fn set_podcast_keywords(&mut self, keywords: impl Into<String>) {
+        self.set_text("TKWD", keywords);
+    }
+
+    /// Removes the podcast keywords (TKWD).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_keywords("id3,metadata");
+    /// tag.remove_podcast_keywords();
+    /// assert!(tag.podcast_keywords().is_none());
+    /// ```
+    fn remove_podcast_keywords(&mut self) {
+        self.remove("TKWD");
+    }
+
+    /// Returns the podcast feed URL (WFED).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("WFED", "https://example.com/feed.xml"));
+    /// assert_eq!(tag.podcast_feed_url(), Some("https://example.com/feed.xml"));
+    /// ```
+    fn podcast_feed_url(&self) -> Option<&str> {
+        self.text_for_frame_id("WFED")
+    }
+
+    /// Sets the podcast feed URL (WFED).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_feed_url("https://example.com/feed.xml");
+    /// assert_eq!(tag.podcast_feed_url(), Some("https://example.com/feed.xml"));
+    /// ```
+    fn set_podcast_feed_url(&mut self, feed_url: impl Into<String>) {
+        self.set_text("WFED", feed_url);
+    }
+
+    /// Removes the podcast feed URL (WFED).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_podcast_feed_url("https://example.com/feed.xml");
+    /// tag.remove_podcast_feed_url();
+    /// assert!(tag.podcast_feed_url().is_none());
+    /// ```
+    fn remove_podcast_feed_url(&mut self) {
+        self.remove("WFED");
     }
 
-    /// Removes all pictures of the specified type.
+    /// Returns whether the album is a compilation by various artists, as indicated by the iTunes
+    /// compilation flag (TCMP).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(!tag.is_compilation());
+    /// tag.add_frame(Frame::text("TCMP", "1"));
+    /// assert!(tag.is_compilation());
+    /// tag.add_frame(Frame::text("TCMP", "0"));
+    /// assert!(!tag.is_compilation());
+    /// ```
+    fn is_compilation(&self) -> bool {
+        self.text_for_frame_id("TCMP")
+            .and_then(|text| text.trim().parse::<u32>().ok())
+            .is_some_and(|flag| flag != 0)
+    }
+
+    /// Sets the iTunes compilation flag (TCMP) to "1", or removes it.
     ///
     /// # Example
     /// ```
     /// use id3::{Tag, TagLike};
-    /// use id3::frame::{Picture, PictureType};
     ///
-    /// fn main() -> Result<(), Box<dyn std::error::Error>> {
-    ///     let mut tag = Tag::new();
-    ///     tag.add_picture(Picture {
-    ///         mime_type: "image/jpeg".to_string(),
-    ///         picture_type: PictureType::Other,
-    ///         description: "some image".to_string(),
-    ///         data: vec![],
-    ///     });
-    ///     tag.add_picture(Picture {
-    ///         mime_type: "image/png".to_string(),
-    ///         picture_type: PictureType::CoverFront,
-    ///         description: "some other image".to_string(),
-    ///         data: vec![],
-    ///     });
+    /// let mut tag = Tag::new();
+    /// tag.set_compilation(true);
+    /// assert_eq!(tag.get("TCMP").and_then(|frame| frame.content().text()), Some("1"));
+    /// tag.set_compilation(false);
+    /// assert!(tag.get("TCMP").is_none());
+    /// ```
+    fn set_compilation(&mut self, compilation: bool) {
+        if compilation {
+            self.set_text("TCMP", "1");
+        } else {
+            self.remove("TCMP");
+        }
+    }
+
+    /// Returns the name of the movement (MVNM).
     ///
-    ///     assert_eq!(tag.pictures().count(), 2);
-    ///     tag.remove_picture_by_type(PictureType::CoverFront);
-    ///     assert_eq!(tag.pictures().count(), 1);
-    ///     assert_eq!(tag.pictures().nth(0).ok_or("no such picture")?.picture_type, PictureType::Other);
-    ///     Ok(())
-    /// }
+    /// # Example
     /// ```
-    fn remove_picture_by_type(&mut self, picture_type: PictureType) {
-        self.frames_vec_mut().retain(|frame| {
-            if frame.id() == "APIC" {
-                let pic = match *frame.content() {
-                    Content::Picture(ref picture) => picture,
-                    _ => return false,
-                };
-                return pic.picture_type != picture_type;
-            }
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("MVNM", "Allegro con brio"));
+    /// assert_eq!(tag.movement(), Some("Allegro con brio"));
+    /// ```
+    fn movement(&self) -> Option<&str> {
+        self.text_for_frame_id("MVNM")
+    }
 
-            true
-        });
+    /// Sets the name of the movement (MVNM).
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_movement("Allegro con brio");
+    /// assert_eq!(tag.movement(), Some("Allegro con brio"));
+    /// ```
+    fn set_movement(&mut self, movement: impl Into<String>) {
+        self.set_text("MVNM", movement);
     }
 
-    /// Removes all pictures.
+    /// Removes the name of the movement (MVNM).
     ///
     /// # Example
     /// ```
     /// use id3::{Tag, TagLike};
-    /// use id3::frame::{Picture, PictureType};
     ///
     /// let mut tag = Tag::new();
-    /// tag.add_picture(Picture {
-    ///     mime_type: "image/jpeg".to_string(),
-    ///     picture_type: PictureType::Other,
-    ///     description: "some image".to_string(),
-    ///     data: vec![],
-    /// });
-    /// tag.add_picture(Picture {
-    ///     mime_type: "image/png".to_string(),
-    ///     picture_type: PictureType::CoverFront,
-    ///     description: "some other image".to_string(),
-    ///     data: vec![],
-    /// });
+    /// tag.set_movement("Allegro con brio");
+    /// tag.remove_movement();
+    /// assert!(tag.movement().is_none());
+    /// ```
+    fn remove_movement(&mut self) {
+        self.remove("MVNM");
+    }
+
+    /// Returns the number of the movement (MVIN).
     ///
-    /// assert_eq!(tag.pictures().count(), 2);
-    /// tag.remove_all_pictures();
-    /// assert_eq!(tag.pictures().count(), 0);
+    /// # Example
     /// ```
-    fn remove_all_pictures(&mut self) {
-        self.frames_vec_mut().retain(|frame| frame.id() != "APIC");
+    /// use id3::{Frame, Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("MVIN", "2/4"));
+    /// assert_eq!(tag.movement_number(), Some(2));
+    /// ```
+    fn movement_number(&self) -> Option<u32> {
+        self.text_pair("MVIN").map(|(number, _)| number)
     }
 
-    /// Adds a comment (COMM).
+    /// Sets the number of the movement (MVIN), keeping the total number of movements.
     ///
     /// # Example
     /// ```
     /// use id3::{Tag, TagLike};
-    /// use id3::frame::Comment;
     ///
     /// let mut tag = Tag::new();
+    /// tag.set_total_movements(4);
+    /// tag.set_movement_number(2);
+    /// assert_eq!(tag.movement_number(), Some(2));
+    /// assert_eq!(tag.total_movements(), Some(4));
+    /// ```
+    fn set_movement_number(&mut self, number: u32) {
+        let text = match self.text_pair("MVIN").and_then(|(_, total)| total) {
+            Some(total) => format!("{}/{}", number, total),
+            None => format!("{}", number),
+        };
+        self.set_text("MVIN", text);
+    }
+
+    /// Returns the total number of movements (MVIN).
     ///
-    /// let com1 = Comment {
-    ///     lang: "eng".to_string(),
-    ///     description: "key1".to_string(),
-    ///     text: "value1".to_string(),
-    /// };
-    /// let com2 = Comment {
-    ///     lang: "eng".to_string(),
-    ///     description: "key2".to_string(),
-    ///     text: "value2".to_string(),
-    /// };
-    /// tag.add_comment(com1.clone());
-    /// tag.add_comment(com2.clone());
+    /// # Example
+    /// ```
+    /// use id3::{Frame, Tag, TagLike};
     ///
-    /// assert_eq!(tag.comments().count(), 2);
-    /// assert_ne!(None, tag.comments().position(|c| *c == com1));
-    /// assert_ne!(None, tag.comments().position(|c| *c == com2));
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Frame::text("MVIN", "2/4"));
+    /// assert_eq!(tag.total_movements(), Some(4));
     /// ```
-    #[deprecated(note = "Use add_frame(frame::Comment{ .. })")]
-    fn add_comment(&mut self, comment: Comment) {
-        self.add_frame(comment);
+    fn total_movements(&self) -> Option<u32> {
+        self.text_pair("MVIN").and_then(|(_, total)| total)
     }
 
-    /// Removes the comment (COMM) with the specified key and value.
-    ///
-    /// A key or value may be `None` to specify a wildcard value.
+    /// Sets the total number of movements (MVIN), keeping the number of the movement.
+    fn set_total_movements(&mut self, total_movements: u32) {
+        let text = match self.text_pair("MVIN") {
+            Some((number, _)) => format!("{}/{}", number, total_movements),
+            None => format!("1/{}", total_movements),
+        };
+        self.set_text("MVIN", text);
+    }
+
+    /// Removes the number and total number of movements (MVIN).
     ///
     /// # Example
     /// ```
     /// use id3::{Tag, TagLike};
-    /// use id3::frame::Comment;
     ///
     /// let mut tag = Tag::new();
+    /// tag.set_movement_number(2);
+    /// tag.remove_movement_number();
+    /// assert!(tag.movement_number().is_none());
+    /// ```
+    fn remove_movement_number(&mut self) {
+        self.remove("MVIN");
+    }
+
+    /// Returns the star rating from the popularimeter frame (POPM) of the application that uses
+    /// the specified convention.
     ///
-    /// tag.add_comment(Comment {
-    ///     lang: "eng".to_string(),
-    ///     description: "key1".to_string(),
-    ///     text: "value1".to_string(),
-    /// });
-    /// tag.add_comment(Comment {
-    ///     lang: "eng".to_string(),
-    ///     description: "key2".to_string(),
-    ///     text: "value2".to_string(),
+    /// For [`RatingConvention::Kodi`], the first POPM frame with a known rating is used
+    /// regardless of its user.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::{Popularimeter, RatingConvention};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.add_frame(Popularimeter {
+    ///     user: "Windows Media Player 9 Series".to_string(),
+    ///     rating: 196,
+    ///     counter: 0,
     /// });
-    /// assert_eq!(tag.comments().count(), 2);
+    /// assert_eq!(tag.rating_stars(RatingConvention::WindowsMediaPlayer), Some(4.0));
+    /// assert_eq!(tag.rating_stars(RatingConvention::Kodi), Some(4.0));
+    /// assert_eq!(tag.rating_stars(RatingConvention::MusicBee), None);
+    /// ```
+    fn rating_stars(&self, convention: RatingConvention) -> Option<f32> {
+        self.frames_vec()
+            .iter()
+            .filter(|frame| frame.id() == "POPM")
+            .filter_map(|frame| frame.content().popularimeter())
+            .filter(|popm| convention == RatingConvention::Kodi || popm.user == convention.user())
+            .find_map(|popm| popm.stars(convention))
+    }
+
+    /// Sets the star rating in the popularimeter frame (POPM) of the application that uses the
+    /// specified convention.
     ///
-    /// tag.remove_comment(Some("key1"), None);
-    /// assert_eq!(tag.comments().count(), 1);
+    /// The play counter of an existing frame is kept. A new frame is added if the application
+    /// did not have a frame yet.
     ///
-    /// tag.remove_comment(None, Some("value2"));
-    /// assert_eq!(tag.comments().count(), 0);
+    /// # Example
     /// ```
-    fn remove_comment(&mut self, description: Option<&str>, text: Option<&str>) {
-        self.frames_vec_mut().retain(|frame| {
-            if frame.id() == "COMM" {
-                match *frame.content() {
-                    Content::Comment(ref com) => {
-                        let descr_match = description.map(|v| v == com.description).unwrap_or(true);
-                        let text_match = text.map(|v| v == com.text).unwrap_or(true);
-                        // True if we want to keep the frame.
-                        !(descr_match && text_match)
-                    }
-                    _ => {
-                        // A COMM frame must always have content of the Comment type. Remove frames
-                        // that do not fit this requirement.
-                        false
-                    }
-                }
-            } else {
-                true
-            }
-        });
+    /// use id3::{Tag, TagLike};
+    /// use id3::frame::RatingConvention;
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_rating_stars(RatingConvention::MusicBee, 2.5);
+    /// assert_eq!(tag.rating_stars(RatingConvention::MusicBee), Some(2.5));
+    /// assert_eq!(tag.get("POPM").unwrap().content().popularimeter().unwrap().rating, 118);
+    /// ```
+    fn set_rating_stars(&mut self, convention: RatingConvention, stars: f32) {
+        let existing = self
+            .frames_vec_mut()
+            .iter_mut()
+            .filter(|frame| frame.id() == "POPM")
+            .find_map(|frame| match frame.content_mut() {
+                Content::Popularimeter(popm) if popm.user == convention.user() => Some(popm),
+                _ => None,
+            });
+        if let Some(popm) = existing {
+            popm.set_stars(convention, stars);
+            return;
+        }
+        let mut popm = Popularimeter {
+            user: convention.user().to_string(),
+            rating: 0,
+            counter: 0,
+        };
+        popm.set_stars(convention, stars);
+        self.add_frame(popm);
     }
 
     /// Adds an encapsulated object frame (GEOB).
@@ -1294,6 +2884,52 @@ pub trait TagLike: private::Sealed {
         });
     }
 
+    /// Replaces the data of every encapsulated object frame (GEOB) with the specified
+    /// description, keeping the text encoding, MIME type and filename of each frame.
+    ///
+    /// Serato stores its GEOB frames twice, once as Latin1 and once as UTF-16, and expects
+    /// both copies to be present. Updating the frames in place preserves this layout, where
+    /// adding a new frame would only replace one of the copies.
+    ///
+    /// Returns the number of frames that were updated.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Encoding, Frame, Tag, TagLike};
+    /// use id3::frame::EncapsulatedObject;
+    ///
+    /// let mut tag = Tag::new();
+    /// for encoding in [Encoding::Latin1, Encoding::UTF16] {
+    ///     let frame = Frame::from(EncapsulatedObject {
+    ///         description: "Serato Markers2".to_string(),
+    ///         mime_type: "application/octet-stream".to_string(),
+    ///         filename: "".to_string(),
+    ///         data: vec![1, 2, 3],
+    ///     });
+    ///     tag.add_frame(frame.set_encoding(Some(encoding)));
+    /// }
+    /// assert_eq!(tag.encapsulated_objects().count(), 2);
+    ///
+    /// assert_eq!(tag.set_encapsulated_object_data("Serato Markers2", vec![4, 5]), 2);
+    /// assert!(tag.encapsulated_objects().all(|obj| obj.data == [4, 5]));
+    /// assert_eq!(tag.set_encapsulated_object_data("Serato BeatGrid", vec![]), 0);
+    /// ```
+    fn set_encapsulated_object_data(&mut self, description: &str, data: Vec<u8>) -> usize {
+        let mut count = 0;
+        for frame in self.frames_vec_mut() {
+            if frame.id() != "GEOB" {
+                continue;
+            }
+            if let Content::EncapsulatedObject(ref mut obj) = *frame.content_mut() {
+                if obj.description == description {
+                    obj.data = data.clone();
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
     /// Sets the lyrics (USLT).
     ///
     /// # Example
@@ -1391,6 +3027,53 @@ pub trait TagLike: private::Sealed {
         self.remove("SYLT");
     }
 
+    /// Returns the terms of use (USER).
+    ///
+    /// If the tag contains terms of use in multiple languages, the first is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// assert!(tag.terms_of_use().is_none());
+    /// tag.set_terms_of_use("eng", "Licensed under CC BY 4.0");
+    /// let terms = tag.terms_of_use().unwrap();
+    /// assert_eq!(terms.lang, "eng");
+    /// assert_eq!(terms.text, "Licensed under CC BY 4.0");
+    /// ```
+    fn terms_of_use(&self) -> Option<&TermsOfUse> {
+        self.frames_vec()
+            .iter()
+            .find_map(|frame| frame.content().terms_of_use())
+    }
+
+    /// Sets the terms of use (USER) in the specified language, replacing any existing terms of
+    /// use in the same language.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_terms_of_use("eng", "All rights reserved");
+    /// tag.set_terms_of_use("deu", "Alle Rechte vorbehalten");
+    /// tag.set_terms_of_use("eng", "Licensed under CC BY 4.0");
+    /// assert_eq!(tag.frames().count(), 2);
+    /// assert_eq!(tag.terms_of_use().unwrap().text, "Licensed under CC BY 4.0");
+    /// ```
+    fn set_terms_of_use(&mut self, lang: impl Into<String>, text: impl Into<String>) {
+        self.add_frame(TermsOfUse {
+            lang: lang.into(),
+            text: text.into(),
+        });
+    }
+
+    /// Removes all terms of use (USER) from the tag.
+    fn remove_terms_of_use(&mut self) {
+        self.remove("USER");
+    }
+
     /// /// Removes all chapters (CHAP) frames from the tag.
     ///
     /// # Example
@@ -1513,6 +3196,114 @@ pub trait TagLike: private::Sealed {
     }
 }
 
+/// Returns the value of the first user defined text frame (TXXX) with the specified description.
+fn extended_text_value<'a, T: TagLike + ?Sized>(tag: &'a T, description: &str) -> Option<&'a str> {
+    tag.frames_vec()
+        .iter()
+        .filter(|frame| frame.id() == "TXXX")
+        .filter_map(|frame| frame.content().extended_text())
+        .find(|ext| ext.description == description)
+        .map(|ext| ext.value.as_str())
+}
+
+/// Returns whether the string is a UUID in its hyphenated form.
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.bytes().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+fn is_valid_barcode(s: &str) -> bool {
+    if !matches!(s.len(), 8 | 12 | 13) || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    // Starting from the check digit, digits are alternately weighted 1 and 3.
+    let sum: u32 = s
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, b)| u32::from(b - b'0') * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    sum % 10 == 0
+}
+
+/// A view into a single frame of a tag that may or may not be present, see [`TagLike::entry`].
+#[derive(Debug)]
+pub struct FrameEntry<'a> {
+    frames: &'a mut Vec<Frame>,
+    index: Option<usize>,
+}
+
+impl<'a> FrameEntry<'a> {
+    /// Returns the frame, if present.
+    pub fn get(&self) -> Option<&Frame> {
+        self.index.map(|index| &self.frames[index])
+    }
+
+    /// Calls `f` with the frame if it is present.
+    pub fn and_modify(self, f: impl FnOnce(&mut Frame)) -> Self {
+        if let Some(index) = self.index {
+            f(&mut self.frames[index]);
+        }
+        self
+    }
+
+    /// Returns the frame, appending `frame` to the tag first if the frame is not present.
+    ///
+    /// The inserted frame is not checked against the key of the entry. Like with
+    /// [`TagLike::add_frame`], it replaces a frame it conflicts with instead of being appended.
+    pub fn or_insert(self, frame: impl Into<Frame>) -> &'a mut Frame {
+        self.or_insert_with(|| frame)
+    }
+
+    /// Returns the frame, appending the result of `f` to the tag first if the frame is not
+    /// present.
+    ///
+    /// The inserted frame is not checked against the key of the entry. Like with
+    /// [`TagLike::add_frame`], it replaces a frame it conflicts with instead of being appended.
+    pub fn or_insert_with<F: Into<Frame>>(self, f: impl FnOnce() -> F) -> &'a mut Frame {
+        let index = match self.index {
+            Some(index) => index,
+            None => {
+                let frame = f().into();
+                match self.frames.iter().position(|other| other.compare(&frame)) {
+                    Some(conflict_index) => {
+                        self.frames[conflict_index] = frame;
+                        conflict_index
+                    }
+                    None => {
+                        self.frames.push(frame);
+                        self.frames.len() - 1
+                    }
+                }
+            }
+        };
+        &mut self.frames[index]
+    }
+
+    /// Removes the frame from the tag and returns it, if present.
+    pub fn remove(self) -> Option<Frame> {
+        self.index.map(|index| self.frames.remove(index))
+    }
+}
+
+/// Parses the integer at the start of a text frame value, ignoring surrounding whitespace and
+/// anything after the digits, like a fractional part or further values.
+fn parse_leading_integer(text: &str) -> Option<i64> {
+    let text = text.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+    let sign_len = usize::from(text.starts_with(['-', '+']));
+    let digits_len = text[sign_len..]
+        .bytes()
+        .take_while(u8::is_ascii_digit)
+        .count();
+    if digits_len == 0 {
+        return None;
+    }
+    text[..sign_len + digits_len].parse().ok()
+}
+
 // https://rust-lang.github.io/api-guidelines/future-proofing.html#c-sealed
 mod private {
     use crate::frame::Chapter;
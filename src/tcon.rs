@@ -1,51 +1,306 @@
 use crate::v1::GENRE_LIST;
 use std::borrow::Cow;
+use std::fmt;
 use std::mem::swap;
 
+/// A single part of the content of a TCON frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Component {
+    /// A reference to a genre in the ID3v1 genre list, e.g. "(13)" or "13".
+    Genre(u8),
+    /// A remix, written as "(RX)" or "RX".
+    Remix,
+    /// A cover, written as "(CR)" or "CR".
+    Cover,
+    /// Free text that is not a reference.
+    Text(String),
+}
+
+impl Component {
+    /// Returns the component as a genre name, looking up references in the specified table.
+    /// References to genres that do not exist are written as "(nn)".
+    ///
+    /// # Example
+    /// ```
+    /// use id3::tcon::{Component, GenreTable};
+    ///
+    /// assert_eq!(Component::Genre(80).name(GenreTable::WINAMP), "Folk");
+    /// assert_eq!(Component::Genre(80).name(GenreTable::ID3V1), "(80)");
+    /// ```
+    pub fn name(&self, table: GenreTable<'_>) -> String {
+        match self {
+            Component::Genre(index) => match table.name(*index) {
+                Some(name) => name.to_string(),
+                None => format!("({})", index),
+            },
+            Component::Remix => "Remix".to_string(),
+            Component::Cover => "Cover".to_string(),
+            Component::Text(text) => text.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Component {
+    /// Formats the component as a genre name using [`GenreTable::WINAMP`]. References to genres
+    /// that do not exist are written as "(nn)".
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name(GenreTable::WINAMP))
+    }
+}
+
+/// A list of genre names that ID3v1 genre references are resolved against.
+///
+/// The table is used to translate between indices and names. Besides the two predefined tables,
+/// custom tables, e.g. with localized names, can be created with [`GenreTable::new`].
+///
+/// # Example
+/// ```
+/// use id3::tcon::GenreTable;
+///
+/// let table = GenreTable::new(&["Blues", "Klassischer Rock", "Country"]);
+/// assert_eq!(table.name(1), Some("Klassischer Rock"));
+/// assert_eq!(table.index("country"), Some(2));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GenreTable<'a> {
+    names: &'a [&'a str],
+    len: usize,
+}
+
+impl<'a> GenreTable<'a> {
+    /// The 80 genres that are defined by the ID3v1 standard.
+    pub const ID3V1: GenreTable<'static> = GenreTable {
+        names: GENRE_LIST,
+        len: 80,
+    };
+
+    /// The ID3v1 genres together with the extensions introduced by Winamp. This is the table
+    /// that is used unless specified otherwise.
+    pub const WINAMP: GenreTable<'static> = GenreTable {
+        names: GENRE_LIST,
+        len: GENRE_LIST.len(),
+    };
+
+    /// Creates a table from a list of names. The position of a name in the list is its index, so
+    /// only the first 256 names can be referenced.
+    pub fn new(names: &'a [&'a str]) -> Self {
+        GenreTable {
+            names,
+            len: names.len(),
+        }
+    }
+
+    /// Returns the name of the genre at the specified index.
+    pub fn name(self, index: u8) -> Option<&'a str> {
+        self.names().get(index as usize).copied()
+    }
+
+    /// Returns the index of the genre with the specified name. Names are compared
+    /// case-insensitively.
+    pub fn index(self, name: &str) -> Option<u8> {
+        self.names()
+            .iter()
+            .take(256)
+            .position(|genre| genre.eq_ignore_ascii_case(name))
+            .map(|index| index as u8)
+    }
+
+    /// Returns the names in the table.
+    pub fn names(self) -> &'a [&'a str] {
+        &self.names[..self.len]
+    }
+}
+
+impl Default for GenreTable<'_> {
+    fn default() -> Self {
+        GenreTable::WINAMP
+    }
+}
+
+/// Parses a single TCON value into its components.
+///
+/// Both the ID3v2.3 notation with references in parentheses followed by an optional refinement,
+/// and the ID3v2.4 notation where a value is either a bare reference or free text are accepted.
+/// Text that does not contain any reference is returned as a single [`Component::Text`].
+///
+/// # Example
+/// ```
+/// use id3::tcon::{self, Component};
+///
+/// assert_eq!(
+///     tcon::parse("(4)(RX)Eurodisco"),
+///     vec![
+///         Component::Genre(4),
+///         Component::Remix,
+///         Component::Text("Eurodisco".to_string()),
+///     ],
+/// );
+/// assert_eq!(tcon::parse("CR"), vec![Component::Cover]);
+/// assert_eq!(tcon::parse("17"), vec![Component::Genre(17)]);
+/// ```
+pub fn parse(tcon: &str) -> Vec<Component> {
+    match tcon {
+        "RX" => return vec![Component::Remix],
+        "CR" => return vec![Component::Cover],
+        _ => {}
+    }
+    if !tcon.is_empty() && tcon.bytes().all(|b| b.is_ascii_digit()) {
+        if let Ok(index) = tcon.parse() {
+            return vec![Component::Genre(index)];
+        }
+    }
+    Parser::components(tcon).unwrap_or_else(|| vec![Component::Text(tcon.to_string())])
+}
+
+/// The notation of a reference to an ID3v1 genre.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Notation {
+    /// The index in parentheses followed by the name, e.g. "(13)Pop". This is understood by most
+    /// ID3v2.3 readers. When the genre is resolved again, the repeated name is left out.
+    Parenthesized,
+    /// Just the index, e.g. "13". This is the notation of ID3v2.4.
+    Bare,
+}
+
+/// Converts a genre name to a reference to the ID3v1 genre list in the specified notation.
+///
+/// Names that are not in the list are returned as is.
+///
+/// # Example
+/// ```
+/// use id3::tcon::{self, Notation};
+///
+/// assert_eq!(tcon::encode("Pop", Notation::Parenthesized), "(13)Pop");
+/// assert_eq!(tcon::encode("pop", Notation::Bare), "13");
+/// assert_eq!(tcon::encode("Vaporwave", Notation::Bare), "Vaporwave");
+/// ```
+pub fn encode(name: &str, notation: Notation) -> String {
+    encode_with(name, notation, GenreTable::WINAMP)
+}
+
+/// Converts a genre name to a reference in the specified notation, like [`encode`], but looks the
+/// name up in the specified table.
+///
+/// # Example
+/// ```
+/// use id3::tcon::{self, GenreTable, Notation};
+///
+/// assert_eq!(tcon::encode_with("Folk", Notation::Bare, GenreTable::WINAMP), "80");
+/// assert_eq!(tcon::encode_with("Folk", Notation::Bare, GenreTable::ID3V1), "Folk");
+/// ```
+pub fn encode_with(name: &str, notation: Notation, table: GenreTable<'_>) -> String {
+    match (table.index(name), notation) {
+        (Some(index), Notation::Parenthesized) => {
+            format!("({}){}", index, table.names()[index as usize])
+        }
+        (Some(index), Notation::Bare) => index.to_string(),
+        (None, _) => name.to_string(),
+    }
+}
+
+/// Parses a single TCON value and joins the names of its components. The value is borrowed if it
+/// contains no references.
+pub(crate) fn resolve<'a>(tcon: &'a str, table: GenreTable<'_>) -> Cow<'a, str> {
+    match &parse(tcon)[..] {
+        [Component::Text(text)] if text == tcon => Cow::Borrowed(tcon),
+        components => Cow::Owned(join_names(components, table)),
+    }
+}
+
+/// Joins the names of the components. A refinement that repeats the name of the genre before it,
+/// as in "(13)Pop", is left out.
+fn join_names(components: &[Component], table: GenreTable<'_>) -> String {
+    let mut names: Vec<String> = Vec::with_capacity(components.len());
+    for (i, component) in components.iter().enumerate() {
+        let name = component.name(table);
+        let repeats_genre = i > 0
+            && matches!(component, Component::Text(_))
+            && matches!(components[i - 1], Component::Genre(_))
+            && names[i - 1].eq_ignore_ascii_case(&name);
+        if !repeats_genre {
+            names.push(name);
+        }
+    }
+    names.join(" ")
+}
+
+/// Returns the name of the genre at the specified index in the ID3v1 genre list.
+///
+/// # Example
+/// ```
+/// use id3::tcon;
+///
+/// assert_eq!(tcon::genre_name(31), Some("Trance"));
+/// assert_eq!(tcon::genre_name(255), None);
+/// ```
+pub fn genre_name(index: u8) -> Option<&'static str> {
+    GenreTable::WINAMP.name(index)
+}
+
+/// Returns the index of the genre in the ID3v1 genre list. Names are compared case-insensitively.
+///
+/// # Example
+/// ```
+/// use id3::tcon;
+///
+/// assert_eq!(tcon::genre_index("trance"), Some(31));
+/// assert_eq!(tcon::genre_index("Vaporwave"), None);
+/// ```
+pub fn genre_index(name: &str) -> Option<u8> {
+    GenreTable::WINAMP.index(name)
+}
+
 #[derive(Copy, Clone)]
-pub struct Parser<'a>(&'a str);
+pub(crate) struct Parser<'a>(&'a str);
 
 type ParseFunc<P, T> = dyn Fn(&mut P) -> Result<T, ()>;
 
 impl<'a> Parser<'a> {
-    pub fn parse_tcon(s: &'a str) -> Cow<'a, str> {
-        let mut parser = Parser(s);
-        let v1_genre_ids = match parser.one_or_more(&Self::content_type) {
-            Ok(v) => v,
-            Err(_) => return Cow::Borrowed(parser.0),
-        };
-        let trailer = parser.trailer();
+    pub(crate) fn parse_tcon(s: &'a str) -> Cow<'a, str> {
+        Self::parse_tcon_with(s, GenreTable::WINAMP)
+    }
+
+    pub(crate) fn parse_tcon_with(s: &'a str, table: GenreTable<'_>) -> Cow<'a, str> {
+        match Self::components(s) {
+            Some(components) => Cow::Owned(join_names(&components, table)),
+            None => Cow::Borrowed(s),
+        }
+    }
 
-        let strs: Vec<String> = v1_genre_ids.into_iter().chain(trailer).collect();
-        Cow::Owned(strs.join(" "))
+    /// Parses references in parentheses followed by an optional trailer. Returns `None` if the
+    /// text does not start with a reference.
+    fn components(s: &'a str) -> Option<Vec<Component>> {
+        let mut parser = Parser(s);
+        let content_types = parser.one_or_more(&Self::content_type).ok()?;
+        let trailer = parser.trailer().map(Component::Text);
+        Some(content_types.into_iter().chain(trailer).collect())
     }
 
-    fn content_type(&mut self) -> Result<String, ()> {
+    fn content_type(&mut self) -> Result<Component, ()> {
         self.first_of([&Self::escaped_content_type, &Self::v1_content_type])
     }
 
-    fn v1_content_type(&mut self) -> Result<String, ()> {
+    fn v1_content_type(&mut self) -> Result<Component, ()> {
         self.expect("(")?;
         let t = self.first_of([
-            &|p: &mut Self| p.expect("RX").map(|_| "Remix".to_string()),
-            &|p: &mut Self| p.expect("CR").map(|_| "Cover".to_string()),
+            &|p: &mut Self| p.expect("RX").map(|_| Component::Remix),
+            &|p: &mut Self| p.expect("CR").map(|_| Component::Cover),
             &|p: &mut Self| {
-                p.parse_number()
-                    .map(|index| match GENRE_LIST.get(index as usize) {
-                        Some(v1_genre) => v1_genre.to_string(),
-                        None => format!("({})", index),
-                    })
+                p.parse_number().map(|index| match u8::try_from(index) {
+                    Ok(index) => Component::Genre(index),
+                    Err(_) => Component::Text(format!("({})", index)),
+                })
             },
         ])?;
         self.expect(")")?;
         Ok(t)
     }
 
-    fn escaped_content_type(&mut self) -> Result<String, ()> {
+    fn escaped_content_type(&mut self) -> Result<Component, ()> {
         self.expect("((")?;
         let t = format!("({}", self.0);
         self.0 = "";
-        Ok(t)
+        Ok(Component::Text(t))
     }
 
     fn trailer(&mut self) -> Result<String, ()> {
@@ -135,6 +390,17 @@ mod tests {
         assert_eq!(s, "Vocal Trance");
     }
 
+    #[test]
+    fn encode_round_trip() {
+        let encoded = encode("Pop", Notation::Parenthesized);
+        assert_eq!(Parser::parse_tcon(&encoded), "Pop");
+        assert_eq!(resolve(&encoded, GenreTable::WINAMP), "Pop");
+        let encoded = encode("Pop", Notation::Bare);
+        assert_eq!(resolve(&encoded, GenreTable::WINAMP), "Pop");
+        assert_eq!(Parser::parse_tcon("(13)pop"), "Pop");
+        assert_eq!(Parser::parse_tcon("(13)Pop Rock"), "Pop Pop Rock");
+    }
+
     #[test]
     fn escaping() {
         let s = Parser::parse_tcon("((Foo)");
@@ -151,6 +417,51 @@ mod tests {
         assert_eq!(s, "Cover");
     }
 
+    #[test]
+    fn components() {
+        assert_eq!(
+            parse("(28)(31)Trance"),
+            vec![
+                Component::Genre(28),
+                Component::Genre(31),
+                Component::Text("Trance".to_string())
+            ]
+        );
+        assert_eq!(parse("(300)"), vec![Component::Text("(300)".to_string())]);
+        assert_eq!(parse("RX"), vec![Component::Remix]);
+        assert_eq!(parse("300"), vec![Component::Text("300".to_string())]);
+        assert_eq!(parse("((Foo)"), vec![Component::Text("(Foo)".to_string())]);
+        assert_eq!(parse(""), vec![Component::Text("".to_string())]);
+    }
+
+    #[test]
+    fn genre_lookup() {
+        for index in 0..=255 {
+            if let Some(name) = genre_name(index) {
+                assert_eq!(genre_index(name), Some(index));
+            }
+        }
+    }
+
+    #[test]
+    fn genre_tables() {
+        assert_eq!(GenreTable::ID3V1.names().len(), 80);
+        assert_eq!(GenreTable::ID3V1.name(79), Some("Hard Rock"));
+        assert_eq!(GenreTable::ID3V1.name(80), None);
+        assert_eq!(GenreTable::WINAMP.name(80), Some("Folk"));
+        assert_eq!(GenreTable::default(), GenreTable::WINAMP);
+
+        let s = Parser::parse_tcon_with("(80)(1)", GenreTable::ID3V1);
+        assert_eq!(s, "(80) Classic Rock");
+        let table = GenreTable::new(&["Blues", "Klassischer Rock"]);
+        assert_eq!(resolve("1", table), "Klassischer Rock");
+        assert_eq!(resolve("2", table), "(2)");
+        assert_eq!(
+            encode_with("klassischer rock", Notation::Parenthesized, table),
+            "(1)Klassischer Rock"
+        );
+    }
+
     #[test]
     fn malformed() {
         let s = Parser::parse_tcon("(lol)");
@@ -0,0 +1 @@
+pub use crate::stream::unsynch::{decode_u32, encode_u32, encode_vec, Reader};
@@ -1,5 +1,7 @@
-use crate::{Error, ErrorKind, StorageFile};
+use crate::tcon::GenreTable;
+use crate::{Error, ErrorKind, StorageFile, TagLike};
 use std::cmp;
+use std::fmt;
 use std::fs;
 use std::io;
 use std::ops;
@@ -10,7 +12,7 @@ static TAG_CHUNK: ops::Range<i64> = -128..0;
 /// Location of the ID3v1 extended tag chunk relative to the end of the file.
 static XTAG_CHUNK: ops::Range<i64> = -355..-128;
 
-pub(crate) static GENRE_LIST: &[&str] = &[
+pub(crate) const GENRE_LIST: &[&str] = &[
     "Blues",
     "Classic Rock",
     "Country",
@@ -161,6 +163,97 @@ pub(crate) static GENRE_LIST: &[&str] = &[
     "SynthPop",
 ];
 
+/// Returns the index of the genre with the specified name in the Winamp extended genre list, for
+/// use as [`Tag::genre_id`]. Names are compared case-insensitively.
+///
+/// # Example
+/// ```
+/// use id3::v1;
+///
+/// assert_eq!(v1::genre_index("Trance"), Some(31));
+/// assert_eq!(v1::genre_index("synthpop"), Some(147));
+/// assert_eq!(v1::genre_index("Vaporwave"), None);
+/// ```
+pub fn genre_index(name: &str) -> Option<u8> {
+    GenreTable::WINAMP.index(name)
+}
+
+/// Returns an iterator over the names of the Winamp extended genre list, in the order of their
+/// index.
+///
+/// # Example
+/// ```
+/// use id3::v1;
+///
+/// assert_eq!(v1::genres().next(), Some("Blues"));
+/// assert_eq!(v1::genres().position(|genre| genre == "Trance"), Some(31));
+/// ```
+pub fn genres() -> impl ExactSizeIterator<Item = &'static str> {
+    GENRE_LIST.iter().copied()
+}
+
+/// Options for reading ID3v1 tags.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ReadOptions {
+    /// The encoding of the text fields.
+    pub encoding: Encoding,
+}
+
+/// The encoding of the text in an ID3v1 tag.
+///
+/// The ID3v1 specification only allows ISO-8859-1, but many taggers wrote the legacy codepage of
+/// the system instead.
+#[derive(Copy, Clone, Default)]
+pub enum Encoding {
+    /// ISO-8859-1, as mandated by the specification.
+    #[default]
+    Latin1,
+    /// Windows-1251, commonly used for Cyrillic text.
+    Windows1251,
+    /// A custom decoder. The function receives the bytes of a single field without any null
+    /// terminator.
+    Custom(fn(&[u8]) -> String),
+}
+
+impl Encoding {
+    fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            // This works because the ISO 8859-1 code points match the unicode code points. So,
+            // `c as char` will map correctly from ISO to unicode.
+            Encoding::Latin1 => bytes.iter().map(|c| *c as char).collect(),
+            Encoding::Windows1251 => bytes
+                .iter()
+                .map(|&c| match c {
+                    0x00..=0x7f => c as char,
+                    0x80..=0xbf => WINDOWS_1251_HIGH[c as usize - 0x80],
+                    // А..я are contiguous in both Windows-1251 and unicode.
+                    0xc0..=0xff => char::from_u32(0x0410 + (c as u32 - 0xc0)).unwrap_or('\u{fffd}'),
+                })
+                .collect(),
+            Encoding::Custom(decode) => decode(bytes),
+        }
+    }
+}
+
+impl fmt::Debug for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Encoding::Latin1 => write!(f, "Latin1"),
+            Encoding::Windows1251 => write!(f, "Windows1251"),
+            Encoding::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Windows-1251 code points 0x80 through 0xbf.
+#[rustfmt::skip]
+static WINDOWS_1251_HIGH: [char; 64] = [
+    'Ђ', 'Ѓ', '‚', 'ѓ', '„', '…', '†', '‡', '€', '‰', 'Љ', '‹', 'Њ', 'Ќ', 'Ћ', 'Џ',
+    'ђ', '‘', '’', '“', '”', '•', '–', '—', '\u{fffd}', '™', 'љ', '›', 'њ', 'ќ', 'ћ', 'џ',
+    '\u{a0}', 'Ў', 'ў', 'Ј', '¤', 'Ґ', '¦', '§', 'Ё', '©', 'Є', '«', '¬', '\u{ad}', '®', 'Ї',
+    '°', '±', 'І', 'і', 'ґ', 'µ', '¶', '·', 'ё', '№', 'є', '»', 'ј', 'Ѕ', 'ѕ', 'ї',
+];
+
 /// A structure containing ID3v1 metadata.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Tag {
@@ -212,7 +305,30 @@ impl Tag {
     }
 
     /// Seeks to and reads a ID3v1 tag from the reader.
-    pub fn read_from(mut reader: impl io::Read + io::Seek) -> crate::Result<Tag> {
+    ///
+    /// Text is decoded as ISO-8859-1, use [`read_from_with_options`](Tag::read_from_with_options)
+    /// to decode tags written with another codepage.
+    pub fn read_from(reader: impl io::Read + io::Seek) -> crate::Result<Tag> {
+        Tag::read_from_with_options(reader, ReadOptions::default())
+    }
+
+    /// Seeks to and reads a ID3v1 tag from the reader using the specified options.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::v1::{Encoding, ReadOptions, Tag};
+    ///
+    /// let options = ReadOptions {
+    ///     encoding: Encoding::Windows1251,
+    /// };
+    /// let tag = Tag::read_from_path_with_options("testdata/id3v1.id3", options)?;
+    /// assert_eq!(tag.title, "Title");
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn read_from_with_options(
+        mut reader: impl io::Read + io::Seek,
+        options: ReadOptions,
+    ) -> crate::Result<Tag> {
         let mut tag_buf = [0; 355];
         let file_len = reader.seek(io::SeekFrom::End(0))?;
         if file_len >= XTAG_CHUNK.start.unsigned_abs() {
@@ -245,20 +361,20 @@ impl Tag {
         };
 
         // Decodes a string consisting out of a base and possible extension to a String.
-        // The input are one or two null-terminated ISO-8859-1 byte slices.
-        fn decode_str(base: &[u8], ext: Option<&[u8]>) -> String {
-            base.iter()
+        // The input are one or two null-terminated byte slices in the configured encoding.
+        let decode_str = |base: &[u8], ext: Option<&[u8]>| -> String {
+            let bytes: Vec<u8> = base
+                .iter()
                 .take_while(|c| **c != 0)
                 .chain({
                     ext.into_iter()
                         .flat_map(|s| s.iter())
                         .take_while(|c| **c != 0)
                 })
-                // This works because the ISO 8859-1 code points match the unicode code
-                // points. So,`c as char` will map correctly from ISO to unicode.
-                .map(|c| *c as char)
-                .collect()
-        }
+                .copied()
+                .collect();
+            options.encoding.decode(&bytes)
+        };
         let title = decode_str(&tag[3..33], xtag.as_ref().map(|t| &t[4..64]));
         let artist = decode_str(&tag[33..63], xtag.as_ref().map(|t| &t[64..124]));
         let album = decode_str(&tag[63..93], xtag.as_ref().map(|t| &t[124..184]));
@@ -301,6 +417,16 @@ impl Tag {
         Tag::read_from(file)
     }
 
+    /// Attempts to read an ID3v1 tag from the file at the indicated path using the specified
+    /// options.
+    pub fn read_from_path_with_options(
+        path: impl AsRef<Path>,
+        options: ReadOptions,
+    ) -> crate::Result<Tag> {
+        let file = fs::File::open(path)?;
+        Tag::read_from_with_options(file, options)
+    }
+
     /// Removes an ID3v1 tag plus possible extended data if any.
     ///
     /// The file cursor position will be reset back to the previous position before returning.
@@ -318,31 +444,8 @@ impl Tag {
     /// Returns true if the file initially contained a tag.
     pub fn remove_from_file(mut file: impl StorageFile) -> crate::Result<bool> {
         let cur_pos = file.stream_position()?;
-        let file_len = file.seek(io::SeekFrom::End(0))?;
-        let has_ext_tag = if file_len >= XTAG_CHUNK.start.unsigned_abs() {
-            file.seek(io::SeekFrom::End(XTAG_CHUNK.start))?;
-            let mut b = [0; 4];
-            file.read_exact(&mut b)?;
-            &b == b"TAG+"
-        } else {
-            false
-        };
-        let has_tag = if file_len >= TAG_CHUNK.start.unsigned_abs() {
-            file.seek(io::SeekFrom::End(TAG_CHUNK.start))?;
-            let mut b = [0; 3];
-            file.read_exact(&mut b)?;
-            &b == b"TAG"
-        } else {
-            false
-        };
-
-        let truncate_to = if has_ext_tag && has_tag {
-            Some(file_len - XTAG_CHUNK.start.unsigned_abs())
-        } else if has_tag {
-            Some(file_len - TAG_CHUNK.start.unsigned_abs())
-        } else {
-            None
-        };
+        let truncate_to =
+            locate(&mut file)?.map(|location| location.ext.unwrap_or(location.tag).start);
         file.seek(io::SeekFrom::Start(cmp::min(
             truncate_to.unwrap_or(cur_pos),
             cur_pos,
@@ -361,14 +464,236 @@ impl Tag {
         Tag::remove_from_file(&mut file)
     }
 
+    /// Writes the tag to the end of the file, replacing any existing ID3v1 tag and extended data.
+    ///
+    /// Only the 128 byte ID3v1.1 structure is written. Values are encoded as ISO-8859-1 and cut
+    /// off at the width of their fields, characters that can not be encoded are replaced by `?`.
+    /// The extended data (`speed`, `genre_str`, `start_time` and `end_time`) is not written.
+    pub fn write_to_file(&self, mut file: impl StorageFile) -> crate::Result<()> {
+        Tag::remove_from_file(&mut file)?;
+        file.seek(io::SeekFrom::End(0))?;
+        file.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Conventience function for [`write_to_file`](Tag::write_to_file).
+    pub fn write_to_path(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        let mut file = fs::OpenOptions::new().read(true).write(true).open(path)?;
+        self.write_to_file(&mut file)
+    }
+
+    /// Encodes the tag as an ID3v1.1 structure.
+    fn to_bytes(&self) -> [u8; 128] {
+        // Encodes a string as a null-padded ISO-8859-1 byte slice.
+        fn encode_str(dst: &mut [u8], s: &str) {
+            let bytes = s.chars().map(|c| u8::try_from(c).unwrap_or(b'?'));
+            for (d, b) in dst.iter_mut().zip(bytes) {
+                *d = b;
+            }
+        }
+        let mut buf = [0; 128];
+        buf[0..3].copy_from_slice(b"TAG");
+        encode_str(&mut buf[3..33], &self.title);
+        encode_str(&mut buf[33..63], &self.artist);
+        encode_str(&mut buf[63..93], &self.album);
+        encode_str(&mut buf[93..97], &self.year);
+        match self.track.filter(|track| *track != 0) {
+            Some(track) => {
+                encode_str(&mut buf[97..125], &self.comment);
+                buf[126] = track;
+            }
+            None => encode_str(&mut buf[97..127], &self.comment),
+        }
+        buf[127] = self.genre_id;
+        buf
+    }
+
     /// Returns `genre_str`, falling back to translating `genre_id` to a string.
     pub fn genre(&self) -> Option<&str> {
+        self.genre_with(GenreTable::WINAMP)
+    }
+
+    /// Returns `genre_str`, falling back to translating `genre_id` to a string using the
+    /// specified genre table.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::tcon::GenreTable;
+    /// use id3::v1::Tag;
+    ///
+    /// let tag = Tag {
+    ///     genre_id: 80,
+    ///     ..Tag::new()
+    /// };
+    /// assert_eq!(tag.genre(), Some("Folk"));
+    /// assert_eq!(tag.genre_with(GenreTable::ID3V1), None);
+    /// ```
+    pub fn genre_with<'a>(&'a self, table: GenreTable<'a>) -> Option<&'a str> {
         if let Some(ref g) = self.genre_str {
             if !g.is_empty() {
                 return Some(g.as_str());
             }
         }
-        GENRE_LIST.get(self.genre_id as usize).cloned()
+        table.name(self.genre_id)
+    }
+
+    /// Converts an ID3v2 tag to an ID3v1 tag.
+    ///
+    /// The title (TIT2), artist (TPE1), album (TALB), year (TYER or TDRC), comment (COMM), track
+    /// (TRCK) and genre (TCON) are copied. Values that are longer than the fixed width fields of
+    /// an ID3v1 tag are handled according to `truncation`. Genres that are not in the Winamp
+    /// genre list are stored in `genre_str`.
+    ///
+    /// # Example
+    /// ```
+    /// use id3::v1::{self, Truncation};
+    /// use id3::{Tag, TagLike};
+    ///
+    /// let mut tag = Tag::new();
+    /// tag.set_title("A title that is longer than thirty characters");
+    /// tag.set_year(2017);
+    /// tag.set_track(3);
+    /// tag.set_genre("Trance");
+    ///
+    /// let tag_v1 = v1::Tag::from_id3v2(&tag, Truncation::Truncate)?;
+    /// assert_eq!(tag_v1.title, "A title that is longer than th");
+    /// assert_eq!(tag_v1.year, "2017");
+    /// assert_eq!(tag_v1.track, Some(3));
+    /// assert_eq!(tag_v1.genre_id, 31);
+    ///
+    /// assert!(v1::Tag::from_id3v2(&tag, Truncation::Error).is_err());
+    /// # Ok::<(), id3::Error>(())
+    /// ```
+    pub fn from_id3v2(tag: &crate::Tag, truncation: Truncation) -> crate::Result<Tag> {
+        let track = match tag.track().filter(|track| *track != 0) {
+            Some(track) => match u8::try_from(track) {
+                Ok(track) => Some(track),
+                Err(_) => truncation.discard("track", 255)?,
+            },
+            None => None,
+        };
+        let year = match tag
+            .year()
+            .or_else(|| tag.date_recorded().map(|timestamp| timestamp.year))
+        {
+            Some(year) if (0..=9999).contains(&year) => format!("{:04}", year),
+            Some(_) => truncation.discard("year", 4)?.unwrap_or_default(),
+            None => String::new(),
+        };
+        let comment = tag
+            .comments()
+            .find(|comment| comment.description.is_empty())
+            .or_else(|| tag.comments().next())
+            .map(|comment| comment.text.as_str())
+            .unwrap_or_default();
+        // The comment field is shortened to make room for the track number.
+        let comment_width = if track.is_some() { 28 } else { 30 };
+
+        // The first genre that is in the genre list is used, falling back to the first genre.
+        let genres = tag.genres_parsed().unwrap_or_default();
+        let genre_id = genres
+            .iter()
+            .find_map(|genre| GenreTable::WINAMP.index(genre));
+        let (genre_id, genre_str) = match (genre_id, genres.first()) {
+            (Some(index), _) => (index, None),
+            (None, Some(genre)) => (255, Some(truncation.fit("genre", genre, 30)?)),
+            (None, None) => (255, None),
+        };
+
+        Ok(Tag {
+            title: truncation.fit("title", tag.title().unwrap_or_default(), 30)?,
+            artist: truncation.fit("artist", tag.artist().unwrap_or_default(), 30)?,
+            album: truncation.fit("album", tag.album().unwrap_or_default(), 30)?,
+            year,
+            comment: truncation.fit("comment", comment, comment_width)?,
+            track,
+            genre_id,
+            genre_str,
+            ..Tag::default()
+        })
+    }
+}
+
+impl From<&crate::Tag> for Tag {
+    /// Converts an ID3v2 tag to an ID3v1 tag, truncating values that do not fit. See
+    /// [`Tag::from_id3v2`].
+    fn from(tag: &crate::Tag) -> Tag {
+        match Tag::from_id3v2(tag, Truncation::Truncate) {
+            Ok(tag) => tag,
+            Err(_) => unreachable!("truncation never fails"),
+        }
+    }
+}
+
+/// The byte ranges of an ID3v1 tag and of the extended data preceding it.
+pub(crate) struct Location {
+    pub tag: ops::Range<u64>,
+    pub ext: Option<ops::Range<u64>>,
+}
+
+/// Locates the ID3v1 tag at the end of the reader and the extended data preceding it, if any.
+pub(crate) fn locate(mut reader: impl io::Read + io::Seek) -> crate::Result<Option<Location>> {
+    let file_len = reader.seek(io::SeekFrom::End(0))?;
+    if file_len < TAG_CHUNK.start.unsigned_abs() {
+        return Ok(None);
+    }
+    reader.seek(io::SeekFrom::End(TAG_CHUNK.start))?;
+    let mut b = [0; 3];
+    reader.read_exact(&mut b)?;
+    if &b != b"TAG" {
+        return Ok(None);
+    }
+    let tag = file_len - TAG_CHUNK.start.unsigned_abs()..file_len;
+
+    let ext = if file_len >= XTAG_CHUNK.start.unsigned_abs() {
+        reader.seek(io::SeekFrom::End(XTAG_CHUNK.start))?;
+        let mut b = [0; 4];
+        reader.read_exact(&mut b)?;
+        (&b == b"TAG+").then(|| file_len - XTAG_CHUNK.start.unsigned_abs()..tag.start)
+    } else {
+        None
+    };
+    Ok(Some(Location { tag, ext }))
+}
+
+/// How [`Tag::from_id3v2`] handles values that do not fit in the fixed width fields of an ID3v1
+/// tag.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Truncation {
+    /// Text is cut off at the width of its field and numbers that can not be represented are
+    /// left out.
+    Truncate,
+    /// An error with [`ErrorKind::InvalidInput`] is returned.
+    Error,
+}
+
+impl Truncation {
+    /// Fits the text in a field of `width` characters.
+    fn fit(self, field: &str, text: &str, width: usize) -> crate::Result<String> {
+        if text.chars().count() <= width {
+            return Ok(text.to_string());
+        }
+        match self {
+            Truncation::Truncate => Ok(text.chars().take(width).collect()),
+            Truncation::Error => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "the {} does not fit in the {} characters of an ID3v1 tag",
+                    field, width
+                ),
+            )),
+        }
+    }
+
+    /// Handles a value that can not be represented in an ID3v1 tag.
+    fn discard<T>(self, field: &str, max: u32) -> crate::Result<Option<T>> {
+        match self {
+            Truncation::Truncate => Ok(None),
+            Truncation::Error => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("the {} of an ID3v1 tag must not exceed {}", field, max),
+            )),
+        }
     }
 }
 
@@ -397,6 +722,113 @@ mod tests {
         assert!(tag.end_time.is_none());
     }
 
+    #[test]
+    fn read_id3v1_windows_1251() {
+        let mut data = vec![0; 128];
+        data[0..3].copy_from_slice(b"TAG");
+        // "Кино" in Windows-1251.
+        data[3..7].copy_from_slice(&[0xca, 0xe8, 0xed, 0xee]);
+        data[33] = 0xa8;
+
+        let tag = Tag::read_from(io::Cursor::new(&data)).unwrap();
+        assert_eq!(tag.title, "Êèíî");
+
+        let options = ReadOptions {
+            encoding: Encoding::Windows1251,
+        };
+        let tag = Tag::read_from_with_options(io::Cursor::new(&data), options).unwrap();
+        assert_eq!(tag.title, "Кино");
+        assert_eq!(tag.artist, "Ё");
+
+        let options = ReadOptions {
+            encoding: Encoding::Custom(|bytes| format!("{} bytes", bytes.len())),
+        };
+        let tag = Tag::read_from_with_options(io::Cursor::new(&data), options).unwrap();
+        assert_eq!(tag.title, "4 bytes");
+        assert_eq!(tag.album, "0 bytes");
+    }
+
+    #[test]
+    fn write_id3v1() {
+        let tag = Tag::read_from_path("testdata/id3v1.id3").unwrap();
+        let mut file = io::Cursor::new(fs::read("testdata/id3v1.id3").unwrap());
+        tag.write_to_file(&mut file).unwrap();
+        assert_eq!(file.get_ref().len(), 128);
+        assert_eq!(Tag::read_from(&mut file).unwrap(), tag);
+
+        let tag = Tag {
+            title: "Ünïcødé ♫ title".to_string(),
+            comment: "A comment of exactly 30 chars.".to_string(),
+            ..Tag::new()
+        };
+        let mut file = io::Cursor::new(vec![0xaa; 64]);
+        tag.write_to_file(&mut file).unwrap();
+        assert_eq!(file.get_ref().len(), 64 + 128);
+        let tag_read = Tag::read_from(&mut file).unwrap();
+        assert_eq!(tag_read.title, "Ünïcødé ? title");
+        assert_eq!(tag_read.comment, tag.comment);
+        assert_eq!(tag_read.track, None);
+    }
+
+    #[test]
+    fn genre_lookup() {
+        assert_eq!(genres().len(), 148);
+        for (index, name) in genres().enumerate() {
+            assert_eq!(genre_index(name), Some(index as u8));
+            assert_eq!(genre_index(&name.to_uppercase()), Some(index as u8));
+            let tag = Tag {
+                genre_id: index as u8,
+                ..Tag::new()
+            };
+            assert_eq!(tag.genre(), Some(name));
+        }
+    }
+
+    #[test]
+    fn from_id3v2() {
+        use crate::frame::Comment;
+
+        let mut tag = crate::Tag::new();
+        tag.set_artist("Artist");
+        tag.set_album("Album");
+        tag.add_frame(Comment {
+            lang: "eng".to_string(),
+            description: "other".to_string(),
+            text: "Other".to_string(),
+        });
+        tag.add_frame(Comment {
+            lang: "eng".to_string(),
+            description: String::new(),
+            text: "A comment of exactly 30 chars.".to_string(),
+        });
+        tag.set_text_values("TCON", ["Vaporwave", "(31)"]);
+
+        let tag_v1 = Tag::from_id3v2(&tag, Truncation::Error).unwrap();
+        assert_eq!(tag_v1.title, "");
+        assert_eq!(tag_v1.artist, "Artist");
+        assert_eq!(tag_v1.album, "Album");
+        assert_eq!(tag_v1.year, "");
+        assert_eq!(tag_v1.comment, "A comment of exactly 30 chars.");
+        assert_eq!(tag_v1.track, None);
+        assert_eq!(tag_v1.genre(), Some("Trance"));
+
+        // The track number takes two bytes of the comment.
+        tag.set_track(300);
+        assert!(Tag::from_id3v2(&tag, Truncation::Error).is_err());
+        tag.set_track(12);
+        assert!(Tag::from_id3v2(&tag, Truncation::Error).is_err());
+        let tag_v1 = Tag::from(&tag);
+        assert_eq!(tag_v1.comment, "A comment of exactly 30 char");
+        assert_eq!(tag_v1.track, Some(12));
+
+        tag.set_genre("Vaporwave");
+        tag.set_year(12345);
+        let tag_v1 = Tag::from(&tag);
+        assert_eq!(tag_v1.genre_id, 255);
+        assert_eq!(tag_v1.genre(), Some("Vaporwave"));
+        assert_eq!(tag_v1.year, "");
+    }
+
     #[test]
     fn remove_id3v1() {
         let tmp = tempdir().unwrap();
@@ -1,4 +1,4 @@
-use crate::{v1, Error, ErrorKind, StorageFile, Tag, Version};
+use crate::{v1, Error, ErrorKind, Format, StorageFile, Tag, Version, WriteOutcome};
 use std::fs;
 use std::fs::File;
 use std::io;
@@ -61,18 +61,76 @@ pub fn read_from_path(path: impl AsRef<Path>) -> crate::Result<Tag> {
 ///
 /// If any ID3v1 tag is present it will be REMOVED as it is not able to fully represent a ID3v2
 /// tag.
-pub fn write_to_file(mut file: impl StorageFile, tag: &Tag, version: Version) -> crate::Result<()> {
-    tag.write_to_file(&mut file, version)?;
-    v1::Tag::remove_from_file(&mut file)?;
-    Ok(())
+pub fn write_to_file(
+    mut file: impl StorageFile,
+    tag: &Tag,
+    version: Version,
+) -> crate::Result<WriteOutcome> {
+    let mut outcome = tag.write_to_file(&mut file, version)?;
+    outcome.v1_touched = v1::Tag::remove_from_file(&mut file)?;
+    Ok(outcome)
 }
 
 /// Conventience function for [`write_to_file`].
-pub fn write_to_path(path: impl AsRef<Path>, tag: &Tag, version: Version) -> crate::Result<()> {
+pub fn write_to_path(
+    path: impl AsRef<Path>,
+    tag: &Tag,
+    version: Version,
+) -> crate::Result<WriteOutcome> {
     let file = fs::OpenOptions::new().read(true).write(true).open(path)?;
     write_to_file(file, tag, version)
 }
 
+/// Writes the specified tag to a file and keeps the ID3v1 tag in sync with it. Any existing
+/// ID3v2 tag is replaced or added if it is not present.
+///
+/// An ID3v1 tag is derived from the ID3v2 tag (see [`v1::Tag::from_id3v2`]) and replaces any
+/// existing ID3v1 tag. Values that do not fit in an ID3v1 tag are truncated. AIFF, WAV and DSDIFF
+/// files store their tag in a chunk and DSF files store it at the end of the file, so these are
+/// not given an ID3v1 tag.
+pub fn write_synced_to_file(
+    mut file: impl StorageFile,
+    tag: &Tag,
+    version: Version,
+) -> crate::Result<WriteOutcome> {
+    let mut probe = [0; 16];
+    let nread = file.read(&mut probe)?;
+    file.seek(io::SeekFrom::Start(0))?;
+    let storage_format = Format::magic(&probe[..nread]);
+
+    let mut outcome = tag.write_to_file(&mut file, version)?;
+    if let Some(Format::Aiff | Format::Wav | Format::Dsdiff | Format::Dsf) = storage_format {
+        return Ok(outcome);
+    }
+    v1::Tag::from(tag).write_to_file(&mut file)?;
+    outcome.v1_touched = true;
+    Ok(outcome)
+}
+
+/// Conventience function for [`write_synced_to_file`].
+pub fn write_synced_to_path(
+    path: impl AsRef<Path>,
+    tag: &Tag,
+    version: Version,
+) -> crate::Result<WriteOutcome> {
+    let file = fs::OpenOptions::new().read(true).write(true).open(path)?;
+    write_synced_to_file(file, tag, version)
+}
+
+/// Ensures that both ID3v1 and ID3v2 are not present in the specified file.
+///
+/// Returns [`FormatVersion`] representing the previous state.
+pub fn strip_all(mut file: impl StorageFile) -> crate::Result<FormatVersion> {
+    let v2 = Tag::remove_from_file(&mut file)?;
+    let v1 = v1::Tag::remove_from_file(&mut file)?;
+    Ok(match (v1, v2) {
+        (false, false) => FormatVersion::None,
+        (true, false) => FormatVersion::Id3v1,
+        (false, true) => FormatVersion::Id3v2,
+        (true, true) => FormatVersion::Both,
+    })
+}
+
 /// Ensures that both ID3v1 and ID3v2 are not present in the specified file.
 ///
 /// Returns [`FormatVersion`] representing the previous state.
@@ -154,11 +212,45 @@ mod tests {
 
         let mut tag = read_from_path(&tmp).unwrap();
         tag.set_artist("High Contrast");
-        write_to_path(&tmp, &tag, Version::Id3v24).unwrap();
+        let outcome = write_to_path(&tmp, &tag, Version::Id3v24).unwrap();
+        assert!(outcome.v1_touched);
 
         assert_eq!(is_candidate_path(&tmp).unwrap(), FormatVersion::Id3v2);
     }
 
+    #[test]
+    fn test_write_synced_to_path() {
+        let tmp = file_with_both_formats();
+
+        let mut tag = read_from_path(&tmp).unwrap();
+        tag.set_artist("High Contrast");
+        let outcome = write_synced_to_path(&tmp, &tag, Version::Id3v24).unwrap();
+        assert!(outcome.v1_touched);
+
+        assert_eq!(is_candidate_path(&tmp).unwrap(), FormatVersion::Both);
+        let tag_v1 = v1::Tag::read_from_path(&tmp).unwrap();
+        assert_eq!(tag_v1.title, tag.title().unwrap());
+        assert_eq!(tag_v1.artist, "High Contrast");
+        // Writing again replaces the ID3v1 tag instead of appending another one.
+        let len = tmp.as_file().metadata().unwrap().len();
+        write_synced_to_path(&tmp, &tag, Version::Id3v24).unwrap();
+        assert_eq!(tmp.as_file().metadata().unwrap().len(), len);
+    }
+
+    #[test]
+    fn test_strip_all() {
+        let tmp = file_with_both_formats();
+
+        let mut file = fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&tmp)
+            .unwrap();
+        assert_eq!(strip_all(&mut file).unwrap(), FormatVersion::Both);
+        assert_eq!(strip_all(&mut file).unwrap(), FormatVersion::None);
+        assert_eq!(is_candidate(&mut file).unwrap(), FormatVersion::None);
+    }
+
     #[test]
     fn test_remove_from_path() {
         let tmp = file_with_both_formats();